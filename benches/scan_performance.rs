@@ -1,5 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use heft::config::Config;
+use heft::report::OutputFormat;
 use heft::scan;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -130,6 +131,25 @@ edition = "2021"
         Ok(())
     }
 
+    /// Create `count` sibling Node projects, each with a small node_modules.
+    /// Lots of detected artifacts side by side: the "inside something already
+    /// claimed?" handling must not grow with the number found so far
+    pub fn create_sibling_projects(base: &Path, count: usize) -> std::io::Result<()> {
+        for i in 0..count {
+            let project = base.join(format!("project-{i}"));
+            let module = project.join("node_modules").join("dep");
+            fs::create_dir_all(&module)?;
+            fs::write(
+                project.join("package.json"),
+                format!(r#"{{"name": "project-{i}"}}"#),
+            )?;
+            fs::write(module.join("index.js"), "module.exports = {};")?;
+            fs::create_dir_all(project.join("src"))?;
+            fs::write(project.join("src").join("main.js"), "require('dep');")?;
+        }
+        Ok(())
+    }
+
     /// Create mixed cache directories (npm, cargo, etc)
     pub fn create_cache_dirs(base: &Path) -> std::io::Result<()> {
         // NPM cache
@@ -154,6 +174,30 @@ edition = "2021"
 
         Ok(())
     }
+
+    /// Several populated caches under a fake home (Linux layout), each a
+    /// few directories deep, for the caches detector on its own.
+    pub fn create_home_caches(home: &Path) -> std::io::Result<()> {
+        for cache in [
+            ".npm/_cacache",
+            ".cache/pip/http",
+            ".cache/yarn/v6",
+            ".cargo/registry/src/index",
+            "go/pkg/mod/github.com",
+            ".gradle/caches/modules-2",
+            ".m2/repository/org",
+            ".local/share/pnpm/store/v3",
+        ] {
+            for pkg in 0..20 {
+                let dir = home.join(cache).join(format!("pkg-{pkg}"));
+                fs::create_dir_all(&dir)?;
+                for file in 0..25 {
+                    fs::write(dir.join(format!("file-{file}")), vec![0u8; 1024])?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Helper to create config for benchmarking
@@ -162,10 +206,13 @@ fn create_bench_config(roots: Vec<PathBuf>) -> Config {
         roots,
         timeout: std::time::Duration::from_secs(30),
         disabled_detectors: std::collections::HashSet::from(["docker".to_string()]), // Skip docker in benchmarks for consistency
-        json_output: false,
-        verbose: false,
+        output_format: OutputFormat::Table,
+        verbose_level: 0,
         progressive: false,
+        // fixtures are a few bytes each
+        min_entry_bytes: 0,
         platform: heft::platform::detect(),
+        ..Config::default()
     }
 }
 
@@ -206,6 +253,36 @@ fn bench_node_modules_scan(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark: many sibling projects (monorepo-style workspace). Time per
+/// project should stay flat as the count grows
+fn bench_sibling_projects_scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scan_sibling_projects");
+    group.sample_size(20);
+
+    for count in [100, 400, 1600] {
+        group.bench_with_input(BenchmarkId::new("projects", count), &count, |b, &count| {
+            let temp_dir = TempDir::new().unwrap();
+            // TempDir names start with '.', which the project walk treats as hidden
+            let workspace = temp_dir.path().join("workspace");
+            fixtures::create_sibling_projects(&workspace, count).unwrap();
+            let mut config = create_bench_config(vec![workspace]);
+            // projects only, so the fixture dominates the measurement
+            config.disabled_detectors = ["caches", "docker", "xcode", "database", "system"]
+                .into_iter()
+                .map(String::from)
+                .collect();
+
+            b.iter(|| {
+                let result = scan::run(black_box(&config));
+                assert_eq!(result.entries.len(), count);
+                black_box(result);
+            });
+        });
+    }
+
+    group.finish();
+}
+
 /// Benchmark: Rust project with build artifacts
 fn bench_rust_project_scan(c: &mut Criterion) {
     c.bench_function("scan_rust_with_target", |b| {
@@ -249,6 +326,39 @@ fn bench_cache_scan(c: &mut Criterion) {
     });
 }
 
+/// Benchmark: caches detector alone over eight populated cache locations,
+/// which it sizes in parallel
+fn bench_home_caches(c: &mut Criterion) {
+    c.bench_function("scan_home_caches", |b| {
+        let temp_dir = TempDir::new().unwrap();
+        fixtures::create_home_caches(temp_dir.path()).unwrap();
+        // the caches detector looks under $HOME; put it back for the
+        // benchmarks that run after this one
+        let real_home = std::env::var_os("HOME");
+        std::env::set_var("HOME", temp_dir.path());
+        let config = Config {
+            disabled_detectors: heft::config::DETECTOR_NAMES
+                .iter()
+                .filter(|name| **name != "caches")
+                .map(|name| name.to_string())
+                .collect(),
+            platform: heft::platform::Platform::Linux,
+            ..create_bench_config(Vec::new())
+        };
+
+        b.iter(|| {
+            let result = scan::run(black_box(&config));
+            assert!(result.entries.len() >= 8, "every cache should be found");
+            black_box(result);
+        });
+
+        match real_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    });
+}
+
 /// Benchmark: Memory usage validation
 fn bench_memory_usage(c: &mut Criterion) {
     c.bench_function("scan_memory_tracking", |b| {
@@ -308,9 +418,11 @@ criterion_group!(
     benches,
     bench_small_scan,
     bench_node_modules_scan,
+    bench_sibling_projects_scan,
     bench_rust_project_scan,
     bench_deep_tree_scan,
     bench_cache_scan,
+    bench_home_caches,
     bench_memory_usage,
     bench_timing_accuracy,
 );
@@ -10,15 +10,25 @@
 //!
 //! Never deletes Docker volumes without explicit opt-in.
 
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
+use serde::Serialize;
+
+use crate::cli::CleanCategory;
 use crate::platform;
 use crate::scan::{
-    detector::{BloatCategory, BloatEntry, Location},
-    ScanResult,
+    detector::{BloatCategory, BloatEntry, CleanupAction, Location},
+    docker, downloads,
+    filter::PathFilter,
+    mobilesync, toolchains, ScanResult,
 };
+use crate::store;
 use crate::util;
 
 #[derive(Copy, Clone, PartialEq)]
@@ -28,58 +38,426 @@ pub enum CleanMode {
     Execute,
 }
 
+#[derive(Default)]
 pub struct CleanResult {
-    pub deleted: Vec<String>,
-    pub errors: Vec<String>,
+    /// One per entry acted on (or, in a dry run, that would be), in order
+    pub actions: Vec<CleanAction>,
     pub bytes_freed: u64,
+    /// Stopped early because the interrupt flag was set
+    pub interrupted: bool,
+    /// Nothing was deleted because the batch was over
+    /// `CleanOptions::large_deletion`; says why
+    pub refused: Option<String>,
+    /// Selected entries left alone because `clean.never_delete` covers them
+    pub protected: Vec<String>,
 }
 
-pub fn run(
-    result: &ScanResult,
-    mode: CleanMode,
-    category_filter: Option<Vec<BloatCategory>>,
-) -> CleanResult {
-    let mut clean_result = CleanResult {
-        deleted: Vec::new(),
-        errors: Vec::new(),
-        bytes_freed: 0,
-    };
+/// How an entry is cleaned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionKind {
+    /// Removed from disk
+    Delete,
+    /// docker rm / docker prune
+    Docker,
+    /// The entry's own cleanup command (--use-hints)
+    Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ActionStatus {
+    Done,
+    DryRun,
+    Failed,
+}
+
+/// What clean did with one entry. `message` is the line printed for it:
+/// "deleted: ...", "[dry-run] would delete: ..." or the error.
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanAction {
+    pub action: ActionKind,
+    pub location: String,
+    pub category: BloatCategory,
+    /// Freed, or would be in a dry run; 0 when the action failed
+    pub bytes_freed: u64,
+    pub status: ActionStatus,
+    pub message: String,
+}
+
+impl CleanResult {
+    /// Messages of the actions that went through (or would, in a dry run).
+    pub fn deleted(&self) -> Vec<&str> {
+        self.actions
+            .iter()
+            .filter(|a| a.status != ActionStatus::Failed)
+            .map(|a| a.message.as_str())
+            .collect()
+    }
+
+    /// Messages of the actions that failed.
+    pub fn errors(&self) -> Vec<&str> {
+        self.actions
+            .iter()
+            .filter(|a| a.status == ActionStatus::Failed)
+            .map(|a| a.message.as_str())
+            .collect()
+    }
+
+    fn record(
+        &mut self,
+        entry: &BloatEntry,
+        options: &CleanOptions,
+        outcome: Result<(String, u64), String>,
+    ) {
+        let (status, bytes_freed, message) = match outcome {
+            Ok((message, freed)) => (ActionStatus::Done, freed, message),
+            Err(message) => (ActionStatus::Failed, 0, message),
+        };
+        self.bytes_freed += bytes_freed;
+        self.actions.push(CleanAction {
+            action: action_kind(entry, options),
+            location: location_display(&entry.location),
+            category: entry.category,
+            bytes_freed,
+            status,
+            message,
+        });
+    }
+
+    /// `clean --json`: every action plus a summary, for bots and audit logs.
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct Summary<'a> {
+            total_freed: u64,
+            items_deleted: usize,
+            errors_count: usize,
+            interrupted: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            refused: Option<String>,
+            #[serde(skip_serializing_if = "<[String]>::is_empty")]
+            protected: &'a [String],
+        }
+        #[derive(Serialize)]
+        struct Report<'a> {
+            actions: &'a [CleanAction],
+            summary: Summary<'a>,
+        }
+
+        let report = Report {
+            actions: &self.actions,
+            summary: Summary {
+                total_freed: self.bytes_freed,
+                items_deleted: self.deleted().len(),
+                errors_count: self.errors().len(),
+                interrupted: self.interrupted,
+                refused: self.refused.clone(),
+                protected: &self.protected,
+            },
+        };
+        // plain data, serialization can't fail
+        serde_json::to_string_pretty(&report).unwrap_or_default()
+    }
+}
+
+/// How much `clean --yes` may delete in one go before it also wants
+/// --confirm-large. Guards scripts against a misconfigured root that
+/// suddenly covers far more than intended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargeDeletion {
+    pub bytes: u64,
+    pub items: usize,
+}
+
+impl Default for LargeDeletion {
+    fn default() -> Self {
+        LargeDeletion {
+            bytes: 50 * 1024 * 1024 * 1024,
+            items: 1000,
+        }
+    }
+}
+
+impl LargeDeletion {
+    /// Why deleting `entries` (in order, stopping at `target`) is over the
+    /// limit, or None if it isn't.
+    fn exceeded_by(&self, entries: &[&BloatEntry], target: Option<u64>) -> Option<String> {
+        let mut bytes = 0u64;
+        let mut items = 0usize;
+        for entry in entries {
+            if target.is_some_and(|t| bytes >= t) {
+                break;
+            }
+            bytes = bytes.saturating_add(entry.reclaimable_bytes);
+            items += 1;
+        }
+        if bytes <= self.bytes && items <= self.items {
+            return None;
+        }
+        Some(format!(
+            "refusing to delete {} across {items} items: over the limit of {} or {} items for --yes. \
+             check the scan roots, then pass --confirm-large (limits: clean.large_threshold and clean.large_items in config.toml)",
+            util::format_bytes(bytes),
+            util::format_bytes(self.bytes),
+            self.items
+        ))
+    }
+}
+
+/// Which entries to clean and when to stop.
+#[derive(Default)]
+pub struct CleanOptions {
+    /// Only clean these categories (all when None)
+    pub category_filter: Option<Vec<BloatCategory>>,
+    /// Never clean these categories (--exclude-category); wins over the filter
+    pub exclude_categories: Vec<BloatCategory>,
+    /// Only clean entries located at exactly one of these paths
+    pub paths: Option<Vec<PathBuf>>,
+    /// Only clean entries whose name matches one of these (exact or glob)
+    pub names: Option<Vec<String>>,
+    /// Only clean entries carrying at least one of these tags (--tag); all
+    /// when empty
+    pub tags: Vec<String>,
+    /// Keep entries modified after this unix timestamp (--min-age). Entries
+    /// with no modification time (caches, docker) are always eligible
+    pub modified_before: Option<i64>,
+    /// Stop once this many bytes have been freed, largest entries first
+    pub target_bytes: Option<u64>,
+    /// Show a running bar toward freeing this many bytes (--goal); unlike
+    /// target_bytes it never stops anything
+    pub goal_bytes: Option<u64>,
+    /// Re-measure directories before deleting and skip any whose size or
+    /// file count moved by more than this factor since the scan (--verify).
+    /// Entries without a recorded file count (snapshots, plans) compare size
+    pub verify_tolerance: Option<f64>,
+    /// Run an entry's cleanup command instead of deleting it, when its hint
+    /// is one (--use-hints). Entries with prose hints are still deleted
+    pub use_hints: bool,
+    /// After each delete, remove the parent directories it left empty, up to
+    /// but never including the deepest of these roots that holds the entry
+    /// (--prune-empty-parents). Off when None
+    pub prune_empty_parents: Option<Vec<PathBuf>>,
+    /// With CleanMode::Execute, refuse the whole batch if it's over this
+    /// (None when --confirm-large is given)
+    pub large_deletion: Option<LargeDeletion>,
+    /// `detectors.docker_context`: passed as `--context` to every docker
+    /// command, so clean prunes the daemon the scan looked at
+    pub docker_context: Option<String>,
+    /// Checked between deletions; once set no new deletion is started
+    pub interrupt: Option<Arc<AtomicBool>>,
+    /// `clean.never_delete`: entries at, under or above a matching path are
+    /// never deleted, whatever else selects them
+    pub never_delete: Vec<String>,
+}
+
+impl CleanOptions {
+    fn target_reached(&self, freed: u64) -> bool {
+        self.target_bytes.is_some_and(|target| freed >= target)
+    }
+
+    /// Whether an opt-in entry tagged `tag` was picked explicitly, by that
+    /// tag or by `--path`.
+    fn asked_for(&self, tag: &str) -> bool {
+        self.paths.is_some() || self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
 
-    // filter entries by category if specified, using iterator to avoid allocation
-    let entries = result.entries.iter().filter(|entry| {
-        // allow docker aggregates through, filter out other aggregates
-        if let Location::Aggregate(ref name) = entry.location {
-            if !is_docker_aggregate(name) {
+    fn selects(&self, entry: &BloatEntry) -> bool {
+        if let Some(ref filter) = self.category_filter {
+            if !filter.contains(&entry.category) {
+                return false;
+            }
+        }
+        if self.exclude_categories.contains(&entry.category) {
+            return false;
+        }
+        // the user's own files: only with an explicit --category other
+        if downloads::is_download(entry)
+            && !self
+                .category_filter
+                .as_ref()
+                .is_some_and(|f| f.contains(&BloatCategory::Other))
+        {
+            return false;
+        }
+        // device backups are the user's data too, and an unpinned runtime
+        // may still be used from a shell or a virtualenv: only by tag or path
+        if mobilesync::is_backup(entry) && !self.asked_for(mobilesync::BACKUP_TAG) {
+            return false;
+        }
+        if toolchains::is_toolchain(entry) && !self.asked_for(toolchains::TOOLCHAIN_TAG) {
+            return false;
+        }
+        if let Some(ref paths) = self.paths {
+            match entry.location {
+                Location::FilesystemPath(ref path) if paths.contains(path) => {}
+                _ => return false,
+            }
+        }
+        if let Some(ref names) = self.names {
+            if !names
+                .iter()
+                .any(|n| *n == entry.name || util::glob_match(n, &entry.name))
+            {
                 return false;
             }
         }
+        if !entry.has_any_tag(&self.tags) {
+            return false;
+        }
+        if let (Some(cutoff), Some(modified)) = (self.modified_before, entry.last_modified) {
+            if modified > cutoff {
+                return false;
+            }
+        }
+        true
+    }
 
-        if let Some(ref filter) = category_filter {
-            filter.contains(&entry.category)
-        } else {
-            true
+    /// The command to run for `entry` instead of deleting it, if any.
+    fn hint_command(&self, entry: &BloatEntry) -> Option<Vec<String>> {
+        if !self.use_hints || !matches!(entry.location, Location::FilesystemPath(_)) {
+            return None;
+        }
+        match entry.cleanup_action()? {
+            CleanupAction::Command(argv) => Some(argv),
+            CleanupAction::Manual(_) => None,
+        }
+    }
+
+    /// The running bar toward --goal after each deletion, on stderr.
+    fn report_goal(&self, freed: u64) {
+        if let Some(goal) = self.goal_bytes {
+            eprintln!("  {}", util::goal_bar(freed, goal));
+        }
+    }
+
+    fn interrupted(&self) -> bool {
+        self.interrupt
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::SeqCst))
+    }
+}
+
+/// Whether clean may ever delete `entry`, whatever the flags: not an
+/// informational entry and not heft's own database.
+fn deletable(entry: &BloatEntry, heft_db: Option<&Path>) -> bool {
+    // allow docker aggregates through, filter out other aggregates
+    if let Location::Aggregate(ref name) = entry.location {
+        if !is_docker_aggregate(name) {
+            return false;
+        }
+    }
+
+    // informational entries (VMs, .git, app data) are reported so the
+    // space is visible, but only a manual step frees it
+    if entry.reclaimable_bytes == 0 {
+        return false;
+    }
+
+    // heft's own database is reported so its growth is visible, but it's
+    // compacted with `heft db vacuum`, never deleted out from under us
+    if let Location::FilesystemPath(ref path) = entry.location {
+        if heft_db == Some(path.as_path()) {
+            return false;
         }
-    });
+    }
+    true
+}
+
+/// Whether a plain `heft clean` (no selection flags) would delete `entry`,
+/// for `scan --list-paths --reclaimable-only`: deletable, not an opt-in
+/// entry (downloads, device backups, runtimes) and not covered by
+/// `clean.never_delete`.
+pub fn would_clean(entry: &BloatEntry, never_delete: &[String]) -> bool {
+    let heft_db = store::snapshot::db_path();
+    if !deletable(entry, heft_db.as_deref()) || !CleanOptions::default().selects(entry) {
+        return false;
+    }
+    match entry.location {
+        Location::FilesystemPath(ref path) => !never_delete_covers(never_delete, path),
+        _ => true,
+    }
+}
+
+pub fn run(result: &ScanResult, mode: CleanMode, options: &CleanOptions) -> CleanResult {
+    let mut clean_result = CleanResult::default();
+
+    let heft_db = store::snapshot::db_path();
+
+    let mut entries: Vec<&BloatEntry> = result
+        .entries
+        .iter()
+        .filter(|entry| {
+            if !deletable(entry, heft_db.as_deref()) {
+                return false;
+            }
+            if !options.selects(entry) {
+                return false;
+            }
+            if let Location::FilesystemPath(ref path) = entry.location {
+                if never_delete_covers(&options.never_delete, path) {
+                    clean_result.protected.push(path.display().to_string());
+                    return false;
+                }
+            }
+            true
+        })
+        .collect();
+
+    // with a target, free the biggest things first so we stop after as few
+    // deletions as possible. entries with nothing to reclaim can't help.
+    if options.target_bytes.is_some() {
+        entries.retain(|e| e.reclaimable_bytes > 0);
+        entries.sort_by_key(|e| std::cmp::Reverse(e.reclaimable_bytes));
+    }
 
     // process based on mode - match once instead of per entry
     match mode {
         CleanMode::DryRun => {
+            // docker usage may have moved since the scan, so re-ask the daemon
+            // once for current figures instead of trusting the scan's numbers
+            let live_docker = entries
+                .iter()
+                .any(|e| matches!(e.location, Location::Aggregate(_)))
+                .then(|| live_docker_reclaimable(options.docker_context.as_deref()));
+
             for entry in entries {
-                let location_str = location_display(&entry.location);
-                clean_result
-                    .deleted
-                    .push(format!("[dry-run] would delete: {location_str}"));
-                clean_result.bytes_freed += entry.reclaimable_bytes;
+                if options.target_reached(clean_result.bytes_freed) {
+                    break;
+                }
+                let (line, bytes) =
+                    match (&entry.location, &live_docker, options.hint_command(entry)) {
+                        (Location::Aggregate(name), Some(live), _) => {
+                            dry_run_docker_aggregate(name, entry.reclaimable_bytes, live)
+                        }
+                        (location, _, Some(argv)) => (
+                            format!(
+                                "[dry-run] would run: {} (for {})",
+                                argv.join(" "),
+                                location_display(location)
+                            ),
+                            entry.reclaimable_bytes,
+                        ),
+                        (location, _, None) => (
+                            format!("[dry-run] would delete: {}", location_display(location)),
+                            entry.reclaimable_bytes,
+                        ),
+                    };
+                clean_result.bytes_freed += bytes;
+                clean_result.actions.push(CleanAction {
+                    action: action_kind(entry, options),
+                    location: location_display(&entry.location),
+                    category: entry.category,
+                    bytes_freed: bytes,
+                    status: ActionStatus::DryRun,
+                    message: line,
+                });
             }
         }
         CleanMode::Interactive => {
-            // collect entries first (can't iterate twice)
-            let entries_vec: Vec<_> = entries.collect();
-
-            // group by category
+            // group by category, keeping the size ordering within each group
             use std::collections::HashMap;
             let mut by_category: HashMap<BloatCategory, Vec<&BloatEntry>> = HashMap::new();
-            for entry in &entries_vec {
+            for entry in &entries {
                 by_category.entry(entry.category).or_default().push(entry);
             }
 
@@ -89,13 +467,26 @@ pub fn run(
             }
 
             // calculate totals
-            let total_bytes: u64 = entries_vec.iter().map(|e| e.reclaimable_bytes).sum();
+            let total_bytes: u64 = entries.iter().map(|e| e.reclaimable_bytes).sum();
 
             println!(
                 "\nFound {} reclaimable across {} categories:\n",
                 util::format_bytes(total_bytes),
                 by_category.len()
             );
+            if let Some(target) = options.target_bytes {
+                println!(
+                    "Target: free {} (stops once reached)\n",
+                    util::format_bytes(target)
+                );
+            }
+            if let Some(goal) = options.goal_bytes {
+                println!(
+                    "Goal: {} (this selection covers {}%)\n",
+                    util::format_bytes(goal),
+                    util::goal_percent(total_bytes, goal)
+                );
+            }
 
             // sort categories for consistent display order
             let mut categories: Vec<_> = by_category.iter().collect();
@@ -103,6 +494,14 @@ pub fn run(
 
             // prompt per category
             for (category, entries) in categories {
+                if options.target_reached(clean_result.bytes_freed) {
+                    break;
+                }
+                if options.interrupted() {
+                    clean_result.interrupted = true;
+                    break;
+                }
+
                 let cat_bytes: u64 = entries.iter().map(|e| e.reclaimable_bytes).sum();
                 let cat_items = entries.len();
 
@@ -112,6 +511,13 @@ pub fn run(
                     util::format_bytes(cat_bytes),
                     cat_items
                 );
+                // the catch-all holds whatever nothing else claimed; show
+                // exactly what would go before asking
+                if *category == BloatCategory::Other {
+                    for entry in entries.iter() {
+                        println!("    {}", crate::report::location_text(&entry.location));
+                    }
+                }
 
                 print!("  Delete? [y/n]: ");
                 use std::io::{self, Write};
@@ -126,23 +532,34 @@ pub fn run(
                     continue;
                 }
 
+                // Ctrl-C at the prompt only lands once enter is pressed
+                if options.interrupted() {
+                    clean_result.interrupted = true;
+                    break;
+                }
+
                 if input.trim().eq_ignore_ascii_case("y") {
                     // delete this category
                     for entry in entries {
-                        match delete_entry(entry) {
-                            Ok(msg) => {
-                                clean_result.deleted.push(msg);
-                                clean_result.bytes_freed += entry.reclaimable_bytes;
-                            }
-                            Err(e) => {
-                                clean_result.errors.push(e);
-                            }
+                        if options.target_reached(clean_result.bytes_freed) {
+                            break;
                         }
+                        if options.interrupted() {
+                            clean_result.interrupted = true;
+                            break;
+                        }
+                        let nested = nested_entries(result, entry);
+                        let outcome = delete_entry(entry, options, &nested, true);
+                        clean_result.record(entry, options, outcome);
+                        options.report_goal(clean_result.bytes_freed);
                     }
                 } else {
                     println!("  Skipped");
                 }
                 println!();
+                if clean_result.interrupted {
+                    break;
+                }
             }
 
             if clean_result.bytes_freed > 0 {
@@ -150,16 +567,29 @@ pub fn run(
             }
         }
         CleanMode::Execute => {
+            if util::read_only() {
+                clean_result.refused = Some("read-only mode: nothing is deleted".to_string());
+                return clean_result;
+            }
+            if let Some(reason) = options
+                .large_deletion
+                .and_then(|limit| limit.exceeded_by(&entries, options.target_bytes))
+            {
+                clean_result.refused = Some(reason);
+                return clean_result;
+            }
             for entry in entries {
-                match delete_entry(entry) {
-                    Ok(msg) => {
-                        clean_result.deleted.push(msg);
-                        clean_result.bytes_freed += entry.reclaimable_bytes;
-                    }
-                    Err(e) => {
-                        clean_result.errors.push(e);
-                    }
+                if options.target_reached(clean_result.bytes_freed) {
+                    break;
+                }
+                if options.interrupted() {
+                    clean_result.interrupted = true;
+                    break;
                 }
+                let nested = nested_entries(result, entry);
+                let outcome = delete_entry(entry, options, &nested, false);
+                clean_result.record(entry, options, outcome);
+                options.report_goal(clean_result.bytes_freed);
             }
         }
     }
@@ -167,30 +597,202 @@ pub fn run(
     clean_result
 }
 
-fn delete_entry(entry: &BloatEntry) -> Result<String, String> {
+/// Paths of the other entries in `result` nested inside `entry`'s path
+/// (the cargo registry's sources and index inside the registry). Their
+/// space isn't counted in `entry`, so deleting it leaves them in place.
+fn nested_entries(result: &ScanResult, entry: &BloatEntry) -> Vec<PathBuf> {
+    let Location::FilesystemPath(ref outer) = entry.location else {
+        return Vec::new();
+    };
+    result
+        .entries
+        .iter()
+        .filter_map(|other| match other.location {
+            Location::FilesystemPath(ref path) if path != outer && path.starts_with(outer) => {
+                Some(path.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn action_kind(entry: &BloatEntry, options: &CleanOptions) -> ActionKind {
+    if options.hint_command(entry).is_some() {
+        return ActionKind::Command;
+    }
+    match entry.location {
+        Location::FilesystemPath(_) => ActionKind::Delete,
+        Location::DockerObject(_) | Location::Aggregate(_) => ActionKind::Docker,
+    }
+}
+
+/// Directories at least this big can take minutes to remove (a few hundred
+/// thousand node_modules files), so clean says so before starting.
+const SLOW_DELETE_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Delete one entry. With `progress`, a slow delete is announced and shows a
+/// running file count on stderr (interactive mode, where someone is
+/// watching). Without it nothing is printed, so --yes output and the TUI's
+/// screen stay clean. Paths in `nested` are kept (see `nested_entries`).
+/// Returns what happened and how many bytes that freed.
+fn delete_entry(
+    entry: &BloatEntry,
+    options: &CleanOptions,
+    nested: &[PathBuf],
+    progress: bool,
+) -> Result<(String, u64), String> {
     match &entry.location {
-        Location::FilesystemPath(path) => delete_filesystem_path(path),
-        Location::DockerObject(obj_id) => delete_docker_object(obj_id),
-        Location::Aggregate(name) => delete_docker_aggregate(name),
+        Location::FilesystemPath(path) => {
+            if let Some(argv) = options.hint_command(entry) {
+                return run_hint_command(&argv, path);
+            }
+            // gone since the scan: nothing left to free, so nothing counted
+            if fs::symlink_metadata(path).is_err_and(|e| e.kind() == std::io::ErrorKind::NotFound) {
+                return Ok((format!("already removed: {}", path.display()), 0));
+            }
+            if let Some(tolerance) = options.verify_tolerance {
+                verify_unchanged(path, entry, tolerance)?;
+            }
+            // not every entry has a file count, so size stands in for it
+            let slow = progress && entry.size_bytes >= SLOW_DELETE_BYTES;
+            if slow {
+                eprintln!(
+                    "deleting {} ({}) may take a while",
+                    path.display(),
+                    util::format_bytes(entry.size_bytes)
+                );
+            }
+            let message = if nested.is_empty() {
+                delete_filesystem_path(path, slow, &options.never_delete)?
+            } else {
+                empty_except(path, nested, &options.never_delete)?
+            };
+            let message = match options.prune_empty_parents {
+                Some(ref roots) => match prune_empty_parents(path, roots, &options.never_delete) {
+                    0 => message,
+                    1 => format!("{message} (and 1 empty parent)"),
+                    n => format!("{message} (and {n} empty parents)"),
+                },
+                None => message,
+            };
+            Ok((message, entry.reclaimable_bytes))
+        }
+        Location::DockerObject(obj_id) => {
+            delete_docker_object(obj_id, options.docker_context.as_deref())
+                .map(|message| (message, entry.reclaimable_bytes))
+        }
+        Location::Aggregate(name) => {
+            delete_docker_aggregate(name, options.docker_context.as_deref())
+                .map(|message| (message, entry.reclaimable_bytes))
+        }
+    }
+}
+
+/// Run a cache's own cleanup command with the terminal attached, so the
+/// tool's output and any prompts reach the user.
+fn run_cleanup_command(argv: &[String]) -> Result<String, String> {
+    let display = argv.join(" ");
+    let Some((program, args)) = argv.split_first() else {
+        return Err("empty cleanup command".to_string());
+    };
+    match Command::new(program).args(args).status() {
+        Ok(status) if status.success() => Ok(format!("ran: {display}")),
+        Ok(status) => Err(format!("'{display}' failed ({status})")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(format!("'{display}' failed: {program} not found on PATH"))
+        }
+        Err(e) => Err(format!("'{display}' failed: {e}")),
     }
 }
 
+/// Run a cache's cleanup command and count what it actually freed under
+/// `path`. The tool decides what goes: `pip cache purge` also empties the
+/// wheels cache, which can be an entry of its own, and others keep part of
+/// their cache, so the scan's figure is no measure of it.
+fn run_hint_command(argv: &[String], path: &Path) -> Result<(String, u64), String> {
+    let before = measured_size(path);
+    let message = run_cleanup_command(argv)?;
+    Ok((message, before.saturating_sub(measured_size(path))))
+}
+
+/// Size of `path` now; nothing there counts as empty.
+fn measured_size(path: &Path) -> u64 {
+    crate::scan::calculate_dir_size(path).map_or(0, |(size, _)| size)
+}
+
+/// Changes smaller than these never fail --verify, so tiny directories
+/// (a few KB of pycache) don't trip the ratio check.
+const VERIFY_SLACK_BYTES: u64 = 1024 * 1024;
+const VERIFY_SLACK_FILES: u64 = 100;
+
+/// Re-measure `path` and refuse if its size, or its file count where the
+/// scan recorded one, moved more than `tolerance`x either way since the
+/// scan, which suggests the path now holds something else.
+fn verify_unchanged(path: &Path, scanned: &BloatEntry, tolerance: f64) -> Result<(), String> {
+    let current = match crate::scan::measure_dir(path) {
+        Ok(current) => current,
+        // missing paths are handled by the delete itself
+        Err(_) => return Ok(()),
+    };
+    if !size_within_tolerance(scanned.size_bytes, current.bytes, tolerance) {
+        return Err(format!(
+            "skipped {}: size changed from {} to {} since the scan (--verify)",
+            path.display(),
+            util::format_bytes(scanned.size_bytes),
+            util::format_bytes(current.bytes)
+        ));
+    }
+    if let Some(files) = scanned.file_count {
+        if !within_tolerance(files, current.files, tolerance, VERIFY_SLACK_FILES) {
+            return Err(format!(
+                "skipped {}: file count changed from {files} to {} since the scan (--verify)",
+                path.display(),
+                current.files
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn size_within_tolerance(scanned: u64, current: u64, tolerance: f64) -> bool {
+    within_tolerance(scanned, current, tolerance, VERIFY_SLACK_BYTES)
+}
+
+/// `current` is within `tolerance`x of `scanned` either way, give or take `slack`.
+fn within_tolerance(scanned: u64, current: u64, tolerance: f64, slack: u64) -> bool {
+    let grown_ok = current as f64 <= scanned as f64 * tolerance + slack as f64;
+    let shrunk_ok = current as f64 * tolerance + slack as f64 >= scanned as f64;
+    grown_ok && shrunk_ok
+}
+
 fn is_docker_aggregate(name: &str) -> bool {
     matches!(
         name,
         "Images" | "Containers" | "Local Volumes" | "Build Cache"
-    )
+    ) || docker::build_cache_bucket_days(name).is_some()
 }
 
-fn delete_filesystem_path(path: &Path) -> Result<String, String> {
+fn delete_filesystem_path(
+    path: &Path,
+    progress: bool,
+    never_delete: &[String],
+) -> Result<String, String> {
     // validate path is in a safe location before deletion (issue #59)
-    validate_deletion_path(path)?;
+    validate_deletion_path(path, never_delete)?;
 
     // security: use symlink_metadata to avoid following symlinks (issue #55)
     // this also mitigates TOCTOU attacks where a directory could be replaced
     // with a symlink between scan and clean operations (issue #56)
-    let metadata = fs::symlink_metadata(path)
-        .map_err(|e| format!("failed to get metadata for {}: {}", path.display(), e))?;
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(e) => {
+            return Err(format!(
+                "failed to get metadata for {}: {}",
+                path.display(),
+                e
+            ))
+        }
+    };
 
     // refuse to delete symlinks - prevents deletion of symlink targets
     // which could be anywhere on the filesystem (including system directories)
@@ -202,7 +804,22 @@ fn delete_filesystem_path(path: &Path) -> Result<String, String> {
     }
 
     // now safe to delete - we know it's not a symlink
-    let result = if metadata.is_dir() {
+    let result = if metadata.is_dir() && progress {
+        match crate::spinner::Spinner::start(&format!("deleting {}", path.display())) {
+            Some(spinner) => {
+                let name = path
+                    .file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy();
+                let removed = remove_tree(path, &mut |files| {
+                    spinner.set_message(&format!("deleting {name}: {files} files"));
+                });
+                spinner.stop();
+                removed.map(|_| ())
+            }
+            None => fs::remove_dir_all(path),
+        }
+    } else if metadata.is_dir() {
         fs::remove_dir_all(path)
     } else {
         fs::remove_file(path)
@@ -214,16 +831,172 @@ fn delete_filesystem_path(path: &Path) -> Result<String, String> {
     }
 }
 
+/// Delete everything inside `dir` except the `keep` paths under it and the
+/// directories leading to them, which stay with `dir` itself. Checked like
+/// `delete_filesystem_path`.
+fn empty_except(dir: &Path, keep: &[PathBuf], never_delete: &[String]) -> Result<String, String> {
+    fn walk(dir: &Path, keep: &[PathBuf]) -> std::io::Result<()> {
+        if !fs::symlink_metadata(dir)?.is_dir() {
+            return Err(std::io::Error::other(format!(
+                "{} changed while being deleted",
+                dir.display()
+            )));
+        }
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if keep.contains(&path) {
+                continue;
+            }
+            if !entry.file_type()?.is_dir() {
+                fs::remove_file(&path)?;
+            } else if keep.iter().any(|k| k.starts_with(&path)) {
+                walk(&path, keep)?;
+            } else {
+                fs::remove_dir_all(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    validate_deletion_path(dir, never_delete)?;
+    match fs::symlink_metadata(dir) {
+        Ok(metadata) if metadata.is_symlink() => {
+            return Err(format!(
+                "refusing to delete symlink: {} (security: could point anywhere)",
+                dir.display()
+            ))
+        }
+        Ok(_) => {}
+        Err(e) => return Err(format!("failed to get metadata for {}: {e}", dir.display())),
+    }
+    match (walk(dir, keep), keep.len()) {
+        (Ok(()), 1) => Ok(format!("deleted: {} (kept 1 nested entry)", dir.display())),
+        (Ok(()), n) => Ok(format!(
+            "deleted: {} (kept {n} nested entries)",
+            dir.display()
+        )),
+        (Err(e), _) => Err(format!("failed to delete {}: {e}", dir.display())),
+    }
+}
+
+/// How many files `remove_tree` removes between progress updates.
+const PROGRESS_EVERY_FILES: u64 = 1000;
+
+/// remove_dir_all that reports as it goes: removes everything under `dir`
+/// and `dir` itself, calling `on_progress` with the running file count every
+/// `PROGRESS_EVERY_FILES` files, and returns the total. Symlinks are removed,
+/// never followed: the type comes from the directory entry, and each
+/// directory is checked again just before it is read.
+fn remove_tree(dir: &Path, on_progress: &mut dyn FnMut(u64)) -> std::io::Result<u64> {
+    fn walk(dir: &Path, files: &mut u64, on_progress: &mut dyn FnMut(u64)) -> std::io::Result<()> {
+        if !fs::symlink_metadata(dir)?.is_dir() {
+            return Err(std::io::Error::other(format!(
+                "{} changed while being deleted",
+                dir.display()
+            )));
+        }
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                walk(&path, files, on_progress)?;
+                continue;
+            }
+            // a symlink to a directory is removed with remove_dir on Windows
+            if let Err(e) = fs::remove_file(&path) {
+                if !(cfg!(windows) && fs::remove_dir(&path).is_ok()) {
+                    return Err(e);
+                }
+            }
+            *files += 1;
+            if *files % PROGRESS_EVERY_FILES == 0 {
+                on_progress(*files);
+            }
+        }
+        fs::remove_dir(dir)
+    }
+
+    let mut files = 0;
+    walk(dir, &mut files, on_progress)?;
+    Ok(files)
+}
+
+/// Remove the directories above a deleted `path` that are now empty,
+/// innermost first, and return how many went. Stops at the first parent that
+/// still holds something, fails validate_deletion_path, or is the scan root
+/// the entry came from (or home); entries outside every root prune nothing.
+fn prune_empty_parents(path: &Path, roots: &[PathBuf], never_delete: &[String]) -> usize {
+    let Some(root) = roots
+        .iter()
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.components().count())
+    else {
+        return 0;
+    };
+    let home = platform::home_dir();
+
+    let mut removed = 0;
+    let mut dir = path.parent();
+    while let Some(parent) = dir {
+        if parent == root || !parent.starts_with(root) || home.as_deref() == Some(parent) {
+            break;
+        }
+        if validate_deletion_path(parent, never_delete).is_err() {
+            break;
+        }
+        // remove_dir only succeeds on an empty directory, so anything that
+        // appeared since the delete keeps its parent
+        if fs::remove_dir(parent).is_err() {
+            break;
+        }
+        removed += 1;
+        dir = parent.parent();
+    }
+    removed
+}
+
 /// Validates that a path is safe to delete.
 ///
 /// Checks:
 /// - Path must be absolute
 /// - Path must be under user's home directory or temp directories
 /// - Path must not be the home directory itself
+/// - Path must not be covered by `clean.never_delete`
 ///
 /// Note: Does NOT follow symlinks. The symlink check in delete_filesystem_path()
 /// handles symlink cases separately for security (issues #55, #56).
-fn validate_deletion_path(path: &Path) -> Result<(), String> {
+fn validate_deletion_path(path: &Path, never_delete: &[String]) -> Result<(), String> {
+    if never_delete_covers(never_delete, path) {
+        return Err(format!(
+            "refusing to delete {}: protected by clean.never_delete",
+            path.display()
+        ));
+    }
+    check_deletion_path(path, platform::home_dir().as_deref())
+}
+
+/// Whether a `clean.never_delete` pattern covers `path`: it or one of its
+/// ancestors matches (globs as in --exclude), or deleting it would take a
+/// protected path along. The latter only sees the literal part of a
+/// pattern, up to its first wildcard.
+fn never_delete_covers(patterns: &[String], path: &Path) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+    let filter = PathFilter::new(Vec::new(), patterns.to_vec());
+    if path.ancestors().any(|a| filter.is_excluded(a)) {
+        return true;
+    }
+    patterns.iter().filter(|p| p.contains('/')).any(|pattern| {
+        let literal = pattern
+            .find(['*', '?'])
+            .map_or(pattern.as_str(), |i| &pattern[..i]);
+        Path::new(literal).starts_with(path)
+    })
+}
+
+fn check_deletion_path(path: &Path, home: Option<&Path>) -> Result<(), String> {
     // path must be absolute
     if !path.is_absolute() {
         return Err(format!(
@@ -232,19 +1005,42 @@ fn validate_deletion_path(path: &Path) -> Result<(), String> {
         ));
     }
 
+    // starts_with compares components, so /home/me/../../srv would pass the
+    // home check below. scans never produce "..", but a plan file can
+    if path
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(format!(
+            "refusing to delete path with '..': {} (security: must be normalized)",
+            path.display()
+        ));
+    }
+
     // check if path is under home directory
     // note: using starts_with on the path directly, not canonicalizing
-    // this avoids following symlinks and handles non-existent paths correctly
-    if let Some(home) = platform::home_dir() {
-        if path.starts_with(&home) {
-            // path is under home, but make sure it's not home itself
-            if path == home {
-                return Err(format!(
-                    "refusing to delete home directory: {} (security: too dangerous)",
-                    path.display()
-                ));
+    // this avoids following symlinks and handles non-existent paths correctly.
+    // $HOME itself may be a symlink (/home/me -> /data/home/me), and paths from
+    // the scan roots can be in either form, so home is checked as given and
+    // resolved
+    if let Some(home) = home {
+        let mut homes = vec![home.to_path_buf()];
+        if let Ok(resolved) = fs::canonicalize(home) {
+            if resolved != home {
+                homes.push(resolved);
+            }
+        }
+        for home in &homes {
+            if path.starts_with(home) {
+                // path is under home, but make sure it's not home itself
+                if path == home {
+                    return Err(format!(
+                        "refusing to delete home directory: {} (security: too dangerous)",
+                        path.display()
+                    ));
+                }
+                return Ok(());
             }
-            return Ok(());
         }
     }
 
@@ -275,8 +1071,8 @@ fn validate_deletion_path(path: &Path) -> Result<(), String> {
     ))
 }
 
-fn delete_docker_object(obj_id: &str) -> Result<String, String> {
-    let output = Command::new("docker")
+fn delete_docker_object(obj_id: &str, context: Option<&str>) -> Result<String, String> {
+    let output = docker::docker_command(context)
         .arg("rmi")
         .arg("-f")
         .arg("--")
@@ -297,21 +1093,30 @@ fn delete_docker_object(obj_id: &str) -> Result<String, String> {
     }
 }
 
-fn delete_docker_aggregate(aggregate_type: &str) -> Result<String, String> {
+fn delete_docker_aggregate(aggregate_type: &str, context: Option<&str>) -> Result<String, String> {
     // map aggregate type to docker prune command
     let (subcommand, extra_args) = match aggregate_type {
         "Images" => ("image", vec!["prune", "-a", "-f"]),
         "Containers" => ("container", vec!["prune", "-f"]),
         "Local Volumes" => ("volume", vec!["prune", "-f"]),
         "Build Cache" => ("builder", vec!["prune", "-a", "-f"]),
-        _ => return Err(format!("unknown docker aggregate type: {aggregate_type}")),
+        _ => match docker::build_cache_bucket_days(aggregate_type) {
+            // an age bucket: prune what is older than its lower bound. that
+            // takes older buckets along, but clean lists the oldest first, so
+            // each bucket's bytes are freed (and counted) once
+            Some(_) => ("builder", vec!["prune", "-a", "-f", "--filter"]),
+            None => return Err(format!("unknown docker aggregate type: {aggregate_type}")),
+        },
     };
 
-    let mut cmd = Command::new("docker");
+    let mut cmd = docker::docker_command(context);
     cmd.arg(subcommand);
     for arg in extra_args {
         cmd.arg(arg);
     }
+    if let Some(days) = docker::build_cache_bucket_days(aggregate_type) {
+        cmd.arg(format!("until={}h", days * 24));
+    }
 
     let output = cmd.output();
 
@@ -338,6 +1143,60 @@ fn delete_docker_aggregate(aggregate_type: &str) -> Result<String, String> {
     }
 }
 
+const DOCKER_DF_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Current reclaimable bytes per docker aggregate type, from `docker system df`.
+/// Types with no data are absent (nothing to reclaim).
+fn live_docker_reclaimable(context: Option<&str>) -> Result<HashMap<String, u64>, String> {
+    let entries = docker::run_docker_system_df(DOCKER_DF_TIMEOUT, false, context, false)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|e| match e.location {
+            Location::Aggregate(name) => Some((name, e.reclaimable_bytes)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Dry-run line and byte count for a docker aggregate. None of the prune
+/// commands have a real dry-run, so the figure comes from a fresh
+/// `docker system df`, falling back to the scan estimate if that fails.
+fn dry_run_docker_aggregate(
+    name: &str,
+    scan_bytes: u64,
+    live: &Result<HashMap<String, u64>, String>,
+) -> (String, u64) {
+    // df only totals the build cache, so age buckets keep the scan's figure
+    if docker::build_cache_bucket_days(name).is_some() {
+        return (
+            format!(
+                "[dry-run] would delete: {name} ({} from scan; docker system df has no per-age figures)",
+                util::format_bytes(scan_bytes)
+            ),
+            scan_bytes,
+        );
+    }
+    match live {
+        Ok(current) => {
+            let bytes = current.get(name).copied().unwrap_or(0);
+            (
+                format!(
+                    "[dry-run] would delete: {name} ({} reclaimable per docker system df now; prune has no dry-run)",
+                    util::format_bytes(bytes)
+                ),
+                bytes,
+            )
+        }
+        Err(e) => (
+            format!(
+                "[dry-run] would delete: {name} ({} from scan; {e})",
+                util::format_bytes(scan_bytes)
+            ),
+            scan_bytes,
+        ),
+    }
+}
+
 fn location_display(location: &Location) -> String {
     match location {
         Location::FilesystemPath(path) => path.display().to_string(),
@@ -346,7 +1205,129 @@ fn location_display(location: &Location) -> String {
     }
 }
 
-fn category_sort_order(category: &BloatCategory) -> u8 {
+/// Entries of a reviewed plan for `clean --from-plan`: `heft scan --format
+/// json` output (or a snapshot export), usually trimmed by hand to what
+/// should go. Also returns a note for every entry skipped because its path
+/// no longer exists. The rest are re-measured and still pass
+/// `validate_deletion_path` when deleted, like scanned entries.
+pub fn load_plan(path: &Path) -> Result<(ScanResult, Vec<String>), Box<dyn std::error::Error>> {
+    let plan = store::export::read_baseline(path)?;
+    Ok(plan_result(plan.entries))
+}
+
+/// Plan entries as a scan result to clean from, minus those whose path is
+/// gone (with a note for each). Shared by `--from-plan` and
+/// `--execute-plan`. A plan's figures may be stale or edited, and the
+/// --confirm-large guard adds them up, so every path is measured again.
+pub fn plan_result(entries: Vec<BloatEntry>) -> (ScanResult, Vec<String>) {
+    let (mut result, skipped) = snapshot_result(entries);
+    for entry in &mut result.entries {
+        remeasure(entry);
+    }
+    (result, skipped)
+}
+
+/// Snapshot entries as a scan result to clean from, minus those whose path
+/// is gone (with a note for each). For `--use-recent`, where the snapshot
+/// was just taken by a scan and its figures are trusted.
+pub fn snapshot_result(entries: Vec<BloatEntry>) -> (ScanResult, Vec<String>) {
+    let (entries, skipped) = drop_missing(entries);
+    let result = ScanResult {
+        entries,
+        ..ScanResult::empty()
+    };
+    (result, skipped)
+}
+
+/// Replace a plan entry's sizes with what its path holds now. Deleting a
+/// path frees all of it, so reclaimable follows the size unless the entry
+/// was reported-only.
+fn remeasure(entry: &mut BloatEntry) {
+    let Location::FilesystemPath(ref path) = entry.location else {
+        return;
+    };
+    if let Ok((size, _)) = crate::scan::calculate_dir_size(path) {
+        entry.size_bytes = size;
+        if entry.reclaimable_bytes > 0 {
+            entry.reclaimable_bytes = size;
+        }
+    }
+}
+
+/// The entries a dry run selected, for `--save-plan`: those of `result`
+/// with a dry-run action in `cleaned`.
+pub fn planned_entries(result: &ScanResult, cleaned: &CleanResult) -> Vec<BloatEntry> {
+    result
+        .entries
+        .iter()
+        .filter(|entry| {
+            let location = location_display(&entry.location);
+            cleaned
+                .actions
+                .iter()
+                .any(|a| a.status == ActionStatus::DryRun && a.location == location)
+        })
+        .cloned()
+        .collect()
+}
+
+fn drop_missing(entries: Vec<BloatEntry>) -> (Vec<BloatEntry>, Vec<String>) {
+    let mut skipped = Vec::new();
+    let entries = entries
+        .into_iter()
+        .filter(|entry| match &entry.location {
+            Location::FilesystemPath(path) if fs::symlink_metadata(path).is_err() => {
+                skipped.push(format!(
+                    "skipped {}: {} no longer exists",
+                    entry.name,
+                    path.display()
+                ));
+                false
+            }
+            _ => true,
+        })
+        .collect();
+    (entries, skipped)
+}
+
+/// Resolve --category / --exclude-category values: each is a category name
+/// (`package-cache`) or a glob over the names (`*cache`), and must match at
+/// least one category so typos don't silently select nothing.
+pub fn parse_category_patterns(patterns: &[String]) -> Result<Vec<BloatCategory>, String> {
+    use clap::ValueEnum;
+
+    let mut categories = Vec::new();
+    for pattern in patterns {
+        let mut matched = false;
+        for variant in CleanCategory::value_variants() {
+            let Some(value) = variant.to_possible_value() else {
+                continue;
+            };
+            if util::glob_match(pattern, value.get_name()) {
+                matched = true;
+                let category = BloatCategory::from(variant.clone());
+                if !categories.contains(&category) {
+                    categories.push(category);
+                }
+            }
+        }
+        if !matched {
+            let valid: Vec<String> = CleanCategory::value_variants()
+                .iter()
+                .filter_map(|v| v.to_possible_value())
+                .map(|v| v.get_name().to_string())
+                .collect();
+            return Err(format!(
+                "no category matches '{pattern}' (valid: {})",
+                valid.join(", ")
+            ));
+        }
+    }
+    Ok(categories)
+}
+
+/// Display order for categories, shared with the TUI.
+pub fn category_sort_order(category: &BloatCategory) -> u8 {
     match category {
         BloatCategory::ProjectArtifacts => 0,
         BloatCategory::PackageCache => 1,
@@ -356,3 +1337,706 @@ fn category_sort_order(category: &BloatCategory) -> u8 {
         BloatCategory::Other => 5,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn entry(name: &str, reclaimable: u64) -> BloatEntry {
+        BloatEntry {
+            category: BloatCategory::PackageCache,
+            name: name.to_string(),
+            location: Location::FilesystemPath(PathBuf::from(format!("/tmp/heft-test/{name}"))),
+            size_bytes: reclaimable,
+            reclaimable_bytes: reclaimable,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    fn scan_result(entries: Vec<BloatEntry>) -> ScanResult {
+        ScanResult {
+            entries,
+            ..ScanResult::empty()
+        }
+    }
+
+    #[test]
+    fn category_patterns_accept_names_and_globs() {
+        let parse = |patterns: &[&str]| {
+            parse_category_patterns(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            parse(&["package-cache"]).unwrap(),
+            [BloatCategory::PackageCache]
+        );
+        assert_eq!(
+            parse(&["*cache", "package-cache"]).unwrap(),
+            [BloatCategory::PackageCache, BloatCategory::SystemCache]
+        );
+        let err = parse(&["docker"]).unwrap_err();
+        assert!(err.starts_with("no category matches 'docker'"));
+        assert!(err.contains("container-data"));
+    }
+
+    #[test]
+    fn excluded_categories_never_selected() {
+        let mut docker = entry("images", 500);
+        docker.category = BloatCategory::ContainerData;
+        let result = scan_result(vec![entry("npm", 100), docker]);
+        let options = CleanOptions {
+            exclude_categories: vec![BloatCategory::ContainerData],
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+        assert_eq!(cleaned.deleted().len(), 1);
+        assert!(cleaned.deleted()[0].ends_with("/npm"));
+
+        // exclusion wins when both name the same category
+        let options = CleanOptions {
+            category_filter: Some(vec![BloatCategory::ContainerData]),
+            exclude_categories: vec![BloatCategory::ContainerData],
+            ..Default::default()
+        };
+        assert!(run(&result, CleanMode::DryRun, &options)
+            .deleted()
+            .is_empty());
+    }
+
+    #[test]
+    fn dry_run_without_target_lists_everything() {
+        let result = scan_result(vec![entry("a", 100), entry("b", 300), entry("c", 200)]);
+        let cleaned = run(&result, CleanMode::DryRun, &CleanOptions::default());
+        assert_eq!(cleaned.deleted().len(), 3);
+        assert_eq!(cleaned.bytes_freed, 600);
+    }
+
+    #[test]
+    fn reported_only_entries_never_selected() {
+        // a .git or VM folder: visible in the scan, nothing heft may delete
+        let result = scan_result(vec![entry("a", 100), entry(".git (app)", 0)]);
+        let cleaned = run(&result, CleanMode::DryRun, &CleanOptions::default());
+        assert_eq!(cleaned.deleted().len(), 1);
+    }
+
+    #[test]
+    fn target_stops_after_largest_entries() {
+        let result = scan_result(vec![entry("a", 100), entry("b", 300), entry("c", 200)]);
+        let options = CleanOptions {
+            target_bytes: Some(400),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+
+        // b (300) then c (200) reaches 400, a is left alone
+        assert_eq!(cleaned.deleted().len(), 2);
+        assert!(cleaned.deleted()[0].ends_with("/b"));
+        assert!(cleaned.deleted()[1].ends_with("/c"));
+        assert_eq!(cleaned.bytes_freed, 500);
+    }
+
+    #[test]
+    fn target_above_total_cleans_everything() {
+        let result = scan_result(vec![entry("a", 100), entry("zero", 0)]);
+        let options = CleanOptions {
+            target_bytes: Some(1_000),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+
+        // zero-reclaimable entries can't help reach a target
+        assert_eq!(cleaned.deleted().len(), 1);
+        assert_eq!(cleaned.bytes_freed, 100);
+    }
+
+    #[test]
+    fn path_selects_only_that_entry() {
+        let result = scan_result(vec![entry("a", 100), entry("b", 300)]);
+        let options = CleanOptions {
+            paths: Some(vec![PathBuf::from("/tmp/heft-test/b")]),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+        assert_eq!(cleaned.deleted().len(), 1);
+        assert!(cleaned.deleted()[0].ends_with("/b"));
+    }
+
+    #[test]
+    fn name_selects_exact_and_glob_matches() {
+        let result = scan_result(vec![
+            entry("npm cache", 100),
+            entry("pip cache", 200),
+            entry("web", 300),
+        ]);
+        let options = CleanOptions {
+            names: Some(vec!["*cache".to_string()]),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+        assert_eq!(cleaned.bytes_freed, 300);
+
+        let options = CleanOptions {
+            names: Some(vec!["web".to_string()]),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+        assert_eq!(cleaned.deleted().len(), 1);
+        assert_eq!(cleaned.bytes_freed, 300);
+    }
+
+    #[test]
+    fn tag_selects_entries_carrying_any_listed_tag() {
+        let mut registry = entry("cargo registry", 100);
+        registry.tags = vec!["rust".to_string(), "package-manager".to_string()];
+        let mut npm = entry("npm cache", 200);
+        npm.tags = vec!["nodejs".to_string(), "package-manager".to_string()];
+        let result = scan_result(vec![registry, npm, entry("untagged", 400)]);
+
+        let options = CleanOptions {
+            tags: vec!["Rust".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(run(&result, CleanMode::DryRun, &options).bytes_freed, 100);
+
+        let options = CleanOptions {
+            tags: vec!["package-manager".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(run(&result, CleanMode::DryRun, &options).bytes_freed, 300);
+    }
+
+    #[test]
+    fn device_backups_cleaned_only_by_tag_or_path() {
+        let mut backup = entry("iOS backup (old iPad)", 900);
+        backup.tags = vec![mobilesync::BACKUP_TAG.to_string()];
+        let result = scan_result(vec![backup, entry("npm cache", 100)]);
+
+        let cleaned = run(&result, CleanMode::DryRun, &CleanOptions::default());
+        assert_eq!(cleaned.bytes_freed, 100);
+
+        let options = CleanOptions {
+            tags: vec!["ios-backup".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(run(&result, CleanMode::DryRun, &options).bytes_freed, 900);
+
+        let options = CleanOptions {
+            paths: Some(vec![PathBuf::from("/tmp/heft-test/iOS backup (old iPad)")]),
+            ..Default::default()
+        };
+        assert_eq!(run(&result, CleanMode::DryRun, &options).bytes_freed, 900);
+    }
+
+    #[test]
+    fn unpinned_runtimes_cleaned_only_by_tag() {
+        let mut runtime = entry("node v16.20.0 (nvm)", 700);
+        runtime.tags = vec!["node".to_string(), toolchains::TOOLCHAIN_TAG.to_string()];
+        let result = scan_result(vec![runtime, entry("npm cache", 100)]);
+
+        let options = CleanOptions {
+            category_filter: Some(vec![BloatCategory::PackageCache]),
+            ..Default::default()
+        };
+        assert_eq!(run(&result, CleanMode::DryRun, &options).bytes_freed, 100);
+
+        let options = CleanOptions {
+            tags: vec!["toolchain".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(run(&result, CleanMode::DryRun, &options).bytes_freed, 700);
+    }
+
+    #[test]
+    fn never_delete_paths_skipped_whatever_selects_them() {
+        let result = scan_result(vec![
+            entry("maven repo", 500),
+            entry("npm cache", 100),
+            entry("gradle cache", 200),
+        ]);
+        let options = CleanOptions {
+            names: Some(vec!["*".to_string()]),
+            never_delete: vec![
+                "/tmp/heft-test/maven repo".to_string(),
+                "/tmp/heft-test/gradle*".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+
+        assert_eq!(cleaned.bytes_freed, 100);
+        assert_eq!(
+            cleaned.protected,
+            ["/tmp/heft-test/maven repo", "/tmp/heft-test/gradle cache"]
+        );
+        // the deletion itself refuses too, and so does anything holding a
+        // protected path
+        let never = &options.never_delete;
+        assert!(
+            validate_deletion_path(Path::new("/tmp/heft-test/maven repo/x"), never)
+                .unwrap_err()
+                .contains("clean.never_delete")
+        );
+        assert!(validate_deletion_path(Path::new("/tmp/heft-test"), never).is_err());
+        assert!(validate_deletion_path(Path::new("/tmp/heft-test/npm cache"), never).is_ok());
+    }
+
+    #[test]
+    fn min_age_keeps_recent_entries_only_when_dated() {
+        let mut recent = entry("recent", 100);
+        recent.last_modified = Some(2_000);
+        let mut old = entry("old", 200);
+        old.last_modified = Some(500);
+        let undated = entry("cache", 400);
+
+        let result = scan_result(vec![recent, old, undated]);
+        let options = CleanOptions {
+            modified_before: Some(1_000),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+        assert_eq!(cleaned.deleted().len(), 2);
+        assert_eq!(cleaned.bytes_freed, 600);
+        assert!(!cleaned.deleted().iter().any(|d| d.ends_with("/recent")));
+    }
+
+    #[test]
+    fn use_hints_runs_commands_and_deletes_the_rest() {
+        let mut npm = entry("npm cache", 100);
+        npm.cleanup_hint = Some("npm cache clean --force".to_string());
+        let mut gradle = entry("gradle cache", 200);
+        gradle.cleanup_hint = Some("safe to delete, rebuilt on next gradle build".to_string());
+
+        let result = scan_result(vec![npm.clone(), gradle]);
+        let options = CleanOptions {
+            use_hints: true,
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+        assert_eq!(
+            cleaned.deleted(),
+            [
+                "[dry-run] would run: npm cache clean --force (for /tmp/heft-test/npm cache)",
+                "[dry-run] would delete: /tmp/heft-test/gradle cache",
+            ]
+        );
+        assert_eq!(cleaned.actions[0].action, ActionKind::Command);
+        assert_eq!(cleaned.actions[1].action, ActionKind::Delete);
+
+        // without the flag the hint is only advice
+        let cleaned = run(
+            &scan_result(vec![npm]),
+            CleanMode::DryRun,
+            &CleanOptions::default(),
+        );
+        assert!(cleaned.deleted()[0].starts_with("[dry-run] would delete:"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hint_commands_credit_what_they_actually_freed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("pip");
+        std::fs::create_dir_all(cache.join("http")).unwrap();
+        std::fs::create_dir_all(cache.join("wheels")).unwrap();
+        std::fs::write(cache.join("http/a"), [0u8; 300]).unwrap();
+        std::fs::write(cache.join("wheels/b"), [0u8; 200]).unwrap();
+
+        // stands in for a tool that clears only part of its cache
+        let argv: Vec<String> = ["rm", "-r"]
+            .iter()
+            .map(|s| s.to_string())
+            .chain([cache.join("http").display().to_string()])
+            .collect();
+        let (message, freed) = run_hint_command(&argv, &cache).unwrap();
+        assert!(message.starts_with("ran: rm -r"));
+        assert_eq!(freed, 300);
+        assert!(cache.join("wheels/b").exists());
+    }
+
+    #[test]
+    fn missing_cleanup_tool_is_an_error() {
+        let argv = vec!["heft-no-such-tool".to_string(), "clean".to_string()];
+        assert_eq!(
+            run_cleanup_command(&argv).unwrap_err(),
+            "'heft-no-such-tool clean' failed: heft-no-such-tool not found on PATH"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_home_allows_paths_through_either_form() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("data/home/me");
+        std::fs::create_dir_all(real.join("project/target")).unwrap();
+        let link = dir.path().join("home-me");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        let real = std::fs::canonicalize(&real).unwrap();
+
+        for path in [link.join("project/target"), real.join("project/target")] {
+            assert!(check_deletion_path(&path, Some(&link)).is_ok(), "{path:?}");
+        }
+        for path in [&link, &real] {
+            let err = check_deletion_path(path, Some(&link)).unwrap_err();
+            assert!(err.contains("refusing to delete home directory"), "{err}");
+        }
+        assert!(check_deletion_path(Path::new("/etc/passwd"), Some(&link)).is_err());
+        let escape = link.join("../../../srv/x");
+        let err = check_deletion_path(&escape, Some(&link)).unwrap_err();
+        assert!(err.contains("with '..'"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn empty_parents_pruned_up_to_the_scan_root() {
+        let root = tempfile::tempdir().unwrap();
+        let root = std::fs::canonicalize(root.path()).unwrap();
+        let roots = [root.clone()];
+        let deleted = root.join("work/old/app/node_modules");
+        std::fs::create_dir_all(&deleted).unwrap();
+        std::fs::write(root.join("work/notes.txt"), "keep").unwrap();
+        std::fs::remove_dir(&deleted).unwrap();
+
+        assert_eq!(prune_empty_parents(&deleted, &roots, &[]), 2);
+        assert!(!root.join("work/old").exists());
+        assert!(root.join("work/notes.txt").exists());
+
+        // the root itself is never removed, even when it ends up empty
+        let lone = root.join("lone/target");
+        std::fs::create_dir_all(&lone).unwrap();
+        std::fs::remove_dir(&lone).unwrap();
+        std::fs::remove_dir_all(root.join("work")).unwrap();
+        assert_eq!(prune_empty_parents(&lone, &roots, &[]), 1);
+        assert!(root.is_dir());
+
+        // outside every root nothing is touched
+        let other = root.join("other/target");
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::remove_dir(&other).unwrap();
+        assert_eq!(
+            prune_empty_parents(&other, &[root.join("elsewhere")], &[]),
+            0
+        );
+        assert!(root.join("other").is_dir());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn remove_tree_counts_files_and_leaves_link_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("keep"), "x").unwrap();
+        let tree = dir.path().join("node_modules");
+        for i in 0..PROGRESS_EVERY_FILES + 5 {
+            let pkg = tree.join(format!("pkg{}", i % 3)).join("lib");
+            std::fs::create_dir_all(&pkg).unwrap();
+            std::fs::write(pkg.join(format!("f{i}")), "x").unwrap();
+        }
+        std::os::unix::fs::symlink(&outside, tree.join("link")).unwrap();
+
+        let mut updates = Vec::new();
+        let removed = remove_tree(&tree, &mut |files| updates.push(files)).unwrap();
+
+        assert_eq!(removed, PROGRESS_EVERY_FILES + 6);
+        assert_eq!(updates, [PROGRESS_EVERY_FILES]);
+        assert!(!tree.exists());
+        assert!(outside.join("keep").exists());
+    }
+
+    #[test]
+    fn verify_tolerance_bounds() {
+        const MB: u64 = 1024 * 1024;
+        // within 2x either way
+        assert!(size_within_tolerance(100 * MB, 180 * MB, 2.0));
+        assert!(size_within_tolerance(100 * MB, 60 * MB, 2.0));
+        // grew 10x
+        assert!(!size_within_tolerance(100 * MB, 1000 * MB, 2.0));
+        // shrank to almost nothing
+        assert!(!size_within_tolerance(100 * MB, MB, 2.0));
+        // tiny directories get slack
+        assert!(size_within_tolerance(1024, 500 * 1024, 2.0));
+    }
+
+    #[test]
+    fn verify_skips_directory_that_grew() {
+        let temp = tempfile::Builder::new()
+            .prefix("heft-verify")
+            .tempdir_in("/tmp")
+            .unwrap();
+        let path = temp.path().join("build");
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("big"), vec![0u8; 3 * 1024 * 1024]).unwrap();
+
+        let mut scanned = entry("build", 10);
+        scanned.location = Location::FilesystemPath(path.clone());
+        let options = CleanOptions {
+            verify_tolerance: Some(2.0),
+            ..Default::default()
+        };
+        let cleaned = run(&scan_result(vec![scanned]), CleanMode::Execute, &options);
+
+        assert!(cleaned.deleted().is_empty());
+        assert!(cleaned.errors()[0].contains("since the scan (--verify)"));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn verify_skips_directory_whose_file_count_changed() {
+        let temp = tempfile::Builder::new()
+            .prefix("heft-verify")
+            .tempdir_in("/tmp")
+            .unwrap();
+        let path = temp.path().join("build");
+        std::fs::create_dir(&path).unwrap();
+        for i in 0..300 {
+            std::fs::write(path.join(i.to_string()), "x").unwrap();
+        }
+
+        // same size as scanned, but a tenth of the files
+        let mut scanned = entry("build", 300);
+        scanned.location = Location::FilesystemPath(path.clone());
+        scanned.file_count = Some(30);
+        let options = CleanOptions {
+            verify_tolerance: Some(2.0),
+            ..Default::default()
+        };
+        let cleaned = run(&scan_result(vec![scanned]), CleanMode::Execute, &options);
+
+        assert!(cleaned.deleted().is_empty());
+        assert!(cleaned.errors()[0].contains("file count changed from 30 to 300"));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn json_lists_actions_with_summary() {
+        let result = scan_result(vec![entry("a", 100), entry("b", 300)]);
+        let cleaned = run(&result, CleanMode::DryRun, &CleanOptions::default());
+        let json: serde_json::Value = serde_json::from_str(&cleaned.to_json()).unwrap();
+
+        assert_eq!(json["actions"][0]["action"], "delete");
+        assert_eq!(json["actions"][0]["location"], "/tmp/heft-test/a");
+        assert_eq!(json["actions"][0]["category"], "PackageCache");
+        assert_eq!(json["actions"][0]["bytes_freed"], 100);
+        assert_eq!(json["actions"][0]["status"], "dry-run");
+        assert_eq!(json["summary"]["total_freed"], 400);
+        assert_eq!(json["summary"]["items_deleted"], 2);
+        assert_eq!(json["summary"]["errors_count"], 0);
+        assert!(json["summary"].get("refused").is_none());
+    }
+
+    #[test]
+    fn large_batch_refused_without_confirmation() {
+        let temp = tempfile::Builder::new()
+            .prefix("heft-large")
+            .tempdir_in("/tmp")
+            .unwrap();
+        let path = temp.path().join("cache");
+        std::fs::create_dir(&path).unwrap();
+        let mut big = entry("cache", 600);
+        big.location = Location::FilesystemPath(path.clone());
+
+        let options = CleanOptions {
+            large_deletion: Some(LargeDeletion {
+                bytes: 500,
+                items: 10,
+            }),
+            ..Default::default()
+        };
+        let cleaned = run(&scan_result(vec![big]), CleanMode::Execute, &options);
+
+        assert!(cleaned.deleted().is_empty());
+        assert!(cleaned
+            .refused
+            .as_deref()
+            .unwrap()
+            .starts_with("refusing to delete 600 B across 1 items"));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn large_limit_counts_items_and_respects_target() {
+        let limit = LargeDeletion {
+            bytes: 1_000,
+            items: 2,
+        };
+        let entries = [entry("a", 300), entry("b", 200), entry("c", 100)];
+        let refs: Vec<&BloatEntry> = entries.iter().collect();
+
+        // three items is one too many
+        assert!(limit.exceeded_by(&refs, None).is_some());
+        // but a target reached after two only plans two
+        assert!(limit.exceeded_by(&refs, Some(400)).is_none());
+        assert!(LargeDeletion::default().exceeded_by(&refs, None).is_none());
+    }
+
+    #[test]
+    fn docker_dry_run_uses_live_df_figure() {
+        let live = Ok(HashMap::from([("Images".to_string(), 4096)]));
+        let (line, bytes) = dry_run_docker_aggregate("Images", 1024, &live);
+        assert_eq!(bytes, 4096);
+        assert!(line.contains("4.0 KB reclaimable per docker system df now"));
+
+        // type gone from df output means nothing is left to reclaim
+        let (_, bytes) = dry_run_docker_aggregate("Build Cache", 1024, &live);
+        assert_eq!(bytes, 0);
+
+        // but df never reports build cache age buckets
+        let (line, bytes) = dry_run_docker_aggregate("Build Cache (unused 30d+)", 1024, &live);
+        assert_eq!(bytes, 1024);
+        assert!(line.contains("from scan"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn deleting_a_cache_keeps_the_entries_carved_out_of_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = dir.path().join("registry");
+        for sub in ["index/crates", "cache/github", "src/github/serde"] {
+            fs::create_dir_all(registry.join(sub)).unwrap();
+            fs::write(registry.join(sub).join("f"), [0u8; 100]).unwrap();
+        }
+        let mut outer = entry("cargo registry", 100);
+        outer.location = Location::FilesystemPath(registry.clone());
+        let mut index = entry("cargo registry index", 100);
+        index.location = Location::FilesystemPath(registry.join("index"));
+        let mut sources = entry("cargo registry sources", 100);
+        sources.location = Location::FilesystemPath(registry.join("src/github"));
+        let result = scan_result(vec![outer, index, sources]);
+
+        let options = CleanOptions {
+            names: Some(vec!["cargo registry".to_string()]),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::Execute, &options);
+
+        assert_eq!(cleaned.bytes_freed, 100);
+        assert!(cleaned.deleted()[0].ends_with("(kept 2 nested entries)"));
+        assert!(!registry.join("cache").exists());
+        assert!(registry.join("index/crates/f").exists());
+        assert!(registry.join("src/github/serde/f").exists());
+    }
+
+    #[test]
+    fn paths_gone_since_the_scan_free_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut wheels = entry("pip wheel cache", 300);
+        wheels.location = Location::FilesystemPath(dir.path().join("pip/wheels"));
+        let result = scan_result(vec![wheels]);
+
+        let cleaned = run(&result, CleanMode::Execute, &CleanOptions::default());
+
+        assert_eq!(cleaned.bytes_freed, 0);
+        assert!(cleaned.deleted()[0].starts_with("already removed:"));
+    }
+
+    #[test]
+    fn build_cache_age_buckets_are_cleanable() {
+        let mut old = entry("docker build cache, unused 30d+", 500);
+        old.location = Location::Aggregate("Build Cache (unused 30d+)".to_string());
+        let mut recent = entry("docker build cache, used in last 7d", 0);
+        recent.location = Location::Aggregate("Build Cache (used in last 7d)".to_string());
+        let result = scan_result(vec![old, recent]);
+
+        let cleaned = run(&result, CleanMode::DryRun, &CleanOptions::default());
+        assert_eq!(cleaned.bytes_freed, 500);
+        assert!(is_docker_aggregate("Build Cache (unused 7-30d)"));
+        assert!(!is_docker_aggregate("Build Cache (used in last 7d)"));
+    }
+
+    #[test]
+    fn docker_dry_run_falls_back_to_scan_figure() {
+        let live = Err("docker: daemon not running".to_string());
+        let (line, bytes) = dry_run_docker_aggregate("Images", 1024, &live);
+        assert_eq!(bytes, 1024);
+        assert!(line.contains("from scan; docker: daemon not running"));
+    }
+
+    #[test]
+    fn interrupt_stops_before_next_deletion() {
+        let temp = tempfile::Builder::new()
+            .prefix("heft-clean")
+            .tempdir_in("/tmp")
+            .unwrap();
+        let path = temp.path().join("cache");
+        std::fs::create_dir(&path).unwrap();
+
+        let mut target = entry("cache", 10);
+        target.location = Location::FilesystemPath(path.clone());
+        let result = scan_result(vec![target]);
+
+        let options = CleanOptions {
+            interrupt: Some(Arc::new(AtomicBool::new(true))),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::Execute, &options);
+
+        assert!(cleaned.interrupted);
+        assert!(cleaned.deleted().is_empty());
+        assert_eq!(cleaned.bytes_freed, 0);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn unset_interrupt_flag_does_not_stop() {
+        let result = scan_result(vec![entry("a", 100)]);
+        let options = CleanOptions {
+            interrupt: Some(Arc::new(AtomicBool::new(false))),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+        assert!(!cleaned.interrupted);
+        assert_eq!(cleaned.deleted().len(), 1);
+    }
+
+    #[test]
+    fn plan_entries_that_are_gone_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("target");
+        fs::create_dir(&kept).unwrap();
+        fs::write(kept.join("lib.rlib"), vec![0u8; 4096]).unwrap();
+        // an edited plan can't shrink an entry past the --confirm-large guard
+        let mut present = entry("target", 1);
+        present.location = Location::FilesystemPath(kept);
+        let mut docker = entry("docker images", 100);
+        docker.location = Location::Aggregate("Images".to_string());
+
+        let plan = serde_json::json!({
+            "status": "found",
+            "entries": [present, entry("vanished", 50), docker],
+        });
+        let plan_path = dir.path().join("plan.json");
+        fs::write(&plan_path, plan.to_string()).unwrap();
+
+        let (result, skipped) = load_plan(&plan_path).unwrap();
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["target", "docker images"]);
+        assert!(result.entries[0].reclaimable_bytes >= 4096);
+        assert_eq!(result.entries[1].reclaimable_bytes, 100);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("skipped vanished:"));
+    }
+
+    #[test]
+    fn saved_plan_holds_what_the_dry_run_selected() {
+        let result = scan_result(vec![
+            entry("npm cache", 100),
+            entry("pip cache", 200),
+            entry("web", 300),
+        ]);
+        let options = CleanOptions {
+            names: Some(vec!["*cache".to_string()]),
+            ..Default::default()
+        };
+        let cleaned = run(&result, CleanMode::DryRun, &options);
+
+        let planned = planned_entries(&result, &cleaned);
+        let names: Vec<_> = planned.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["npm cache", "pip cache"]);
+    }
+}
@@ -1,6 +1,9 @@
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
+use crate::report::{GroupBy, OutputFormat};
+use crate::scan::detector::BloatCategory;
+
 #[derive(Parser)]
 #[command(name = "heft")]
 #[command(about = "A disk space auditor for developers")]
@@ -8,6 +11,20 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Command,
+
+    /// Size units: binary (1024-based, default), si (1000-based, like macOS Finder) or bytes
+    #[arg(long, global = true, value_enum)]
+    pub units: Option<crate::util::Units>,
+
+    /// Touch nothing: no snapshot saved, no database created, clean refused
+    /// (also HEFT_READ_ONLY=1)
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// Plain ASCII in human-readable output: no dashes, arrows or spinner
+    /// glyphs (automatic when stdout is not a terminal or NO_COLOR is set)
+    #[arg(long, global = true)]
+    pub ascii: bool,
 }
 
 #[derive(Subcommand)]
@@ -23,37 +40,119 @@ pub enum Command {
 
     /// Compare two snapshots
     Diff(DiffArgs),
+
+    /// Manage saved snapshots
+    Snapshot(SnapshotArgs),
+
+    /// Maintain the snapshot database
+    Db(DbArgs),
+
+    /// Quickly count candidate artifacts and files, to gauge how long a scan will take
+    Estimate(EstimateArgs),
+
+    /// Break one directory down by its largest subdirectories and files
+    Inspect(InspectArgs),
+
+    /// Summarize reclaimable space across the snapshots of a time window
+    Stats(StatsArgs),
+
+    /// Show where heft looks for its config and keeps its database, and what it scans
+    Paths(PathsArgs),
 }
 
-#[derive(Parser)]
+#[derive(Parser, Default)]
 pub struct ScanArgs {
-    /// Directories to scan (defaults to home directory)
+    /// Directories to scan (defaults to $HEFT_ROOTS, then config, then home directory)
     #[arg(long, value_delimiter = ',')]
     pub roots: Option<Vec<PathBuf>>,
 
-    /// Output as JSON instead of table
+    /// Read newline-separated scan roots from a file ('-' for stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "roots")]
+    pub roots_from: Option<PathBuf>,
+
+    /// Scan a system directory such as / or /usr anyway (normally refused)
+    #[arg(long, default_value_t = false)]
+    pub allow_system_root: bool,
+
+    /// Only check directories matching these globs for artifacts (e.g. '**/node_modules')
+    #[arg(long, value_delimiter = ',', value_name = "GLOB")]
+    pub include: Option<Vec<String>>,
+
+    /// Skip directories matching these globs entirely; wins over --include
+    #[arg(long, value_delimiter = ',', value_name = "GLOB")]
+    pub exclude: Option<Vec<String>>,
+
+    /// Walk into other mounted filesystems (NFS/SMB shares, bind mounts) below the roots
     #[arg(long, default_value_t = false)]
+    pub cross_filesystems: bool,
+
+    /// Only list where artifacts and caches are, without walking them for sizes (much
+    /// faster on network mounts); sizes show as 0 and no snapshot is saved
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["remote", "baseline", "diff", "tui", "goal", "score"]
+    )]
+    pub no_sizes: bool,
+
+    /// Skip caches and project artifacts smaller than SIZE (default 1MB, or scan.min_entry_size)
+    #[arg(long, value_name = "SIZE", value_parser = crate::util::parse_size)]
+    pub min_size: Option<u64>,
+
+    /// Report caches and artifacts of any size, like --min-size 0
+    #[arg(long, default_value_t = false, conflicts_with = "min_size")]
+    pub show_all: bool,
+
+    /// Also report items in ~/Downloads untouched for --downloads-age days (clean needs --category other)
+    #[arg(long, default_value_t = false)]
+    pub detect_downloads: bool,
+
+    /// Days a ~/Downloads item must be untouched to be reported (default 90)
+    #[arg(long, value_name = "DAYS")]
+    pub downloads_age: Option<u64>,
+
+    /// Output format (overrides `format` / `json` in the config file)
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<OutputFormat>,
+
+    /// Deprecated: same as --format json
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "format",
+        hide_short_help = true
+    )]
     pub json: bool,
 
-    /// Disable JSON output (overrides config file)
-    #[arg(long, conflicts_with = "json", hide_short_help = true)]
+    /// Print the table even if the config file picks another format
+    #[arg(long, conflicts_with_all = ["json", "format"], hide_short_help = true)]
     pub no_json: bool,
 
     /// Skip the Docker detector (shorthand for --disable docker)
     #[arg(long, default_value_t = false)]
     pub no_docker: bool,
 
-    /// Disable specific detectors (comma-separated: docker,xcode,projects,caches)
-    #[arg(long, value_delimiter = ',')]
+    /// Disable specific detectors (comma-separated: docker,xcode,projects,caches,database,system,downloads)
+    #[arg(long, value_delimiter = ',', value_parser = crate::util::parse_detector)]
     pub disable: Option<Vec<String>>,
 
+    /// Run only these detectors, disabling all others (comma-separated)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["disable", "no_docker"],
+        value_parser = crate::util::parse_detector
+    )]
+    pub only: Option<Vec<String>>,
+
     /// Per-detector timeout in seconds
     #[arg(long)]
     pub timeout: Option<u64>,
 
-    /// Show detailed output including diagnostics
-    #[arg(long, short = 'v', default_value_t = false)]
-    pub verbose: bool,
+    /// Show detailed output: -v diagnostics and timings, -vv adds per-detector
+    /// memory and explain traces, -vvv logs what the scan visits
+    #[arg(long, short = 'v', action = clap::ArgAction::Count)]
+    pub verbose: u8,
 
     /// Disable verbose output (overrides config file)
     #[arg(long, conflicts_with = "verbose", hide_short_help = true)]
@@ -66,6 +165,112 @@ pub struct ScanArgs {
     /// Disable progressive output (overrides config file)
     #[arg(long, conflicts_with = "progressive", hide_short_help = true)]
     pub no_progressive: bool,
+
+    /// Record why each candidate artifact directory was accepted or rejected (implies --verbose)
+    #[arg(long, default_value_t = false)]
+    pub explain: bool,
+
+    /// List each entry's location in the table, shortened to ~/... or relative to its root
+    #[arg(long, default_value_t = false)]
+    pub relative: bool,
+
+    /// Show each entry's cleanup hint, the manual command to reclaim it (always on with --verbose)
+    #[arg(long, default_value_t = false)]
+    pub hints: bool,
+
+    /// Print only the filesystem paths of the entries found, one per line, for
+    /// xargs or fzf. Nothing else goes to stdout
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["json", "format", "compact", "tui", "diff", "baseline", "summary", "goal"]
+    )]
+    pub list_paths: bool,
+
+    /// With --list-paths, list only what a plain `heft clean` would delete: no reported-only
+    /// entries, heft's own database, opt-in backups and runtimes, or clean.never_delete paths
+    #[arg(
+        long,
+        default_value_t = false,
+        requires = "list_paths",
+        conflicts_with = "no_sizes"
+    )]
+    pub reclaimable_only: bool,
+
+    /// With --list-paths, end each path with a NUL byte instead of a newline (xargs -0)
+    #[arg(long, default_value_t = false, requires = "list_paths")]
+    pub print0: bool,
+
+    /// Exit with this code when the scan finds nothing (for scripts; default 0)
+    #[arg(long, value_name = "CODE")]
+    pub empty_exit_code: Option<i32>,
+
+    /// Also total the space only a manual step can free (WSL and Docker Desktop disks,
+    /// the hibernation file), below the automatic total
+    #[arg(long, default_value_t = false)]
+    pub include_manual: bool,
+
+    /// List the N biggest entries across all categories above the table (default 10)
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "10"
+    )]
+    pub summary: Option<usize>,
+
+    /// Show the reclaimable total as progress toward freeing SIZE (e.g. 20GB)
+    #[arg(long, value_name = "SIZE", value_parser = crate::util::parse_size)]
+    pub goal: Option<u64>,
+
+    /// Group and subtotal the table by category, the detector that found each
+    /// entry, scan root, age, or filesystem
+    #[arg(long, value_enum, default_value_t = GroupBy::Category)]
+    pub group_by: GroupBy,
+
+    /// Add a reclaim_score per entry to JSON / JSONL output (higher = bigger and older)
+    #[arg(long, default_value_t = false)]
+    pub score: bool,
+
+    /// Add a `by_category` object to JSON output: per-category totals and
+    /// entries, grouped like the table (the flat `entries` list stays).
+    /// Implies --json
+    #[arg(long, default_value_t = false, conflicts_with_all = ["compact", "no_json", "tui"])]
+    pub json_grouped: bool,
+
+    /// Same as --format compact: one line per entry, `size<TAB>category<TAB>name`, largest first
+    #[arg(long, default_value_t = false, conflicts_with_all = ["json", "format", "tui"])]
+    pub compact: bool,
+
+    /// Break docker build cache down by last use (needs buildx), instead of one total
+    #[arg(long, default_value_t = false)]
+    pub build_cache_ages: bool,
+
+    /// Scan a server over SFTP instead, as [user@]host[:port]:path (needs the `remote` feature).
+    /// Only projects and caches run; the result is shown but not saved
+    #[arg(long, value_name = "TARGET", conflicts_with_all = ["tui", "diff"])]
+    pub remote: Option<String>,
+
+    /// Browse results in a full-screen UI and delete from there (needs the `tui` feature)
+    #[arg(long, default_value_t = false, conflicts_with_all = ["json", "format"])]
+    pub tui: bool,
+
+    /// Also show what changed since the latest saved snapshot, before saving this one
+    #[arg(long, default_value_t = false, conflicts_with_all = ["json", "format", "tui"])]
+    pub diff: bool,
+
+    /// Also compare against a baseline file instead of the snapshot history: the
+    /// output of `heft scan --format json` or a snapshot export (.json, .gz, .zst)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["json", "format", "tui", "diff", "remote"])]
+    pub baseline: Option<PathBuf>,
+
+    /// With --baseline: only count an entry as grown when it gained more than SIZE (default 0)
+    #[arg(long, value_name = "SIZE", requires = "baseline", value_parser = crate::util::parse_size)]
+    pub growth_tolerance: Option<u64>,
+
+    /// With --baseline: exit 1 when any entry grew or appeared beyond the tolerance
+    #[arg(long, default_value_t = false, requires = "baseline")]
+    pub fail_on_growth: bool,
 }
 
 #[derive(Parser)]
@@ -78,8 +283,43 @@ pub struct ReportArgs {
     #[arg(long)]
     pub id: Option<String>,
 
-    /// Output as JSON
-    #[arg(long, default_value_t = false)]
+    /// With --list, only snapshots taken at or after this time (YYYY-MM-DD or an age like 7d, 2w)
+    #[arg(long, value_name = "WHEN", requires = "list", value_parser = crate::util::parse_since)]
+    pub since: Option<i64>,
+
+    /// With --list, only snapshots taken before this time (a YYYY-MM-DD date includes that whole day)
+    #[arg(long, value_name = "WHEN", requires = "list", value_parser = crate::util::parse_until)]
+    pub until: Option<i64>,
+
+    /// With --list, show at most N snapshots (newest first)
+    #[arg(long, value_name = "N", requires = "list")]
+    pub limit: Option<usize>,
+
+    /// With --id, the output format (default: table)
+    #[arg(long, value_enum, value_name = "FORMAT", requires = "id")]
+    pub format: Option<OutputFormat>,
+
+    /// Only show entries with one of these tags, e.g. rust, docker (repeatable or comma-separated)
+    #[arg(
+        long,
+        value_name = "TAG",
+        value_delimiter = ',',
+        conflicts_with = "list"
+    )]
+    pub tag: Vec<String>,
+
+    /// Group and subtotal the table by category, the detector that found each
+    /// entry, age, or filesystem (snapshots don't record scan roots)
+    #[arg(long, value_enum, default_value_t = GroupBy::Category, conflicts_with = "list")]
+    pub group_by: GroupBy,
+
+    /// Deprecated: same as --format json
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "format",
+        hide_short_help = true
+    )]
     pub json: bool,
 }
 
@@ -99,8 +339,25 @@ pub enum CleanCategory {
     Other,
 }
 
+impl From<CleanCategory> for BloatCategory {
+    fn from(category: CleanCategory) -> Self {
+        match category {
+            CleanCategory::ProjectArtifacts => BloatCategory::ProjectArtifacts,
+            CleanCategory::ContainerData => BloatCategory::ContainerData,
+            CleanCategory::PackageCache => BloatCategory::PackageCache,
+            CleanCategory::IdeData => BloatCategory::IdeData,
+            CleanCategory::SystemCache => BloatCategory::SystemCache,
+            CleanCategory::Other => BloatCategory::Other,
+        }
+    }
+}
+
 #[derive(Parser)]
 pub struct CleanArgs {
+    /// Exit with this code when the scan finds nothing to clean (default 0)
+    #[arg(long, value_name = "CODE")]
+    pub empty_exit_code: Option<i32>,
+
     /// Skip confirmation and execute deletion (conflicts with --dry-run)
     #[arg(long, default_value_t = false, conflicts_with = "dry_run")]
     pub yes: bool,
@@ -109,29 +366,165 @@ pub struct CleanArgs {
     #[arg(long, default_value_t = false)]
     pub dry_run: bool,
 
-    /// Only clean specific categories
-    #[arg(long, value_delimiter = ',')]
-    pub category: Option<Vec<CleanCategory>>,
+    /// Only clean these categories: project-artifacts, container-data,
+    /// package-cache, ide-data, system-cache, other. Globs work ('*cache')
+    #[arg(long, value_delimiter = ',', value_name = "CATEGORY")]
+    pub category: Option<Vec<String>>,
+
+    /// Clean everything except these categories (names or globs, as --category)
+    #[arg(long, value_delimiter = ',', value_name = "CATEGORY")]
+    pub exclude_category: Option<Vec<String>>,
+
+    /// Only clean the scanned entry at exactly this path (repeatable)
+    #[arg(long, value_name = "PATH")]
+    pub path: Option<Vec<PathBuf>>,
+
+    /// Only clean entries whose name matches (exact or glob, repeatable)
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<Vec<String>>,
+
+    /// Only clean entries with one of these tags, e.g. rust, docker, package-manager
+    /// (repeatable or comma-separated; `scan --format json` lists each entry's tags)
+    #[arg(long, value_name = "TAG", value_delimiter = ',')]
+    pub tag: Vec<String>,
+
+    /// Re-measure each directory before deleting and skip it if its size or file count
+    /// changed by more than FACTOR either way since the scan (default 2)
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        num_args = 0..=1,
+        default_missing_value = "2",
+        value_parser = crate::util::parse_tolerance
+    )]
+    pub verify: Option<f64>,
+
+    /// Keep anything modified within the last DAYS days (caches and docker have no age and stay eligible)
+    #[arg(long, value_name = "DAYS")]
+    pub min_age: Option<u32>,
+
+    /// Run an entry's own cleanup command (npm cache clean, go clean -modcache, ...) instead of deleting it
+    #[arg(long)]
+    pub use_hints: bool,
+
+    /// After each delete, also remove parent directories it left empty, up to (not including) the scan root
+    #[arg(long, default_value_t = false)]
+    pub prune_empty_parents: bool,
+
+    /// Print what was done (or would be, with --dry-run) as JSON; needs --yes or --dry-run
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+
+    /// Let --yes delete more than clean.large_threshold (50 GB) or clean.large_items (1000) at once
+    #[arg(long, default_value_t = false)]
+    pub confirm_large: bool,
+
+    /// Clean exactly the entries in FILE (`heft scan --format json` output, trimmed by
+    /// hand) instead of scanning; entries whose path is gone are skipped, the rest re-measured
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["roots", "roots_from"])]
+    pub from_plan: Option<PathBuf>,
+
+    /// Reuse the latest snapshot instead of scanning when it is at most SECS old
+    /// (default 60); paths deleted since are skipped
+    #[arg(
+        long,
+        value_name = "SECS",
+        num_args = 0..=1,
+        default_missing_value = "60",
+        conflicts_with_all = [
+            "from_plan", "execute_plan", "roots", "roots_from", "include", "exclude",
+            "only", "disable", "no_docker", "cross_filesystems", "detect_downloads"
+        ]
+    )]
+    pub use_recent: Option<u64>,
+
+    /// Always scan, even when clean.use_recent in the config would reuse a snapshot
+    #[arg(long, default_value_t = false, conflicts_with = "use_recent")]
+    pub fresh: bool,
+
+    /// With --dry-run, also save the selection to the database as a pending plan for
+    /// someone else to review (--list-plans) and approve (--execute-plan)
+    #[arg(long, default_value_t = false, requires = "dry_run")]
+    pub save_plan: bool,
+
+    /// List plans saved with --save-plan, pending and executed, and exit
+    #[arg(long, default_value_t = false, conflicts_with_all = ["save_plan", "execute_plan", "from_plan"])]
+    pub list_plans: bool,
 
-    /// Directories to scan (defaults to home directory)
+    /// Clean the entries of a plan saved with --save-plan instead of scanning; paths
+    /// that are gone are skipped and the rest re-measured and re-checked before deletion
+    #[arg(
+        long,
+        value_name = "ID",
+        conflicts_with_all = ["from_plan", "save_plan", "roots", "roots_from"]
+    )]
+    pub execute_plan: Option<i64>,
+
+    /// Show a running bar toward freeing SIZE as entries are deleted (never stops early; see --target)
+    #[arg(long, value_name = "SIZE", value_parser = crate::util::parse_size)]
+    pub goal: Option<u64>,
+
+    /// Stop once this much has been freed, largest entries first (e.g. 20GB)
+    #[arg(long, value_name = "SIZE", value_parser = crate::util::parse_size)]
+    pub target: Option<u64>,
+
+    /// Directories to scan (defaults to $HEFT_ROOTS, then config, then home directory)
     #[arg(long, value_delimiter = ',')]
     pub roots: Option<Vec<PathBuf>>,
 
+    /// Read newline-separated scan roots from a file ('-' for stdin)
+    #[arg(long, value_name = "FILE", conflicts_with = "roots")]
+    pub roots_from: Option<PathBuf>,
+
+    /// Scan a system directory such as / or /usr anyway (normally refused)
+    #[arg(long, default_value_t = false)]
+    pub allow_system_root: bool,
+
+    /// Only check directories matching these globs for artifacts (e.g. '**/node_modules')
+    #[arg(long, value_delimiter = ',', value_name = "GLOB")]
+    pub include: Option<Vec<String>>,
+
+    /// Skip directories matching these globs entirely; wins over --include
+    #[arg(long, value_delimiter = ',', value_name = "GLOB")]
+    pub exclude: Option<Vec<String>>,
+
+    /// Walk into other mounted filesystems (NFS/SMB shares, bind mounts) below the roots
+    #[arg(long, default_value_t = false)]
+    pub cross_filesystems: bool,
+
+    /// Also report items in ~/Downloads untouched for --downloads-age days (clean needs --category other)
+    #[arg(long, default_value_t = false)]
+    pub detect_downloads: bool,
+
+    /// Days a ~/Downloads item must be untouched to be reported (default 90)
+    #[arg(long, value_name = "DAYS")]
+    pub downloads_age: Option<u64>,
+
     /// Skip the Docker detector (shorthand for --disable docker)
     #[arg(long, default_value_t = false)]
     pub no_docker: bool,
 
-    /// Disable specific detectors (comma-separated: docker,xcode,projects,caches)
-    #[arg(long, value_delimiter = ',')]
+    /// Disable specific detectors (comma-separated: docker,xcode,projects,caches,database,system,downloads)
+    #[arg(long, value_delimiter = ',', value_parser = crate::util::parse_detector)]
     pub disable: Option<Vec<String>>,
 
+    /// Run only these detectors, disabling all others (comma-separated)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        conflicts_with_all = ["disable", "no_docker"],
+        value_parser = crate::util::parse_detector
+    )]
+    pub only: Option<Vec<String>>,
+
     /// Per-detector timeout in seconds
     #[arg(long)]
     pub timeout: Option<u64>,
 
-    /// Show detailed output including diagnostics
-    #[arg(long, short = 'v', default_value_t = false)]
-    pub verbose: bool,
+    /// Show detailed output: -v diagnostics and timings, -vv adds per-detector
+    /// memory and explain traces, -vvv logs what the scan visits
+    #[arg(long, short = 'v', action = clap::ArgAction::Count)]
+    pub verbose: u8,
 
     /// Disable verbose output (overrides config file)
     #[arg(long, conflicts_with = "verbose", hide_short_help = true)]
@@ -140,11 +533,157 @@ pub struct CleanArgs {
 
 #[derive(Parser)]
 pub struct DiffArgs {
-    /// Starting snapshot ID for comparison
+    /// Starting snapshot ID for comparison, or 'live' to scan now
     #[arg(long)]
     pub from: Option<String>,
 
-    /// Ending snapshot ID for comparison
+    /// Ending snapshot ID for comparison, or 'live' to scan now
     #[arg(long)]
     pub to: Option<String>,
+
+    /// Pair gone and new entries that look like the same thing renamed
+    /// (same path, or a similar name) and show them as grown or shrunk
+    #[arg(long, default_value_t = false)]
+    pub fuzzy: bool,
+
+    /// Match project and cache names ignoring case and surrounding
+    /// whitespace, so "MyApp" becoming "myapp" is not gone + new
+    #[arg(long, default_value_t = false)]
+    pub ignore_case: bool,
+}
+
+#[derive(Parser)]
+pub struct SnapshotArgs {
+    #[command(subcommand)]
+    pub command: SnapshotCommand,
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotCommand {
+    /// Write a snapshot to a file (.json, .json.gz or .json.zst)
+    Export(SnapshotExportArgs),
+
+    /// Load a snapshot file into the local database under a new ID
+    Import(SnapshotImportArgs),
+
+    /// Delete every snapshot older than a point in time
+    Delete(SnapshotDeleteArgs),
+
+    /// Attach a note to a snapshot, replacing any earlier one ("" removes it)
+    Annotate(SnapshotAnnotateArgs),
+}
+
+#[derive(Parser)]
+pub struct SnapshotExportArgs {
+    /// Snapshot ID to export
+    pub id: i64,
+
+    /// Output file; compression is chosen by extension (defaults to JSON on stdout)
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+pub struct SnapshotImportArgs {
+    /// Exported snapshot file (.json, .json.gz or .json.zst)
+    pub path: PathBuf,
+}
+
+#[derive(Parser)]
+pub struct SnapshotDeleteArgs {
+    /// Delete snapshots taken before this time (an age like 30d, 2w or a YYYY-MM-DD date)
+    #[arg(long, value_name = "WHEN", value_parser = crate::util::parse_since)]
+    pub older_than: i64,
+
+    /// List the snapshots that would be deleted without deleting them
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// Allow the cutoff to delete every snapshot
+    #[arg(long, default_value_t = false)]
+    pub all: bool,
+}
+
+#[derive(Parser)]
+pub struct SnapshotAnnotateArgs {
+    /// Snapshot ID to annotate
+    pub id: i64,
+
+    /// The note, e.g. "right before the big cleanup"
+    pub note: String,
+}
+
+#[derive(Parser)]
+pub struct StatsArgs {
+    /// Window to summarize: an age like 30d, 2w or 12h, or a YYYY-MM-DD start date
+    #[arg(long, value_name = "WHEN", default_value = "30d", value_parser = crate::util::parse_since)]
+    pub last: i64,
+
+    /// Output as JSON
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+#[derive(Parser)]
+pub struct PathsArgs {
+    /// Open the data directory (where the database lives) in the file manager
+    #[arg(long, default_value_t = false)]
+    pub open: bool,
+}
+
+#[derive(Parser)]
+pub struct EstimateArgs {
+    /// Directories to look at (defaults to $HEFT_ROOTS, then config, then home directory)
+    #[arg(long, value_delimiter = ',')]
+    pub roots: Option<Vec<PathBuf>>,
+
+    /// Skip directories matching these globs, as for scan
+    #[arg(long, value_delimiter = ',', value_name = "GLOB")]
+    pub exclude: Option<Vec<String>>,
+
+    /// How deep to walk below each root
+    #[arg(long, default_value_t = 6, value_name = "N")]
+    pub max_depth: usize,
+}
+
+#[derive(Parser)]
+pub struct InspectArgs {
+    /// Directory to break down, e.g. a node_modules from the scan
+    pub path: PathBuf,
+
+    /// How many of the largest children to list
+    #[arg(long, default_value_t = 20, value_name = "N")]
+    pub top: usize,
+}
+
+#[derive(Parser)]
+pub struct DbArgs {
+    #[command(subcommand)]
+    pub command: DbCommand,
+}
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Compact the database file, returning free pages to disk
+    Vacuum,
+
+    /// Dump every entry of every snapshot for external analysis
+    Export(DbExportArgs),
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum DbExportFormat {
+    /// One JSON object per line
+    Ndjson,
+}
+
+#[derive(Parser)]
+pub struct DbExportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DbExportFormat::Ndjson)]
+    pub format: DbExportFormat,
+
+    /// Write to a file instead of stdout
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
 }
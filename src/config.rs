@@ -1,12 +1,20 @@
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use clap::ValueEnum;
 use directories::BaseDirs;
 use serde::Deserialize;
 
-use crate::cli::{CleanArgs, ScanArgs};
+use crate::clean::LargeDeletion;
+use crate::cli::{CleanArgs, CleanCategory, ScanArgs};
 use crate::platform::{self, Platform};
+use crate::report::{GroupBy, OutputFormat};
+use crate::scan::detector::BloatCategory;
+use crate::scan::filter::PathFilter;
+use crate::scan::projects::ArtifactRule;
+use crate::util::{self, Units};
 
 // ---------------------------------------------------------------------------
 // File config (~/.config/heft/config.toml)
@@ -17,18 +25,97 @@ use crate::platform::{self, Platform};
 struct FileScanConfig {
     roots: Option<Vec<PathBuf>>,
     timeout: Option<u64>,
+    format: Option<OutputFormat>,
+    /// Older spelling of `format = "json"`; `format` wins if both are set
     json: Option<bool>,
     verbose: Option<bool>,
     progressive: Option<bool>,
+    /// caches and artifacts smaller than this get no entry, e.g. "1MB"
+    min_entry_size: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
 struct FileDetectorsConfig {
-    docker: Option<bool>,
-    xcode: Option<bool>,
-    projects: Option<bool>,
-    caches: Option<bool>,
+    docker: Option<FileDetector>,
+    xcode: Option<FileDetector>,
+    appsupport: Option<FileDetector>,
+    mobilesync: Option<FileDetector>,
+    projects: Option<FileDetector>,
+    git: Option<FileDetector>,
+    caches: Option<FileDetector>,
+    toolchains: Option<FileDetector>,
+    database: Option<FileDetector>,
+    system: Option<FileDetector>,
+    logs: Option<FileDetector>,
+    windows: Option<FileDetector>,
+    /// Opt-in: report old items in ~/Downloads (same as --detect-downloads)
+    downloads: Option<FileDetector>,
+    /// Days an item in ~/Downloads must be untouched to be reported (default 90)
+    downloads_age: Option<u64>,
+    /// Docker context for scan and clean (`docker --context <name>`)
+    docker_context: Option<String>,
+}
+
+/// A detector's entry under `[detectors]`: either `docker = false`, or a
+/// table such as `[detectors.docker]` with `enabled` and `timeout`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(untagged)]
+enum FileDetector {
+    Enabled(bool),
+    Settings(FileDetectorSettings),
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct FileDetectorSettings {
+    enabled: Option<bool>,
+    /// seconds for this detector's external commands, instead of scan.timeout
+    timeout: Option<u64>,
+}
+
+impl FileDetector {
+    fn enabled(self) -> Option<bool> {
+        match self {
+            FileDetector::Enabled(enabled) => Some(enabled),
+            FileDetector::Settings(settings) => settings.enabled,
+        }
+    }
+
+    fn timeout(self) -> Option<u64> {
+        match self {
+            FileDetector::Enabled(_) => None,
+            FileDetector::Settings(settings) => settings.timeout,
+        }
+    }
+}
+
+impl FileDetectorsConfig {
+    fn by_name(&self) -> [(&'static str, Option<FileDetector>); 13] {
+        [
+            ("docker", self.docker),
+            ("xcode", self.xcode),
+            ("appsupport", self.appsupport),
+            ("mobilesync", self.mobilesync),
+            ("projects", self.projects),
+            ("git", self.git),
+            ("caches", self.caches),
+            ("toolchains", self.toolchains),
+            ("database", self.database),
+            ("system", self.system),
+            ("logs", self.logs),
+            ("windows", self.windows),
+            ("downloads", self.downloads),
+        ]
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileDisplayConfig {
+    units: Option<Units>,
+    /// Plain ASCII output, as with --ascii
+    ascii: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -38,42 +125,498 @@ struct FileConfig {
     scan: FileScanConfig,
     #[serde(default)]
     detectors: FileDetectorsConfig,
+    #[serde(default)]
+    display: FileDisplayConfig,
+    #[serde(default)]
+    clean: FileCleanConfig,
+    /// entry name or glob -> category name (same names as clean --category)
+    #[serde(default)]
+    category_overrides: BTreeMap<String, String>,
+    /// extra artifact directories for the projects detector
+    #[serde(default, rename = "project_artifact")]
+    project_artifacts: Vec<FileArtifactRule>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct FileCleanConfig {
+    /// size above which `clean --yes` also needs --confirm-large, e.g. "50GB"
+    large_threshold: Option<String>,
+    /// item count above which the same applies
+    large_items: Option<usize>,
+    /// reuse the latest snapshot when it is at most this many seconds old,
+    /// like `clean --use-recent`
+    use_recent: Option<u64>,
+    /// paths or globs clean never deletes, whatever the flags
+    never_delete: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileArtifactRule {
+    dir_name: String,
+    requires_sibling: Option<String>,
+    category: Option<String>,
+    cleanup_hint: Option<String>,
+}
+
+/// Where the config file is looked for (~/.config/heft/config.toml or the
+/// platform equivalent), whether or not it exists.
+pub fn config_path() -> Option<PathBuf> {
+    BaseDirs::new().map(|base| base.config_dir().join("heft").join("config.toml"))
 }
 
+/// The config file, or a warning and `None` when it can't be read or parsed.
 fn load_file_config() -> Option<FileConfig> {
-    let base = BaseDirs::new()?;
-    let path = base.config_dir().join("heft").join("config.toml");
-    let content = std::fs::read_to_string(&path).ok()?;
-    match toml::from_str(&content) {
-        Ok(cfg) => Some(cfg),
+    match read_file_config() {
+        Ok(cfg) => cfg,
         Err(e) => {
-            eprintln!(
-                "warning: failed to parse config file {}: {e}",
-                path.display()
-            );
+            eprintln!("warning: {e}");
             None
         }
     }
 }
 
+/// The config file, `None` when there is none. A file that exists but
+/// can't be read or parsed is an error.
+fn read_file_config() -> Result<Option<FileConfig>, String> {
+    let Some(path) = config_path() else {
+        return Ok(None);
+    };
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(format!(
+                "failed to read config file {}: {e}",
+                path.display()
+            ))
+        }
+    };
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("failed to parse config file {}: {e}", path.display()))
+}
+
+/// The config file for commands that delete: dropping it would drop
+/// `clean.never_delete`, so a broken file stops them instead.
+fn file_config_for_deleting() -> Result<FileConfig, String> {
+    read_file_config()
+        .map(Option::unwrap_or_default)
+        .map_err(|e| {
+            format!("{e}\nrefusing to delete anything without its clean.never_delete list")
+        })
+}
+
+/// Every detector heft knows about, in run order.
+pub const DETECTOR_NAMES: &[&str] = &[
+    "projects",
+    "git",
+    "caches",
+    "toolchains",
+    "docker",
+    "xcode",
+    "appsupport",
+    "mobilesync",
+    "database",
+    "system",
+    "logs",
+    "windows",
+    "downloads",
+];
+
+/// `--downloads-age` when neither the flag nor `detectors.downloads_age` is set.
+pub const DEFAULT_DOWNLOADS_AGE_DAYS: u64 = 90;
+
+/// Caches and project artifacts smaller than this get no entry unless
+/// `scan.min_entry_size`, --min-size or --show-all says otherwise.
+pub const DEFAULT_MIN_ENTRY_BYTES: u64 = 1024 * 1024;
+
 /// Collect detector names disabled by the file config.
 fn disabled_from_file(det: &FileDetectorsConfig) -> HashSet<String> {
-    let mut out = HashSet::new();
-    if det.docker == Some(false) {
-        out.insert("docker".to_string());
+    det.by_name()
+        .into_iter()
+        .filter(|(_, detector)| detector.and_then(FileDetector::enabled) == Some(false))
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// `[detectors.<name>] timeout` overrides. A --timeout on the command line
+/// applies to every detector, so it clears them.
+fn detector_timeouts_from_file(
+    det: &FileDetectorsConfig,
+    cli_timeout: Option<u64>,
+) -> HashMap<String, Duration> {
+    if cli_timeout.is_some() {
+        return HashMap::new();
     }
-    if det.xcode == Some(false) {
-        out.insert("xcode".to_string());
+    det.by_name()
+        .into_iter()
+        .filter_map(|(name, detector)| {
+            let secs = detector.and_then(FileDetector::timeout)?;
+            Some((name.to_string(), Duration::from_secs(secs)))
+        })
+        .collect()
+}
+
+/// Disabled set from the file config and CLI flags. `--only` replaces
+/// everything else with the complement of its list.
+fn resolve_disabled(
+    det: &FileDetectorsConfig,
+    no_docker: bool,
+    disable: &Option<Vec<String>>,
+    only: &Option<Vec<String>>,
+) -> HashSet<String> {
+    if let Some(only) = only {
+        return DETECTOR_NAMES
+            .iter()
+            .filter(|name| !only.iter().any(|o| o == *name))
+            .map(|name| name.to_string())
+            .collect();
     }
-    if det.projects == Some(false) {
-        out.insert("projects".to_string());
+    let mut disabled = disabled_from_file(det);
+    if no_docker {
+        disabled.insert("docker".to_string());
     }
-    if det.caches == Some(false) {
-        out.insert("caches".to_string());
+    if let Some(names) = disable {
+        disabled.extend(names.iter().cloned());
+    }
+    disabled
+}
+
+/// Resolve `[category_overrides]` into (pattern, category) pairs.
+/// Category names are the clean --category values; unknown ones are warned about and skipped.
+fn category_overrides_from_file(raw: &BTreeMap<String, String>) -> Vec<(String, BloatCategory)> {
+    let mut out = Vec::new();
+    for (pattern, name) in raw {
+        match CleanCategory::from_str(name, false) {
+            Ok(category) => out.push((pattern.clone(), category.into())),
+            Err(_) => {
+                let valid: Vec<String> = CleanCategory::value_variants()
+                    .iter()
+                    .filter_map(|v| v.to_possible_value())
+                    .map(|v| v.get_name().to_string())
+                    .collect();
+                eprintln!(
+                    "warning: unknown category '{name}' for override '{pattern}' (expected one of: {})",
+                    valid.join(", ")
+                );
+            }
+        }
     }
     out
 }
 
+/// `[clean]` limits over the built-in defaults; a bad size is warned about and ignored.
+fn large_deletion_from_file(raw: &FileCleanConfig) -> LargeDeletion {
+    let mut limit = LargeDeletion::default();
+    if let Some(ref threshold) = raw.large_threshold {
+        match util::parse_size(threshold) {
+            Ok(bytes) => limit.bytes = bytes,
+            Err(e) => eprintln!("warning: clean.large_threshold: {e}"),
+        }
+    }
+    if let Some(items) = raw.large_items {
+        limit.items = items;
+    }
+    limit
+}
+
+/// Smallest entry worth reporting: --show-all, then --min-size, then
+/// `scan.min_entry_size`, then the default.
+fn min_entry_bytes(cli: Option<u64>, show_all: bool, file: Option<&str>) -> u64 {
+    if show_all {
+        return 0;
+    }
+    if let Some(bytes) = cli {
+        return bytes;
+    }
+    match file.map(util::parse_size) {
+        Some(Ok(bytes)) => bytes,
+        Some(Err(e)) => {
+            eprintln!("warning: scan.min_entry_size: {e}");
+            DEFAULT_MIN_ENTRY_BYTES
+        }
+        None => DEFAULT_MIN_ENTRY_BYTES,
+    }
+}
+
+/// `clean.never_delete` with a leading `~/` expanded to home.
+fn never_delete_from_file(raw: &FileCleanConfig) -> Vec<String> {
+    let home = platform::home_dir();
+    raw.never_delete
+        .iter()
+        .flatten()
+        .map(|pattern| match (pattern.strip_prefix("~/"), &home) {
+            (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+            _ => pattern.clone(),
+        })
+        .collect()
+}
+
+/// Verbosity from the -v count: --no-verbose forces 0, any -v wins over
+/// the file's `verbose = true` (level 1).
+fn verbose_level(count: u8, no_verbose: bool, file: Option<bool>) -> u8 {
+    if no_verbose {
+        0
+    } else if count > 0 {
+        count
+    } else {
+        u8::from(file.unwrap_or(false))
+    }
+}
+
+/// How old a snapshot clean may reuse instead of scanning. --fresh always
+/// scans; clean.use_recent only applies when no flag changes what a scan
+/// would look at, since the snapshot came from a default scan.
+fn recent_snapshot_secs(args: &CleanArgs, file: &FileCleanConfig) -> Option<u64> {
+    let reshaped = args.roots.is_some()
+        || args.roots_from.is_some()
+        || args.include.is_some()
+        || args.exclude.is_some()
+        || args.only.is_some()
+        || args.disable.is_some()
+        || args.no_docker
+        || args.cross_filesystems
+        || args.detect_downloads
+        || args.from_plan.is_some()
+        || args.execute_plan.is_some();
+    match (args.fresh, args.use_recent) {
+        (true, _) => None,
+        (false, Some(secs)) => Some(secs),
+        (false, None) if reshaped => None,
+        (false, None) => file.use_recent,
+    }
+}
+
+/// Resolve `[[project_artifact]]` entries. The category defaults to
+/// project-artifacts; rules with a bad name or category are warned about and skipped.
+fn artifact_rules_from_file(raw: &[FileArtifactRule]) -> Vec<ArtifactRule> {
+    let mut out = Vec::new();
+    for rule in raw {
+        let dir_name = rule.dir_name.trim();
+        if dir_name.is_empty() || dir_name.contains(['/', '\\']) {
+            eprintln!(
+                "warning: project_artifact dir_name '{}' must be a single directory name",
+                rule.dir_name
+            );
+            continue;
+        }
+        let category = match rule.category.as_deref() {
+            None => BloatCategory::ProjectArtifacts,
+            Some(name) => match CleanCategory::from_str(name, false) {
+                Ok(category) => category.into(),
+                Err(_) => {
+                    eprintln!(
+                        "warning: unknown category '{name}' for project_artifact '{dir_name}'"
+                    );
+                    continue;
+                }
+            },
+        };
+        out.push(ArtifactRule {
+            dir_name: dir_name.to_string(),
+            requires_sibling: rule.requires_sibling.clone(),
+            category,
+            cleanup_hint: rule
+                .cleanup_hint
+                .clone()
+                .unwrap_or_else(|| format!("matched project_artifact rule for {dir_name}")),
+        });
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Roots list (--roots-from)
+// ---------------------------------------------------------------------------
+
+/// Parse a newline-separated list of roots. Blank lines and `#` comments are skipped.
+fn parse_roots_list(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Read roots from a file, or from stdin when the source is `-`.
+fn read_roots_from(source: &Path) -> Result<Vec<PathBuf>, String> {
+    let content = if source == Path::new("-") {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .map_err(|e| format!("failed to read roots from stdin: {e}"))?;
+        buf
+    } else {
+        std::fs::read_to_string(source)
+            .map_err(|e| format!("failed to read roots from {}: {e}", source.display()))?
+    };
+
+    let roots = parse_roots_list(&content);
+    if roots.is_empty() {
+        let name = if source == Path::new("-") {
+            "stdin".to_string()
+        } else {
+            source.display().to_string()
+        };
+        return Err(format!("no roots found in {name}"));
+    }
+    Ok(roots)
+}
+
+/// Roots given on the command line, either inline (--roots) or via --roots-from.
+/// Fails if the roots list can't be read. Roots that don't exist are left in
+/// place; the project detector reports and skips them.
+fn cli_roots(
+    roots: &Option<Vec<PathBuf>>,
+    roots_from: &Option<PathBuf>,
+) -> Result<Option<Vec<PathBuf>>, String> {
+    match roots_from {
+        Some(source) => read_roots_from(source).map(Some),
+        None => Ok(roots.clone()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Environment overrides
+// ---------------------------------------------------------------------------
+
+/// Env var holding scan roots; sits between CLI flags and the config file.
+const ROOTS_ENV: &str = "HEFT_ROOTS";
+
+/// Parse HEFT_ROOTS: comma-separated like --roots, and each piece may also use
+/// the platform path-list separator (`:` on Unix, `;` on Windows) like PATH.
+fn parse_roots_env(value: &std::ffi::OsStr) -> Vec<PathBuf> {
+    value
+        .to_string_lossy()
+        .split(',')
+        .flat_map(|piece| std::env::split_paths(piece.trim()).collect::<Vec<_>>())
+        .filter(|p| !p.as_os_str().is_empty())
+        .collect()
+}
+
+/// Roots from HEFT_ROOTS, or None when unset or empty.
+fn env_roots() -> Option<Vec<PathBuf>> {
+    let roots = parse_roots_env(&std::env::var_os(ROOTS_ENV)?);
+    (!roots.is_empty()).then_some(roots)
+}
+
+const READ_ONLY_ENV: &str = "HEFT_READ_ONLY";
+
+/// Whether HEFT_READ_ONLY asks for read-only mode ("1", "true" or "yes").
+fn read_only_env(value: Option<&str>) -> bool {
+    value.is_some_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+/// ascii: --ascii > NO_COLOR (set and non-empty) > `display.ascii` > on in
+/// CI > whether stdout is a terminal
+fn ascii_from(
+    cli: bool,
+    no_color: Option<&str>,
+    file: Option<bool>,
+    ci: bool,
+    stdout_tty: bool,
+) -> bool {
+    cli || no_color.is_some_and(|v| !v.is_empty()) || file.unwrap_or(ci || !stdout_tty)
+}
+
+pub fn resolve_ascii(cli: bool) -> bool {
+    use std::io::IsTerminal;
+
+    ascii_from(
+        cli,
+        std::env::var("NO_COLOR").ok().as_deref(),
+        load_file_config().and_then(|file| file.display.ascii),
+        platform::is_ci(),
+        std::io::stdout().is_terminal(),
+    )
+}
+
+/// read-only: --read-only or HEFT_READ_ONLY
+pub fn resolve_read_only(cli: bool) -> bool {
+    cli || read_only_env(std::env::var(READ_ONLY_ENV).ok().as_deref())
+}
+
+/// roots: CLI (--roots or --roots-from) > HEFT_ROOTS > file > home dir
+fn resolve_roots(
+    cli: Option<Vec<PathBuf>>,
+    env: Option<Vec<PathBuf>>,
+    file: Option<Vec<PathBuf>>,
+) -> Vec<PathBuf> {
+    cli.or(env)
+        .or(file)
+        .unwrap_or_else(|| platform::home_dir().map(|h| vec![h]).unwrap_or_default())
+}
+
+// ---------------------------------------------------------------------------
+// Scan root safety
+// ---------------------------------------------------------------------------
+
+/// Directories that hold the OS or every user's data. Walking one of them
+/// means thousands of permission errors and a scan that takes forever.
+fn system_dirs(platform: Platform) -> &'static [&'static str] {
+    match platform {
+        Platform::Windows => &[
+            "C:\\",
+            "C:\\Windows",
+            "C:\\Program Files",
+            "C:\\Program Files (x86)",
+            "C:\\ProgramData",
+            "C:\\Users",
+        ],
+        Platform::MacOS => &[
+            "/",
+            "/System",
+            "/Library",
+            "/Applications",
+            "/Users",
+            "/Volumes",
+            "/private",
+            "/usr",
+            "/bin",
+            "/sbin",
+            "/etc",
+            "/var",
+            "/opt",
+        ],
+        Platform::Linux | Platform::Unknown => &[
+            "/", "/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc", "/var", "/opt", "/boot",
+            "/dev", "/proc", "/sys", "/run", "/home", "/mnt", "/media",
+        ],
+    }
+}
+
+/// Whether `root` is a system directory on `platform`. Relative roots are
+/// resolved against the current directory first, so `.` run from `/` counts.
+pub fn is_system_root(root: &Path, platform: Platform) -> bool {
+    let resolved = std::fs::canonicalize(root).unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(root))
+            .unwrap_or_else(|_| root.to_path_buf())
+    });
+    [root, resolved.as_path()]
+        .iter()
+        .any(|path| is_system_dir(path, platform))
+}
+
+fn is_system_dir(path: &Path, platform: Platform) -> bool {
+    if platform == Platform::Windows {
+        // compare as text: canonicalize adds a \\?\ prefix, Windows paths
+        // ignore case, and this also works when checked from another OS
+        let text = path.to_string_lossy().to_lowercase();
+        let text = text.trim_start_matches(r"\\?\").trim_end_matches('\\');
+        system_dirs(platform)
+            .iter()
+            .any(|dir| dir.to_lowercase().trim_end_matches('\\') == text)
+    } else {
+        system_dirs(platform)
+            .iter()
+            .any(|dir| path == Path::new(dir))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Runtime config
 // ---------------------------------------------------------------------------
@@ -81,51 +624,180 @@ fn disabled_from_file(det: &FileDetectorsConfig) -> HashSet<String> {
 pub struct Config {
     pub roots: Vec<PathBuf>,
     pub timeout: Duration,
+    /// Per-detector overrides of `timeout` (`[detectors.docker] timeout = 20`)
+    pub detector_timeouts: HashMap<String, Duration>,
     pub disabled_detectors: HashSet<String>,
-    pub json_output: bool,
-    pub verbose: bool,
+    /// --format, or `format` / `json` in the config file
+    pub output_format: OutputFormat,
+    /// -v count: 1 for diagnostics and timings, 2 adds per-detector memory
+    /// and explain traces, 3 adds debug logging of the scan itself
+    pub verbose_level: u8,
     pub progressive: bool,
     pub platform: Platform,
+    /// Entry name or glob -> category, applied to every entry after detection
+    pub category_overrides: Vec<(String, BloatCategory)>,
+    /// Emit a diagnostic for every accepted or rejected artifact candidate
+    pub explain: bool,
+    /// --include / --exclude globs for the project walk
+    pub path_filter: PathFilter,
+    /// Run the opt-in downloads detector (--detect-downloads)
+    pub detect_downloads: bool,
+    /// How long a ~/Downloads item must be untouched to be reported
+    pub downloads_min_age_days: u64,
+    /// Walk and size across mount points (--cross-filesystems); by default
+    /// the walks stay on each root's filesystem
+    pub cross_filesystems: bool,
+    /// Report locations only, skipping the size walks (--no-sizes)
+    pub skip_sizes: bool,
+    /// Caches and project artifacts smaller than this get no entry
+    /// (`scan.min_entry_size`, --min-size; 0 with --show-all)
+    pub min_entry_bytes: u64,
+    /// Print bare entry paths instead of a report (--list-paths), only
+    /// reclaimable ones (--reclaimable-only), NUL-terminated (--print0)
+    pub list_paths: bool,
+    pub reclaimable_only: bool,
+    pub print0: bool,
+    /// Show shortened entry locations in the table (--relative)
+    pub relative_paths: bool,
+    /// Print each entry's cleanup hint in the table (--hints, or verbose)
+    pub show_hints: bool,
+    /// Show a separate "manually reclaimable" total in the table (--include-manual)
+    pub include_manual: bool,
+    /// Add a reclaim_score to each entry in JSON output (--score)
+    pub score: bool,
+    /// Add per-category groups to JSON output (--json-grouped)
+    pub json_grouped: bool,
+    /// List this many of the biggest entries above the table (--summary)
+    pub summary_top: Option<usize>,
+    /// Show the reclaimable total as progress toward this many bytes (--goal)
+    pub goal: Option<u64>,
+    /// What the table groups and subtotals entries by (--group-by)
+    pub group_by: GroupBy,
+    /// Break docker build cache down by last use via buildx (--build-cache-ages)
+    pub build_cache_ages: bool,
+    /// `detectors.docker_context`; None leaves it to $DOCKER_HOST / the CLI default
+    pub docker_context: Option<String>,
+    /// `[clean]` large_threshold / large_items for `clean --yes`
+    pub large_deletion: LargeDeletion,
+    /// `clean.never_delete`: paths clean refuses to delete, or to delete
+    /// anything containing
+    pub never_delete: Vec<String>,
+    /// clean reuses the latest snapshot instead of scanning when it is at
+    /// most this many seconds old (--use-recent or clean.use_recent)
+    pub recent_snapshot_secs: Option<u64>,
+    /// `[[project_artifact]]` rules, checked after the built-in ones
+    pub artifact_rules: Vec<ArtifactRule>,
+}
+
+/// --format > --json / --json-grouped / --compact / --no-json > config file > table.
+fn resolve_output_format(args: &ScanArgs, file: &FileScanConfig) -> OutputFormat {
+    if let Some(format) = args.format {
+        format
+    } else if args.json || args.json_grouped {
+        OutputFormat::Json
+    } else if args.compact {
+        OutputFormat::Compact
+    } else if args.no_json {
+        OutputFormat::Table
+    } else {
+        file_output_format(file)
+    }
+}
+
+fn file_output_format(file: &FileScanConfig) -> OutputFormat {
+    match (file.format, file.json) {
+        (Some(format), _) => format,
+        (None, Some(true)) => OutputFormat::Json,
+        _ => OutputFormat::Table,
+    }
+}
+
+/// Size units for every command: --units > `display.units` in the config file > binary.
+pub fn resolve_units(cli: Option<Units>) -> Units {
+    cli.or_else(|| load_file_config().and_then(|file| file.display.units))
+        .unwrap_or_default()
 }
 
 impl Config {
+    /// Any -v at all: diagnostics, timings and hints are shown.
+    pub fn verbose(&self) -> bool {
+        self.verbose_level > 0
+    }
+
+    /// Roots that are system directories; scan and clean refuse these unless
+    /// --allow-system-root is given.
+    pub fn system_roots(&self) -> Vec<&Path> {
+        self.roots
+            .iter()
+            .map(PathBuf::as_path)
+            .filter(|root| is_system_root(root, self.platform))
+            .collect()
+    }
+
+    /// Everything that shapes which entries a scan finds, as text. A
+    /// snapshot is only reused by `clean --use-recent` when its scan ran
+    /// with the same key; display settings are left out.
+    pub fn scan_key(&self) -> String {
+        let mut disabled: Vec<&String> = self.disabled_detectors.iter().collect();
+        disabled.sort();
+        format!(
+            "{:?}",
+            (
+                &self.roots,
+                &self.path_filter,
+                disabled,
+                self.platform,
+                (self.detect_downloads, self.downloads_min_age_days),
+                self.cross_filesystems,
+                self.min_entry_bytes,
+                self.build_cache_ages,
+                &self.docker_context,
+                &self.category_overrides,
+                &self.artifact_rules,
+            )
+        )
+    }
+
     pub fn is_detector_enabled(&self, name: &str) -> bool {
         !self.disabled_detectors.contains(name)
     }
 
-    pub fn from_scan_args(args: &ScanArgs) -> Self {
-        let file = load_file_config().unwrap_or_default();
+    /// How long `detector`'s external commands (docker, brew) may take.
+    pub fn timeout_for(&self, detector: &str) -> Duration {
+        self.detector_timeouts
+            .get(detector)
+            .copied()
+            .unwrap_or(self.timeout)
+    }
+
+    pub fn from_scan_args(args: &ScanArgs) -> Result<Self, String> {
+        // the TUI deletes, and --reclaimable-only lists paths to delete
+        let file = if args.tui || args.reclaimable_only {
+            file_config_for_deleting()?
+        } else {
+            load_file_config().unwrap_or_default()
+        };
         Self::merge_scan(args, &file)
     }
 
-    fn merge_scan(args: &ScanArgs, file: &FileConfig) -> Self {
+    fn merge_scan(args: &ScanArgs, file: &FileConfig) -> Result<Self, String> {
         let platform = platform::detect();
 
-        // roots: CLI > file > home dir
-        let roots = args
-            .roots
-            .clone()
-            .or(file.scan.roots.clone())
-            .unwrap_or_else(|| platform::home_dir().map(|h| vec![h]).unwrap_or_default());
+        let roots = resolve_roots(
+            cli_roots(&args.roots, &args.roots_from)?,
+            env_roots(),
+            file.scan.roots.clone(),
+        );
 
         // timeout: CLI > file > default 30s
         let timeout = args.timeout.or(file.scan.timeout).unwrap_or(30);
 
+        let output_format = resolve_output_format(args, &file.scan);
+        // --explain output is only useful in full, so it turns verbose on,
+        // and -vv turns the explain traces on
+        let verbose_level = verbose_level(args.verbose, args.no_verbose, file.scan.verbose)
+            .max(u8::from(args.explain));
         // booleans: --flag forces on, --no-flag forces off, otherwise file config
-        let json_output = if args.no_json {
-            false
-        } else if args.json {
-            true
-        } else {
-            file.scan.json.unwrap_or(false)
-        };
-        let verbose = if args.no_verbose {
-            false
-        } else if args.verbose {
-            true
-        } else {
-            file.scan.verbose.unwrap_or(false)
-        };
         let progressive = if args.no_progressive {
             false
         } else if args.progressive {
@@ -134,62 +806,115 @@ impl Config {
             file.scan.progressive.unwrap_or(false)
         };
 
-        // disabled detectors: file config base, then CLI --no-docker / --disable
-        let mut disabled = disabled_from_file(&file.detectors);
-        if args.no_docker {
-            disabled.insert("docker".to_string());
-        }
-        if let Some(ref names) = args.disable {
-            disabled.extend(names.iter().cloned());
-        }
+        // disabled detectors: file config base, then CLI --no-docker / --disable / --only
+        let disabled = resolve_disabled(&file.detectors, args.no_docker, &args.disable, &args.only);
 
-        Config {
+        Ok(Config {
             roots,
             timeout: Duration::from_secs(timeout),
+            detector_timeouts: detector_timeouts_from_file(&file.detectors, args.timeout),
             disabled_detectors: disabled,
-            json_output,
-            verbose,
+            output_format,
+            verbose_level,
             progressive,
             platform,
-        }
+            category_overrides: category_overrides_from_file(&file.category_overrides),
+            artifact_rules: artifact_rules_from_file(&file.project_artifacts),
+            explain: args.explain || verbose_level >= 2,
+            relative_paths: args.relative,
+            show_hints: args.hints || verbose_level > 0,
+            include_manual: args.include_manual,
+            score: args.score,
+            json_grouped: args.json_grouped,
+            summary_top: args.summary,
+            goal: args.goal,
+            group_by: args.group_by,
+            build_cache_ages: args.build_cache_ages,
+            docker_context: file.detectors.docker_context.clone(),
+            large_deletion: large_deletion_from_file(&file.clean),
+            never_delete: never_delete_from_file(&file.clean),
+            recent_snapshot_secs: None,
+            path_filter: PathFilter::new(
+                args.include.clone().unwrap_or_default(),
+                args.exclude.clone().unwrap_or_default(),
+            ),
+            cross_filesystems: args.cross_filesystems,
+            skip_sizes: args.no_sizes,
+            min_entry_bytes: min_entry_bytes(
+                args.min_size,
+                args.show_all,
+                file.scan.min_entry_size.as_deref(),
+            ),
+            list_paths: args.list_paths,
+            reclaimable_only: args.reclaimable_only,
+            print0: args.print0,
+            detect_downloads: args.detect_downloads
+                || file.detectors.downloads.and_then(FileDetector::enabled) == Some(true),
+            downloads_min_age_days: args
+                .downloads_age
+                .or(file.detectors.downloads_age)
+                .unwrap_or(DEFAULT_DOWNLOADS_AGE_DAYS),
+        })
     }
 
-    pub fn from_clean_args(args: &CleanArgs) -> Self {
+    pub fn from_clean_args(args: &CleanArgs) -> Result<Self, String> {
         let platform = platform::detect();
-        let file = load_file_config().unwrap_or_default();
+        let file = file_config_for_deleting()?;
+        let output_format = file_output_format(&file.scan);
 
-        let roots = args
-            .roots
-            .clone()
-            .or(file.scan.roots)
-            .unwrap_or_else(|| platform::home_dir().map(|h| vec![h]).unwrap_or_default());
+        let roots = resolve_roots(
+            cli_roots(&args.roots, &args.roots_from)?,
+            env_roots(),
+            file.scan.roots,
+        );
 
         let timeout = args.timeout.or(file.scan.timeout).unwrap_or(30);
-        let verbose = if args.no_verbose {
-            false
-        } else if args.verbose {
-            true
-        } else {
-            file.scan.verbose.unwrap_or(false)
-        };
+        let verbose_level = verbose_level(args.verbose, args.no_verbose, file.scan.verbose);
 
-        let mut disabled = disabled_from_file(&file.detectors);
-        if args.no_docker {
-            disabled.insert("docker".to_string());
-        }
-        if let Some(ref names) = args.disable {
-            disabled.extend(names.iter().cloned());
-        }
+        let disabled = resolve_disabled(&file.detectors, args.no_docker, &args.disable, &args.only);
 
-        Config {
+        Ok(Config {
             roots,
             timeout: Duration::from_secs(timeout),
+            detector_timeouts: detector_timeouts_from_file(&file.detectors, args.timeout),
             disabled_detectors: disabled,
-            json_output: file.scan.json.unwrap_or(false),
-            verbose,
+            output_format,
+            verbose_level,
             progressive: file.scan.progressive.unwrap_or(false),
             platform,
-        }
+            category_overrides: category_overrides_from_file(&file.category_overrides),
+            artifact_rules: artifact_rules_from_file(&file.project_artifacts),
+            explain: false,
+            relative_paths: false,
+            show_hints: false,
+            include_manual: false,
+            score: false,
+            json_grouped: false,
+            summary_top: None,
+            goal: None,
+            group_by: GroupBy::Category,
+            build_cache_ages: false,
+            docker_context: file.detectors.docker_context.clone(),
+            large_deletion: large_deletion_from_file(&file.clean),
+            never_delete: never_delete_from_file(&file.clean),
+            recent_snapshot_secs: recent_snapshot_secs(args, &file.clean),
+            path_filter: PathFilter::new(
+                args.include.clone().unwrap_or_default(),
+                args.exclude.clone().unwrap_or_default(),
+            ),
+            cross_filesystems: args.cross_filesystems,
+            skip_sizes: false,
+            min_entry_bytes: min_entry_bytes(None, false, file.scan.min_entry_size.as_deref()),
+            list_paths: false,
+            reclaimable_only: false,
+            print0: false,
+            detect_downloads: args.detect_downloads
+                || file.detectors.downloads.and_then(FileDetector::enabled) == Some(true),
+            downloads_min_age_days: args
+                .downloads_age
+                .or(file.detectors.downloads_age)
+                .unwrap_or(DEFAULT_DOWNLOADS_AGE_DAYS),
+        })
     }
 }
 
@@ -201,11 +926,37 @@ impl Default for Config {
         Config {
             roots,
             timeout: Duration::from_secs(30),
+            detector_timeouts: HashMap::new(),
             disabled_detectors: HashSet::new(),
-            json_output: false,
-            verbose: false,
+            output_format: OutputFormat::Table,
+            verbose_level: 0,
             progressive: false,
             platform,
+            category_overrides: Vec::new(),
+            artifact_rules: Vec::new(),
+            explain: false,
+            path_filter: PathFilter::default(),
+            cross_filesystems: false,
+            skip_sizes: false,
+            min_entry_bytes: DEFAULT_MIN_ENTRY_BYTES,
+            list_paths: false,
+            reclaimable_only: false,
+            print0: false,
+            detect_downloads: false,
+            downloads_min_age_days: DEFAULT_DOWNLOADS_AGE_DAYS,
+            relative_paths: false,
+            show_hints: false,
+            include_manual: false,
+            score: false,
+            json_grouped: false,
+            summary_top: None,
+            goal: None,
+            group_by: GroupBy::Category,
+            build_cache_ages: false,
+            docker_context: None,
+            large_deletion: LargeDeletion::default(),
+            never_delete: Vec::new(),
+            recent_snapshot_secs: None,
         }
     }
 }
@@ -218,15 +969,48 @@ mod tests {
     fn default_scan_args() -> ScanArgs {
         ScanArgs {
             roots: None,
+            roots_from: None,
+            allow_system_root: false,
+            include: None,
+            exclude: None,
+            cross_filesystems: false,
+            no_sizes: false,
+            min_size: None,
+            show_all: false,
+            list_paths: false,
+            reclaimable_only: false,
+            print0: false,
+            detect_downloads: false,
+            downloads_age: None,
+            format: None,
+            empty_exit_code: None,
+            include_manual: false,
+            score: false,
+            json_grouped: false,
+            summary: None,
+            group_by: GroupBy::Category,
             json: false,
             no_json: false,
             no_docker: false,
             disable: None,
+            only: None,
             timeout: None,
-            verbose: false,
+            verbose: 0,
             no_verbose: false,
             progressive: false,
             no_progressive: false,
+            explain: false,
+            relative: false,
+            hints: false,
+            compact: false,
+            build_cache_ages: false,
+            remote: None,
+            tui: false,
+            diff: false,
+            baseline: None,
+            growth_tolerance: None,
+            fail_on_growth: false,
+            goal: None,
         }
     }
 
@@ -241,10 +1025,21 @@ mod tests {
     #[test]
     fn disabled_from_file_true_does_not_disable() {
         let det = FileDetectorsConfig {
-            docker: Some(true),
-            xcode: Some(true),
-            projects: Some(true),
-            caches: Some(true),
+            docker: Some(FileDetector::Enabled(true)),
+            xcode: Some(FileDetector::Enabled(true)),
+            appsupport: Some(FileDetector::Enabled(true)),
+            mobilesync: Some(FileDetector::Enabled(true)),
+            projects: Some(FileDetector::Enabled(true)),
+            git: Some(FileDetector::Enabled(true)),
+            caches: Some(FileDetector::Enabled(true)),
+            toolchains: Some(FileDetector::Enabled(true)),
+            database: Some(FileDetector::Enabled(true)),
+            system: Some(FileDetector::Enabled(true)),
+            logs: Some(FileDetector::Enabled(true)),
+            windows: Some(FileDetector::Enabled(true)),
+            downloads: Some(FileDetector::Enabled(true)),
+            downloads_age: None,
+            docker_context: None,
         };
         assert!(disabled_from_file(&det).is_empty());
     }
@@ -252,16 +1047,28 @@ mod tests {
     #[test]
     fn disabled_from_file_false_disables() {
         let det = FileDetectorsConfig {
-            docker: Some(false),
-            xcode: Some(false),
+            docker: Some(FileDetector::Enabled(false)),
+            xcode: Some(FileDetector::Enabled(false)),
+            appsupport: None,
+            mobilesync: None,
             projects: None,
-            caches: Some(false),
+            git: None,
+            caches: Some(FileDetector::Enabled(false)),
+            toolchains: None,
+            database: None,
+            system: Some(FileDetector::Enabled(false)),
+            logs: None,
+            windows: None,
+            downloads: None,
+            downloads_age: None,
+            docker_context: None,
         };
         let disabled = disabled_from_file(&det);
         assert!(disabled.contains("docker"));
         assert!(disabled.contains("xcode"));
         assert!(disabled.contains("caches"));
         assert!(!disabled.contains("projects"));
+        assert!(disabled.contains("system"));
     }
 
     // ── merge_scan: timeout precedence ──────────────────────────────────────
@@ -270,7 +1077,7 @@ mod tests {
     fn timeout_defaults_to_30() {
         let args = default_scan_args();
         let file = FileConfig::default();
-        let config = Config::merge_scan(&args, &file);
+        let config = Config::merge_scan(&args, &file).unwrap();
         assert_eq!(config.timeout, Duration::from_secs(30));
     }
 
@@ -284,7 +1091,7 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
+        let config = Config::merge_scan(&args, &file).unwrap();
         assert_eq!(config.timeout, Duration::from_secs(60));
     }
 
@@ -301,7 +1108,7 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
+        let config = Config::merge_scan(&args, &file).unwrap();
         assert_eq!(config.timeout, Duration::from_secs(10));
     }
 
@@ -309,8 +1116,8 @@ mod tests {
 
     #[test]
     fn verbose_defaults_to_false() {
-        let config = Config::merge_scan(&default_scan_args(), &FileConfig::default());
-        assert!(!config.verbose);
+        let config = Config::merge_scan(&default_scan_args(), &FileConfig::default()).unwrap();
+        assert!(!config.verbose());
     }
 
     #[test]
@@ -322,8 +1129,70 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&default_scan_args(), &file);
-        assert!(config.verbose);
+        let config = Config::merge_scan(&default_scan_args(), &file).unwrap();
+        assert!(config.verbose());
+    }
+
+    #[test]
+    fn min_entry_size_from_flag_file_or_default() {
+        assert_eq!(min_entry_bytes(None, false, None), DEFAULT_MIN_ENTRY_BYTES);
+        assert_eq!(min_entry_bytes(None, false, Some("10MB")), 10 * 1024 * 1024);
+        assert_eq!(
+            min_entry_bytes(None, false, Some("lots")),
+            DEFAULT_MIN_ENTRY_BYTES
+        );
+        assert_eq!(min_entry_bytes(Some(0), false, Some("10MB")), 0);
+        assert_eq!(min_entry_bytes(None, true, Some("10MB")), 0);
+    }
+
+    #[test]
+    fn scan_key_changes_with_what_the_scan_finds_only() {
+        use clap::Parser;
+        let key = |argv: &[&str]| {
+            let cli = crate::cli::Cli::try_parse_from([&["heft", "scan"], argv].concat()).unwrap();
+            match cli.command {
+                crate::cli::Command::Scan(args) => {
+                    Config::merge_scan(&args, &FileConfig::default())
+                        .unwrap()
+                        .scan_key()
+                }
+                _ => unreachable!(),
+            }
+        };
+
+        let plain = key(&["--roots", "/srv/code"]);
+        assert_eq!(plain, key(&["--roots", "/srv/code", "--json", "-v"]));
+        for reshaped in [
+            &["--roots", "/srv/other"][..],
+            &["--roots", "/srv/code", "--min-size", "0"],
+            &["--roots", "/srv/code", "--detect-downloads"],
+            &["--roots", "/srv/code", "--disable", "docker"],
+            &["--roots", "/srv/code", "--exclude", "*/vendor"],
+        ] {
+            assert_ne!(plain, key(reshaped), "{reshaped:?}");
+        }
+    }
+
+    #[test]
+    fn repeated_v_raises_the_level() {
+        use clap::Parser;
+        let config = |argv: &[&str]| {
+            let cli = crate::cli::Cli::try_parse_from([&["heft", "scan"], argv].concat()).unwrap();
+            match cli.command {
+                crate::cli::Command::Scan(args) => {
+                    Config::merge_scan(&args, &FileConfig::default()).unwrap()
+                }
+                _ => unreachable!(),
+            }
+        };
+
+        assert_eq!(config(&["-v"]).verbose_level, 1);
+        assert!(!config(&["-v"]).explain);
+        assert_eq!(config(&["-vv"]).verbose_level, 2);
+        assert!(config(&["-vv"]).explain);
+        assert_eq!(config(&["-v", "-v", "-v"]).verbose_level, 3);
+        // --explain alone still implies -v
+        assert_eq!(config(&["--explain"]).verbose_level, 1);
     }
 
     #[test]
@@ -339,8 +1208,8 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
-        assert!(!config.verbose);
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert!(!config.verbose());
     }
 
     #[test]
@@ -356,8 +1225,25 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
-        assert!(config.json_output);
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn json_grouped_implies_json_unless_format_given() {
+        let args = ScanArgs {
+            json_grouped: true,
+            ..default_scan_args()
+        };
+        let config = Config::merge_scan(&args, &FileConfig::default()).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Json);
+
+        let args = ScanArgs {
+            format: Some(OutputFormat::Yaml),
+            ..args
+        };
+        let config = Config::merge_scan(&args, &FileConfig::default()).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Yaml);
     }
 
     #[test]
@@ -373,8 +1259,46 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
-        assert!(!config.json_output);
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Table);
+    }
+
+    #[test]
+    fn compact_overrides_json_from_file() {
+        let file = FileConfig {
+            scan: FileScanConfig {
+                json: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let args = ScanArgs {
+            compact: true,
+            ..default_scan_args()
+        };
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Compact);
+    }
+
+    #[test]
+    fn format_flag_beats_file_format() {
+        let file: FileConfig = toml::from_str("[scan]\nformat = \"csv\"\njson = true\n").unwrap();
+        let config = Config::merge_scan(&default_scan_args(), &file).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Csv);
+
+        let args = ScanArgs {
+            format: Some(OutputFormat::Prometheus),
+            ..default_scan_args()
+        };
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Prometheus);
+    }
+
+    #[test]
+    fn display_units_parsed_from_file() {
+        let file: FileConfig = toml::from_str("[display]\nunits = \"si\"").unwrap();
+        assert_eq!(file.display.units, Some(Units::Si));
+        assert!(toml::from_str::<FileConfig>("[display]\nunits = \"metric\"").is_err());
     }
 
     // ── merge_scan: disabled detectors ──────────────────────────────────────
@@ -385,7 +1309,7 @@ mod tests {
             no_docker: true,
             ..default_scan_args()
         };
-        let config = Config::merge_scan(&args, &FileConfig::default());
+        let config = Config::merge_scan(&args, &FileConfig::default()).unwrap();
         assert!(config.disabled_detectors.contains("docker"));
     }
 
@@ -395,12 +1319,39 @@ mod tests {
             disable: Some(vec!["xcode".to_string(), "caches".to_string()]),
             ..default_scan_args()
         };
-        let config = Config::merge_scan(&args, &FileConfig::default());
+        let config = Config::merge_scan(&args, &FileConfig::default()).unwrap();
         assert!(config.disabled_detectors.contains("xcode"));
         assert!(config.disabled_detectors.contains("caches"));
         assert!(!config.disabled_detectors.contains("docker"));
     }
 
+    #[test]
+    fn only_flag_disables_everything_else() {
+        let args = ScanArgs {
+            only: Some(vec!["docker".to_string()]),
+            ..default_scan_args()
+        };
+        let file = FileConfig {
+            detectors: FileDetectorsConfig {
+                docker: Some(FileDetector::Enabled(false)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert!(config.is_detector_enabled("docker"));
+        for name in [
+            "projects",
+            "caches",
+            "xcode",
+            "appsupport",
+            "database",
+            "system",
+        ] {
+            assert!(!config.is_detector_enabled(name), "{name} should be off");
+        }
+    }
+
     #[test]
     fn file_and_cli_disabled_merge() {
         let args = ScanArgs {
@@ -409,12 +1360,12 @@ mod tests {
         };
         let file = FileConfig {
             detectors: FileDetectorsConfig {
-                xcode: Some(false),
+                xcode: Some(FileDetector::Enabled(false)),
                 ..Default::default()
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
+        let config = Config::merge_scan(&args, &file).unwrap();
         assert!(config.disabled_detectors.contains("docker"));
         assert!(config.disabled_detectors.contains("xcode"));
     }
@@ -434,7 +1385,7 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
+        let config = Config::merge_scan(&args, &file).unwrap();
         assert_eq!(config.roots, vec![PathBuf::from("/cli/path")]);
     }
 
@@ -448,7 +1399,382 @@ mod tests {
             },
             ..Default::default()
         };
-        let config = Config::merge_scan(&args, &file);
+        let config = Config::merge_scan(&args, &file).unwrap();
         assert_eq!(config.roots, vec![PathBuf::from("/file/path")]);
     }
+
+    // ── ascii ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn ascii_precedence() {
+        assert!(ascii_from(true, None, Some(false), false, true));
+        assert!(ascii_from(false, Some("1"), Some(false), false, true));
+        // NO_COLOR only counts when non-empty
+        assert!(!ascii_from(false, Some(""), None, false, true));
+        assert!(!ascii_from(false, None, Some(false), false, false));
+        assert!(ascii_from(false, None, Some(true), false, true));
+        // piped output defaults to ASCII
+        assert!(ascii_from(false, None, None, false, false));
+        assert!(!ascii_from(false, None, None, false, true));
+        // so does CI, even on a pseudo-terminal, unless the file says otherwise
+        assert!(ascii_from(false, None, None, true, true));
+        assert!(!ascii_from(false, None, Some(false), true, true));
+    }
+
+    // ── HEFT_READ_ONLY ──────────────────────────────────────────────────────
+
+    #[test]
+    fn read_only_env_accepts_common_truthy_values() {
+        for value in ["1", "true", "YES", " yes "] {
+            assert!(read_only_env(Some(value)), "{value}");
+        }
+        for value in ["", "0", "false", "no"] {
+            assert!(!read_only_env(Some(value)), "{value}");
+        }
+        assert!(!read_only_env(None));
+    }
+
+    // ── HEFT_ROOTS ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn roots_env_accepts_commas_and_path_separator() {
+        let sep = if cfg!(windows) { ";" } else { ":" };
+        let value = format!("/a, /b{sep}/c,,");
+        assert_eq!(
+            parse_roots_env(std::ffi::OsStr::new(&value)),
+            vec![
+                PathBuf::from("/a"),
+                PathBuf::from("/b"),
+                PathBuf::from("/c")
+            ]
+        );
+    }
+
+    #[test]
+    fn roots_precedence_cli_env_file() {
+        let cli = Some(vec![PathBuf::from("/cli")]);
+        let env = Some(vec![PathBuf::from("/env")]);
+        let file = Some(vec![PathBuf::from("/file")]);
+
+        assert_eq!(
+            resolve_roots(cli, env.clone(), file.clone()),
+            vec![PathBuf::from("/cli")]
+        );
+        assert_eq!(
+            resolve_roots(None, env, file.clone()),
+            vec![PathBuf::from("/env")]
+        );
+        assert_eq!(
+            resolve_roots(None, None, file),
+            vec![PathBuf::from("/file")]
+        );
+    }
+
+    // ── roots list (--roots-from) ───────────────────────────────────────────
+
+    #[test]
+    fn roots_list_skips_blanks_and_comments() {
+        let content = "# curated projects\n/a/one\n\n   \n  /b/two  \n# /c/skipped\n";
+        assert_eq!(
+            parse_roots_list(content),
+            vec![PathBuf::from("/a/one"), PathBuf::from("/b/two")]
+        );
+    }
+
+    #[test]
+    fn roots_from_file_overrides_file_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("roots.txt");
+        std::fs::write(&list, "/from/list\n").unwrap();
+
+        let args = ScanArgs {
+            roots_from: Some(list),
+            ..default_scan_args()
+        };
+        let file = FileConfig {
+            scan: FileScanConfig {
+                roots: Some(vec![PathBuf::from("/file/path")]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert_eq!(config.roots, vec![PathBuf::from("/from/list")]);
+    }
+
+    #[test]
+    fn roots_from_missing_file_errors() {
+        let args = ScanArgs {
+            roots_from: Some(PathBuf::from("/nonexistent/heft-roots.txt")),
+            ..default_scan_args()
+        };
+        let err = Config::merge_scan(&args, &FileConfig::default())
+            .err()
+            .unwrap();
+        assert!(err.contains("failed to read roots"));
+    }
+
+    #[test]
+    fn system_roots_detected_per_platform() {
+        assert!(is_system_root(Path::new("/"), Platform::Linux));
+        assert!(is_system_root(Path::new("/usr/"), Platform::Linux));
+        assert!(is_system_root(Path::new("/System"), Platform::MacOS));
+        assert!(!is_system_root(Path::new("/System"), Platform::Linux));
+        assert!(is_system_root(Path::new("C:\\Windows"), Platform::Windows));
+        assert!(is_system_root(
+            Path::new("c:\\windows\\"),
+            Platform::Windows
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_system_root(dir.path(), Platform::Linux));
+        assert!(!is_system_root(
+            Path::new("/usr/local/src/app"),
+            Platform::Linux
+        ));
+    }
+
+    #[test]
+    fn config_lists_only_system_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            roots: vec![dir.path().to_path_buf(), PathBuf::from("/")],
+            platform: Platform::Linux,
+            ..Config::default()
+        };
+        assert_eq!(config.system_roots(), vec![Path::new("/")]);
+    }
+
+    #[test]
+    fn roots_from_empty_list_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("roots.txt");
+        std::fs::write(&list, "# nothing here\n\n").unwrap();
+        assert!(read_roots_from(&list)
+            .unwrap_err()
+            .contains("no roots found"));
+    }
+
+    // ── category overrides ──────────────────────────────────────────────────
+
+    #[test]
+    fn category_overrides_parse_from_toml() {
+        let file: FileConfig = toml::from_str(
+            r#"
+            [category_overrides]
+            "xcode DerivedData" = "project-artifacts"
+            "docker*" = "other"
+            "#,
+        )
+        .unwrap();
+        let config = Config::merge_scan(&default_scan_args(), &file).unwrap();
+        assert!(config.category_overrides.contains(&(
+            "xcode DerivedData".to_string(),
+            BloatCategory::ProjectArtifacts
+        )));
+        assert!(config
+            .category_overrides
+            .contains(&("docker*".to_string(), BloatCategory::Other)));
+    }
+
+    #[test]
+    fn per_detector_timeouts_fall_back_to_global() {
+        let file: FileConfig = toml::from_str(
+            r#"
+            [scan]
+            timeout = 10
+
+            [detectors]
+            xcode = false
+
+            [detectors.docker]
+            timeout = 20
+
+            [detectors.caches]
+            enabled = false
+            timeout = 2
+            "#,
+        )
+        .unwrap();
+        let config = Config::merge_scan(&default_scan_args(), &file).unwrap();
+        assert_eq!(config.timeout_for("docker"), Duration::from_secs(20));
+        assert_eq!(config.timeout_for("caches"), Duration::from_secs(2));
+        assert_eq!(config.timeout_for("projects"), Duration::from_secs(10));
+        assert!(!config.is_detector_enabled("xcode"));
+        assert!(!config.is_detector_enabled("caches"));
+        assert!(config.is_detector_enabled("docker"));
+
+        // --timeout is for everything
+        let args = ScanArgs {
+            timeout: Some(5),
+            ..default_scan_args()
+        };
+        let config = Config::merge_scan(&args, &file).unwrap();
+        assert_eq!(config.timeout_for("docker"), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn downloads_detector_is_opt_in() {
+        let config = Config::merge_scan(&default_scan_args(), &FileConfig::default()).unwrap();
+        assert!(!config.detect_downloads);
+        assert_eq!(config.downloads_min_age_days, DEFAULT_DOWNLOADS_AGE_DAYS);
+
+        let file: FileConfig = toml::from_str(
+            r#"
+            [detectors]
+            downloads = true
+            downloads_age = 30
+            "#,
+        )
+        .unwrap();
+        let config = Config::merge_scan(&default_scan_args(), &file).unwrap();
+        assert!(config.detect_downloads);
+        assert_eq!(config.downloads_min_age_days, 30);
+
+        // the flag wins over the file
+        let args = ScanArgs {
+            downloads_age: Some(7),
+            ..default_scan_args()
+        };
+        assert_eq!(
+            Config::merge_scan(&args, &file)
+                .unwrap()
+                .downloads_min_age_days,
+            7
+        );
+    }
+
+    #[test]
+    fn category_overrides_skip_unknown_category() {
+        let raw = BTreeMap::from([
+            ("npm cache".to_string(), "ProjectArtifacts".to_string()),
+            ("pip cache".to_string(), "system-cache".to_string()),
+        ]);
+        // only the clean --category spelling is accepted
+        assert_eq!(
+            category_overrides_from_file(&raw),
+            vec![("pip cache".to_string(), BloatCategory::SystemCache)]
+        );
+    }
+
+    #[test]
+    fn docker_context_read_from_detectors_section() {
+        let file: FileConfig =
+            toml::from_str("[detectors]\ndocker_context = \"build-box\"\n").unwrap();
+        let config = Config::merge_scan(&default_scan_args(), &file).unwrap();
+        assert_eq!(config.docker_context.as_deref(), Some("build-box"));
+        // a context is not a detector switch
+        assert!(config.is_detector_enabled("docker"));
+    }
+
+    #[test]
+    fn large_deletion_limits_from_clean_section() {
+        let file: FileConfig =
+            toml::from_str("[clean]\nlarge_threshold = \"200GB\"\nlarge_items = 50\n").unwrap();
+        let limit = large_deletion_from_file(&file.clean);
+        assert_eq!(limit.bytes, 200 * 1024 * 1024 * 1024);
+        assert_eq!(limit.items, 50);
+
+        let file: FileConfig = toml::from_str("[clean]\nlarge_threshold = \"lots\"\n").unwrap();
+        assert_eq!(
+            large_deletion_from_file(&file.clean),
+            LargeDeletion::default()
+        );
+    }
+
+    #[test]
+    fn never_delete_expands_home() {
+        let file: FileConfig =
+            toml::from_str("[clean]\nnever_delete = [\"~/.m2/repository\", \"/data/*\"]\n")
+                .unwrap();
+        let never = never_delete_from_file(&file.clean);
+        let home = platform::home_dir().unwrap();
+        assert_eq!(
+            never,
+            [
+                home.join(".m2/repository").to_string_lossy().into_owned(),
+                "/data/*".to_string()
+            ]
+        );
+        assert!(never_delete_from_file(&FileCleanConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn recent_snapshot_only_reused_for_default_shaped_cleans() {
+        use clap::Parser;
+        let clean_args = |argv: &[&str]| {
+            let cli = crate::cli::Cli::try_parse_from([&["heft", "clean"], argv].concat()).unwrap();
+            match cli.command {
+                crate::cli::Command::Clean(args) => args,
+                _ => unreachable!(),
+            }
+        };
+        let file: FileConfig = toml::from_str("[clean]\nuse_recent = 120\n").unwrap();
+
+        assert_eq!(
+            recent_snapshot_secs(&clean_args(&[]), &file.clean),
+            Some(120)
+        );
+        assert_eq!(
+            recent_snapshot_secs(&clean_args(&["--use-recent"]), &FileCleanConfig::default()),
+            Some(60)
+        );
+        assert_eq!(
+            recent_snapshot_secs(&clean_args(&["--use-recent", "10"]), &file.clean),
+            Some(10)
+        );
+        assert_eq!(
+            recent_snapshot_secs(&clean_args(&["--fresh"]), &file.clean),
+            None
+        );
+        // the snapshot came from a default scan, not one of these roots
+        assert_eq!(
+            recent_snapshot_secs(&clean_args(&["--roots", "/tmp"]), &file.clean),
+            None
+        );
+    }
+
+    // ── project artifact rules ──────────────────────────────────────────────
+
+    #[test]
+    fn project_artifact_rules_parse_from_toml() {
+        let file: FileConfig = toml::from_str(
+            r#"
+            [[project_artifact]]
+            dir_name = ".terraform"
+            requires_sibling = "*.tf"
+            cleanup_hint = "terraform init restores it"
+
+            [[project_artifact]]
+            dir_name = "zig-cache"
+            category = "other"
+
+            [[project_artifact]]
+            dir_name = "nested/dir"
+
+            [[project_artifact]]
+            dir_name = "out"
+            category = "bogus"
+            "#,
+        )
+        .unwrap();
+        let config = Config::merge_scan(&default_scan_args(), &file).unwrap();
+        assert_eq!(
+            config.artifact_rules,
+            vec![
+                ArtifactRule {
+                    dir_name: ".terraform".to_string(),
+                    requires_sibling: Some("*.tf".to_string()),
+                    category: BloatCategory::ProjectArtifacts,
+                    cleanup_hint: "terraform init restores it".to_string(),
+                },
+                ArtifactRule {
+                    dir_name: "zig-cache".to_string(),
+                    requires_sibling: None,
+                    category: BloatCategory::Other,
+                    cleanup_hint: "matched project_artifact rule for zig-cache".to_string(),
+                },
+            ]
+        );
+    }
 }
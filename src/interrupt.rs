@@ -0,0 +1,44 @@
+//! Ctrl-C handling for long-running operations.
+//!
+//! The first SIGINT only sets a flag; callers check it between units of work
+//! (e.g. between deletions in clean) and wind down cleanly so they can report
+//! what already happened. A second SIGINT exits immediately.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Exit code used when an operation stops early because of Ctrl-C (128 + SIGINT).
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Set along with the flag from `install`, for code too far from main to be
+/// handed it (the docker poll loop). Never set without an installed handler.
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether Ctrl-C was pressed since the handler was installed.
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Install the SIGINT handler and return the flag it sets.
+///
+/// Only one handler can be installed per process; if that fails the returned
+/// flag is never set and Ctrl-C keeps its default behaviour.
+pub fn install() -> Arc<AtomicBool> {
+    let flag = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&flag);
+
+    let result = ctrlc::set_handler(move || {
+        REQUESTED.store(true, Ordering::SeqCst);
+        if handler_flag.swap(true, Ordering::SeqCst) {
+            // second Ctrl-C: the user really wants out
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+        eprintln!("\ninterrupt received, stopping after the current item (Ctrl-C again to abort)");
+    });
+
+    if let Err(e) = result {
+        eprintln!("warning: could not install Ctrl-C handler: {e}");
+    }
+
+    flag
+}
@@ -1,9 +1,14 @@
 pub mod clean;
 pub mod cli;
 pub mod config;
+pub mod interrupt;
 pub mod platform;
+#[cfg(feature = "remote")]
+pub mod remote;
 pub mod report;
 pub mod scan;
 pub mod spinner;
 pub mod store;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod util;
@@ -1,29 +1,324 @@
 use clap::Parser;
 use heft::clean;
-use heft::cli::{CleanCategory, Cli, Command};
+use heft::cli::{
+    Cli, Command, DbArgs, DbCommand, DbExportFormat, EstimateArgs, InspectArgs, PathsArgs,
+    ScanArgs, SnapshotArgs, SnapshotCommand, SnapshotDeleteArgs, StatsArgs,
+};
 use heft::config::Config;
+use heft::interrupt;
 use heft::report;
 use heft::scan;
-use heft::scan::detector::BloatCategory;
+use heft::scan::detector::{BloatCategory, Location};
 use heft::store::diff::{DiffResult, DiffType};
-use heft::store::snapshot::Store;
+use heft::store::snapshot::{SnapshotFilter, Store};
 use heft::util;
 use std::collections::HashMap;
 
+/// Latest saved snapshot with its entries, for `scan --diff`. Load errors
+/// are treated as "no baseline" so the scan itself still prints.
+fn latest_with_entries(
+    store: &Store,
+) -> Option<(
+    heft::store::snapshot::Snapshot,
+    Vec<heft::scan::detector::BloatEntry>,
+)> {
+    let snapshot = store.get_latest_snapshot().ok()??;
+    let entries = store.load_snapshot_entries(snapshot.id).ok()?;
+    Some((snapshot, entries))
+}
+
+/// `clean --use-recent`: the latest snapshot as a scan result, if it is at
+/// most `max_age` seconds old and its scan ran with the settings clean's
+/// own scan would use. Entries whose path is gone are skipped.
+fn recent_snapshot(max_age: u64, config: &Config) -> Option<scan::ScanResult> {
+    let store = Store::open().ok()?;
+    let snapshot = store.get_latest_snapshot().ok()??;
+    let age = chrono::Utc::now()
+        .timestamp()
+        .saturating_sub(snapshot.timestamp);
+    if !(0..=i64::try_from(max_age).unwrap_or(i64::MAX)).contains(&age) {
+        return None;
+    }
+    if store.scan_config(snapshot.id).ok()? != Some(config.scan_key()) {
+        eprintln!(
+            "Snapshot {} was taken with different scan settings; scanning instead",
+            snapshot.id
+        );
+        return None;
+    }
+    let entries = store.load_snapshot_entries(snapshot.id).ok()?;
+    let (result, skipped) = clean::snapshot_result(entries);
+    eprintln!(
+        "Using snapshot {} from {age}s ago instead of scanning (--fresh to rescan)",
+        snapshot.id
+    );
+    for note in skipped {
+        eprintln!("{note}");
+    }
+    Some(result)
+}
+
+/// `scan --remote`: print the result without saving it, since remote paths
+/// must never end up in the local history that clean and diff work from.
+fn scan_remote(target: &str, config: &Config) {
+    #[cfg(feature = "remote")]
+    {
+        let result = target
+            .parse::<heft::remote::RemoteTarget>()
+            .and_then(|target| heft::remote::scan(&target, config));
+        match result {
+            Ok(result) => report::print(&result, config),
+            Err(e) => {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    #[cfg(not(feature = "remote"))]
+    {
+        let _ = (target, config);
+        eprintln!("error: this build of heft has no remote scanning; reinstall with `cargo install --path . --features remote`");
+        std::process::exit(1);
+    }
+}
+
+/// The settings a command runs with, or exit when they can't be worked out.
+fn config_or_exit(config: Result<Config, String>) -> Config {
+    config.unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    })
+}
+
+/// Exit before scanning if a root is a system directory, unless allowed.
+fn refuse_system_roots(config: &Config, allow: bool) {
+    let system = config.system_roots();
+    if system.is_empty() {
+        return;
+    }
+    for root in &system {
+        if allow {
+            eprintln!(
+                "warning: scanning system directory {} (expect permission errors and a slow scan)",
+                root.display()
+            );
+        } else {
+            eprintln!("error: {} is a system directory", root.display());
+        }
+    }
+    if !allow {
+        eprintln!("pass --allow-system-root to scan it anyway, or pick narrower --roots");
+        std::process::exit(1);
+    }
+}
+
+/// One side of `heft diff`: a stored snapshot, or "live" for a fresh scan.
+enum DiffSide {
+    Snapshot(i64),
+    Live,
+}
+
+fn parse_diff_side(value: &str, flag: &str) -> DiffSide {
+    if value.eq_ignore_ascii_case("live") {
+        return DiffSide::Live;
+    }
+    match value.parse() {
+        Ok(id) => DiffSide::Snapshot(id),
+        Err(_) => {
+            eprintln!("Invalid '{flag}' snapshot ID: '{value}'. Must be a number or 'live'.");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Id, timestamp and entries for one side of a diff. A live side scans with
+/// the same defaults as a plain `heft scan`, isn't saved, and gets id 0.
+fn load_diff_side(
+    store: &Store,
+    side: &DiffSide,
+) -> (i64, i64, Vec<heft::scan::detector::BloatEntry>) {
+    let id = match side {
+        DiffSide::Snapshot(id) => *id,
+        DiffSide::Live => {
+            let config = config_or_exit(Config::from_scan_args(&ScanArgs::default()));
+            refuse_system_roots(&config, false);
+            let result = scan::run(&config);
+            return (0, chrono::Utc::now().timestamp(), result.entries);
+        }
+    };
+
+    let snapshot = match store.get_snapshot(id) {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            eprintln!("Snapshot {id} not found");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error loading snapshot {id}: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match store.load_snapshot_entries(id) {
+        Ok(entries) => (id, snapshot.timestamp, entries),
+        Err(e) => {
+            eprintln!("Error loading entries for snapshot {id}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn side_label(id: i64, date: &str) -> String {
+    if id > 0 {
+        format!("#{id} ({date})")
+    } else {
+        format!("this scan, not saved ({date})")
+    }
+}
+
 fn print_diff(result: &DiffResult) {
-    let from_date = chrono::DateTime::from_timestamp(result.from_timestamp, 0)
-        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-        .unwrap_or_else(|| "unknown".to_string());
+    let from_date = format_timestamp(result.from_timestamp);
+    let to_date = format_timestamp(result.to_timestamp);
+
+    println!("\nComparing snapshots:");
+    println!("  From: {}", side_label(result.from_id, &from_date));
+    println!("  To:   {}", side_label(result.to_id, &to_date));
+    println!();
 
-    let to_date = chrono::DateTime::from_timestamp(result.to_timestamp, 0)
+    print_diff_changes(result);
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
         .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-        .unwrap_or_else(|| "unknown".to_string());
+        .unwrap_or_else(|| "unknown".to_string())
+}
 
-    println!("\nComparing snapshots:");
-    println!("  From: #{} ({from_date})", result.from_id);
-    println!("  To:   #{} ({to_date})", result.to_id);
+/// `clean --list-plans`: every saved plan, newest first.
+fn list_plans() {
+    let plans = match Store::open().and_then(|store| store.list_plans()) {
+        Ok(plans) => plans,
+        Err(e) => {
+            eprintln!("Error listing plans: {e}");
+            std::process::exit(1);
+        }
+    };
+    if plans.is_empty() {
+        println!("No saved plans. Create one with 'heft clean --dry-run --save-plan'.");
+        return;
+    }
+    println!("Plans:");
+    println!(
+        "{:<6} {:<20} {:<8} {:<12} Status",
+        "ID", "Created", "Entries", "Reclaimable"
+    );
+    println!("{}", "-".repeat(70));
+    for plan in plans {
+        let status = match plan.executed_at {
+            Some(at) => format!("executed {}", format_timestamp(at)),
+            None => "pending".to_string(),
+        };
+        println!(
+            "{:<6} {:<20} {:<8} {:<12} {status}",
+            plan.id,
+            format_timestamp(plan.created_at),
+            plan.entry_count,
+            util::format_bytes(plan.reclaimable_bytes)
+        );
+    }
+}
+
+/// `clean --dry-run --save-plan`: store what the dry run selected. The note
+/// goes to stderr so `--json` output stays clean.
+fn save_plan(entries: &[heft::scan::detector::BloatEntry]) {
+    let saved = Store::open().and_then(|mut store| store.save_plan(entries));
+    match saved {
+        Ok(id) => {
+            let bytes: u64 = entries.iter().map(|e| e.reclaimable_bytes).sum();
+            eprintln!(
+                "Saved plan {id} ({} entries, {}). Review with 'heft clean --list-plans', run with 'heft clean --execute-plan {id}'.",
+                entries.len(),
+                util::format_bytes(bytes)
+            );
+        }
+        Err(e) => {
+            eprintln!("Error saving plan: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `clean --execute-plan`: the entries of a pending plan. Running a plan a
+/// second time is refused; the first run is what was approved.
+fn load_saved_plan(id: i64) -> Vec<heft::scan::detector::BloatEntry> {
+    let loaded = Store::open().and_then(|store| {
+        let plan = store.get_plan(id)?;
+        let entries = match &plan {
+            Some(_) => store.load_plan_entries(id)?,
+            None => Vec::new(),
+        };
+        Ok(plan.map(|plan| (plan, entries)))
+    });
+    match loaded {
+        Ok(Some((plan, entries))) => {
+            if let Some(at) = plan.executed_at {
+                eprintln!(
+                    "error: plan {id} was already executed at {}",
+                    format_timestamp(at)
+                );
+                std::process::exit(1);
+            }
+            entries
+        }
+        Ok(None) => {
+            eprintln!("Plan {id} not found. Run 'heft clean --list-plans' to see saved plans.");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error loading plan {id}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `scan --baseline`: the changes since the baseline file, then the entries
+/// that grew past the tolerance. Returns how many did.
+fn print_baseline_diff(result: &DiffResult, path: &std::path::Path, tolerance: u64) -> usize {
+    println!("\nComparing against baseline:");
+    println!(
+        "  From: {} ({})",
+        path.display(),
+        format_timestamp(result.from_timestamp)
+    );
+    println!(
+        "  To:   {}",
+        side_label(result.to_id, &format_timestamp(result.to_timestamp))
+    );
     println!();
 
+    print_diff_changes(result);
+
+    let grown = heft::store::diff::growth_beyond(result, tolerance);
+    if !grown.is_empty() {
+        println!(
+            "\n{} entr{} grew more than {} past the baseline:",
+            grown.len(),
+            if grown.len() == 1 { "y" } else { "ies" },
+            util::format_bytes(tolerance)
+        );
+        for entry in &grown {
+            println!(
+                "  {} +{}",
+                entry.label(),
+                util::format_bytes(entry.delta.unsigned_abs())
+            );
+        }
+    }
+    grown.len()
+}
+
+/// The body of a diff: changes grouped by category, then the net change.
+fn print_diff_changes(result: &DiffResult) {
     if result.entries.is_empty() {
         println!("No changes detected.");
         return;
@@ -72,7 +367,7 @@ fn print_diff(result: &DiffResult) {
         for entry in grew {
             println!(
                 "  [+] {} grew {} -> {} (+{})",
-                entry.name,
+                entry.label(),
                 util::format_bytes(entry.old_size),
                 util::format_bytes(entry.new_size),
                 util::format_bytes(entry.delta.unsigned_abs())
@@ -82,7 +377,7 @@ fn print_diff(result: &DiffResult) {
         for entry in shrank {
             println!(
                 "  [-] {} shrank {} -> {} (-{})",
-                entry.name,
+                entry.label(),
                 util::format_bytes(entry.old_size),
                 util::format_bytes(entry.new_size),
                 util::format_bytes(entry.delta.unsigned_abs())
@@ -122,30 +417,529 @@ fn print_diff(result: &DiffResult) {
     }
 }
 
+fn run_snapshot(args: SnapshotArgs) {
+    use heft::store::export;
+
+    let mut store = match Store::open() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error opening snapshot store: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match args.command {
+        SnapshotCommand::Export(export_args) => {
+            if let Err(e) = export::export_to(&store, export_args.id, export_args.output.as_deref())
+            {
+                eprintln!("Error exporting snapshot {}: {e}", export_args.id);
+                std::process::exit(1);
+            }
+            if let Some(path) = &export_args.output {
+                eprintln!("exported snapshot {} to {}", export_args.id, path.display());
+            }
+        }
+        SnapshotCommand::Import(import_args) => {
+            match export::import_from(&mut store, &import_args.path) {
+                Ok(id) => println!("imported {} as snapshot {id}", import_args.path.display()),
+                Err(e) => {
+                    eprintln!("Error importing {}: {e}", import_args.path.display());
+                    std::process::exit(1);
+                }
+            }
+        }
+        SnapshotCommand::Delete(delete_args) => delete_snapshots(&store, &delete_args),
+        SnapshotCommand::Annotate(annotate_args) => {
+            let id = annotate_args.id;
+            match store.set_note(id, &annotate_args.note) {
+                Ok(true) if annotate_args.note.trim().is_empty() => {
+                    println!("removed the note from snapshot {id}")
+                }
+                Ok(true) => println!("annotated snapshot {id}"),
+                Ok(false) => {
+                    eprintln!(
+                        "Snapshot {id} not found. Run 'heft report --list' to see saved snapshots."
+                    );
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error annotating snapshot {id}: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// `heft snapshot delete --older-than`: drop old history in one go. Refuses
+/// to empty the history unless --all is given.
+fn delete_snapshots(store: &Store, args: &SnapshotDeleteArgs) {
+    let (all, old) = match (
+        store.list_snapshots(),
+        store.list_snapshots_filtered(&SnapshotFilter {
+            until: Some(args.older_than),
+            ..Default::default()
+        }),
+    ) {
+        (Ok(all), Ok(old)) => (all, old),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error listing snapshots: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    if old.is_empty() {
+        println!("No snapshots older than that.");
+        return;
+    }
+    if old.len() == all.len() && !args.all {
+        eprintln!(
+            "This would delete all {} snapshots. Pass --all to confirm.",
+            all.len()
+        );
+        std::process::exit(1);
+    }
+
+    if args.dry_run {
+        println!("Would delete {} snapshot(s):", old.len());
+        for snapshot in &old {
+            let datetime = chrono::DateTime::from_timestamp(snapshot.timestamp, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            let entries = store.entry_count(snapshot.id).unwrap_or(0);
+            println!("  {:<6} {:<20} {entries} entries", snapshot.id, datetime);
+        }
+        return;
+    }
+
+    match store.delete_snapshots_before(args.older_than) {
+        Ok(deleted) => println!("Deleted {deleted} snapshot(s)."),
+        Err(e) => {
+            eprintln!("Error deleting snapshots: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The database with its -wal and -shm files, as the database detector
+/// counts it; 0 when there is none.
+fn database_size() -> u64 {
+    heft::store::snapshot::db_path()
+        .as_deref()
+        .and_then(heft::store::snapshot::database_size)
+        .unwrap_or(0)
+}
+
+fn run_db(args: DbArgs) {
+    // before opening, which creates the -shm file
+    let size_before = database_size();
+    let store = match Store::open() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error opening snapshot store: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match args.command {
+        DbCommand::Vacuum => {
+            if let Err(e) = store.vacuum() {
+                eprintln!("Error vacuuming database: {e}");
+                std::process::exit(1);
+            }
+            // closing removes the -shm file again
+            drop(store);
+            let (before, after) = (size_before, database_size());
+
+            println!(
+                "database vacuumed: {} -> {} ({} freed)",
+                util::format_bytes(before),
+                util::format_bytes(after),
+                util::format_bytes(before.saturating_sub(after))
+            );
+        }
+        DbCommand::Export(export_args) => {
+            use heft::store::export;
+            use std::io::BufWriter;
+
+            let result = match (export_args.format, &export_args.output) {
+                (DbExportFormat::Ndjson, Some(path)) => std::fs::File::create(path)
+                    .map_err(|e| format!("failed to create {}: {e}", path.display()).into())
+                    .and_then(|file| export::write_entries_ndjson(&store, BufWriter::new(file))),
+                (DbExportFormat::Ndjson, None) => {
+                    export::write_entries_ndjson(&store, BufWriter::new(std::io::stdout().lock()))
+                }
+            };
+
+            match result {
+                Ok(count) => {
+                    if let Some(path) = &export_args.output {
+                        eprintln!("exported {count} entries to {}", path.display());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error exporting database: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// `heft stats`: averages, peak and freed space across the snapshots since
+/// `--last`.
+fn run_stats(args: StatsArgs) {
+    use heft::store::snapshot::SnapshotFilter;
+    use heft::store::stats;
+
+    let store = match Store::open() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error opening snapshot store: {e}");
+            std::process::exit(1);
+        }
+    };
+    // snapshots dated in the future (a skewed clock, an import) aren't part
+    // of the last N days
+    let filter = SnapshotFilter {
+        since: Some(args.last),
+        until: Some(chrono::Utc::now().timestamp().saturating_add(1)),
+        ..Default::default()
+    };
+    let series: Result<Vec<_>, _> = store
+        .list_snapshots_filtered(&filter)
+        .and_then(|snapshots| {
+            snapshots
+                .into_iter()
+                .rev()
+                .map(|snapshot| {
+                    let entries = store.load_snapshot_entries(snapshot.id)?;
+                    Ok((snapshot, stats::largest_category(&entries)))
+                })
+                .collect()
+        });
+    let series = match series {
+        Ok(series) => series,
+        Err(e) => {
+            eprintln!("Error reading snapshots: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let Some(stats) = stats::summarize(&series) else {
+        println!("No snapshots in that time range.");
+        return;
+    };
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&stats).unwrap_or_default()
+        );
+        return;
+    }
+
+    let day = |timestamp| {
+        chrono::DateTime::from_timestamp(timestamp, 0)
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+    println!(
+        "{:<22} {} ({} to {})",
+        "snapshots",
+        stats.snapshots,
+        day(stats.from_timestamp),
+        day(stats.to_timestamp)
+    );
+    println!(
+        "{:<22} {}",
+        "average reclaimable",
+        util::format_bytes(stats.average_reclaimable)
+    );
+    println!(
+        "{:<22} {} (snapshot {}, {})",
+        "peak reclaimable",
+        util::format_bytes(stats.peak_reclaimable),
+        stats.peak_snapshot_id,
+        day(stats.peak_timestamp)
+    );
+    println!(
+        "{:<22} {}",
+        "freed between scans",
+        util::format_bytes(stats.freed_bytes)
+    );
+    if let Some(category) = stats.top_category {
+        println!(
+            "{:<22} {} (in {} of {} snapshots)",
+            "most often largest",
+            category.label(),
+            stats.top_category_count,
+            stats.snapshots
+        );
+    }
+}
+
+/// `heft paths`: the config file and database locations, the platform and
+/// the roots a plain `heft scan` would use.
+fn run_paths(args: PathsArgs) {
+    let describe = |path: Option<std::path::PathBuf>| match path {
+        Some(path) => match std::fs::metadata(&path) {
+            Ok(meta) => format!(
+                "{} (exists, {})",
+                path.display(),
+                util::format_bytes(meta.len())
+            ),
+            Err(_) => format!("{} (not found)", path.display()),
+        },
+        None => "unknown (no home directory)".to_string(),
+    };
+    let db_path = heft::store::snapshot::db_path();
+
+    if args.open {
+        let Some(data_dir) = db_path.as_ref().and_then(|p| p.parent()) else {
+            eprintln!("Error: could not determine the data directory");
+            std::process::exit(1);
+        };
+        if !data_dir.is_dir() {
+            eprintln!(
+                "Error: {} does not exist yet; run 'heft scan' to create it",
+                data_dir.display()
+            );
+            std::process::exit(1);
+        }
+        let opener = match heft::platform::detect() {
+            heft::platform::Platform::MacOS => "open",
+            heft::platform::Platform::Windows => "explorer",
+            _ => "xdg-open",
+        };
+        // explorer exits 1 even when it opened the folder, so only a failure
+        // to start counts
+        if let Err(e) = std::process::Command::new(opener).arg(data_dir).status() {
+            eprintln!("Error: could not run {opener}: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let config = config_or_exit(Config::from_scan_args(&ScanArgs::default()));
+    println!("{:<10} {}", "config", describe(heft::config::config_path()));
+    println!("{:<10} {}", "database", describe(db_path));
+    println!("{:<10} {}", "platform", config.platform.name());
+    for (i, root) in config.roots.iter().enumerate() {
+        let label = if i == 0 { "roots" } else { "" };
+        println!("{label:<10} {}", root.display());
+    }
+}
+
+/// `heft estimate`: a depth-limited walk that recognizes artifacts without
+/// sizing them, so huge trees can be gauged before a full scan.
+fn run_estimate(args: EstimateArgs) {
+    let config = config_or_exit(Config::from_scan_args(&ScanArgs {
+        roots: args.roots,
+        exclude: args.exclude,
+        ..Default::default()
+    }));
+
+    let mut total = scan::projects::Estimate::default();
+    for root in &config.roots {
+        if !root.exists() {
+            eprintln!("skipping {}: directory does not exist", root.display());
+            continue;
+        }
+        let estimate = scan::projects::estimate(root, &config, args.max_depth);
+        if config.roots.len() > 1 {
+            println!(
+                "{}: ~{} candidates, ~{} entries",
+                root.display(),
+                estimate.candidates,
+                estimate.entries
+            );
+        }
+        total.add(estimate);
+    }
+    println!(
+        "{}",
+        util::plain(&report::estimate_summary(&total, args.max_depth))
+    );
+}
+
+/// `heft inspect`: du-style breakdown of one directory by immediate child.
+fn run_inspect(args: InspectArgs) {
+    let (children, warnings) = match scan::child_sizes(&args.path) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("error: cannot inspect {}: {e}", args.path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let total: u64 = children.iter().map(|c| c.size_bytes).sum();
+    println!("{} ({})\n", args.path.display(), util::format_bytes(total));
+    for child in children.iter().take(args.top) {
+        let name = child
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let share = if total > 0 {
+            child.size_bytes as f64 * 100.0 / total as f64
+        } else {
+            0.0
+        };
+        println!(
+            "  {:>10}  {share:>5.1}%  {name}{}",
+            util::format_bytes(child.size_bytes),
+            if child.is_dir { "/" } else { "" }
+        );
+    }
+    if children.len() > args.top {
+        let rest = &children[args.top..];
+        println!(
+            "  {:>10}          ... and {} more",
+            util::format_bytes(rest.iter().map(|c| c.size_bytes).sum()),
+            rest.len()
+        );
+    }
+    if !warnings.is_empty() {
+        eprintln!(
+            "\n{} warnings (sizes may be underestimated), e.g. {}",
+            warnings.len(),
+            warnings[0]
+        );
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
+    util::set_units(heft::config::resolve_units(cli.units));
+    util::set_read_only(heft::config::resolve_read_only(cli.read_only));
+    util::set_ascii(heft::config::resolve_ascii(cli.ascii));
 
     match cli.command {
         Command::Scan(args) => {
-            let config = Config::from_scan_args(&args);
+            let config = config_or_exit(Config::from_scan_args(&args));
+
+            if let Some(ref remote) = args.remote {
+                scan_remote(remote, &config);
+                return;
+            }
+
+            refuse_system_roots(&config, args.allow_system_root);
+
+            // read the baseline first so a bad file fails before a long scan
+            let file_baseline = args.baseline.as_deref().map(|path| {
+                match heft::store::export::read_baseline(path) {
+                    Ok(baseline) => baseline,
+                    Err(e) => {
+                        eprintln!("Error reading baseline {}: {e}", path.display());
+                        std::process::exit(1);
+                    }
+                }
+            });
+
             let result = scan::run(&config);
 
-            match Store::open() {
-                Ok(mut store) => {
-                    if let Err(e) = store.save_snapshot(&result) {
-                        if config.verbose {
+            let mut store = match Store::open() {
+                Ok(store) => Some(store),
+                Err(e) => {
+                    if config.verbose() {
+                        eprintln!("warning: failed to open snapshot store: {e}");
+                    }
+                    None
+                }
+            };
+
+            // load the baseline before saving, otherwise it would be this scan
+            let baseline = if args.diff {
+                store.as_ref().and_then(latest_with_entries)
+            } else {
+                None
+            };
+
+            let mut saved_id = None;
+            if util::read_only() {
+                if config.verbose() {
+                    eprintln!("read-only mode: snapshot not saved");
+                }
+            } else if config.skip_sizes {
+                // all-zero sizes would read as everything shrinking in the next diff
+                if config.verbose() {
+                    eprintln!("--no-sizes: snapshot not saved");
+                }
+            } else if let Some(store) = store.as_mut() {
+                match store.save_snapshot(&result) {
+                    Ok(id) => {
+                        saved_id = Some(id);
+                        // only lets clean --use-recent reuse it; not worth a warning
+                        let _ = store.set_scan_config(id, &config.scan_key());
+                    }
+                    Err(e) => {
+                        if config.verbose() {
                             eprintln!("warning: failed to save snapshot: {e}");
                         }
                     }
                 }
-                Err(e) => {
-                    if config.verbose {
-                        eprintln!("warning: failed to open snapshot store: {e}");
+            }
+
+            if args.tui {
+                #[cfg(feature = "tui")]
+                if let Err(e) = heft::tui::run(
+                    &result,
+                    &config.never_delete,
+                    config.docker_context.as_deref(),
+                ) {
+                    eprintln!("Error running TUI: {e}");
+                    std::process::exit(1);
+                }
+                #[cfg(not(feature = "tui"))]
+                {
+                    eprintln!("error: this build of heft has no TUI; reinstall with `cargo install --path . --features tui`");
+                    std::process::exit(1);
+                }
+            } else {
+                report::print(&result, &config);
+            }
+
+            if args.diff {
+                match baseline {
+                    Some((snapshot, entries)) => {
+                        let now = chrono::Utc::now().timestamp();
+                        let diff_result = heft::store::diff::compare_entries(
+                            &entries,
+                            &result.entries,
+                            snapshot.id,
+                            saved_id.unwrap_or(0),
+                            snapshot.timestamp,
+                            now,
+                            false,
+                        );
+                        print_diff(&diff_result);
                     }
+                    None => println!("\nNo previous snapshot to compare against."),
                 }
             }
 
-            report::print(&result, &config);
+            if let (Some(path), Some(baseline)) = (args.baseline.as_deref(), file_baseline) {
+                let diff_result = heft::store::diff::compare_entries(
+                    &baseline.entries,
+                    &result.entries,
+                    0,
+                    saved_id.unwrap_or(0),
+                    baseline.timestamp,
+                    chrono::Utc::now().timestamp(),
+                    false,
+                );
+                let grown =
+                    print_baseline_diff(&diff_result, path, args.growth_tolerance.unwrap_or(0));
+                if grown > 0 && args.fail_on_growth {
+                    std::process::exit(1);
+                }
+            }
+
+            if result.entries.is_empty() {
+                if let Some(code) = args.empty_exit_code {
+                    std::process::exit(code);
+                }
+            }
         }
         Command::Report(args) => {
             let store = match Store::open() {
@@ -157,14 +951,22 @@ fn main() {
             };
 
             if args.list {
-                match store.list_snapshots() {
+                let filter = SnapshotFilter {
+                    since: args.since,
+                    until: args.until,
+                    limit: args.limit,
+                };
+                let filtered = args.since.is_some() || args.until.is_some();
+                match store.list_snapshots_filtered(&filter) {
                     Ok(snapshots) => {
-                        if snapshots.is_empty() {
+                        if snapshots.is_empty() && filtered {
+                            println!("No snapshots in that time range.");
+                        } else if snapshots.is_empty() {
                             println!("No snapshots found. Run 'heft scan' to create one.");
                         } else {
                             println!("Snapshots:");
                             println!(
-                                "{:<6} {:<20} {:<12} {:<12}",
+                                "{:<6} {:<20} {:<12} {:<12} Note",
                                 "ID", "Date", "Total", "Reclaimable"
                             );
                             println!("{}", "-".repeat(60));
@@ -178,10 +980,17 @@ fn main() {
                                 let total = util::format_bytes(snapshot.total_bytes);
                                 let reclaimable = util::format_bytes(snapshot.reclaimable_bytes);
 
-                                println!(
-                                    "{:<6} {:<20} {:<12} {:<12}",
+                                let note = snapshot
+                                    .note
+                                    .as_deref()
+                                    .map(|n| report::table::truncate(n, 40))
+                                    .unwrap_or_default();
+
+                                let line = format!(
+                                    "{:<6} {:<20} {:<12} {:<12} {note}",
                                     snapshot.id, datetime, total, reclaimable
                                 );
+                                println!("{}", line.trim_end());
                             }
                         }
                     }
@@ -191,45 +1000,93 @@ fn main() {
                     }
                 }
             } else {
-                let snapshot_result = if let Some(id_str) = &args.id {
-                    let id: i64 = id_str.parse().unwrap_or_else(|_| {
+                if args.group_by == report::GroupBy::Root {
+                    eprintln!(
+                        "Error: --group-by root needs the scan roots, which snapshots don't record"
+                    );
+                    std::process::exit(1);
+                }
+                let requested_id: Option<i64> = args.id.as_deref().map(|id_str| {
+                    id_str.trim().parse().unwrap_or_else(|_| {
                         eprintln!("Invalid snapshot ID: '{id_str}'. Must be a number.");
                         std::process::exit(1);
-                    });
-                    store.get_snapshot(id)
-                } else {
-                    store.get_latest_snapshot()
+                    })
+                });
+                let snapshot_result = match requested_id {
+                    Some(id) => store.get_snapshot(id),
+                    None => store.get_latest_snapshot(),
                 };
 
                 match snapshot_result {
                     Ok(Some(snapshot)) => {
-                        let entries = match store.load_snapshot_entries(snapshot.id) {
+                        let mut entries = match store.load_snapshot_entries(snapshot.id) {
                             Ok(e) => e,
                             Err(e) => {
                                 eprintln!("Error loading snapshot entries: {e}");
                                 std::process::exit(1);
                             }
                         };
+                        entries.retain(|e| e.has_any_tag(&args.tag));
 
                         let scan_result = scan::ScanResult {
                             entries,
                             diagnostics: vec![],
-                            duration_ms: Some(snapshot.scan_duration_ms as u128),
+                            duration_ms: Some(snapshot.scan_duration_ms),
                             detector_timings: vec![],
                             peak_memory_bytes: snapshot.peak_memory_bytes,
                             detector_memory: vec![],
+                            files_scanned: None,
+                            disks: vec![],
+                            coverage: vec![],
+                        };
+
+                        let format = match args.format {
+                            Some(format) => format,
+                            None if args.json => report::OutputFormat::Json,
+                            None => report::OutputFormat::Table,
                         };
+                        let age_secs = chrono::Utc::now()
+                            .timestamp()
+                            .saturating_sub(snapshot.timestamp)
+                            .max(0) as u64;
+                        let stale = report::stale_snapshot_warning(age_secs);
 
-                        if args.json {
-                            println!("{}", report::json::render(&scan_result));
+                        if format == report::OutputFormat::Json {
+                            println!("{}", report::json::render_snapshot(&scan_result, age_secs));
+                        } else if format != report::OutputFormat::Table {
+                            print!("{}", report::render(&scan_result, format));
+                        }
+                        if format != report::OutputFormat::Table {
+                            if args.group_by != report::GroupBy::Category {
+                                eprintln!(
+                                    "warning: --group-by only applies to the table output; ignored"
+                                );
+                            }
+                            // stdout is probably a pipe; keep the warning where a human sees it
+                            if let Some(warning) = stale {
+                                eprintln!("Warning: {}", util::plain(&warning));
+                            }
                         } else {
-                            print!("{}", report::table::render(&scan_result));
+                            if let Some(warning) = &stale {
+                                println!("{}", util::plain(warning));
+                            }
+                            let options = report::table::TableOptions {
+                                group_by: args.group_by,
+                                ..Default::default()
+                            };
+                            print!(
+                                "{}",
+                                util::plain(&report::table::render_with(&scan_result, &options))
+                            );
 
                             let datetime = chrono::DateTime::from_timestamp(snapshot.timestamp, 0)
                                 .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
                                 .unwrap_or_else(|| "unknown".to_string());
 
                             println!("\nsnapshot: {} ({datetime})", snapshot.id);
+                            if let Some(note) = &snapshot.note {
+                                println!("note: {note}");
+                            }
                             println!(
                                 "scan duration: {:.2}s",
                                 snapshot.scan_duration_ms as f64 / 1000.0
@@ -243,19 +1100,101 @@ fn main() {
                         }
                     }
                     Ok(None) => {
-                        eprintln!("No snapshots found. Run 'heft scan' to create one.");
+                        match requested_id {
+                            Some(id) => eprintln!(
+                                "Snapshot {id} not found. Run 'heft report --list' to see saved snapshots."
+                            ),
+                            None => eprintln!("No snapshots found. Run 'heft scan' to create one."),
+                        }
                         std::process::exit(1);
                     }
                     Err(e) => {
-                        eprintln!("Error loading snapshot: {e}");
+                        match requested_id {
+                            Some(id) => eprintln!("Error loading snapshot {id}: {e}"),
+                            None => eprintln!("Error loading snapshot: {e}"),
+                        }
                         std::process::exit(1);
                     }
                 }
             }
         }
         Command::Clean(args) => {
-            let config = Config::from_clean_args(&args);
-            let scan_result = scan::run(&config);
+            if util::read_only() {
+                eprintln!(
+                    "error: clean is disabled in read-only mode (--read-only or HEFT_READ_ONLY)"
+                );
+                std::process::exit(1);
+            }
+            if args.list_plans {
+                list_plans();
+                return;
+            }
+            let config = config_or_exit(Config::from_clean_args(&args));
+            // bad category names are caught before the scan, not after
+            let categories = |patterns: Option<Vec<String>>| {
+                patterns.map(|patterns| {
+                    clean::parse_category_patterns(&patterns).unwrap_or_else(|e| {
+                        eprintln!("error: {e}");
+                        std::process::exit(1);
+                    })
+                })
+            };
+            let category_filter = categories(args.category.clone());
+            let exclude_categories = categories(args.exclude_category.clone()).unwrap_or_default();
+            // prompts and JSON would share stdout
+            if args.json && !args.yes && !args.dry_run {
+                eprintln!("error: --json needs --yes or --dry-run");
+                std::process::exit(1);
+            }
+            // nobody is there to answer the prompts; fail before the scan
+            // instead of hanging after it
+            if !args.yes && !args.dry_run && heft::platform::is_ci() {
+                eprintln!("error: running in CI, so clean can't prompt; pass --yes to delete or --dry-run to preview");
+                std::process::exit(1);
+            }
+            let scan_result = match (args.from_plan.as_deref(), args.execute_plan) {
+                (Some(plan), _) => match clean::load_plan(plan) {
+                    Ok((result, skipped)) => {
+                        for note in skipped {
+                            eprintln!("{note}");
+                        }
+                        result
+                    }
+                    Err(e) => {
+                        eprintln!("Error reading plan {}: {e}", plan.display());
+                        std::process::exit(1);
+                    }
+                },
+                (None, Some(id)) => {
+                    let (result, skipped) = clean::plan_result(load_saved_plan(id));
+                    for note in skipped {
+                        eprintln!("{note}");
+                    }
+                    result
+                }
+                (None, None) => match config
+                    .recent_snapshot_secs
+                    .and_then(|secs| recent_snapshot(secs, &config))
+                {
+                    Some(result) => result,
+                    None => {
+                        refuse_system_roots(&config, args.allow_system_root);
+                        scan::run(&config)
+                    }
+                },
+            };
+
+            if scan_result.entries.is_empty() {
+                if args.json {
+                    println!("{}", clean::CleanResult::default().to_json());
+                } else if args.from_plan.is_some() || args.execute_plan.is_some() {
+                    println!("Nothing to clean: no entry in the plan still exists.");
+                } else {
+                    println!("Nothing to clean: the scan found no bloat.");
+                    println!("{}", report::empty_scan_hint(&config.roots));
+                }
+                std::process::exit(args.empty_exit_code.unwrap_or(0));
+            }
 
             let mode = if args.dry_run {
                 clean::CleanMode::DryRun
@@ -265,37 +1204,90 @@ fn main() {
                 clean::CleanMode::Interactive
             };
 
-            let category_filter = args.category.map(|cats| {
-                cats.into_iter()
-                    .map(|c| match c {
-                        CleanCategory::ProjectArtifacts => {
-                            heft::scan::detector::BloatCategory::ProjectArtifacts
-                        }
-                        CleanCategory::ContainerData => {
-                            heft::scan::detector::BloatCategory::ContainerData
-                        }
-                        CleanCategory::PackageCache => {
-                            heft::scan::detector::BloatCategory::PackageCache
-                        }
-                        CleanCategory::IdeData => heft::scan::detector::BloatCategory::IdeData,
-                        CleanCategory::SystemCache => {
-                            heft::scan::detector::BloatCategory::SystemCache
-                        }
-                        CleanCategory::Other => heft::scan::detector::BloatCategory::Other,
-                    })
-                    .collect()
+            // dry runs delete nothing, so Ctrl-C can keep its default behaviour
+            let interrupt = (mode != clean::CleanMode::DryRun).then(interrupt::install);
+
+            // --path must name something the scan actually reported; clean
+            // never deletes arbitrary paths
+            let paths = args.path.map(|paths| {
+                let cwd = std::env::current_dir().unwrap_or_default();
+                paths.into_iter().map(|p| cwd.join(p)).collect::<Vec<_>>()
             });
+            if let Some(ref paths) = paths {
+                for path in paths {
+                    let found = scan_result.entries.iter().any(
+                        |e| matches!(e.location, Location::FilesystemPath(ref p) if p == path),
+                    );
+                    if !found {
+                        eprintln!(
+                            "error: {} is not an entry found by this scan",
+                            path.display()
+                        );
+                        std::process::exit(1);
+                    }
+                }
+            }
 
-            let clean_result = clean::run(&scan_result, mode, category_filter);
+            let options = clean::CleanOptions {
+                category_filter,
+                exclude_categories,
+                paths,
+                names: args.name,
+                tags: args.tag,
+                modified_before: args
+                    .min_age
+                    .map(|days| chrono::Utc::now().timestamp() - i64::from(days) * 86_400),
+                verify_tolerance: args.verify,
+                target_bytes: args.target,
+                goal_bytes: args.goal,
+                use_hints: args.use_hints,
+                prune_empty_parents: args.prune_empty_parents.then(|| config.roots.clone()),
+                docker_context: config.docker_context.clone(),
+                large_deletion: (!args.confirm_large).then_some(config.large_deletion),
+                never_delete: config.never_delete.clone(),
+                interrupt,
+            };
+            let clean_result = clean::run(&scan_result, mode, &options);
+            if args.save_plan {
+                save_plan(&clean::planned_entries(&scan_result, &clean_result));
+            }
+            if let Some(id) = args.execute_plan {
+                let ran = mode != clean::CleanMode::DryRun
+                    && clean_result.refused.is_none()
+                    && !clean_result.actions.is_empty();
+                if ran {
+                    if let Err(e) = Store::open().and_then(|store| store.mark_plan_executed(id)) {
+                        eprintln!("Warning: could not mark plan {id} as executed: {e}");
+                    }
+                }
+            }
+            if args.json {
+                println!("{}", clean_result.to_json());
+                if clean_result.refused.is_some() {
+                    std::process::exit(1);
+                }
+                if clean_result.interrupted {
+                    std::process::exit(interrupt::EXIT_INTERRUPTED);
+                }
+                return;
+            }
+            if let Some(reason) = &clean_result.refused {
+                eprintln!("error: {reason}");
+                std::process::exit(1);
+            }
 
+            for path in &clean_result.protected {
+                eprintln!("kept {path}: protected by clean.never_delete");
+            }
+            let errors = clean_result.errors();
             if !matches!(mode, clean::CleanMode::Interactive) {
-                for item in &clean_result.deleted {
+                for item in clean_result.deleted() {
                     println!("{item}");
                 }
 
-                if !clean_result.errors.is_empty() {
+                if !errors.is_empty() {
                     eprintln!("\nerrors encountered:");
-                    for error in &clean_result.errors {
+                    for error in &errors {
                         eprintln!("  {error}");
                     }
                 }
@@ -306,12 +1298,47 @@ fn main() {
                 } else {
                     println!("\nfreed: {mb_freed:.2} MB");
                 }
-            } else if !clean_result.errors.is_empty() {
+            } else if !errors.is_empty() {
                 eprintln!("\nerrors encountered:");
-                for error in &clean_result.errors {
+                for error in &errors {
                     eprintln!("  {error}");
                 }
             }
+
+            if clean_result.interrupted {
+                if matches!(mode, clean::CleanMode::Interactive) {
+                    for item in clean_result.deleted() {
+                        println!("{item}");
+                    }
+                }
+                eprintln!(
+                    "\ninterrupted: {} entries deleted, {} freed before stopping",
+                    clean_result.deleted().len(),
+                    util::format_bytes(clean_result.bytes_freed)
+                );
+                std::process::exit(interrupt::EXIT_INTERRUPTED);
+            }
+
+            if let Some(target) = args.target {
+                if clean_result.bytes_freed < target {
+                    println!(
+                        "note: target of {} not reached, {} short",
+                        util::format_bytes(target),
+                        util::format_bytes(target - clean_result.bytes_freed)
+                    );
+                }
+            }
+            if let Some(goal) = args.goal {
+                let verb = if args.dry_run {
+                    "Would reclaim"
+                } else {
+                    "Reclaimed"
+                };
+                println!(
+                    "{verb} {}",
+                    util::goal_progress(clean_result.bytes_freed, goal)
+                );
+            }
         }
         Command::Diff(args) => {
             use heft::store::diff;
@@ -330,16 +1357,11 @@ fn main() {
                 std::process::exit(1);
             }
 
-            let (from_id, to_id) = if let (Some(from_str), Some(to_str)) = (&args.from, &args.to) {
-                let from: i64 = from_str.parse().unwrap_or_else(|_| {
-                    eprintln!("Invalid 'from' snapshot ID: '{from_str}'. Must be a number.");
-                    std::process::exit(1);
-                });
-                let to: i64 = to_str.parse().unwrap_or_else(|_| {
-                    eprintln!("Invalid 'to' snapshot ID: '{to_str}'. Must be a number.");
-                    std::process::exit(1);
-                });
-                (from, to)
+            let (from, to) = if let (Some(from_str), Some(to_str)) = (&args.from, &args.to) {
+                (
+                    parse_diff_side(from_str, "from"),
+                    parse_diff_side(to_str, "to"),
+                )
             } else {
                 match store.list_snapshots() {
                     Ok(snapshots) => {
@@ -347,7 +1369,10 @@ fn main() {
                             eprintln!("Need at least 2 snapshots to compare. Run 'heft scan' a few times.");
                             std::process::exit(1);
                         }
-                        (snapshots[1].id, snapshots[0].id)
+                        (
+                            DiffSide::Snapshot(snapshots[1].id),
+                            DiffSide::Snapshot(snapshots[0].id),
+                        )
                     }
                     Err(e) => {
                         eprintln!("Error loading snapshots: {e}");
@@ -356,56 +1381,34 @@ fn main() {
                 }
             };
 
-            let from_snapshot = match store.get_snapshot(from_id) {
-                Ok(Some(s)) => s,
-                Ok(None) => {
-                    eprintln!("Snapshot {from_id} not found");
-                    std::process::exit(1);
-                }
-                Err(e) => {
-                    eprintln!("Error loading snapshot {from_id}: {e}");
-                    std::process::exit(1);
-                }
-            };
-
-            let to_snapshot = match store.get_snapshot(to_id) {
-                Ok(Some(s)) => s,
-                Ok(None) => {
-                    eprintln!("Snapshot {to_id} not found");
-                    std::process::exit(1);
-                }
-                Err(e) => {
-                    eprintln!("Error loading snapshot {to_id}: {e}");
-                    std::process::exit(1);
-                }
-            };
-
-            let from_entries = match store.load_snapshot_entries(from_id) {
-                Ok(entries) => entries,
-                Err(e) => {
-                    eprintln!("Error loading entries for snapshot {from_id}: {e}");
-                    std::process::exit(1);
-                }
-            };
+            if matches!((&from, &to), (DiffSide::Live, DiffSide::Live)) {
+                eprintln!("Only one of --from and --to can be 'live'.");
+                std::process::exit(1);
+            }
 
-            let to_entries = match store.load_snapshot_entries(to_id) {
-                Ok(entries) => entries,
-                Err(e) => {
-                    eprintln!("Error loading entries for snapshot {to_id}: {e}");
-                    std::process::exit(1);
-                }
-            };
+            let (from_id, from_timestamp, from_entries) = load_diff_side(&store, &from);
+            let (to_id, to_timestamp, to_entries) = load_diff_side(&store, &to);
 
-            let diff_result = diff::compare_entries(
+            let mut diff_result = diff::compare_entries(
                 &from_entries,
                 &to_entries,
                 from_id,
                 to_id,
-                from_snapshot.timestamp,
-                to_snapshot.timestamp,
+                from_timestamp,
+                to_timestamp,
+                args.ignore_case,
             );
+            if args.fuzzy {
+                diff::pair_renamed(&mut diff_result, &from_entries, &to_entries);
+            }
 
             print_diff(&diff_result);
         }
+        Command::Snapshot(args) => run_snapshot(args),
+        Command::Db(args) => run_db(args),
+        Command::Estimate(args) => run_estimate(args),
+        Command::Stats(args) => run_stats(args),
+        Command::Paths(args) => run_paths(args),
+        Command::Inspect(args) => run_inspect(args),
     }
 }
@@ -8,6 +8,17 @@ pub enum Platform {
     Unknown,
 }
 
+impl Platform {
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::MacOS => "macOS",
+            Platform::Linux => "Linux",
+            Platform::Windows => "Windows",
+            Platform::Unknown => "unknown",
+        }
+    }
+}
+
 pub fn detect() -> Platform {
     match std::env::consts::OS {
         "macos" => Platform::MacOS,
@@ -23,9 +34,60 @@ pub fn home_dir() -> Option<PathBuf> {
         .map(PathBuf::from)
 }
 
+/// Variables CI services set to a non-empty value; the generic `CI` is
+/// checked separately since some tools set it to "false".
+const CI_ENV_VARS: &[&str] = &[
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "BUILDKITE",
+    "CIRCLECI",
+    "TRAVIS",
+    "JENKINS_URL",
+    "TF_BUILD",
+    "TEAMCITY_VERSION",
+];
+
+/// Whether heft is running under a CI service. Used for defaults only:
+/// ASCII output, no spinner, and a clean that won't wait on a prompt.
+pub fn is_ci() -> bool {
+    ci_from(|name| std::env::var(name).ok())
+}
+
+fn ci_from(var: impl Fn(&str) -> Option<String>) -> bool {
+    let generic = var("CI")
+        .is_some_and(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "" | "0" | "false"));
+    generic
+        || CI_ENV_VARS
+            .iter()
+            .any(|name| var(name).is_some_and(|v| !v.is_empty()))
+}
+
 /// Returns true when heft is running inside a WSL2 environment.
 /// WSL_INTEROP is set exclusively by WSL2 (not WSL1) and points to the
 /// interop socket. WSL_DISTRO_NAME is set by both WSL1 and WSL2.
 pub fn is_wsl() -> bool {
     std::env::var_os("WSL_INTEROP").is_some()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn ci_detected_from_generic_or_service_variables() {
+        assert!(ci_from(env(&[("CI", "true")])));
+        assert!(ci_from(env(&[("GITHUB_ACTIONS", "true")])));
+        assert!(ci_from(env(&[("JENKINS_URL", "https://ci.example")])));
+        assert!(!ci_from(env(&[("CI", "false")])));
+        assert!(!ci_from(env(&[("CI", "")])));
+        assert!(!ci_from(env(&[])));
+    }
+}
@@ -0,0 +1,397 @@
+//! Remote scans over SFTP (`heft scan --remote user@host:/path`).
+//!
+//! Nothing is installed on the server: directories are listed and sized
+//! through an SFTP session. Only the filesystem detectors (projects and
+//! caches) can run this way; docker, xcode and heft's own database are
+//! skipped with a diagnostic. The host key must already be in
+//! ~/.ssh/known_hosts, and authentication uses ssh-agent or an unencrypted
+//! key from ~/.ssh.
+
+use std::io::{self, Read};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Instant;
+
+use ssh2::{CheckResult, KnownHostFileKind, Session, Sftp};
+
+use crate::config::{Config, DETECTOR_NAMES};
+use crate::platform::{self, Platform};
+use crate::scan::detector::DetectorResult;
+use crate::scan::fs::{dir_size, FileKind, FileSystem, FsEntry};
+use crate::scan::{self, caches, projects, DirSize, ScanResult};
+
+/// `[user@]host[:port]:path`. Without a user, the local $USER is used;
+/// an empty path means the remote home directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    pub user: String,
+    pub host: String,
+    pub port: u16,
+    pub path: PathBuf,
+}
+
+impl FromStr for RemoteTarget {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid remote '{input}' (expected user@host:/path)");
+
+        let (login, rest) = input.split_once(':').ok_or_else(invalid)?;
+        let (user, host) = match login.split_once('@') {
+            Some((user, host)) => (user.to_string(), host),
+            None => (
+                std::env::var("USER")
+                    .or_else(|_| std::env::var("USERNAME"))
+                    .map_err(|_| format!("no user in '{input}' and $USER is not set"))?,
+                login,
+            ),
+        };
+        if user.is_empty() || host.is_empty() {
+            return Err(invalid());
+        }
+
+        // host:2222:/path
+        let (port, path) = match rest.split_once(':') {
+            Some((port, path)) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => {
+                (port.parse().map_err(|_| invalid())?, path)
+            }
+            _ => (22, rest),
+        };
+
+        Ok(RemoteTarget {
+            user,
+            host: host.to_string(),
+            port,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+impl std::fmt::Display for RemoteTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}:{}", self.user, self.host, self.path.display())
+    }
+}
+
+/// A `FileSystem` backed by an SFTP session.
+pub struct SftpFs {
+    sftp: Sftp,
+    // the session must outlive the sftp channel
+    _session: Session,
+}
+
+impl FileSystem for SftpFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+        // readdir attributes come from lstat, so symlinks are reported as such
+        Ok(self
+            .sftp
+            .readdir(path)?
+            .into_iter()
+            .map(|(path, stat)| {
+                let file_type = stat.file_type();
+                let kind = if file_type.is_symlink() {
+                    FileKind::Symlink
+                } else if file_type.is_dir() {
+                    FileKind::Dir
+                } else if file_type.is_file() {
+                    FileKind::File
+                } else {
+                    FileKind::Other
+                };
+                FsEntry {
+                    path,
+                    kind,
+                    len: stat.size.unwrap_or(0),
+                }
+            })
+            .collect())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.sftp.lstat(path).is_ok()
+    }
+
+    fn read_small_file(&self, path: &Path, limit: u64) -> Option<String> {
+        if self.sftp.stat(path).ok()?.size? > limit {
+            return None;
+        }
+        let mut content = String::new();
+        self.sftp
+            .open(path)
+            .ok()?
+            .take(limit)
+            .read_to_string(&mut content)
+            .ok()?;
+        Some(content)
+    }
+
+    fn measure(&self, path: &Path) -> io::Result<DirSize> {
+        dir_size(self, path)
+    }
+}
+
+impl SftpFs {
+    /// Connect, verify the host key against ~/.ssh/known_hosts and authenticate.
+    pub fn connect(target: &RemoteTarget, config: &Config) -> Result<Self, String> {
+        let addr = (target.host.as_str(), target.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("{}: cannot resolve host: {e}", target.host))?
+            .next()
+            .ok_or_else(|| format!("{}: no address found", target.host))?;
+        let tcp = TcpStream::connect_timeout(&addr, config.timeout)
+            .map_err(|e| format!("{}: connection failed: {e}", target.host))?;
+
+        let mut session = Session::new().map_err(|e| format!("ssh: {e}"))?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(config.timeout.as_millis().min(u32::MAX as u128) as u32);
+        session
+            .handshake()
+            .map_err(|e| format!("{}: ssh handshake failed: {e}", target.host))?;
+
+        verify_host_key(&session, target)?;
+        authenticate(&session, target)?;
+
+        let sftp = session
+            .sftp()
+            .map_err(|e| format!("{}: sftp unavailable: {e}", target.host))?;
+        Ok(SftpFs {
+            sftp,
+            _session: session,
+        })
+    }
+
+    /// The remote home directory (where SFTP sessions start).
+    pub fn home(&self) -> Result<PathBuf, String> {
+        self.sftp
+            .realpath(Path::new("."))
+            .map_err(|e| format!("sftp: cannot resolve home directory: {e}"))
+    }
+}
+
+fn ssh_dir() -> Result<PathBuf, String> {
+    platform::home_dir()
+        .map(|home| home.join(".ssh"))
+        .ok_or_else(|| "could not determine home directory for ~/.ssh".to_string())
+}
+
+fn verify_host_key(session: &Session, target: &RemoteTarget) -> Result<(), String> {
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| format!("{}: server sent no host key", target.host))?;
+    let mut known_hosts = session.known_hosts().map_err(|e| format!("ssh: {e}"))?;
+    let file = ssh_dir()?.join("known_hosts");
+    // a missing file just means nothing is known yet
+    let _ = known_hosts.read_file(&file, KnownHostFileKind::OpenSSH);
+
+    match known_hosts.check_port(&target.host, target.port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::NotFound => Err(format!(
+            "{}: host key not in {}; connect once with ssh to verify and add it",
+            target.host,
+            file.display()
+        )),
+        CheckResult::Mismatch => Err(format!(
+            "{}: HOST KEY MISMATCH with {}; refusing to connect",
+            target.host,
+            file.display()
+        )),
+        CheckResult::Failure => Err(format!("{}: could not check host key", target.host)),
+    }
+}
+
+fn authenticate(session: &Session, target: &RemoteTarget) -> Result<(), String> {
+    if session.userauth_agent(&target.user).is_ok() && session.authenticated() {
+        return Ok(());
+    }
+    let ssh_dir = ssh_dir()?;
+    for key in ["id_ed25519", "id_ecdsa", "id_rsa"] {
+        let private = ssh_dir.join(key);
+        if private.exists()
+            && session
+                .userauth_pubkey_file(&target.user, None, &private, None)
+                .is_ok()
+            && session.authenticated()
+        {
+            return Ok(());
+        }
+    }
+    Err(format!(
+        "{}: authentication failed for {} (tried ssh-agent and unencrypted ~/.ssh/id_* keys)",
+        target.host, target.user
+    ))
+}
+
+/// Connect to `target` and scan it.
+pub fn scan(target: &RemoteTarget, config: &Config) -> Result<ScanResult, String> {
+    let fs = SftpFs::connect(target, config)?;
+    let home = fs.home()?;
+    Ok(scan_with(&fs, &home, &target.path, config))
+}
+
+/// Run the filesystem detectors against `fs`. `root` is resolved against
+/// `home` when relative. Split out from `scan` so it can run on a local tree.
+pub fn scan_with(fs: &dyn FileSystem, home: &Path, root: &Path, config: &Config) -> ScanResult {
+    let start = Instant::now();
+    let visited_before = scan::entries_visited();
+    let mut scan_result = ScanResult::empty();
+    let root = home.join(root);
+
+    // no way to ask the server, but home under /Users is a safe macOS tell
+    let remote_platform = if home.starts_with("/Users") {
+        Platform::MacOS
+    } else {
+        Platform::Linux
+    };
+
+    for &name in DETECTOR_NAMES {
+        if !config.is_detector_enabled(name) {
+            scan_result
+                .diagnostics
+                .push(format!("{name}: skipped (disabled by config)"));
+            continue;
+        }
+
+        let detector_start = Instant::now();
+        let mut result = match name {
+            "projects" if fs.exists(&root) => projects::scan_tree(fs, &root, config),
+            "projects" => DetectorResult::with_diagnostic(format!(
+                "skipping {}: directory does not exist",
+                root.display()
+            )),
+            "caches" => caches::scan_caches_on(fs, home, remote_platform, config.min_entry_bytes),
+            _ => {
+                scan_result
+                    .diagnostics
+                    .push(format!("{name}: skipped (not available for remote scans)"));
+                continue;
+            }
+        };
+        scan::apply_category_overrides(&mut result.entries, &config.category_overrides);
+
+        scan_result
+            .detector_timings
+            .push((name.to_string(), scan::millis(detector_start.elapsed())));
+        scan_result.entries.extend(result.entries);
+        scan_result.diagnostics.extend(result.diagnostics);
+    }
+
+    scan_result.duration_ms = Some(scan::millis(start.elapsed()));
+    scan_result.files_scanned = Some(scan::entries_visited().saturating_sub(visited_before));
+    scan_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::fs::LocalFs;
+
+    #[test]
+    fn remote_target_parses_user_port_and_path() {
+        let target: RemoteTarget = "deploy@build01:/srv/app".parse().unwrap();
+        assert_eq!(target.user, "deploy");
+        assert_eq!(target.host, "build01");
+        assert_eq!(target.port, 22);
+        assert_eq!(target.path, PathBuf::from("/srv/app"));
+
+        let target: RemoteTarget = "deploy@build01:2222:/srv".parse().unwrap();
+        assert_eq!(target.port, 2222);
+        assert_eq!(target.path, PathBuf::from("/srv"));
+
+        let target: RemoteTarget = "deploy@build01:".parse().unwrap();
+        assert_eq!(target.path, PathBuf::new());
+
+        assert!("deploy@build01".parse::<RemoteTarget>().is_err());
+        assert!("@build01:/x".parse::<RemoteTarget>().is_err());
+    }
+
+    #[test]
+    fn remote_scan_runs_filesystem_detectors_only() {
+        let home = tempfile::tempdir().unwrap();
+        let app = home.path().join("code/app");
+        std::fs::create_dir_all(app.join("node_modules/left-pad")).unwrap();
+        std::fs::write(app.join("package.json"), r#"{"name": "app"}"#).unwrap();
+        std::fs::write(app.join("node_modules/left-pad/index.js"), [0u8; 64]).unwrap();
+        std::fs::create_dir_all(home.path().join(".npm")).unwrap();
+        std::fs::write(home.path().join(".npm/blob"), [0u8; 32]).unwrap();
+
+        let config = Config {
+            min_entry_bytes: 0,
+            ..Config::default()
+        };
+        let result = scan_with(&LocalFs, home.path(), Path::new("code"), &config);
+
+        let app_entry = result.entries.iter().find(|e| e.name == "app").unwrap();
+        assert_eq!(app_entry.size_bytes, 64);
+        let npm = result
+            .entries
+            .iter()
+            .find(|e| e.name == "npm cache")
+            .unwrap();
+        assert_eq!(npm.size_bytes, 32);
+        assert!(result
+            .diagnostics
+            .contains(&"docker: skipped (not available for remote scans)".to_string()));
+
+        // like a local scan, the default minimum size hides both
+        let result = scan_with(&LocalFs, home.path(), Path::new("code"), &Config::default());
+        assert!(result.entries.is_empty());
+    }
+
+    /// The local disk, with everything under `mount` on another device.
+    struct MountedAt(PathBuf);
+
+    impl FileSystem for MountedAt {
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+            LocalFs.read_dir(path)
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            LocalFs.exists(path)
+        }
+
+        fn read_small_file(&self, path: &Path, limit: u64) -> Option<String> {
+            LocalFs.read_small_file(path, limit)
+        }
+
+        fn measure(&self, path: &Path) -> io::Result<DirSize> {
+            LocalFs.measure(path)
+        }
+
+        fn device_id(&self, path: &Path) -> Option<u64> {
+            Some(if path.starts_with(&self.0) { 2 } else { 1 })
+        }
+    }
+
+    #[test]
+    fn remote_project_walk_stays_on_one_filesystem() {
+        let home = tempfile::tempdir().unwrap();
+        for project in ["code/app", "code/share/other"] {
+            let project = home.path().join(project);
+            std::fs::create_dir_all(project.join("node_modules")).unwrap();
+            std::fs::write(project.join("package.json"), "{}").unwrap();
+            std::fs::write(project.join("node_modules/index.js"), "x").unwrap();
+        }
+        let fs = MountedAt(home.path().join("code/share"));
+        let config = Config {
+            min_entry_bytes: 0,
+            disabled_detectors: ["caches".to_string()].into(),
+            ..Config::default()
+        };
+
+        let result = scan_with(&fs, home.path(), Path::new("code"), &config);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].name, "app");
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.contains("a different filesystem")));
+
+        let config = Config {
+            cross_filesystems: true,
+            ..config
+        };
+        let result = scan_with(&fs, home.path(), Path::new("code"), &config);
+        assert_eq!(result.entries.len(), 2);
+    }
+}
@@ -0,0 +1,70 @@
+//! Compact output for scan results.
+//!
+//! One entry per line as `<size>\t<category>\t<name>`, largest first across
+//! all categories, with no headers or totals. Meant for `column -t`, `fzf`
+//! and narrow terminals.
+
+use crate::scan::ScanResult;
+use crate::util::format_bytes;
+
+pub fn render(result: &ScanResult) -> String {
+    let mut entries: Vec<_> = result.entries.iter().collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+
+    let mut output = String::new();
+    for entry in entries {
+        output.push_str(&format!(
+            "{}\t{}\t{}\n",
+            format_bytes(entry.size_bytes),
+            entry.category.label(),
+            entry.name
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+    use std::path::PathBuf;
+
+    fn entry(name: &str, category: BloatCategory, size: u64) -> BloatEntry {
+        BloatEntry {
+            category,
+            name: name.to_string(),
+            location: Location::FilesystemPath(PathBuf::from(format!("/tmp/{name}"))),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    #[test]
+    fn one_line_per_entry_sorted_globally() {
+        let result = ScanResult {
+            entries: vec![
+                entry("npm cache", BloatCategory::PackageCache, 1024),
+                entry("web", BloatCategory::ProjectArtifacts, 4096),
+                entry("pip cache", BloatCategory::PackageCache, 2048),
+            ],
+            ..ScanResult::empty()
+        };
+        assert_eq!(
+            render(&result),
+            "4.0 KB\tProject Artifacts\tweb\n\
+             2.0 KB\tPackage Cache\tpip cache\n\
+             1.0 KB\tPackage Cache\tnpm cache\n"
+        );
+    }
+
+    #[test]
+    fn empty_result_prints_nothing() {
+        assert_eq!(render(&ScanResult::empty()), "");
+    }
+}
@@ -0,0 +1,74 @@
+//! CSV output for scan results.
+//!
+//! One row per entry with raw byte counts, so spreadsheets can sum and sort
+//! them. Fields are quoted per RFC 4180 only when they need it.
+
+use super::location_text;
+use crate::scan::ScanResult;
+
+const HEADER: &str =
+    "category,name,location,size_bytes,reclaimable_bytes,last_modified,cleanup_hint";
+
+pub fn render(result: &ScanResult) -> String {
+    let mut output = String::from(HEADER);
+    output.push('\n');
+    for entry in &result.entries {
+        let fields = [
+            entry.category.as_str().to_string(),
+            entry.name.clone(),
+            location_text(&entry.location),
+            entry.size_bytes.to_string(),
+            entry.reclaimable_bytes.to_string(),
+            entry
+                .last_modified
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            entry.cleanup_hint.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| quote(f)).collect();
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+    output
+}
+
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+    use std::path::PathBuf;
+
+    #[test]
+    fn rows_quote_only_when_needed() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::PackageCache,
+                name: "npm cache".to_string(),
+                location: Location::FilesystemPath(PathBuf::from("/home/a/.npm")),
+                size_bytes: 2048,
+                reclaimable_bytes: 1024,
+                last_modified: Some(1_700_000_000),
+                cleanup_hint: Some("npm cache clean --force, or say \"yes\"".to_string()),
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            ..ScanResult::empty()
+        };
+        assert_eq!(
+            render(&result),
+            format!(
+                "{HEADER}\nPackageCache,npm cache,/home/a/.npm,2048,1024,1700000000,\"npm cache clean --force, or say \"\"yes\"\"\"\n"
+            )
+        );
+    }
+}
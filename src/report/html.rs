@@ -0,0 +1,79 @@
+//! HTML output for scan results.
+//!
+//! A standalone page with one table, largest entries first, that can be
+//! opened directly or attached to a report. No scripts or external assets.
+
+use super::location_text;
+use crate::scan::ScanResult;
+use crate::util::format_bytes;
+
+pub fn render(result: &ScanResult) -> String {
+    let mut entries: Vec<_> = result.entries.iter().collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+    let total: u64 = entries.iter().map(|e| e.size_bytes).sum();
+    let reclaimable: u64 = entries.iter().map(|e| e.reclaimable_bytes).sum();
+
+    let mut output = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>heft scan</title>\n\
+         <style>body{font-family:sans-serif}td,th{padding:2px 8px;text-align:left}\
+         td.n{text-align:right}</style>\n</head>\n<body>\n",
+    );
+    output.push_str(&format!(
+        "<p>{} found, {} reclaimable</p>\n",
+        format_bytes(total),
+        format_bytes(reclaimable)
+    ));
+    output.push_str(
+        "<table>\n<tr><th>Category</th><th>Name</th><th>Size</th><th>Reclaimable</th><th>Location</th></tr>\n",
+    );
+    for entry in entries {
+        output.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td>{}</td></tr>\n",
+            entry.category.label(),
+            escape(&entry.name),
+            format_bytes(entry.size_bytes),
+            format_bytes(entry.reclaimable_bytes),
+            escape(&location_text(&entry.location))
+        ));
+    }
+    output.push_str("</table>\n</body>\n</html>\n");
+    output
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+
+    #[test]
+    fn names_and_locations_escaped() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::Other,
+                name: "<script>".to_string(),
+                location: Location::Aggregate("a & b".to_string()),
+                size_bytes: 1,
+                reclaimable_bytes: 0,
+                last_modified: None,
+                cleanup_hint: None,
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            ..ScanResult::empty()
+        };
+        let output = render(&result);
+        assert!(output.contains("<td>&lt;script&gt;</td>"));
+        assert!(output.contains("<td>a &amp; b</td>"));
+        assert!(!output.contains("<script>"));
+        assert!(output.ends_with("</html>\n"));
+    }
+}
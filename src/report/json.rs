@@ -1,11 +1,54 @@
 //! JSON output for scan results.
 //!
-//! Serializes ScanResult to JSON for scripting and piping.
+//! Serializes ScanResult to JSON for scripting and piping, either as one
+//! document or as JSON lines (one entry per line, for streaming tools).
 
-use crate::scan::ScanResult;
+use serde::Serialize;
+
+use crate::scan::{score, ScanResult};
+
+/// The result with a top-level `status`: "clean" when nothing was found,
+/// "found" otherwise, so consumers needn't infer it from `entries`.
+/// Replayed snapshots (`heft report`) also carry their age.
+#[derive(Serialize)]
+pub(crate) struct Report<'a> {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_age_seconds: Option<u64>,
+    #[serde(flatten)]
+    result: &'a ScanResult,
+}
 
 pub fn render(result: &ScanResult) -> String {
-    serde_json::to_string_pretty(result).unwrap_or_else(|e| {
+    render_report(result, None)
+}
+
+/// Like `render`, for a saved snapshot taken `age_seconds` ago.
+pub fn render_snapshot(result: &ScanResult, age_seconds: u64) -> String {
+    render_report(result, Some(age_seconds))
+}
+
+pub(crate) fn report(result: &ScanResult, snapshot_age_seconds: Option<u64>) -> Report<'_> {
+    Report {
+        status: if result.entries.is_empty() {
+            "clean"
+        } else {
+            "found"
+        },
+        snapshot_age_seconds,
+        result,
+    }
+}
+
+/// The document `render` prints, as a value for the TOML renderer to
+/// re-encode. Fails like `render` does (non-UTF-8 paths).
+pub(crate) fn document(result: &ScanResult) -> Result<serde_json::Value, serde_json::Error> {
+    serde_json::to_value(report(result, None))
+}
+
+fn render_report(result: &ScanResult, snapshot_age_seconds: Option<u64>) -> String {
+    let report = report(result, snapshot_age_seconds);
+    serde_json::to_string_pretty(&report).unwrap_or_else(|e| {
         let error_obj = serde_json::json!({
             "error": format!("failed to serialize: {}", e)
         });
@@ -13,3 +56,196 @@ pub fn render(result: &ScanResult) -> String {
             .unwrap_or_else(|_| r#"{"error": "catastrophic serialization failure"}"#.to_string())
     })
 }
+
+/// One compact JSON object per entry, newline-terminated.
+pub fn render_lines(result: &ScanResult) -> String {
+    let mut output = String::new();
+    for entry in &result.entries {
+        // BloatEntry is plain data, serialization can't fail
+        if let Ok(line) = serde_json::to_string(entry) {
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// Like `render`, with a `reclaim_score` on every entry (--score), as of
+/// the unix timestamp `now`.
+pub fn render_scored(result: &ScanResult, now: i64) -> String {
+    let Ok(mut document) = document(result) else {
+        return render(result);
+    };
+    add_scores(&mut document, result, now);
+    serde_json::to_string_pretty(&document).unwrap_or_else(|_| render(result))
+}
+
+/// Set `reclaim_score` on each entry of a `document` built from `result`.
+fn add_scores(document: &mut serde_json::Value, result: &ScanResult, now: i64) {
+    if let Some(entries) = document["entries"].as_array_mut() {
+        for (value, entry) in entries.iter_mut().zip(&result.entries) {
+            value["reclaim_score"] = score::reclaim_score(entry, now).into();
+        }
+    }
+}
+
+/// Like `render` (or `render_scored` when `score_at` is given), plus a
+/// `by_category` object keyed by category: `total_bytes`,
+/// `reclaimable_bytes` and the entries, largest first (--json-grouped).
+pub fn render_grouped(result: &ScanResult, score_at: Option<i64>) -> String {
+    let Ok(mut document) = document(result) else {
+        return render(result);
+    };
+    if let Some(now) = score_at {
+        add_scores(&mut document, result, now);
+    }
+
+    let mut by_category = serde_json::Map::new();
+    for (category, entries) in super::group_by_category(&result.entries) {
+        let values: Vec<serde_json::Value> = entries
+            .iter()
+            .filter_map(|entry| {
+                let mut value = serde_json::to_value(entry).ok()?;
+                if let Some(now) = score_at {
+                    value["reclaim_score"] = score::reclaim_score(entry, now).into();
+                }
+                Some(value)
+            })
+            .collect();
+        by_category.insert(
+            category.as_str().to_string(),
+            serde_json::json!({
+                "total_bytes": entries.iter().map(|e| e.size_bytes).sum::<u64>(),
+                "reclaimable_bytes": entries.iter().map(|e| e.reclaimable_bytes).sum::<u64>(),
+                "entries": values,
+            }),
+        );
+    }
+    document["by_category"] = by_category.into();
+    serde_json::to_string_pretty(&document).unwrap_or_else(|_| render(result))
+}
+
+/// Like `render_lines`, with a `reclaim_score` on every line.
+pub fn render_lines_scored(result: &ScanResult, now: i64) -> String {
+    let mut output = String::new();
+    for entry in &result.entries {
+        if let Ok(mut value) = serde_json::to_value(entry) {
+            value["reclaim_score"] = score::reclaim_score(entry, now).into();
+            output.push_str(&value.to_string());
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+
+    #[test]
+    fn grouped_json_totals_each_category_and_keeps_flat_list() {
+        let entry = |name: &str, category, size| BloatEntry {
+            category,
+            name: name.to_string(),
+            location: Location::Aggregate(name.to_string()),
+            size_bytes: size,
+            reclaimable_bytes: size / 2,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        };
+        let result = ScanResult {
+            entries: vec![
+                entry("npm cache", BloatCategory::PackageCache, 100),
+                entry("app", BloatCategory::ProjectArtifacts, 50),
+                entry("pip cache", BloatCategory::PackageCache, 300),
+            ],
+            ..ScanResult::empty()
+        };
+
+        let json: serde_json::Value = serde_json::from_str(&render_grouped(&result, None)).unwrap();
+        assert_eq!(json["entries"].as_array().unwrap().len(), 3);
+        let packages = &json["by_category"]["PackageCache"];
+        assert_eq!(packages["total_bytes"], 400);
+        assert_eq!(packages["reclaimable_bytes"], 200);
+        assert_eq!(packages["entries"][0]["name"], "pip cache");
+        assert_eq!(json["by_category"]["ProjectArtifacts"]["total_bytes"], 50);
+        assert!(packages["entries"][0].get("reclaim_score").is_none());
+
+        let scored: serde_json::Value =
+            serde_json::from_str(&render_grouped(&result, Some(0))).unwrap();
+        assert!(scored["by_category"]["PackageCache"]["entries"][0]["reclaim_score"].is_number());
+    }
+
+    #[test]
+    fn status_says_whether_anything_was_found() {
+        let empty: serde_json::Value = serde_json::from_str(&render(&ScanResult::empty())).unwrap();
+        assert_eq!(empty["status"], "clean");
+        assert_eq!(empty["entries"], serde_json::json!([]));
+
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::PackageCache,
+                name: "npm cache".to_string(),
+                location: Location::Aggregate("npm".to_string()),
+                size_bytes: 1,
+                reclaimable_bytes: 1,
+                last_modified: None,
+                cleanup_hint: None,
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            ..ScanResult::empty()
+        };
+        let found: serde_json::Value = serde_json::from_str(&render(&result)).unwrap();
+        assert_eq!(found["status"], "found");
+        assert_eq!(found["entries"][0]["name"], "npm cache");
+    }
+
+    #[test]
+    fn scores_added_only_when_asked() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::PackageCache,
+                name: "npm cache".to_string(),
+                location: Location::Aggregate("npm".to_string()),
+                size_bytes: 3 * 1024 * 1024,
+                reclaimable_bytes: 3 * 1024 * 1024,
+                last_modified: None,
+                cleanup_hint: None,
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            ..ScanResult::empty()
+        };
+        assert!(!render(&result).contains("reclaim_score"));
+
+        let scored: serde_json::Value = serde_json::from_str(&render_scored(&result, 0)).unwrap();
+        assert_eq!(scored["status"], "found");
+        assert_eq!(scored["entries"][0]["reclaim_score"], 2.0);
+
+        let line: serde_json::Value =
+            serde_json::from_str(render_lines_scored(&result, 0).trim_end()).unwrap();
+        assert_eq!(line["name"], "npm cache");
+        assert_eq!(line["reclaim_score"], 2.0);
+    }
+
+    #[test]
+    fn snapshot_age_only_on_replayed_snapshots() {
+        let live: serde_json::Value = serde_json::from_str(&render(&ScanResult::empty())).unwrap();
+        assert!(live.get("snapshot_age_seconds").is_none());
+
+        let replayed: serde_json::Value =
+            serde_json::from_str(&render_snapshot(&ScanResult::empty(), 7200)).unwrap();
+        assert_eq!(replayed["snapshot_age_seconds"], 7200);
+        assert_eq!(replayed["status"], "clean");
+    }
+}
@@ -0,0 +1,70 @@
+//! Markdown output for scan results.
+//!
+//! A single GitHub-flavoured table, largest first, with a total line
+//! underneath. Meant for pasting into issues, PRs and wiki pages.
+
+use crate::scan::ScanResult;
+use crate::util::format_bytes;
+
+pub fn render(result: &ScanResult) -> String {
+    let mut entries: Vec<_> = result.entries.iter().collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+
+    let mut output = String::from("| Category | Name | Size | Reclaimable |\n");
+    output.push_str("|---|---|--:|--:|\n");
+    for entry in &entries {
+        output.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            entry.category.label(),
+            escape(&entry.name),
+            format_bytes(entry.size_bytes),
+            format_bytes(entry.reclaimable_bytes)
+        ));
+    }
+
+    let total: u64 = entries.iter().map(|e| e.size_bytes).sum();
+    let reclaimable: u64 = entries.iter().map(|e| e.reclaimable_bytes).sum();
+    output.push_str(&format!(
+        "\n**Total:** {} found, {} reclaimable\n",
+        format_bytes(total),
+        format_bytes(reclaimable)
+    ));
+    output
+}
+
+/// Pipes would end the cell early.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+
+    #[test]
+    fn table_sorted_with_total() {
+        let entry = |name: &str, size: u64| BloatEntry {
+            category: BloatCategory::ProjectArtifacts,
+            name: name.to_string(),
+            location: Location::Aggregate(name.to_string()),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        };
+        let result = ScanResult {
+            entries: vec![entry("small", 1024), entry("a|b", 4096)],
+            ..ScanResult::empty()
+        };
+        let output = render(&result);
+        let rows: Vec<&str> = output.lines().collect();
+        assert_eq!(rows[2], "| Project Artifacts | a\\|b | 4.0 KB | 4.0 KB |");
+        assert_eq!(rows[3], "| Project Artifacts | small | 1.0 KB | 1.0 KB |");
+        assert!(output.ends_with("**Total:** 5.0 KB found, 5.0 KB reclaimable\n"));
+    }
+}
@@ -1,21 +1,355 @@
+pub mod compact;
+pub mod csv;
+pub mod html;
 pub mod json;
+pub mod markdown;
+pub mod paths;
+pub mod prometheus;
 pub mod table;
+pub mod toml;
+pub mod wide;
+pub mod yaml;
 
 use crate::config::Config;
-use crate::scan::ScanResult;
-use crate::util::format_bytes;
+use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+use crate::scan::{CoverageStatus, ScanResult};
+use crate::util::{self, format_bytes};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// How scan results are printed (--format, or `format` in the config file).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Grouped terminal table (the default)
+    #[default]
+    Table,
+    /// The whole result as one pretty-printed JSON document
+    Json,
+    /// One JSON object per entry, one per line
+    Jsonl,
+    /// One row per entry with a header, RFC 4180 quoting
+    Csv,
+    /// A GitHub-flavoured markdown table
+    Markdown,
+    /// A standalone HTML page
+    Html,
+    /// Prometheus text exposition, for node_exporter's textfile collector
+    Prometheus,
+    /// One tab-separated line per entry, largest first, no headers
+    Compact,
+    /// The table with every field as a column: dates, full locations, hints
+    #[value(name = "table-wide")]
+    #[serde(rename = "table-wide")]
+    TableWide,
+    /// The JSON document as YAML
+    Yaml,
+    /// The JSON document as TOML (null fields left out)
+    Toml,
+}
+
+/// What the table groups entries by (--group-by).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum GroupBy {
+    /// Bloat category (the default)
+    #[default]
+    Category,
+    /// The detector that found the entry
+    Detector,
+    /// The scan root the entry is under
+    Root,
+    /// Time since the entry was last modified
+    Age,
+    /// The filesystem (mount point or drive) holding the entry
+    Filesystem,
+}
+
+/// Render `result` in any format except the table, which also prints
+/// scan info and diagnostics and goes through `print`.
+pub fn render(result: &ScanResult, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Table => table::render(result),
+        OutputFormat::Json => json::render(result) + "\n",
+        OutputFormat::Jsonl => json::render_lines(result),
+        OutputFormat::Csv => csv::render(result),
+        OutputFormat::Markdown => markdown::render(result),
+        OutputFormat::Html => html::render(result),
+        OutputFormat::Prometheus => prometheus::render(result),
+        OutputFormat::Compact => compact::render(result),
+        OutputFormat::TableWide => wide::render(result),
+        OutputFormat::Yaml => yaml::render(result),
+        OutputFormat::Toml => toml::render(result),
+    }
+}
+
+/// Snapshots older than this get a staleness warning in `heft report`.
+pub const SNAPSHOT_FRESH_SECS: u64 = 24 * 60 * 60;
+
+/// "snapshot from 3 days ago — ..." once a replayed snapshot is older than
+/// `SNAPSHOT_FRESH_SECS`, so it isn't mistaken for the current state.
+pub fn stale_snapshot_warning(age_secs: u64) -> Option<String> {
+    if age_secs <= SNAPSHOT_FRESH_SECS {
+        return None;
+    }
+    // past the fresh window, so at least a day old
+    let age = match age_secs / (24 * 3600) {
+        1 => "1 day".to_string(),
+        days => format!("{days} days"),
+    };
+    Some(format!(
+        "snapshot from {age} ago — run 'heft scan' to refresh"
+    ))
+}
+
+/// Entries grouped by category, categories largest first and entries
+/// largest first within each, as the table shows them.
+pub(crate) fn group_by_category(entries: &[BloatEntry]) -> Vec<(BloatCategory, Vec<&BloatEntry>)> {
+    let mut by_category: HashMap<BloatCategory, Vec<&BloatEntry>> = HashMap::new();
+    for entry in entries {
+        by_category.entry(entry.category).or_default().push(entry);
+    }
+
+    let mut groups: Vec<_> = by_category.into_iter().collect();
+    groups.sort_by_key(|(_, entries)| {
+        std::cmp::Reverse(entries.iter().map(|e| e.size_bytes).sum::<u64>())
+    });
+    for (_, entries) in &mut groups {
+        entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+    }
+    groups
+}
+
+/// Entries grouped by `by`, groups largest first and entries largest first
+/// within each. Groups are labelled for the table header; `roots` decides
+/// the root groups and `now` the age buckets.
+pub(crate) fn group_entries<'a>(
+    entries: &'a [BloatEntry],
+    by: GroupBy,
+    roots: &[PathBuf],
+    now: i64,
+) -> Vec<(String, Vec<&'a BloatEntry>)> {
+    if by == GroupBy::Category {
+        return group_by_category(entries)
+            .into_iter()
+            .map(|(category, entries)| (format!("{category:?}"), entries))
+            .collect();
+    }
+
+    let mut mounts: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let mut by_key: HashMap<String, Vec<&BloatEntry>> = HashMap::new();
+    for entry in entries {
+        let key = match (by, &entry.location) {
+            (GroupBy::Detector, _) => entry
+                .detector
+                .clone()
+                .unwrap_or_else(|| "unknown detector".to_string()),
+            (GroupBy::Age, _) => age_bucket(entry.last_modified, now).to_string(),
+            (_, Location::DockerObject(_)) => "docker".to_string(),
+            (_, Location::Aggregate(_)) => "no single location".to_string(),
+            (GroupBy::Root, Location::FilesystemPath(path)) => roots
+                .iter()
+                .filter(|root| path.starts_with(root))
+                .max_by_key(|root| root.components().count())
+                .map(|root| root.display().to_string())
+                .unwrap_or_else(|| "outside the scan roots".to_string()),
+            // siblings share a filesystem: one lookup per directory, not per entry
+            (_, Location::FilesystemPath(path)) => {
+                let dir = path.parent().unwrap_or(path);
+                mounts
+                    .entry(dir.to_path_buf())
+                    .or_insert_with(|| crate::scan::disk::mount_point(dir))
+                    .display()
+                    .to_string()
+            }
+        };
+        by_key.entry(key).or_default().push(entry);
+    }
+
+    let mut groups: Vec<_> = by_key.into_iter().collect();
+    groups.sort_by(|(a_key, a), (b_key, b)| {
+        let total = |entries: &[&BloatEntry]| entries.iter().map(|e| e.size_bytes).sum::<u64>();
+        total(b).cmp(&total(a)).then_with(|| a_key.cmp(b_key))
+    });
+    for (_, entries) in &mut groups {
+        entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+    }
+    groups
+}
+
+/// Age bucket label for --group-by age.
+fn age_bucket(last_modified: Option<i64>, now: i64) -> &'static str {
+    let Some(modified) = last_modified else {
+        return "age unknown";
+    };
+    let days = now.saturating_sub(modified) / 86_400;
+    if days < 30 {
+        "under 30 days"
+    } else if days < 90 {
+        "30-90 days"
+    } else if days < 365 {
+        "90 days to a year"
+    } else {
+        "over a year"
+    }
+}
+
+/// Full path, docker object or aggregate name, for formats without a
+/// separate location column type.
+pub(crate) fn location_text(location: &Location) -> String {
+    match location {
+        Location::FilesystemPath(path) => path.display().to_string(),
+        Location::DockerObject(id) => format!("docker:{id}"),
+        Location::Aggregate(name) => name.clone(),
+    }
+}
+
+const NO_SIZES_NOTE: &str =
+    "note: sizes not measured (--no-sizes); entries show where things are, not how big they are";
 
 pub fn print(result: &ScanResult, config: &Config) {
-    if config.json_output {
-        println!("{}", json::render(result));
+    if config.json_grouped && config.output_format != OutputFormat::Json {
+        eprintln!("warning: --json-grouped only applies to JSON output; ignored");
+    }
+    if config.list_paths {
+        use std::io::Write;
+        let output = paths::render(
+            result,
+            config.reclaimable_only,
+            &config.never_delete,
+            config.print0,
+        );
+        // a closed pipe (`| head`) is not an error worth reporting
+        let _ = std::io::stdout().write_all(&output);
+        return;
+    }
+    if config.output_format != OutputFormat::Table {
+        if config.group_by != GroupBy::Category {
+            eprintln!("warning: --group-by only applies to the table output; ignored");
+        }
+        let now = chrono::Utc::now().timestamp();
+        let output = match (config.output_format, config.score) {
+            (OutputFormat::Json, score) if config.json_grouped => {
+                json::render_grouped(result, score.then_some(now)) + "\n"
+            }
+            (OutputFormat::Json, true) => json::render_scored(result, now) + "\n",
+            (OutputFormat::Jsonl, true) => json::render_lines_scored(result, now),
+            // the wide table is for people; the rest is data and stays as is
+            (OutputFormat::TableWide, _) => {
+                util::plain(&render(result, OutputFormat::TableWide)).into_owned()
+            }
+            (format, _) => render(result, format),
+        };
+        print!("{output}");
+        // stdout is probably a pipe; keep the warning where a human sees it
+        if config.output_format == OutputFormat::Compact {
+            for warning in disk_warnings(result) {
+                eprintln!("{warning}");
+            }
+        }
+        if config.skip_sizes {
+            eprintln!("{NO_SIZES_NOTE}");
+        }
+    } else {
+        let paths = config.relative_paths.then(|| table::RelativePaths {
+            roots: config.roots.clone(),
+            home: crate::platform::home_dir(),
+        });
+        let options = table::TableOptions {
+            paths: paths.as_ref(),
+            hints: config.show_hints,
+            include_manual: config.include_manual,
+            summary_top: config.summary_top,
+            goal: config.goal,
+            group_by: config.group_by,
+            roots: &config.roots,
+        };
+        print!("{}", util::plain(&table::render_with(result, &options)));
+        if result.entries.is_empty() {
+            println!("{}", empty_scan_hint(&config.roots));
+        }
+        let warnings = disk_warnings(result);
+        if !warnings.is_empty() {
+            println!();
+            for warning in warnings {
+                println!("{warning}");
+            }
+        }
+        if config.skip_sizes {
+            println!("\n{NO_SIZES_NOTE}");
+        }
+        print_scan_info(result, config.verbose_level);
+        print_diagnostics(result, config.verbose());
+    }
+}
+
+/// Shown under "No bloat detected": an empty result is more often a wrong
+/// root than a spotless machine.
+pub fn empty_scan_hint(roots: &[std::path::PathBuf]) -> String {
+    let roots: Vec<String> = roots.iter().map(|r| r.display().to_string()).collect();
+    format!(
+        "Scanned: {}. If you expected results, check the roots (--roots, $HEFT_ROOTS, or `roots` under [scan] in the config file).",
+        if roots.is_empty() {
+            "no roots".to_string()
+        } else {
+            roots.join(", ")
+        }
+    )
+}
+
+/// Above this many entries, a full scan is likely to take minutes.
+const ESTIMATE_SLOW_ENTRIES: u64 = 200_000;
+
+/// "Found ~42 candidate artifacts, ~800k files to stat — ..." for `heft estimate`.
+pub fn estimate_summary(estimate: &crate::scan::projects::Estimate, max_depth: usize) -> String {
+    let approx = |n: u64| match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{}k", n / 1_000),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    };
+    let mut summary = format!(
+        "Found ~{} candidate artifacts, ~{} files to stat",
+        estimate.candidates,
+        approx(estimate.entries)
+    );
+    if estimate.depth_limited {
+        summary.push_str(&format!(" (more below depth {max_depth})"));
+    }
+    summary.push_str(if estimate.entries > ESTIMATE_SLOW_ENTRIES {
+        " — full scan may take a while."
     } else {
-        print!("{}", table::render(result));
-        print_scan_info(result, config.verbose);
-        print_diagnostics(result, config.verbose);
+        " — full scan should take seconds."
+    });
+    summary
+}
+
+/// One line per distinct near-full filesystem: roots on the same device
+/// are only warned about once. Where device ids aren't available, roots
+/// reporting identical figures are taken to share a disk.
+fn disk_warnings(result: &ScanResult) -> Vec<String> {
+    let mut seen = Vec::new();
+    let mut warnings = Vec::new();
+    for disk in result.disks.iter().filter(|d| d.is_near_full()) {
+        let filesystem = match disk.device_id {
+            Some(device) => (Some(device), 0, 0),
+            None => (None, disk.disk_free_bytes, disk.disk_total_bytes),
+        };
+        if seen.contains(&filesystem) {
+            continue;
+        }
+        seen.push(filesystem);
+        warnings.push(format!(
+            "warning: disk holding {} is {}% full, {} free of {}",
+            disk.root.display(),
+            disk.used_percent(),
+            format_bytes(disk.disk_free_bytes),
+            format_bytes(disk.disk_total_bytes)
+        ));
     }
+    warnings
 }
 
-fn print_scan_info(result: &ScanResult, verbose: bool) {
+fn print_scan_info(result: &ScanResult, verbose_level: u8) {
+    let verbose = verbose_level > 0;
     if let Some(duration_ms) = result.duration_ms {
         let duration_sec = duration_ms as f64 / 1000.0;
 
@@ -26,6 +360,21 @@ fn print_scan_info(result: &ScanResult, verbose: bool) {
             println!("\nScan completed in {duration_sec:.2}s");
         }
 
+        if verbose {
+            if let Some(files) = result.files_scanned {
+                println!("Files scanned: {files}");
+            }
+            for disk in &result.disks {
+                println!(
+                    "Disk free: {} of {} ({}% used) at {}",
+                    format_bytes(disk.disk_free_bytes),
+                    format_bytes(disk.disk_total_bytes),
+                    disk.used_percent(),
+                    disk.root.display()
+                );
+            }
+        }
+
         // Display per-detector metrics in verbose mode
         if verbose && !result.detector_timings.is_empty() {
             println!("\ndetector timing:");
@@ -33,12 +382,17 @@ fn print_scan_info(result: &ScanResult, verbose: bool) {
             for (detector_name, timing_ms) in &result.detector_timings {
                 let timing_sec = *timing_ms as f64 / 1000.0;
 
-                // Linear search for memory delta - only 3 detectors, faster than HashMap
-                let memory_delta = result
-                    .detector_memory
-                    .iter()
-                    .find(|(name, _)| name == detector_name)
-                    .map(|(_, delta)| *delta);
+                // Linear search for memory delta - only a dozen detectors, faster than HashMap
+                // Memory deltas are noisy, so they wait for -vv
+                let memory_delta = (verbose_level >= 2)
+                    .then(|| {
+                        result
+                            .detector_memory
+                            .iter()
+                            .find(|(name, _)| name == detector_name)
+                            .map(|(_, delta)| *delta)
+                    })
+                    .flatten();
 
                 // Show memory delta if available for this detector
                 if let Some(delta) = memory_delta {
@@ -51,9 +405,52 @@ fn print_scan_info(result: &ScanResult, verbose: bool) {
                 }
             }
         }
+
+        if verbose && !result.coverage.is_empty() {
+            println!("\ndetector coverage:");
+            for line in coverage_lines(result) {
+                println!("{line}");
+            }
+        }
     }
 }
 
+/// "  docker     ran        3 entries  7.3 GB, 4.1 GB reclaimable (40%)"
+/// per detector, for the verbose coverage summary. Skipped detectors get
+/// just their status.
+fn coverage_lines(result: &ScanResult) -> Vec<String> {
+    let total_reclaimable: u64 = result.coverage.iter().map(|c| c.reclaimable_bytes).sum();
+    let width = result
+        .coverage
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or(0);
+    result
+        .coverage
+        .iter()
+        .map(|c| {
+            let status = c.status.as_str();
+            if !matches!(c.status, CoverageStatus::Ran | CoverageStatus::Errored) {
+                return format!("  {:<width$}  {status}", c.name);
+            }
+            let share = c
+                .reclaimable_bytes
+                .saturating_mul(100)
+                .checked_div(total_reclaimable)
+                .unwrap_or(0);
+            format!(
+                "  {:<width$}  {status:<11} {:>4} {}  {}, {} reclaimable ({share}%)",
+                c.name,
+                c.entries,
+                if c.entries == 1 { "entry  " } else { "entries" },
+                format_bytes(c.size_bytes),
+                format_bytes(c.reclaimable_bytes)
+            )
+        })
+        .collect()
+}
+
 fn print_diagnostics(result: &ScanResult, verbose: bool) {
     if result.diagnostics.is_empty() {
         return;
@@ -64,11 +461,242 @@ fn print_diagnostics(result: &ScanResult, verbose: bool) {
         println!("Diagnostics:");
         println!("{}", "-".repeat(40));
         for diagnostic in &result.diagnostics {
-            println!("  {diagnostic}");
+            println!("  {}", util::plain(diagnostic));
         }
     } else {
-        for diagnostic in &result.diagnostics {
-            println!("[diagnostic] {diagnostic}");
-        }
+        println!("{}", util::plain(&diagnostics_summary(&result.diagnostics)));
+    }
+}
+
+/// Diagnostics that mean the results may be incomplete (unreadable paths,
+/// failed or timed-out detectors), as opposed to informational notes like
+/// skipped detectors.
+fn is_warning(diagnostic: &str) -> bool {
+    const MARKERS: [&str; 5] = [
+        "underestimated",
+        "failed",
+        "permission denied",
+        "timed out",
+        "could not",
+    ];
+    MARKERS.iter().any(|m| diagnostic.contains(m))
+}
+
+/// One-line count shown instead of the full list when not verbose.
+fn diagnostics_summary(diagnostics: &[String]) -> String {
+    let total = diagnostics.len();
+    let warnings = diagnostics.iter().filter(|d| is_warning(d)).count();
+
+    let noun = if total == 1 {
+        "diagnostic"
+    } else {
+        "diagnostics"
+    };
+    let mut line = format!("{total} {noun}");
+    if warnings > 0 {
+        let noun = if warnings == 1 { "warning" } else { "warnings" };
+        line.push_str(&format!(" ({warnings} {noun}, sizes may be incomplete)"));
+    }
+    line.push_str(" — run with -v for details");
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_lines_show_status_and_share() {
+        use crate::scan::DetectorCoverage;
+
+        let coverage = |name: &str, status, entries, bytes| DetectorCoverage {
+            name: name.to_string(),
+            status,
+            entries,
+            size_bytes: bytes,
+            reclaimable_bytes: bytes,
+        };
+        let result = ScanResult {
+            coverage: vec![
+                coverage("projects", CoverageStatus::Ran, 3, 3072),
+                coverage("docker", CoverageStatus::Errored, 1, 1024),
+                coverage("xcode", CoverageStatus::Unavailable, 0, 0),
+            ],
+            ..ScanResult::empty()
+        };
+        assert_eq!(
+            coverage_lines(&result),
+            vec![
+                "  projects  ran            3 entries  3.0 KB, 3.0 KB reclaimable (75%)",
+                "  docker    errored        1 entry    1.0 KB, 1.0 KB reclaimable (25%)",
+                "  xcode     unavailable",
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_scan_hint_lists_roots() {
+        use std::path::PathBuf;
+
+        let hint = empty_scan_hint(&[PathBuf::from("/home/a/code"), PathBuf::from("/srv")]);
+        assert!(hint.starts_with("Scanned: /home/a/code, /srv."));
+        assert!(hint.contains("--roots"));
+        assert!(empty_scan_hint(&[]).starts_with("Scanned: no roots."));
+    }
+
+    #[test]
+    fn estimate_summary_sets_expectations() {
+        use crate::scan::projects::Estimate;
+
+        let small = Estimate {
+            candidates: 3,
+            entries: 420,
+            depth_limited: false,
+        };
+        assert_eq!(
+            estimate_summary(&small, 6),
+            "Found ~3 candidate artifacts, ~420 files to stat — full scan should take seconds."
+        );
+
+        let big = Estimate {
+            candidates: 42,
+            entries: 812_345,
+            depth_limited: true,
+        };
+        assert_eq!(
+            estimate_summary(&big, 6),
+            "Found ~42 candidate artifacts, ~812k files to stat (more below depth 6) — full scan may take a while."
+        );
+    }
+
+    #[test]
+    fn stale_snapshots_warned_with_age() {
+        assert_eq!(stale_snapshot_warning(3600), None);
+        assert_eq!(stale_snapshot_warning(SNAPSHOT_FRESH_SECS), None);
+        assert_eq!(
+            stale_snapshot_warning(SNAPSHOT_FRESH_SECS + 1).as_deref(),
+            Some("snapshot from 1 day ago — run 'heft scan' to refresh")
+        );
+        assert!(stale_snapshot_warning(3 * 86_400 + 60)
+            .unwrap()
+            .starts_with("snapshot from 3 days ago"));
+    }
+
+    #[test]
+    fn near_full_disks_warned_once_per_filesystem() {
+        use crate::scan::disk::DiskUsage;
+        use std::path::PathBuf;
+
+        let disk = |root: &str, free: u64, device_id| DiskUsage {
+            root: PathBuf::from(root),
+            disk_free_bytes: free,
+            disk_total_bytes: 100 * 1024,
+            device_id,
+        };
+        let result = ScanResult {
+            disks: vec![
+                disk("/home/a/code", 5 * 1024, Some(1)),
+                disk("/home/a/work", 5 * 1024, Some(1)),
+                disk("/mnt/data", 60 * 1024, Some(2)),
+                // another near-full disk that happens to match the first
+                disk("/mnt/backup", 5 * 1024, Some(3)),
+            ],
+            ..ScanResult::empty()
+        };
+        assert_eq!(
+            disk_warnings(&result),
+            vec![
+                "warning: disk holding /home/a/code is 95% full, 5.0 KB free of 100.0 KB",
+                "warning: disk holding /mnt/backup is 95% full, 5.0 KB free of 100.0 KB"
+            ]
+        );
+
+        // without device ids, identical figures mean one disk
+        let result = ScanResult {
+            disks: vec![
+                disk("C:\\code", 5 * 1024, None),
+                disk("C:\\work", 5 * 1024, None),
+            ],
+            ..ScanResult::empty()
+        };
+        assert_eq!(disk_warnings(&result).len(), 1);
+    }
+
+    #[test]
+    fn summary_counts_warnings() {
+        let diagnostics = vec![
+            "docker: skipped (disabled by config)".to_string(),
+            "permission denied: /root/x (size may be underestimated)".to_string(),
+            "failed to scan /a: boom".to_string(),
+        ];
+        assert_eq!(
+            diagnostics_summary(&diagnostics),
+            "3 diagnostics (2 warnings, sizes may be incomplete) — run with -v for details"
+        );
+    }
+
+    #[test]
+    fn summary_without_warnings() {
+        let diagnostics = vec!["xcode: skipped (not available on this platform)".to_string()];
+        assert_eq!(
+            diagnostics_summary(&diagnostics),
+            "1 diagnostic — run with -v for details"
+        );
+    }
+
+    #[test]
+    fn entries_grouped_by_root_detector_and_age() {
+        let day = 86_400;
+        let now = 1_000 * day;
+        let entry = |path: &str, size, detector: &str, age_days: i64| BloatEntry {
+            category: BloatCategory::ProjectArtifacts,
+            name: path.to_string(),
+            location: Location::FilesystemPath(PathBuf::from(path)),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified: Some(now - age_days * day),
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: Some(detector.to_string()),
+            file_count: None,
+        };
+        let entries = vec![
+            entry("/work/a/target", 100, "projects", 5),
+            entry("/work/nested/b/node_modules", 300, "projects", 400),
+            entry("/home/me/.npm", 50, "caches", 60),
+        ];
+        let roots = [PathBuf::from("/work"), PathBuf::from("/work/nested")];
+        let labels = |by| {
+            group_entries(&entries, by, &roots, now)
+                .into_iter()
+                .map(|(label, entries)| (label, entries.len()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            labels(GroupBy::Root),
+            [
+                ("/work/nested".to_string(), 1),
+                ("/work".to_string(), 1),
+                ("outside the scan roots".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            labels(GroupBy::Detector),
+            [("projects".to_string(), 2), ("caches".to_string(), 1)]
+        );
+        assert_eq!(
+            labels(GroupBy::Age),
+            [
+                ("over a year".to_string(), 1),
+                ("under 30 days".to_string(), 1),
+                ("30-90 days".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            labels(GroupBy::Category),
+            [("ProjectArtifacts".to_string(), 3)]
+        );
     }
 }
@@ -0,0 +1,133 @@
+//! Bare path listing for scan results (--list-paths).
+//!
+//! Just the filesystem locations, largest first, one per line or
+//! NUL-terminated with --print0, so heft can find things and the user's
+//! own tools (`xargs rm -rf`, `fzf`) can act on them. Docker objects and
+//! aggregates have no path and are left out.
+//!
+//! With --reclaimable-only the list is what a plain `heft clean` would
+//! delete, using clean's own checks, so piping it to `rm` never takes more.
+
+use crate::clean;
+use crate::scan::detector::Location;
+use crate::scan::ScanResult;
+
+/// Paths of the entries in `result`, each followed by `\n`, or by `\0` when
+/// `print0`. Raw bytes, so paths that aren't UTF-8 survive the pipe.
+/// `reclaimable_only` keeps what clean would delete, honouring
+/// `never_delete`.
+pub fn render(
+    result: &ScanResult,
+    reclaimable_only: bool,
+    never_delete: &[String],
+    print0: bool,
+) -> Vec<u8> {
+    let mut entries: Vec<_> = result
+        .entries
+        .iter()
+        .filter(|e| !reclaimable_only || clean::would_clean(e, never_delete))
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+
+    let mut output = Vec::new();
+    for entry in entries {
+        let Location::FilesystemPath(path) = &entry.location else {
+            continue;
+        };
+        output.extend_from_slice(&path_bytes(path.as_os_str()));
+        output.push(if print0 { b'\0' } else { b'\n' });
+    }
+    output
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &std::ffi::OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &std::ffi::OsStr) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry};
+    use std::path::PathBuf;
+
+    fn entry(location: Location, size: u64, reclaimable: u64) -> BloatEntry {
+        BloatEntry {
+            category: BloatCategory::ProjectArtifacts,
+            name: "x".to_string(),
+            location,
+            size_bytes: size,
+            reclaimable_bytes: reclaimable,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    #[test]
+    fn paths_listed_largest_first_without_other_locations() {
+        let mut result = ScanResult::empty();
+        result.entries = vec![
+            entry(
+                Location::FilesystemPath(PathBuf::from("/a/node_modules")),
+                10,
+                10,
+            ),
+            entry(
+                Location::FilesystemPath(PathBuf::from("/my app/.git")),
+                30,
+                0,
+            ),
+            entry(Location::DockerObject("abc".to_string()), 50, 50),
+            entry(Location::FilesystemPath(PathBuf::from("/b/target")), 20, 20),
+        ];
+
+        assert_eq!(
+            render(&result, false, &[], false),
+            b"/my app/.git\n/b/target\n/a/node_modules\n"
+        );
+        assert_eq!(
+            render(&result, true, &[], true),
+            b"/b/target\0/a/node_modules\0"
+        );
+    }
+
+    #[test]
+    fn reclaimable_only_leaves_out_what_clean_would_keep() {
+        let mut result = ScanResult::empty();
+        let mut backup = entry(
+            Location::FilesystemPath(PathBuf::from("/home/me/Backup/0001")),
+            90,
+            90,
+        );
+        backup.tags = vec![crate::scan::mobilesync::BACKUP_TAG.to_string()];
+        result.entries = vec![
+            backup,
+            entry(
+                Location::FilesystemPath(PathBuf::from("/home/me/keep/target")),
+                20,
+                20,
+            ),
+            entry(
+                Location::FilesystemPath(PathBuf::from("/home/me/app/target")),
+                10,
+                10,
+            ),
+        ];
+
+        let never_delete = vec!["/home/me/keep".to_string()];
+        assert_eq!(
+            render(&result, true, &never_delete, false),
+            b"/home/me/app/target\n"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Prometheus text exposition for scan results.
+//!
+//! Written for node_exporter's textfile collector: run
+//! `heft scan --format prometheus > /var/lib/node_exporter/heft.prom` from
+//! cron and graph or alert on the gauges. Per-entry series are labelled by
+//! category, name and location; two projects can share a name, so the
+//! location keeps each series unique.
+
+use super::location_text;
+use crate::scan::ScanResult;
+
+pub fn render(result: &ScanResult) -> String {
+    let mut output = String::new();
+
+    gauge(
+        &mut output,
+        "heft_entry_size_bytes",
+        "Size of each detected entry.",
+    );
+    for entry in &result.entries {
+        output.push_str(&format!(
+            "heft_entry_size_bytes{{category=\"{}\",name=\"{}\",location=\"{}\"}} {}\n",
+            entry.category.as_str(),
+            escape(&entry.name),
+            escape(&location_text(&entry.location)),
+            entry.size_bytes
+        ));
+    }
+
+    gauge(
+        &mut output,
+        "heft_entry_reclaimable_bytes",
+        "Space heft clean could free for each entry.",
+    );
+    for entry in &result.entries {
+        output.push_str(&format!(
+            "heft_entry_reclaimable_bytes{{category=\"{}\",name=\"{}\",location=\"{}\"}} {}\n",
+            entry.category.as_str(),
+            escape(&entry.name),
+            escape(&location_text(&entry.location)),
+            entry.reclaimable_bytes
+        ));
+    }
+
+    let total: u64 = result.entries.iter().map(|e| e.size_bytes).sum();
+    let reclaimable: u64 = result.entries.iter().map(|e| e.reclaimable_bytes).sum();
+    gauge(
+        &mut output,
+        "heft_total_bytes",
+        "Total size of all entries.",
+    );
+    output.push_str(&format!("heft_total_bytes {total}\n"));
+    gauge(
+        &mut output,
+        "heft_reclaimable_bytes",
+        "Total reclaimable space across all entries.",
+    );
+    output.push_str(&format!("heft_reclaimable_bytes {reclaimable}\n"));
+
+    if !result.disks.is_empty() {
+        gauge(
+            &mut output,
+            "heft_disk_free_bytes",
+            "Free space on the filesystem holding each scan root.",
+        );
+        for disk in &result.disks {
+            output.push_str(&format!(
+                "heft_disk_free_bytes{{root=\"{}\"}} {}\n",
+                escape(&disk.root.display().to_string()),
+                disk.disk_free_bytes
+            ));
+        }
+        gauge(
+            &mut output,
+            "heft_disk_total_bytes",
+            "Size of the filesystem holding each scan root.",
+        );
+        for disk in &result.disks {
+            output.push_str(&format!(
+                "heft_disk_total_bytes{{root=\"{}\"}} {}\n",
+                escape(&disk.root.display().to_string()),
+                disk.disk_total_bytes
+            ));
+        }
+    }
+
+    if let Some(duration_ms) = result.duration_ms {
+        gauge(
+            &mut output,
+            "heft_scan_duration_seconds",
+            "Wall time of the scan.",
+        );
+        output.push_str(&format!(
+            "heft_scan_duration_seconds {:.3}\n",
+            duration_ms as f64 / 1000.0
+        ));
+    }
+
+    output
+}
+
+fn gauge(output: &mut String, name: &str, help: &str) {
+    output.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n"));
+}
+
+/// Label values escape backslash, double quote and newline.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+
+    #[test]
+    fn entries_and_totals_exposed_as_gauges() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::ProjectArtifacts,
+                name: "my \"app\"".to_string(),
+                location: Location::Aggregate("x".to_string()),
+                size_bytes: 300,
+                reclaimable_bytes: 200,
+                last_modified: None,
+                cleanup_hint: None,
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            duration_ms: Some(1500),
+            ..ScanResult::empty()
+        };
+        let output = render(&result);
+        assert!(output.contains("# TYPE heft_entry_size_bytes gauge\n"));
+        assert!(output.contains(
+            "heft_entry_size_bytes{category=\"ProjectArtifacts\",name=\"my \\\"app\\\"\",location=\"x\"} 300\n"
+        ));
+        assert!(output.contains("heft_reclaimable_bytes 200\n"));
+        assert!(output.contains("heft_scan_duration_seconds 1.500\n"));
+        // no roots were measured, so no disk series
+        assert!(!output.contains("heft_disk_free_bytes"));
+    }
+}
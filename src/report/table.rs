@@ -1,40 +1,96 @@
 //! TUI table rendering for scan results.
 //!
 //! Formats output as a categorized terminal table:
-//! - Groups entries by BloatCategory
-//! - Shows per-category totals and grand total
+//! - Groups entries by BloatCategory, or by detector, scan root, age or
+//!   filesystem (--group-by)
+//! - Shows per-group totals and grand total
 //! - Sorts by reclaimable size descending
+//! - Optionally lists each entry's location, shortened (--relative); always
+//!   for Other entries, so they can be recategorized
+//! - Optionally lists each entry's cleanup hint (--hints / verbose)
+//! - Optionally totals what only a manual step can free (--include-manual)
+//! - Optionally leads with the biggest entries across categories (--summary)
 
-use crate::scan::detector::BloatCategory;
+use crate::scan::detector::{BloatCategory, Location};
 use crate::scan::ScanResult;
 use crate::util::format_bytes;
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Shortens locations for display: paths under home become `~/...`, other
+/// paths are shown relative to the scan root that contains them.
+pub struct RelativePaths {
+    pub roots: Vec<PathBuf>,
+    pub home: Option<PathBuf>,
+}
+
+impl RelativePaths {
+    pub fn display(&self, path: &Path) -> String {
+        if let Some(home) = &self.home {
+            if let Ok(rest) = path.strip_prefix(home) {
+                return if rest.as_os_str().is_empty() {
+                    "~".to_string()
+                } else {
+                    format!("~/{}", rest.display())
+                };
+            }
+        }
+        self.roots
+            .iter()
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .find(|rest| !rest.as_os_str().is_empty())
+            .map(|rest| rest.display().to_string())
+            .unwrap_or_else(|| path.display().to_string())
+    }
+}
+
+/// Extra per-entry lines below each table row.
+#[derive(Default)]
+pub struct TableOptions<'a> {
+    /// Show each filesystem entry's location, shortened
+    pub paths: Option<&'a RelativePaths>,
+    /// Show each entry's cleanup hint
+    pub hints: bool,
+    /// Add a "manually reclaimable" total for entries marked `manual`
+    pub include_manual: bool,
+    /// List this many of the biggest entries, across categories, first
+    pub summary_top: Option<usize>,
+    /// Show the reclaimable total as progress toward this many bytes
+    pub goal: Option<u64>,
+    /// What entries are grouped and subtotalled by
+    pub group_by: super::GroupBy,
+    /// Scan roots, for grouping by root
+    pub roots: &'a [PathBuf],
+}
 
 pub fn render(result: &ScanResult) -> String {
+    render_with(result, &TableOptions::default())
+}
+
+/// Like `render`, with the location and/or cleanup hint under each entry.
+pub fn render_with(result: &ScanResult, options: &TableOptions) -> String {
     if result.entries.is_empty() {
         return String::from("No bloat detected.\n");
     }
 
     let mut output = String::new();
 
-    // group entries by category
-    let mut by_category: HashMap<BloatCategory, Vec<_>> = HashMap::new();
-    for entry in &result.entries {
-        by_category.entry(entry.category).or_default().push(entry);
+    if let Some(top) = options.summary_top {
+        output.push_str(&top_entries(result, top));
     }
 
-    // sort categories by total size (largest first)
-    let mut categories: Vec<_> = by_category.keys().copied().collect();
-    categories.sort_by_key(|cat| {
-        std::cmp::Reverse(by_category[cat].iter().map(|e| e.size_bytes).sum::<u64>())
-    });
+    // groups and their entries largest first
+    let groups = super::group_entries(
+        &result.entries,
+        options.group_by,
+        options.roots,
+        chrono::Utc::now().timestamp(),
+    );
 
     let mut grand_found: u64 = 0;
     let mut grand_reclaimable: u64 = 0;
 
-    for category in categories {
-        let entries = &by_category[&category];
-        let category_total: u64 = entries.iter().map(|e| e.size_bytes).sum();
+    for (group, entries) in groups {
+        let group_total: u64 = entries.iter().map(|e| e.size_bytes).sum();
         grand_found += entries
             .iter()
             .filter(|e| e.reclaimable_bytes > 0)
@@ -42,26 +98,36 @@ pub fn render(result: &ScanResult) -> String {
             .sum::<u64>();
         grand_reclaimable += entries.iter().map(|e| e.reclaimable_bytes).sum::<u64>();
 
-        output.push_str(&format!("\n{category:?}\n"));
+        output.push_str(&format!("\n{group}\n"));
         output.push_str(&"-".repeat(40));
         output.push('\n');
 
-        // sort entries within category by size
-        let mut sorted_entries: Vec<_> = entries.iter().collect();
-        sorted_entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
-
-        for entry in sorted_entries {
+        for entry in &entries {
             output.push_str(&format!(
                 "  {:30} {:>10}\n",
                 truncate(&entry.name, 30),
                 format_bytes(entry.size_bytes)
             ));
+            // Other is the catch-all; its names alone rarely say what the
+            // entry is, so the location is always shown to help pick an override
+            match (options.paths, &entry.location) {
+                (Some(paths), Location::FilesystemPath(path)) => {
+                    output.push_str(&format!("    {}\n", paths.display(path)));
+                }
+                (_, location) if entry.category == BloatCategory::Other => {
+                    output.push_str(&format!("    {}\n", super::location_text(location)));
+                }
+                _ => {}
+            }
+            if let (true, Some(hint)) = (options.hints, &entry.cleanup_hint) {
+                output.push_str(&format!("    hint: {hint}\n"));
+            }
         }
 
         output.push_str(&format!(
             "  {:30} {:>10}\n",
             "subtotal",
-            format_bytes(category_total)
+            format_bytes(group_total)
         ));
     }
 
@@ -70,11 +136,61 @@ pub fn render(result: &ScanResult) -> String {
         format_bytes(grand_found),
         format_bytes(grand_reclaimable),
     ));
+    if let Some(goal) = options.goal {
+        output.push_str(&format!(
+            "Goal:  {}\n",
+            crate::util::goal_bar(grand_reclaimable, goal)
+        ));
+    }
+
+    if options.include_manual {
+        let manual: u64 = result
+            .entries
+            .iter()
+            .filter(|e| e.manual)
+            .map(|e| e.size_bytes)
+            .sum();
+        if manual > 0 {
+            output.push_str(&format!(
+                "       {} manually reclaimable (not deleted by heft; see --hints)\n",
+                format_bytes(manual)
+            ));
+        }
+    }
+
+    output
+}
+
+/// "what to delete first": the biggest entries by reclaimable size,
+/// whatever their category.
+fn top_entries(result: &ScanResult, top: usize) -> String {
+    let mut entries: Vec<_> = result
+        .entries
+        .iter()
+        .filter(|e| e.reclaimable_bytes > 0)
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.reclaimable_bytes));
+    entries.truncate(top);
+    if entries.is_empty() {
+        return String::new();
+    }
 
+    let mut output = format!("\nTop {} by reclaimable size\n", entries.len());
+    output.push_str(&"-".repeat(40));
+    output.push('\n');
+    for entry in entries {
+        output.push_str(&format!(
+            "  {:>10}  {:18} {}\n",
+            format_bytes(entry.reclaimable_bytes),
+            entry.category.label(),
+            entry.name
+        ));
+    }
     output
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
+/// `s` cut to `max_len` characters, ending in "..." when it was longer.
+pub fn truncate(s: &str, max_len: usize) -> String {
     if s.chars().count() <= max_len {
         s.to_string()
     } else {
@@ -82,3 +198,220 @@ fn truncate(s: &str, max_len: usize) -> String {
         format!("{truncated}...")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::BloatEntry;
+
+    fn relative() -> RelativePaths {
+        RelativePaths {
+            roots: vec![PathBuf::from("/srv/code")],
+            home: Some(PathBuf::from("/home/alice")),
+        }
+    }
+
+    #[test]
+    fn home_paths_use_tilde() {
+        assert_eq!(
+            relative().display(Path::new("/home/alice/work/app/node_modules")),
+            "~/work/app/node_modules"
+        );
+    }
+
+    #[test]
+    fn root_paths_are_relative_to_root() {
+        assert_eq!(
+            relative().display(Path::new("/srv/code/api/target")),
+            "api/target"
+        );
+        // outside both stays absolute
+        assert_eq!(relative().display(Path::new("/opt/x")), "/opt/x");
+    }
+
+    #[test]
+    fn paths_only_listed_when_requested() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::ProjectArtifacts,
+                name: "app".to_string(),
+                location: Location::FilesystemPath(PathBuf::from("/home/alice/app/node_modules")),
+                size_bytes: 10,
+                reclaimable_bytes: 10,
+                last_modified: None,
+                cleanup_hint: None,
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            ..ScanResult::empty()
+        };
+        assert!(!render(&result).contains("node_modules"));
+        let options = TableOptions {
+            paths: Some(&relative()),
+            ..Default::default()
+        };
+        assert!(render_with(&result, &options).contains("    ~/app/node_modules\n"));
+    }
+
+    #[test]
+    fn other_entries_always_list_their_location() {
+        let entry = |category, path: &str| BloatEntry {
+            category,
+            name: "cache".to_string(),
+            location: Location::FilesystemPath(PathBuf::from(path)),
+            size_bytes: 10,
+            reclaimable_bytes: 10,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        };
+        let result = ScanResult {
+            entries: vec![
+                entry(BloatCategory::Other, "/home/alice/.cache/mystery"),
+                entry(BloatCategory::PackageCache, "/home/alice/.npm"),
+            ],
+            ..ScanResult::empty()
+        };
+
+        let output = render(&result);
+        assert!(output.contains("    /home/alice/.cache/mystery\n"));
+        assert!(!output.contains(".npm"));
+
+        let options = TableOptions {
+            paths: Some(&relative()),
+            ..Default::default()
+        };
+        assert!(render_with(&result, &options).contains("    ~/.cache/mystery\n"));
+    }
+
+    #[test]
+    fn summary_lists_biggest_across_categories() {
+        let entry = |name: &str, category, reclaimable: u64| BloatEntry {
+            category,
+            name: name.to_string(),
+            location: Location::Aggregate(name.to_string()),
+            size_bytes: reclaimable,
+            reclaimable_bytes: reclaimable,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        };
+        let result = ScanResult {
+            entries: vec![
+                entry("npm cache", BloatCategory::PackageCache, 2048),
+                entry("app", BloatCategory::ProjectArtifacts, 4096),
+                entry("pip cache", BloatCategory::PackageCache, 1024),
+                entry("swap file", BloatCategory::SystemCache, 0),
+            ],
+            ..ScanResult::empty()
+        };
+        assert!(!render(&result).contains("Top "));
+
+        let options = TableOptions {
+            summary_top: Some(2),
+            ..Default::default()
+        };
+        let output = render_with(&result, &options);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[1], "Top 2 by reclaimable size");
+        assert_eq!(lines[3], "      4.0 KB  Project Artifacts  app");
+        assert_eq!(lines[4], "      2.0 KB  Package Cache      npm cache");
+        assert!(!lines[5].contains("pip cache"));
+    }
+
+    #[test]
+    fn hints_only_listed_when_requested() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::ContainerData,
+                name: "WSL2 disk".to_string(),
+                location: Location::Aggregate("wsl".to_string()),
+                size_bytes: 10,
+                reclaimable_bytes: 0,
+                last_modified: None,
+                cleanup_hint: Some("wsl --shutdown, then compact the vhdx".to_string()),
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            ..ScanResult::empty()
+        };
+        assert!(!render(&result).contains("hint:"));
+        let options = TableOptions {
+            hints: true,
+            ..Default::default()
+        };
+        assert!(render_with(&result, &options)
+            .contains("    hint: wsl --shutdown, then compact the vhdx\n"));
+    }
+
+    #[test]
+    fn manual_total_only_with_include_manual() {
+        let entry = |name: &str, reclaimable: u64, manual: bool| BloatEntry {
+            category: BloatCategory::SystemCache,
+            name: name.to_string(),
+            location: Location::Aggregate(name.to_string()),
+            size_bytes: 2048,
+            reclaimable_bytes: reclaimable,
+            last_modified: None,
+            cleanup_hint: None,
+            manual,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        };
+        let result = ScanResult {
+            entries: vec![
+                entry("cache", 2048, false),
+                entry("WSL2 disk", 0, true),
+                entry("swap file", 0, false),
+            ],
+            ..ScanResult::empty()
+        };
+        assert!(!render(&result).contains("manually"));
+        let options = TableOptions {
+            include_manual: true,
+            ..Default::default()
+        };
+        let output = render_with(&result, &options);
+        assert!(output.contains("Total: 2.0 KB found, 2.0 KB reclaimable\n"));
+        assert!(output.contains("2.0 KB manually reclaimable"));
+    }
+
+    #[test]
+    fn goal_line_under_total() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::PackageCache,
+                name: "npm cache".to_string(),
+                location: Location::Aggregate("npm cache".to_string()),
+                size_bytes: 1024,
+                reclaimable_bytes: 1024,
+                last_modified: None,
+                cleanup_hint: None,
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            ..ScanResult::empty()
+        };
+        assert!(!render(&result).contains("Goal:"));
+        let options = TableOptions {
+            goal: Some(4096),
+            ..Default::default()
+        };
+        let output = render_with(&result, &options);
+        assert!(output.contains("Goal:  ["));
+        assert!(output.contains("1.0 KB of 4.0 KB goal (25%)\n"));
+    }
+}
@@ -0,0 +1,73 @@
+//! TOML output for scan results.
+//!
+//! The same document as `--format json`, re-encoded as TOML. TOML has no
+//! null, so fields that are null in JSON (an entry without a modification
+//! time) are left out instead.
+
+use super::json;
+use crate::scan::ScanResult;
+
+pub fn render(result: &ScanResult) -> String {
+    json::document(result)
+        .map_err(|e| e.to_string())
+        .and_then(|mut document| {
+            strip_nulls(&mut document);
+            ::toml::to_string_pretty(&document).map_err(|e| e.to_string())
+        })
+        .unwrap_or_else(|e| {
+            let message = ::toml::Value::String(format!("failed to serialize: {e}"));
+            format!("error = {message}\n")
+        })
+}
+
+/// Drop null members from every object, recursively.
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+    use std::path::PathBuf;
+
+    #[test]
+    fn entries_become_array_of_tables_without_nulls() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::ProjectArtifacts,
+                name: "app".to_string(),
+                location: Location::FilesystemPath(PathBuf::from("/code/app/target")),
+                size_bytes: 4096,
+                reclaimable_bytes: 4096,
+                last_modified: None,
+                cleanup_hint: None,
+                manual: false,
+                tags: Vec::new(),
+                detector: None,
+                file_count: None,
+            }],
+            duration_ms: Some(12),
+            detector_timings: vec![("projects".to_string(), 10)],
+            ..ScanResult::empty()
+        };
+
+        let output = render(&result);
+        assert!(output.contains("[[entries]]\n"), "{output}");
+        assert!(!output.contains("last_modified"));
+        let parsed: ::toml::Value = ::toml::from_str(&output).unwrap();
+        assert_eq!(parsed["status"].as_str(), Some("found"));
+        assert_eq!(parsed["entries"][0]["size_bytes"].as_integer(), Some(4096));
+        assert_eq!(
+            parsed["entries"][0]["location"]["FilesystemPath"].as_str(),
+            Some("/code/app/target")
+        );
+    }
+}
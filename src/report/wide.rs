@@ -0,0 +1,159 @@
+//! Wide table output for scan results (`--format table-wide`).
+//!
+//! Every BloatEntry field as an aligned column, one row per entry, largest
+//! first. A flat view of what the JSON carries, without needing jq. Columns
+//! empty on every row (often Modified and Hint) are left out.
+
+use super::location_text;
+use crate::scan::ScanResult;
+use crate::util::format_bytes;
+
+pub fn render(result: &ScanResult) -> String {
+    if result.entries.is_empty() {
+        return String::from("No bloat detected.\n");
+    }
+
+    let mut entries: Vec<_> = result.entries.iter().collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+
+    let header = [
+        "Name",
+        "Category",
+        "Size",
+        "Reclaimable",
+        "Modified",
+        "Tags",
+        "Location",
+        "Hint",
+    ];
+    // size columns read better right-aligned
+    let right_aligned = [false, false, true, true, false, false, false, false];
+    let rows: Vec<[String; 8]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.name.clone(),
+                entry.category.label().to_string(),
+                format_bytes(entry.size_bytes),
+                format_bytes(entry.reclaimable_bytes),
+                entry
+                    .last_modified
+                    .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                entry.tags.join(","),
+                location_text(&entry.location),
+                entry.cleanup_hint.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let columns: Vec<usize> = (0..header.len())
+        .filter(|&col| rows.iter().any(|row| !row[col].is_empty()))
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|&col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .chain([header[col].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |cells: &dyn Fn(usize) -> String| -> String {
+        let mut out: Vec<String> = Vec::new();
+        for (i, (&col, &width)) in columns.iter().zip(&widths).enumerate() {
+            let cell = cells(col);
+            let last = i + 1 == columns.len();
+            out.push(if right_aligned[col] {
+                format!("{cell:>width$}")
+            } else if last {
+                // no trailing padding on the final column
+                cell
+            } else {
+                format!("{cell:<width$}")
+            });
+        }
+        out.join("  ") + "\n"
+    };
+
+    let mut output = line(&|col| header[col].to_string());
+    output.push_str(&"-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1)));
+    output.push('\n');
+    for row in &rows {
+        output.push_str(&line(&|col| row[col].clone()));
+    }
+
+    let total: u64 = entries.iter().map(|e| e.size_bytes).sum();
+    let reclaimable: u64 = entries.iter().map(|e| e.reclaimable_bytes).sum();
+    output.push_str(&format!(
+        "\nTotal: {} found, {} reclaimable\n",
+        format_bytes(total),
+        format_bytes(reclaimable)
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+    use std::path::PathBuf;
+
+    fn entry(name: &str, size: u64, last_modified: Option<i64>) -> BloatEntry {
+        BloatEntry {
+            category: BloatCategory::ProjectArtifacts,
+            name: name.to_string(),
+            location: Location::FilesystemPath(PathBuf::from(format!("/code/{name}/target"))),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    #[test]
+    fn all_fields_as_aligned_columns() {
+        let result = ScanResult {
+            entries: vec![
+                entry("small", 1024, None),
+                entry("big-crate", 4096, Some(1_700_000_000)),
+            ],
+            ..ScanResult::empty()
+        };
+        let output = render(&result);
+        let rows: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            rows[0],
+            "Name       Category             Size  Reclaimable  Modified    Location"
+        );
+        assert_eq!(
+            rows[2],
+            "big-crate  Project Artifacts  4.0 KB       4.0 KB  2023-11-14  /code/big-crate/target"
+        );
+        assert_eq!(
+            rows[3],
+            "small      Project Artifacts  1.0 KB       1.0 KB              /code/small/target"
+        );
+        assert!(output.ends_with("Total: 5.0 KB found, 5.0 KB reclaimable\n"));
+    }
+
+    #[test]
+    fn empty_columns_dropped() {
+        let result = ScanResult {
+            entries: vec![entry("app", 10, None)],
+            ..ScanResult::empty()
+        };
+        let header = render(&result).lines().next().unwrap().to_string();
+        assert!(!header.contains("Modified"));
+        assert!(!header.contains("Hint"));
+        assert!(header.ends_with("Location"));
+    }
+}
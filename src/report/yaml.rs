@@ -0,0 +1,181 @@
+//! YAML output for scan results.
+//!
+//! The same document as `--format json` (status, entries, timings, ...),
+//! re-encoded as block-style YAML for people who'd rather read it. Like the
+//! TOML output it goes through the JSON value, so locations keep JSON's
+//! single-key objects (`location: {FilesystemPath: ...}` as a nested map).
+
+use serde_json::Value;
+
+use super::json;
+use crate::scan::ScanResult;
+
+pub fn render(result: &ScanResult) -> String {
+    // like JSON, a path that isn't valid UTF-8 fails the whole document
+    match json::document(result) {
+        Ok(document) => {
+            let mut out = String::new();
+            write_value(&mut out, &document, 0);
+            out
+        }
+        Err(e) => format!("error: {}\n", scalar(&format!("failed to serialize: {e}"))),
+    }
+}
+
+/// Write `value` as the body of a mapping key or sequence item at `indent`.
+/// Scalars and empty collections go on the current line; the rest start a
+/// new block.
+fn write_value(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                out.push_str(&" ".repeat(indent));
+                out.push_str(&scalar(key));
+                out.push(':');
+                write_nested(out, value, indent);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for item in items {
+                out.push_str(&" ".repeat(indent));
+                out.push('-');
+                if is_block(item) {
+                    // the block's first line goes on the dash line
+                    let mut block = String::new();
+                    write_value(&mut block, item, indent + 2);
+                    out.push(' ');
+                    out.push_str(&block[indent + 2..]);
+                } else {
+                    out.push(' ');
+                    out.push_str(&inline(item));
+                    out.push('\n');
+                }
+            }
+        }
+        other => {
+            out.push_str(&inline(other));
+            out.push('\n');
+        }
+    }
+}
+
+/// After `key:`: a scalar on the same line, or a block below. Sequences
+/// sit at the key's own indent, maps one level in.
+fn write_nested(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Array(_) if is_block(value) => {
+            out.push('\n');
+            write_value(out, value, indent);
+        }
+        Value::Object(_) if is_block(value) => {
+            out.push('\n');
+            write_value(out, value, indent + 2);
+        }
+        other => {
+            out.push(' ');
+            out.push_str(&inline(other));
+            out.push('\n');
+        }
+    }
+}
+
+/// Non-empty maps and sequences take lines of their own.
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => false,
+    }
+}
+
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => scalar(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+/// A string as a plain scalar when YAML would read it back unchanged,
+/// otherwise double-quoted. JSON string syntax is valid double-quoted YAML.
+fn scalar(s: &str) -> String {
+    let plain = s
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '/' || c == '_')
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || " ._/-+@()".contains(c))
+        && !s.ends_with(' ')
+        && !matches!(
+            s.to_ascii_lowercase().as_str(),
+            "true" | "false" | "null" | "yes" | "no" | "on" | "off" | "y" | "n"
+        );
+    if plain {
+        s.to_string()
+    } else {
+        serde_json::to_string(s).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+    use std::path::PathBuf;
+
+    #[test]
+    fn same_fields_as_json_in_block_style() {
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::PackageCache,
+                name: "npm cache".to_string(),
+                location: Location::FilesystemPath(PathBuf::from("/home/u/.npm")),
+                size_bytes: 2048,
+                reclaimable_bytes: 2048,
+                last_modified: None,
+                cleanup_hint: Some("npm cache clean --force".to_string()),
+                manual: false,
+                tags: vec!["nodejs".to_string()],
+                detector: None,
+                file_count: None,
+            }],
+            detector_timings: vec![("caches".to_string(), 12)],
+            ..ScanResult::empty()
+        };
+
+        assert_eq!(
+            render(&result),
+            "\
+detector_timings:
+- - caches
+  - 12
+diagnostics: []
+entries:
+- category: PackageCache
+  cleanup_hint: npm cache clean --force
+  last_modified: null
+  location:
+    FilesystemPath: /home/u/.npm
+  name: npm cache
+  reclaimable_bytes: 2048
+  size_bytes: 2048
+  tags:
+  - nodejs
+status: found
+"
+        );
+    }
+
+    #[test]
+    fn ambiguous_strings_are_quoted() {
+        assert_eq!(scalar("npm cache"), "npm cache");
+        assert_eq!(scalar("yes"), "\"yes\"");
+        assert_eq!(scalar("12"), "\"12\"");
+        assert_eq!(scalar(""), "\"\"");
+        assert_eq!(scalar("a: b"), "\"a: b\"");
+        assert_eq!(scalar("C:\\cache"), "\"C:\\\\cache\"");
+    }
+}
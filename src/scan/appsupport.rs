@@ -0,0 +1,134 @@
+//! ~/Library/Application Support detector (macOS only).
+//!
+//! Apps keep caches, downloaded models and game libraries here, and a
+//! single folder can outgrow the app itself. Each top-level folder of at
+//! least `MIN_FOLDER_BYTES` is reported so the big ones stand out. What's
+//! inside varies too much to call any of it safe, so entries are
+//! informational with zero reclaimable bytes.
+//!
+//! Folders other detectors already report (VSCode and pipx by caches, iOS
+//! backups in MobileSync by mobilesync) are skipped so nothing is counted
+//! twice.
+
+use std::path::{Path, PathBuf};
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform::{self, Platform};
+
+/// Folders smaller than this are not worth a line.
+const MIN_FOLDER_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Folders covered by the caches and mobilesync detectors.
+const COVERED_ELSEWHERE: &[&str] = &["Code", "pipx", "MobileSync"];
+
+pub struct AppSupportDetector;
+
+impl Detector for AppSupportDetector {
+    fn name(&self) -> &'static str {
+        "appsupport"
+    }
+
+    fn available(&self, config: &Config) -> bool {
+        config.platform == Platform::MacOS
+    }
+
+    fn scan(&self, config: &Config) -> DetectorResult {
+        let Some(home) = platform::home_dir() else {
+            return DetectorResult::with_diagnostic(
+                "appsupport: could not determine home directory".into(),
+            );
+        };
+        let mut result =
+            scan_app_support(&home.join("Library/Application Support"), MIN_FOLDER_BYTES);
+        if config.verbose() {
+            result.diagnostics.push(format!(
+                "appsupport: {} folder(s) of 1 GiB or more",
+                result.entries.len()
+            ));
+        }
+        result
+    }
+}
+
+/// One entry per folder in `dir` of at least `min_bytes`.
+fn scan_app_support(dir: &Path, min_bytes: u64) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+    let Ok(children) = std::fs::read_dir(dir) else {
+        return result;
+    };
+
+    let mut folders: Vec<PathBuf> = children
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| !COVERED_ELSEWHERE.contains(&n))
+        })
+        .collect();
+    folders.sort();
+
+    for folder in folders {
+        let folder_name = folder
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // skip folders we can't read
+        let Ok((size, warnings)) = super::calculate_dir_size(&folder) else {
+            continue;
+        };
+        if size == 0 || size < min_bytes {
+            continue;
+        }
+        result.diagnostics.extend(
+            warnings
+                .into_iter()
+                .map(|w| format!("{w} (size may be underestimated)")),
+        );
+        result.entries.push(BloatEntry {
+            category: BloatCategory::SystemCache,
+            name: format!("app data ({folder_name})"),
+            location: Location::FilesystemPath(folder),
+            size_bytes: size,
+            reclaimable_bytes: 0,
+            last_modified: None,
+            cleanup_hint: Some(format!("data kept by {folder_name}. check the app's settings for a cache or downloads option before deleting anything by hand")),
+            manual: false,
+            tags: tags(&["app-data"]),
+            detector: None,
+            file_count: None,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_folders_reported_except_those_covered_elsewhere() {
+        let dir = tempfile::tempdir().unwrap();
+        for (folder, bytes) in [
+            ("LM Studio/models", 400),
+            ("MobileSync/Backup/00008101-ABC", 300),
+            ("Tiny", 10),
+            ("Code/User", 900),
+        ] {
+            std::fs::create_dir_all(dir.path().join(folder)).unwrap();
+            std::fs::write(dir.path().join(folder).join("data"), vec![0u8; bytes]).unwrap();
+        }
+
+        let result = scan_app_support(dir.path(), 100);
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["app data (LM Studio)"]);
+        let app = &result.entries[0];
+        assert_eq!(app.category, BloatCategory::SystemCache);
+        assert_eq!(app.reclaimable_bytes, 0);
+        assert_eq!(app.size_bytes, 400);
+    }
+}
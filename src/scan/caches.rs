@@ -2,9 +2,11 @@
 
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 use super::detector::{BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use super::fs::{FileKind, FileSystem, LocalFs};
 use crate::config::Config;
 use crate::platform::{self, Platform};
 
@@ -20,7 +22,6 @@ impl Detector for CacheDetector {
     }
 
     fn scan(&self, config: &Config) -> DetectorResult {
-        let mut entries = Vec::new();
         let mut diagnostics = Vec::new();
 
         if config.platform == Platform::Unknown {
@@ -39,45 +40,233 @@ impl Detector for CacheDetector {
         };
 
         let (caches, cache_diagnostics) =
-            get_cache_locations(&home, config.platform, config.timeout);
+            get_cache_locations(&home, config.platform, config.timeout_for("caches"));
         diagnostics.extend(cache_diagnostics);
 
-        for cache in caches {
-            if !cache.path.exists() {
-                continue;
-            }
+        let (mut entries, size_diagnostics) = size_locations(
+            caches,
+            |p| p.exists(),
+            |caches| size_in_parallel(caches, |cache| super::measure_dir(&cache.path)),
+            config.min_entry_bytes,
+        );
+        diagnostics.extend(size_diagnostics);
+        sparse_disk_sizes(&mut entries);
+
+        DetectorResult {
+            entries,
+            diagnostics,
+            errored: false,
+        }
+    }
+}
+
+/// `size_of` for every cache, in order. Locations are independent, so a
+/// few threads take them one at a time and a slow one (a huge cargo
+/// registry) doesn't hold up the rest; each result lands in its own slot.
+fn size_in_parallel<R: Send>(
+    caches: &[CacheLocation],
+    size_of: impl Fn(&CacheLocation) -> R + Sync,
+) -> Vec<R> {
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_SIZE_THREADS)
+        .min(caches.len());
+    if threads <= 1 {
+        return caches.iter().map(size_of).collect();
+    }
 
-            match super::calculate_dir_size(&cache.path) {
-                Ok((size, warnings)) if size > 0 => {
-                    let reclaimable = if cache.not_reclaimable { 0 } else { size };
-                    entries.push(BloatEntry {
-                        category: cache.category,
-                        name: cache.name.clone(),
-                        location: Location::FilesystemPath(cache.path.clone()),
-                        size_bytes: size,
-                        reclaimable_bytes: reclaimable,
-                        last_modified: None,
-                        cleanup_hint: Some(cache.cleanup_hint.clone()),
-                    });
-
-                    for warning in warnings {
-                        diagnostics.push(format!("{warning} (size may be underestimated)"));
+    let next = AtomicUsize::new(0);
+    let mut sized: Vec<(usize, R)> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(cache) = caches.get(i) else {
+                            break;
+                        };
+                        done.push((i, size_of(cache)));
                     }
+                    done
+                })
+            })
+            .collect();
+        // a panicking walk takes the detector down, as it would unthreaded
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    });
+    sized.sort_by_key(|(i, _)| *i);
+    sized.into_iter().map(|(_, result)| result).collect()
+}
+
+/// WSL disks are sparse .vhdx files; report what they take on the host
+/// rather than the logical size the walk measured.
+fn sparse_disk_sizes(entries: &mut [BloatEntry]) {
+    for entry in entries {
+        let Location::FilesystemPath(path) = &entry.location else {
+            continue;
+        };
+        if path.extension().and_then(|e| e.to_str()) != Some("vhdx") {
+            continue;
+        }
+        let Ok(metadata) = std::fs::metadata(path) else {
+            continue;
+        };
+        let (allocated, logical) = super::sparse_size(&metadata);
+        entry.size_bytes = allocated;
+        if let (Some(note), Some(hint)) = (
+            super::sparse_note(allocated, logical),
+            entry.cleanup_hint.as_mut(),
+        ) {
+            hint.push_str(&format!(" ({note})"));
+        }
+    }
+}
+
+/// Most threads sizing cache locations at once. The locations usually sit
+/// on one or two disks, so more would only queue on the same I/O.
+const MAX_SIZE_THREADS: usize = 8;
+
+/// Size every existing location with `size_all` (one result per location,
+/// in order) and turn it into an entry if it reaches `min_bytes`. Locations nested
+/// inside another one (pip's wheels inside the pip cache) are carved out of
+/// the parent's size so nothing is counted twice.
+fn size_locations(
+    caches: Vec<CacheLocation>,
+    exists: impl Fn(&Path) -> bool,
+    size_all: impl FnOnce(&[CacheLocation]) -> Vec<std::io::Result<super::DirSize>>,
+    min_bytes: u64,
+) -> (Vec<BloatEntry>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    // size everything first so nested locations can be subtracted
+    let caches: Vec<CacheLocation> = caches
+        .into_iter()
+        .filter(|c| {
+            let found = exists(&c.path);
+            super::debug(|| {
+                let outcome = if found { "found" } else { "not present" };
+                format!("{}: {outcome} at {}", c.name, c.path.display())
+            });
+            found
+        })
+        .collect();
+    let sizes = size_all(&caches);
+    let sized: Vec<_> = caches.into_iter().zip(sizes).collect();
+
+    let nested_sizes: Vec<u64> = sized
+        .iter()
+        .map(|(outer, _)| {
+            sized
+                .iter()
+                .filter(|(inner, _)| {
+                    inner.path != outer.path && inner.path.starts_with(&outer.path)
+                })
+                .filter_map(|(_, size)| size.as_ref().ok().map(|s| s.bytes))
+                .sum()
+        })
+        .collect();
+
+    for ((cache, size), nested) in sized.into_iter().zip(nested_sizes) {
+        // the file count stays whole: --verify re-measures the whole path
+        match size.map(|s| (s.bytes.saturating_sub(nested), s.files, s.warnings)) {
+            // caches that can't be reclaimed are reported however small
+            Ok((size, files, warnings))
+                if super::big_enough(size, if cache.not_reclaimable { 0 } else { min_bytes }) =>
+            {
+                let reclaimable = if cache.not_reclaimable { 0 } else { size };
+                entries.push(BloatEntry {
+                    category: cache.category,
+                    name: cache.name.clone(),
+                    location: Location::FilesystemPath(cache.path.clone()),
+                    size_bytes: size,
+                    reclaimable_bytes: reclaimable,
+                    last_modified: None,
+                    cleanup_hint: Some(cache.cleanup_hint.clone()),
+                    manual: cache.not_reclaimable,
+                    tags: cache_tags(&cache.name),
+                    detector: None,
+                    file_count: Some(files),
+                });
+
+                for warning in warnings {
+                    diagnostics.push(format!("{warning} (size may be underestimated)"));
                 }
-                Ok(_) => {}
-                Err(e) => {
-                    diagnostics.push(format!("failed to scan {}: {}", cache.path.display(), e));
-                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                diagnostics.push(format!("failed to scan {}: {}", cache.path.display(), e));
             }
         }
+    }
 
-        DetectorResult {
-            entries,
-            diagnostics,
-        }
+    (entries, diagnostics)
+}
+
+/// Cache entries on any `FileSystem`, for remote scans. Only the fixed
+/// per-platform locations apply; homebrew and WSL disks need local probes.
+#[cfg(feature = "remote")]
+pub(crate) fn scan_caches_on(
+    fs: &dyn FileSystem,
+    home: &Path,
+    platform: Platform,
+    min_bytes: u64,
+) -> DetectorResult {
+    let mut locations = cache_locations_on(fs, home, platform);
+    // the server's $PIPX_HOME is unknown, assume the default
+    locations.extend(python_venv_locations(fs, home, platform, None));
+    let (entries, diagnostics) =
+        // one connection to the server: sized one after another
+        size_locations(locations, |p| fs.exists(p), |caches| {
+            caches
+                .iter()
+                .map(|cache| super::fs::dir_size(fs, &cache.path))
+                .collect()
+        }, min_bytes);
+    DetectorResult {
+        entries,
+        diagnostics,
+        errored: false,
     }
 }
 
+/// Tags by the first word of a cache's name. Names are stable, so this keeps
+/// the long location list free of per-entry tag lists.
+const CACHE_TAGS: &[(&str, &[&str])] = &[
+    ("npm", &["nodejs", "package-manager"]),
+    ("yarn", &["nodejs", "package-manager"]),
+    ("pnpm", &["nodejs", "package-manager"]),
+    ("pip", &["python", "package-manager"]),
+    ("poetry", &["python", "virtualenv"]),
+    ("pipx", &["python", "virtualenv"]),
+    ("cargo", &["rust", "package-manager"]),
+    ("go", &["go", "package-manager"]),
+    ("gradle", &["java", "build-tool"]),
+    ("maven", &["java", "package-manager"]),
+    ("nuget", &["dotnet", "package-manager"]),
+    ("homebrew", &["homebrew", "package-manager"]),
+    ("texlive", &["tex"]),
+    ("R", &["r", "package-manager"]),
+    ("julia", &["julia", "package-manager"]),
+    ("vscode", &["vscode", "ide"]),
+    ("android", &["android", "ide"]),
+    ("docker", &["docker", "wsl", "vm-disk"]),
+    ("WSL2", &["wsl", "vm-disk"]),
+];
+
+fn cache_tags(name: &str) -> Vec<String> {
+    let first = name.split_whitespace().next().unwrap_or("");
+    CACHE_TAGS
+        .iter()
+        .find(|(word, _)| *word == first)
+        .map(|(_, tags)| super::detector::tags(tags))
+        .unwrap_or_default()
+}
+
 // String fields so WSL entries can include dynamic names (distro package name).
 struct CacheLocation {
     name: String,
@@ -110,9 +299,93 @@ fn get_cache_locations(
     platform: Platform,
     timeout: Duration,
 ) -> (Vec<CacheLocation>, Vec<String>) {
-    let mut locations = Vec::new();
+    let mut locations = cache_locations_on(&LocalFs, home, platform);
+    let pipx_home = std::env::var_os("PIPX_HOME").map(PathBuf::from);
+    locations.extend(python_venv_locations(
+        &LocalFs,
+        home,
+        platform,
+        pipx_home.as_deref(),
+    ));
     let mut diagnostics = Vec::new();
 
+    // homebrew cache (macOS and Linux)
+    match get_homebrew_cache(timeout) {
+        Ok(Some(brew_cache)) => {
+            locations.push(CacheLocation::new(
+                "homebrew cache",
+                brew_cache,
+                BloatCategory::PackageCache,
+                "brew cleanup",
+            ));
+        }
+        Ok(None) => {
+            // brew not installed, this is normal
+        }
+        Err(e) => {
+            diagnostics.push(format!("homebrew cache detection failed: {e}"));
+        }
+    }
+
+    // WSL2 virtual disk detection — when running inside WSL2, the distro's
+    // ext4.vhdx grows as files are written but never shrinks automatically.
+    // Windows drives are mounted at /mnt/c, so we can read AppData paths.
+    // WSL_INTEROP is set exclusively by WSL2 (not WSL1), so this is safe.
+    if platform::is_wsl() {
+        match wsl_windows_username() {
+            Ok(win_user) => {
+                let win_local = PathBuf::from("/mnt/c/Users")
+                    .join(&win_user)
+                    .join("AppData/Local");
+
+                // Docker Desktop WSL2 disks — path varies by version
+                for docker_rel in &["Docker/wsl/data/ext4.vhdx", "Docker/wsl/distro/ext4.vhdx"] {
+                    let vhdx = win_local.join(docker_rel);
+                    if vhdx.exists() {
+                        locations.push(CacheLocation {
+                            name: "docker desktop WSL2 disk".to_string(),
+                            path: vhdx,
+                            category: BloatCategory::ContainerData,
+                            cleanup_hint: "run 'wsl --shutdown' then compact with 'Optimize-VHD' in PowerShell (admin)".to_string(),
+                            not_reclaimable: true,
+                        });
+                    }
+                }
+
+                // WSL distro virtual disks — scan all Packages entries for ext4.vhdx
+                // rather than filtering by publisher prefix (Debian, Kali, openSUSE etc.
+                // all use different prefixes but the vhdx path is consistent).
+                let packages_dir = win_local.join("Packages");
+                if let Ok(entries) = std::fs::read_dir(&packages_dir) {
+                    for entry in entries.flatten() {
+                        let vhdx = entry.path().join("LocalState/ext4.vhdx");
+                        if vhdx.exists() {
+                            let pkg_name = entry.file_name().to_string_lossy().into_owned();
+                            locations.push(CacheLocation {
+                                name: format!("WSL2 distro disk ({pkg_name})"),
+                                path: vhdx,
+                                category: BloatCategory::SystemCache,
+                                cleanup_hint: "run 'wsl --shutdown' then 'wsl --manage <distro> --set-sparse true' to enable sparse VHD".to_string(),
+                                not_reclaimable: true,
+                            });
+                        }
+                    }
+                }
+            }
+            Err(msg) => {
+                diagnostics.push(msg);
+            }
+        }
+    }
+
+    (locations, diagnostics)
+}
+
+/// The fixed per-platform cache locations under `home`. `fs` is only used to
+/// list directories whose names vary (TeX Live release years, R libraries).
+fn cache_locations_on(fs: &dyn FileSystem, home: &Path, platform: Platform) -> Vec<CacheLocation> {
+    let mut locations = Vec::new();
+
     // npm cache
     locations.push(CacheLocation::new(
         "npm cache",
@@ -161,6 +434,14 @@ fn get_cache_locations(
         Platform::Windows => home.join("AppData").join("Local").join("pip").join("Cache"),
         Platform::Linux | Platform::Unknown => home.join(".cache/pip"),
     };
+    // wheels built from sdists are reported on their own and carved out of the
+    // pip cache entry; they're pure build output, rebuilt on the next install
+    locations.push(CacheLocation::new(
+        "pip wheel cache",
+        pip_path.join("wheels"),
+        BloatCategory::PackageCache,
+        "safe to delete, wheels are rebuilt on next install",
+    ));
     locations.push(CacheLocation::new(
         "pip cache",
         pip_path,
@@ -168,7 +449,28 @@ fn get_cache_locations(
         "pip cache purge",
     ));
 
-    // cargo registry and git checkouts
+    // cargo registry, split the way cargo-cache cleans it: --autoclean drops
+    // the extracted sources but keeps the .crate downloads they come from.
+    // anything else under registry/ stays in the "cargo registry" entry
+    let cargo_registry = home.join(".cargo/registry");
+    locations.push(CacheLocation::new(
+        "cargo registry sources",
+        cargo_registry.join("src"),
+        BloatCategory::PackageCache,
+        "safe to delete, re-extracted from the downloaded .crate files on next build (what cargo cache --autoclean removes)",
+    ));
+    locations.push(CacheLocation::new(
+        "cargo registry downloads",
+        cargo_registry.join("cache"),
+        BloatCategory::PackageCache,
+        "the downloaded .crate files. safe to delete, re-downloaded on next build",
+    ));
+    locations.push(CacheLocation::new(
+        "cargo registry index",
+        cargo_registry.join("index"),
+        BloatCategory::PackageCache,
+        "safe to delete, re-fetched on next build",
+    ));
     locations.push(CacheLocation::new(
         "cargo registry",
         home.join(".cargo/registry"),
@@ -182,24 +484,6 @@ fn get_cache_locations(
         "cargo cache --autoclean (requires cargo-cache)",
     ));
 
-    // homebrew cache (macOS and Linux)
-    match get_homebrew_cache(timeout) {
-        Ok(Some(brew_cache)) => {
-            locations.push(CacheLocation::new(
-                "homebrew cache",
-                brew_cache,
-                BloatCategory::PackageCache,
-                "brew cleanup",
-            ));
-        }
-        Ok(None) => {
-            // brew not installed, this is normal
-        }
-        Err(e) => {
-            diagnostics.push(format!("homebrew cache detection failed: {e}"));
-        }
-    }
-
     // go module cache
     locations.push(CacheLocation::new(
         "go module cache",
@@ -208,17 +492,44 @@ fn get_cache_locations(
         "go clean -modcache",
     ));
 
-    // VS Code extensions and cache
+    // VS Code — only the cache-like subdirectories, never the Code dir itself,
+    // which also holds User/settings.json, keybindings and snippets
     let vscode_path = match platform {
         Platform::MacOS => home.join("Library/Application Support/Code"),
         Platform::Windows => home.join("AppData").join("Roaming").join("Code"),
         Platform::Linux | Platform::Unknown => home.join(".config/Code"),
     };
     locations.push(CacheLocation::new(
-        "vscode data",
-        vscode_path,
+        "vscode cached data",
+        vscode_path.join("CachedData"),
         BloatCategory::IdeData,
-        "clear from within vscode or delete unused extensions",
+        "safe to delete while vscode is closed, rebuilt on next launch",
+    ));
+    locations.push(CacheLocation::new(
+        "vscode GPU cache",
+        vscode_path.join("GPUCache"),
+        BloatCategory::IdeData,
+        "safe to delete while vscode is closed, rebuilt on next launch",
+    ));
+    locations.push(CacheLocation::new(
+        "vscode logs",
+        vscode_path.join("logs"),
+        BloatCategory::IdeData,
+        "safe to delete, only diagnostic logs",
+    ));
+    locations.push(CacheLocation::new(
+        "vscode workspace storage",
+        vscode_path.join("User").join("workspaceStorage"),
+        BloatCategory::IdeData,
+        "delete folders for old workspaces; loses per-workspace UI state only",
+    ));
+
+    // extensions live outside the Code dir, under ~/.vscode on every platform
+    locations.push(CacheLocation::new(
+        "vscode extensions",
+        home.join(".vscode").join("extensions"),
+        BloatCategory::IdeData,
+        "uninstall unused extensions from within vscode",
     ));
 
     // gradle cache — cross-platform dotfile path, same on all OSes
@@ -229,6 +540,14 @@ fn get_cache_locations(
         "safe to delete, rebuilt on next gradle build",
     ));
 
+    // gradle wrapper distributions — one full gradle per pinned version
+    locations.push(CacheLocation::new(
+        "gradle wrapper dists",
+        home.join(".gradle/wrapper/dists"),
+        BloatCategory::PackageCache,
+        "safe to delete old versions, re-downloaded by the wrapper",
+    ));
+
     // maven cache
     locations.push(CacheLocation::new(
         "maven cache",
@@ -237,6 +556,14 @@ fn get_cache_locations(
         "mvn dependency:purge-local-repository",
     ));
 
+    // maven wrapper (mvnw) distributions
+    locations.push(CacheLocation::new(
+        "maven wrapper dists",
+        home.join(".m2/wrapper"),
+        BloatCategory::PackageCache,
+        "safe to delete old versions, re-downloaded by the wrapper",
+    ));
+
     // nuget package cache — cross-platform dotfile path, most relevant on Windows
     locations.push(CacheLocation::new(
         "nuget cache",
@@ -245,6 +572,88 @@ fn get_cache_locations(
         "dotnet nuget locals all --clear",
     ));
 
+    // TeX Live per-user caches — one tree per release year (~/.texlive2023 on
+    // Linux/Windows, ~/Library/texlive/2023 on macOS). Only texmf-var, which
+    // holds generated fonts and formats; texmf-config is user configuration
+    let texlive_trees = match platform {
+        Platform::MacOS => subdirs_with_prefix(fs, &home.join("Library/texlive"), ""),
+        _ => subdirs_with_prefix(fs, home, ".texlive"),
+    };
+    for tree in texlive_trees {
+        let release = tree
+            .file_name()
+            .map(|n| {
+                n.to_string_lossy()
+                    .trim_start_matches(".texlive")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        locations.push(CacheLocation {
+            name: format!("texlive cache ({release})"),
+            path: tree.join("texmf-var"),
+            category: BloatCategory::PackageCache,
+            cleanup_hint: "safe to delete, fonts and formats are regenerated on the next TeX run"
+                .to_string(),
+            not_reclaimable: false,
+        });
+    }
+
+    // R user package libraries — one per R minor version, old ones pile up
+    // after upgrades. On Linux ~/R may also hold scripts, so only the
+    // *-library directories R creates there are flagged. these are installed
+    // packages, not a cache, so they are reported but never deleted
+    let r_libraries = match platform {
+        Platform::MacOS => vec![home.join("Library/R")],
+        Platform::Windows => vec![home
+            .join("AppData")
+            .join("Local")
+            .join("R")
+            .join("win-library")],
+        Platform::Linux | Platform::Unknown => subdirs_with_prefix(fs, &home.join("R"), "")
+            .into_iter()
+            .filter(|p| p.to_string_lossy().ends_with("-library"))
+            .collect(),
+    };
+    for library in r_libraries {
+        locations.push(CacheLocation {
+            name: "R package library".to_string(),
+            path: library,
+            category: BloatCategory::PackageCache,
+            cleanup_hint: "remove libraries for R versions you no longer use; reinstall with install.packages()".to_string(),
+            not_reclaimable: true,
+        });
+    }
+
+    let r_cache = match platform {
+        Platform::MacOS => home.join("Library/Caches/org.R-project.R"),
+        Platform::Windows => home.join("AppData").join("Local").join("R").join("cache"),
+        Platform::Linux | Platform::Unknown => home.join(".cache/R"),
+    };
+    locations.push(CacheLocation::new(
+        "R cache",
+        r_cache,
+        BloatCategory::PackageCache,
+        "delete manually, only cached downloads and build output",
+    ));
+
+    // julia depot — ~/.julia on every platform. compiled/ keeps precompile
+    // caches for every julia version ever used and never shrinks on its own.
+    // packages/ is the installed package store, only Pkg.gc() may trim it
+    locations.push(CacheLocation {
+        name: "julia packages".to_string(),
+        path: home.join(".julia").join("packages"),
+        category: BloatCategory::PackageCache,
+        cleanup_hint: "run `using Pkg; Pkg.gc()` in julia to drop unused package versions"
+            .to_string(),
+        not_reclaimable: true,
+    });
+    locations.push(CacheLocation::new(
+        "julia compiled cache",
+        home.join(".julia").join("compiled"),
+        BloatCategory::PackageCache,
+        "delete folders for old julia versions, recompiled on next use",
+    ));
+
     // android avd images — emulator snapshots, can be 4-8 GB each
     // only flag the avd subdirectory, not ~/.android root (contains keychains/device tokens)
     locations.push(CacheLocation::new(
@@ -279,58 +688,94 @@ fn get_cache_locations(
         "remove unused SDK versions via Android Studio SDK Manager",
     ));
 
-    // WSL2 virtual disk detection — when running inside WSL2, the distro's
-    // ext4.vhdx grows as files are written but never shrinks automatically.
-    // Windows drives are mounted at /mnt/c, so we can read AppData paths.
-    // WSL_INTEROP is set exclusively by WSL2 (not WSL1), so this is safe.
-    if platform::is_wsl() {
-        match wsl_windows_username() {
-            Ok(win_user) => {
-                let win_local = PathBuf::from("/mnt/c/Users")
-                    .join(&win_user)
-                    .join("AppData/Local");
+    locations
+}
 
-                // Docker Desktop WSL2 disks — path varies by version
-                for docker_rel in &["Docker/wsl/data/ext4.vhdx", "Docker/wsl/distro/ext4.vhdx"] {
-                    let vhdx = win_local.join(docker_rel);
-                    if vhdx.exists() {
-                        locations.push(CacheLocation {
-                            name: "docker desktop WSL2 disk".to_string(),
-                            path: vhdx,
-                            category: BloatCategory::ContainerData,
-                            cleanup_hint: "run 'wsl --shutdown' then compact with 'Optimize-VHD' in PowerShell (admin)".to_string(),
-                            not_reclaimable: true,
-                        });
-                    }
-                }
+/// Poetry's central virtualenvs and pipx's app venvs, one entry per venv so
+/// a stale 1 GB environment for a deleted project stands out. `pipx_home`
+/// is $PIPX_HOME when set.
+fn python_venv_locations(
+    fs: &dyn FileSystem,
+    home: &Path,
+    platform: Platform,
+    pipx_home: Option<&Path>,
+) -> Vec<CacheLocation> {
+    let mut locations = Vec::new();
 
-                // WSL distro virtual disks — scan all Packages entries for ext4.vhdx
-                // rather than filtering by publisher prefix (Debian, Kali, openSUSE etc.
-                // all use different prefixes but the vhdx path is consistent).
-                let packages_dir = win_local.join("Packages");
-                if let Ok(entries) = std::fs::read_dir(&packages_dir) {
-                    for entry in entries.flatten() {
-                        let vhdx = entry.path().join("LocalState/ext4.vhdx");
-                        if vhdx.exists() {
-                            let pkg_name = entry.file_name().to_string_lossy().into_owned();
-                            locations.push(CacheLocation {
-                                name: format!("WSL2 distro disk ({pkg_name})"),
-                                path: vhdx,
-                                category: BloatCategory::SystemCache,
-                                cleanup_hint: "run 'wsl --shutdown' then 'wsl --manage <distro> --set-sparse true' to enable sparse VHD".to_string(),
-                                not_reclaimable: true,
-                            });
-                        }
-                    }
-                }
-            }
-            Err(msg) => {
-                diagnostics.push(msg);
+    let poetry_venvs = match platform {
+        Platform::MacOS => home.join("Library/Caches/pypoetry/virtualenvs"),
+        Platform::Windows => home
+            .join("AppData")
+            .join("Local")
+            .join("pypoetry")
+            .join("Cache")
+            .join("virtualenvs"),
+        Platform::Linux | Platform::Unknown => home.join(".cache/pypoetry/virtualenvs"),
+    };
+    for venv in subdirs_with_prefix(fs, &poetry_venvs, "") {
+        let name = venv_name(&venv);
+        locations.push(CacheLocation {
+            name: format!("poetry venv ({name})"),
+            path: venv,
+            category: BloatCategory::PackageCache,
+            cleanup_hint:
+                "poetry env remove --all in its project; safe to delete if the project is gone"
+                    .to_string(),
+            not_reclaimable: false,
+        });
+    }
+
+    // pipx moved from ~/.local/pipx to the platform data dir in 1.3; an
+    // existing ~/.local/pipx keeps being used, so look in both
+    let pipx_homes = match pipx_home {
+        Some(dir) => vec![dir.to_path_buf()],
+        None => match platform {
+            Platform::MacOS => vec![
+                home.join(".local/pipx"),
+                home.join("Library/Application Support/pipx"),
+            ],
+            Platform::Windows => vec![home.join(".local").join("pipx"), home.join("pipx")],
+            Platform::Linux | Platform::Unknown => {
+                vec![home.join(".local/pipx"), home.join(".local/share/pipx")]
             }
+        },
+    };
+    for pipx in pipx_homes {
+        for venv in subdirs_with_prefix(fs, &pipx.join("venvs"), "") {
+            let name = venv_name(&venv);
+            // each venv is an installed command; deleting it leaves a broken shim
+            locations.push(CacheLocation {
+                name: format!("pipx venv ({name})"),
+                cleanup_hint: format!("pipx uninstall {name}"),
+                path: venv,
+                category: BloatCategory::PackageCache,
+                not_reclaimable: true,
+            });
         }
     }
 
-    (locations, diagnostics)
+    locations
+}
+
+fn venv_name(venv: &Path) -> String {
+    venv.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Directories directly inside `dir` whose name starts with `prefix`, sorted.
+/// Empty when `dir` doesn't exist.
+fn subdirs_with_prefix(fs: &dyn FileSystem, dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs
+        .read_dir(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.kind == FileKind::Dir)
+        .filter(|e| e.file_name().is_some_and(|n| n.starts_with(prefix)))
+        .map(|e| e.path)
+        .collect();
+    dirs.sort();
+    dirs
 }
 
 /// Resolves the Windows username when running inside WSL2.
@@ -502,6 +947,36 @@ mod tests {
 
     // ── android paths ────────────────────────────────────────────────────────
 
+    #[test]
+    fn parallel_sizes_come_back_in_location_order() {
+        let caches: Vec<_> = (0..40)
+            .map(|i| {
+                CacheLocation::new(
+                    "cache",
+                    PathBuf::from(format!("/caches/{i}")),
+                    BloatCategory::PackageCache,
+                    "",
+                )
+            })
+            .collect();
+
+        let sizes = size_in_parallel(&caches, |cache| {
+            // uneven work, so threads finish out of order
+            let i: u64 = cache
+                .path
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .parse()
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(i % 3));
+            i
+        });
+
+        assert_eq!(sizes, (0..40).collect::<Vec<u64>>());
+    }
+
     #[test]
     fn android_avd_path_present_on_linux() {
         let locs = locations(Platform::Linux);
@@ -590,6 +1065,233 @@ mod tests {
         }
     }
 
+    // ── vscode ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn vscode_split_into_cache_subdirectories() {
+        let locs = locations(Platform::Linux);
+        let code = PathBuf::from("/home/testuser/.config/Code");
+        let expected = [
+            ("vscode cached data", code.join("CachedData")),
+            ("vscode GPU cache", code.join("GPUCache")),
+            ("vscode logs", code.join("logs")),
+            (
+                "vscode workspace storage",
+                code.join("User").join("workspaceStorage"),
+            ),
+            (
+                "vscode extensions",
+                PathBuf::from("/home/testuser/.vscode/extensions"),
+            ),
+        ];
+        for (name, path) in expected {
+            let loc = find(&locs, name).unwrap();
+            assert_eq!(loc.path, path, "{name}");
+            assert_eq!(loc.category, BloatCategory::IdeData, "{name}");
+        }
+    }
+
+    #[test]
+    fn vscode_settings_dir_never_flagged_whole() {
+        for platform in [Platform::Linux, Platform::MacOS, Platform::Windows] {
+            let locs = locations(platform);
+            assert!(
+                !locs.iter().any(|l| l.path.ends_with("Code")
+                    || l.path.ends_with("User")
+                    || l.path.ends_with(".vscode")),
+                "whole vscode config dir flagged on {platform:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn vscode_macos_uses_application_support() {
+        let home = PathBuf::from("/Users/testuser");
+        let (locs, _) = get_cache_locations(&home, Platform::MacOS, Duration::from_secs(5));
+        let logs = find(&locs, "vscode logs").unwrap();
+        assert_eq!(
+            logs.path,
+            PathBuf::from("/Users/testuser/Library/Application Support/Code/logs")
+        );
+    }
+
+    // ── pip ──────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn pip_wheel_cache_nested_in_pip_cache() {
+        let locs = locations(Platform::Linux);
+        let pip = find(&locs, "pip cache").unwrap();
+        let wheels = find(&locs, "pip wheel cache").unwrap();
+        assert_eq!(wheels.path, pip.path.join("wheels"));
+    }
+
+    // ── cargo ────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn cargo_registry_split_into_sources_downloads_and_index() {
+        let home = tempfile::tempdir().unwrap();
+        let registry = home.path().join(".cargo/registry");
+        for (dir, bytes) in [
+            ("src/index.crates.io-6f17d22bba15001f/serde-1.0.0", 500),
+            ("cache/index.crates.io-6f17d22bba15001f", 100),
+            ("index/index.crates.io-6f17d22bba15001f", 20),
+        ] {
+            std::fs::create_dir_all(registry.join(dir)).unwrap();
+            std::fs::write(registry.join(dir).join("data"), vec![0u8; bytes]).unwrap();
+        }
+        std::fs::write(registry.join("CACHEDIR.TAG"), [0u8; 3]).unwrap();
+
+        let caches = cache_locations_on(&LocalFs, home.path(), Platform::Linux);
+        let (entries, _) = size_locations(
+            caches,
+            |p| p.exists(),
+            |caches| size_in_parallel(caches, |cache| super::super::measure_dir(&cache.path)),
+            0,
+        );
+        let size = |name: &str| {
+            entries
+                .iter()
+                .find(|e| e.name == name)
+                .map(|e| e.size_bytes)
+        };
+
+        assert_eq!(size("cargo registry sources"), Some(500));
+        assert_eq!(size("cargo registry downloads"), Some(100));
+        assert_eq!(size("cargo registry index"), Some(20));
+        // only what isn't in a sub-entry
+        assert_eq!(size("cargo registry"), Some(3));
+    }
+
+    // ── jvm wrappers ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn jvm_wrapper_dists_present_on_all_platforms() {
+        for platform in [Platform::Linux, Platform::MacOS, Platform::Windows] {
+            let locs = locations(platform);
+            let gradle = find(&locs, "gradle wrapper dists").unwrap();
+            assert_eq!(
+                gradle.path,
+                PathBuf::from("/home/testuser/.gradle/wrapper/dists")
+            );
+            let maven = find(&locs, "maven wrapper dists").unwrap();
+            assert_eq!(maven.path, PathBuf::from("/home/testuser/.m2/wrapper"));
+            assert_eq!(maven.category, BloatCategory::PackageCache);
+        }
+    }
+
+    // ── scientific computing ─────────────────────────────────────────────────
+
+    #[test]
+    fn julia_depot_split_into_packages_and_compiled() {
+        for platform in [Platform::Linux, Platform::MacOS, Platform::Windows] {
+            let locs = locations(platform);
+            let packages = find(&locs, "julia packages").unwrap();
+            assert_eq!(
+                packages.path,
+                PathBuf::from("/home/testuser/.julia/packages")
+            );
+            assert!(packages.cleanup_hint.contains("Pkg.gc()"));
+            assert!(packages.not_reclaimable);
+            assert!(!find(&locs, "julia compiled cache").unwrap().not_reclaimable);
+        }
+    }
+
+    #[test]
+    fn texlive_and_r_libraries_found_by_listing_home() {
+        let home = tempfile::tempdir().unwrap();
+        for dir in [
+            ".texlive2023/texmf-var",
+            ".texlive2024/texmf-config",
+            "R/x86_64-pc-linux-gnu-library/4.3",
+            "R/analysis-scripts",
+        ] {
+            std::fs::create_dir_all(home.path().join(dir)).unwrap();
+        }
+        let (locs, _) = get_cache_locations(home.path(), Platform::Linux, Duration::from_secs(5));
+
+        let texlive: Vec<_> = locs
+            .iter()
+            .filter(|l| l.name.starts_with("texlive cache"))
+            .collect();
+        assert_eq!(texlive.len(), 2);
+        assert_eq!(texlive[0].name, "texlive cache (2023)");
+        assert_eq!(
+            texlive[0].path,
+            home.path().join(".texlive2023").join("texmf-var")
+        );
+
+        let r: Vec<_> = locs
+            .iter()
+            .filter(|l| l.name == "R package library")
+            .collect();
+        assert_eq!(r.len(), 1);
+        assert!(r[0].not_reclaimable);
+        assert!(!find(&locs, "R cache").unwrap().not_reclaimable);
+        assert_eq!(
+            r[0].path,
+            home.path().join("R").join("x86_64-pc-linux-gnu-library")
+        );
+    }
+
+    // ── python venv stores ────────────────────────────────────────────────────
+
+    #[test]
+    fn poetry_and_pipx_venvs_listed_one_per_env() {
+        let home = tempfile::tempdir().unwrap();
+        for dir in [
+            ".cache/pypoetry/virtualenvs/api-Xy12AbCd-py3.11",
+            ".cache/pypoetry/virtualenvs/old-Qw34ErTy-py3.9",
+            ".local/share/pipx/venvs/black",
+            ".local/pipx/venvs/httpie",
+        ] {
+            std::fs::create_dir_all(home.path().join(dir)).unwrap();
+        }
+
+        let locs = python_venv_locations(&LocalFs, home.path(), Platform::Linux, None);
+        let names: Vec<_> = locs.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "poetry venv (api-Xy12AbCd-py3.11)",
+                "poetry venv (old-Qw34ErTy-py3.9)",
+                "pipx venv (httpie)",
+                "pipx venv (black)",
+            ]
+        );
+        assert!(locs
+            .iter()
+            .all(|l| l.category == BloatCategory::PackageCache));
+        assert!(!locs[0].not_reclaimable);
+        let black = find(&locs, "pipx venv (black)").unwrap();
+        assert_eq!(black.cleanup_hint, "pipx uninstall black");
+        assert!(black.not_reclaimable);
+    }
+
+    #[test]
+    fn pipx_home_env_replaces_default_locations() {
+        let home = tempfile::tempdir().unwrap();
+        let custom = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join(".local/pipx/venvs/black")).unwrap();
+        std::fs::create_dir_all(custom.path().join("venvs/ruff")).unwrap();
+
+        let locs =
+            python_venv_locations(&LocalFs, home.path(), Platform::Linux, Some(custom.path()));
+        assert_eq!(locs.len(), 1);
+        assert_eq!(locs[0].path, custom.path().join("venvs/ruff"));
+    }
+
+    #[test]
+    fn macos_poetry_venvs_under_library_caches() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(
+            home.path()
+                .join("Library/Caches/pypoetry/virtualenvs/web-py3.12"),
+        )
+        .unwrap();
+        let locs = python_venv_locations(&LocalFs, home.path(), Platform::MacOS, None);
+        assert_eq!(locs[0].name, "poetry venv (web-py3.12)");
+    }
+
     // ── wsl username resolution ───────────────────────────────────────────────
 
     #[test]
@@ -604,6 +1306,20 @@ mod tests {
 
     // ── nuget cache ───────────────────────────────────────────────────────────
 
+    #[test]
+    fn cache_tags_follow_the_tool() {
+        assert_eq!(
+            cache_tags("cargo registry index"),
+            ["rust", "package-manager"]
+        );
+        assert_eq!(
+            cache_tags("poetry venv (app-py3.11)"),
+            ["python", "virtualenv"]
+        );
+        assert_eq!(cache_tags("WSL2 distro disk (Ubuntu)"), ["wsl", "vm-disk"]);
+        assert!(cache_tags("something new").is_empty());
+    }
+
     #[test]
     fn nuget_cache_present_on_all_platforms() {
         for platform in [Platform::Linux, Platform::MacOS, Platform::Windows] {
@@ -0,0 +1,71 @@
+//! Reports heft's own snapshot database.
+//!
+//! Every scan auto-saves a snapshot, so heft.db grows without bound unless
+//! it's vacuumed or old snapshots are removed. Only reported once the file
+//! passes MIN_REPORT_SIZE so fresh installs don't see a noise entry.
+//! Size includes the `-wal` and `-shm` files next to it. Reclaimable space
+//! is what `heft db vacuum` would return: free pages in the file and the
+//! write-ahead log.
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::store::snapshot;
+
+const MIN_REPORT_SIZE: u64 = 10 * 1024 * 1024; // 10 MB
+
+pub struct DatabaseDetector;
+
+impl Detector for DatabaseDetector {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    fn available(&self, _config: &Config) -> bool {
+        true
+    }
+
+    fn scan(&self, _config: &Config) -> DetectorResult {
+        let Some(path) = snapshot::db_path() else {
+            return DetectorResult::empty();
+        };
+
+        // no database yet, nothing to report
+        let Some(size) = snapshot::database_size(&path) else {
+            return DetectorResult::empty();
+        };
+
+        if size < MIN_REPORT_SIZE {
+            return DetectorResult::empty();
+        }
+
+        let mut diagnostics = Vec::new();
+        let reclaimable = match snapshot::vacuum_reclaimable_bytes(&path) {
+            Ok(bytes) => bytes.min(size),
+            Err(e) => {
+                diagnostics.push(format!(
+                    "database: could not measure free pages in {}: {e}",
+                    path.display()
+                ));
+                0
+            }
+        };
+
+        DetectorResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::Other,
+                name: "heft snapshot database".to_string(),
+                location: Location::FilesystemPath(path),
+                size_bytes: size,
+                reclaimable_bytes: reclaimable,
+                last_modified: None,
+                cleanup_hint: Some("heft db vacuum".to_string()),
+                manual: false,
+                tags: tags(&["heft"]),
+                detector: None,
+                file_count: None,
+            }],
+            diagnostics,
+            errored: false,
+        }
+    }
+}
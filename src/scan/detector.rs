@@ -53,11 +53,78 @@ pub struct BloatEntry {
     pub reclaimable_bytes: u64,
     pub last_modified: Option<i64>,
     pub cleanup_hint: Option<String>,
+    /// reclaimable_bytes is 0 because heft won't delete it, but following the
+    /// cleanup hint frees roughly size_bytes (a WSL disk, the hibernation file).
+    /// Counted separately with --include-manual.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub manual: bool,
+    /// Finer labels than the category, set by the detector ("rust",
+    /// "package-manager", "docker"). `--tag` on report and clean filters
+    /// on them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Name of the detector that found the entry, filled in by `scan::run`.
+    /// Stored with snapshots but kept out of the JSON/YAML/TOML schema
+    #[serde(skip)]
+    pub detector: Option<String>,
+    /// Files under the path when it was sized, for `clean --verify`. Set
+    /// for project artifacts and caches; snapshots don't keep it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_count: Option<u64>,
+}
+
+/// Hints that are complete, argument-free commands for the tool that owns
+/// the cache. Only these are ever executed by `clean --use-hints`; anything
+/// else is treated as prose for a human.
+const RUNNABLE_HINTS: &[&str] = &[
+    "npm cache clean --force",
+    "yarn cache clean",
+    "pnpm store prune",
+    "pip cache purge",
+    "go clean -modcache",
+    "brew cleanup",
+    "dotnet nuget locals all --clear",
+];
+
+/// How an entry's cleanup hint can be acted on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CleanupAction {
+    /// Run this program with these arguments instead of deleting the path
+    Command(Vec<String>),
+    /// Instructions for a human; clean falls back to deleting the path
+    Manual(String),
+}
+
+/// Owned tags from a detector's static list.
+pub fn tags(names: &[&str]) -> Vec<String> {
+    names.iter().map(|t| t.to_string()).collect()
+}
+
+impl BloatEntry {
+    /// Whether the entry carries any of `wanted` (case-insensitive). An
+    /// empty filter matches everything.
+    pub fn has_any_tag(&self, wanted: &[String]) -> bool {
+        wanted.is_empty()
+            || wanted
+                .iter()
+                .any(|w| self.tags.iter().any(|t| t.eq_ignore_ascii_case(w)))
+    }
+
+    pub fn cleanup_action(&self) -> Option<CleanupAction> {
+        let hint = self.cleanup_hint.as_deref()?;
+        Some(if RUNNABLE_HINTS.contains(&hint) {
+            CleanupAction::Command(hint.split_whitespace().map(String::from).collect())
+        } else {
+            CleanupAction::Manual(hint.to_string())
+        })
+    }
 }
 
 pub struct DetectorResult {
     pub entries: Vec<BloatEntry>,
     pub diagnostics: Vec<String>,
+    /// A panic or timeout cut the detector short, so `entries` may be incomplete
+    pub errored: bool,
 }
 
 impl DetectorResult {
@@ -65,6 +132,7 @@ impl DetectorResult {
         DetectorResult {
             entries: Vec::new(),
             diagnostics: Vec::new(),
+            errored: false,
         }
     }
 
@@ -72,12 +140,85 @@ impl DetectorResult {
         DetectorResult {
             entries: Vec::new(),
             diagnostics: vec![message],
+            errored: false,
+        }
+    }
+
+    /// Nothing found because the detector panicked or timed out.
+    pub fn errored(message: String) -> Self {
+        DetectorResult {
+            errored: true,
+            ..DetectorResult::with_diagnostic(message)
         }
     }
 }
 
 pub trait Detector {
     fn name(&self) -> &'static str;
+    /// Whether the detector can run on this machine (xcode only on macOS).
+    /// Enabling and disabling by config is handled by `scan::run`, not here.
     fn available(&self, config: &Config) -> bool;
     fn scan(&self, config: &Config) -> DetectorResult;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_hint(hint: Option<&str>) -> BloatEntry {
+        BloatEntry {
+            category: BloatCategory::PackageCache,
+            name: "cache".to_string(),
+            location: Location::FilesystemPath(PathBuf::from("/tmp/cache")),
+            size_bytes: 1,
+            reclaimable_bytes: 1,
+            last_modified: None,
+            cleanup_hint: hint.map(String::from),
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    #[test]
+    fn only_known_commands_are_runnable() {
+        assert_eq!(
+            with_hint(Some("go clean -modcache")).cleanup_action(),
+            Some(CleanupAction::Command(vec![
+                "go".to_string(),
+                "clean".to_string(),
+                "-modcache".to_string()
+            ]))
+        );
+        assert_eq!(
+            with_hint(Some("safe to delete, rebuilt on next gradle build")).cleanup_action(),
+            Some(CleanupAction::Manual(
+                "safe to delete, rebuilt on next gradle build".to_string()
+            ))
+        );
+        // needs a plugin, so it stays a suggestion
+        assert!(matches!(
+            with_hint(Some("cargo cache --autoclean (requires cargo-cache)")).cleanup_action(),
+            Some(CleanupAction::Manual(_))
+        ));
+        assert_eq!(with_hint(None).cleanup_action(), None);
+    }
+
+    #[test]
+    fn manual_flag_only_serialized_when_set() {
+        let json = serde_json::to_string(&with_hint(None)).unwrap();
+        assert!(!json.contains("manual"));
+        // snapshots and exports written before the flag existed still load
+        let entry: BloatEntry = serde_json::from_str(&json).unwrap();
+        assert!(!entry.manual);
+
+        let manual = BloatEntry {
+            manual: true,
+            ..with_hint(None)
+        };
+        assert!(serde_json::to_string(&manual)
+            .unwrap()
+            .contains("\"manual\":true"));
+    }
+}
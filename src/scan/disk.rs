@@ -0,0 +1,128 @@
+//! Free space on the filesystems holding the scan roots.
+//!
+//! Puts the reclaimable total in context: 5 GB of caches matter a lot more
+//! on a disk that's 97% full. Queried once per root after the detectors
+//! run; a root whose filesystem can't be queried just gets a diagnostic.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Usage at or above this percentage gets a warning in the report.
+pub const NEAR_FULL_PERCENT: u64 = 90;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiskUsage {
+    pub root: PathBuf,
+    /// Space available to unprivileged users (what df reports as Avail)
+    pub disk_free_bytes: u64,
+    pub disk_total_bytes: u64,
+    /// The filesystem's device id, so roots on one disk can be told apart
+    /// from different disks that happen to report the same figures
+    #[serde(skip)]
+    pub device_id: Option<u64>,
+}
+
+impl DiskUsage {
+    pub fn used_percent(&self) -> u64 {
+        if self.disk_total_bytes == 0 {
+            return 0;
+        }
+        let used = self.disk_total_bytes.saturating_sub(self.disk_free_bytes);
+        (used as u128 * 100 / self.disk_total_bytes as u128) as u64
+    }
+
+    pub fn is_near_full(&self) -> bool {
+        self.used_percent() >= NEAR_FULL_PERCENT
+    }
+}
+
+/// Query each root's filesystem. Failures become diagnostics.
+pub fn usage_for_roots(roots: &[PathBuf]) -> (Vec<DiskUsage>, Vec<String>) {
+    let mut usage = Vec::new();
+    let mut diagnostics = Vec::new();
+    for root in roots {
+        match query(root) {
+            Ok(disk) => usage.push(disk),
+            Err(e) => diagnostics.push(format!(
+                "disk: could not read free space for {}: {e}",
+                root.display()
+            )),
+        }
+    }
+    (usage, diagnostics)
+}
+
+/// The mount point holding `path`: its highest existing ancestor on the
+/// same device. Where device ids aren't available, the path's root ("C:\").
+pub fn mount_point(path: &Path) -> PathBuf {
+    // a path deleted since the scan still has existing parents
+    let mut ancestors = path
+        .ancestors()
+        .filter_map(|p| super::device_id(p).map(|device| (p, device)));
+    let Some((mut mount, device)) = ancestors.next() else {
+        return path
+            .ancestors()
+            .last()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+    };
+    for (ancestor, dev) in ancestors {
+        if dev != device {
+            break;
+        }
+        mount = ancestor;
+    }
+    mount.to_path_buf()
+}
+
+fn query(root: &Path) -> std::io::Result<DiskUsage> {
+    let stats = fs4::statvfs(root)?;
+    // statvfs follows a symlinked root, so look up the device it points to
+    let target = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    Ok(DiskUsage {
+        root: root.to_path_buf(),
+        disk_free_bytes: stats.available_space(),
+        disk_total_bytes: stats.total_space(),
+        device_id: super::device_id(&target),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(free: u64, total: u64) -> DiskUsage {
+        DiskUsage {
+            root: PathBuf::from("/"),
+            disk_free_bytes: free,
+            disk_total_bytes: total,
+            device_id: None,
+        }
+    }
+
+    #[test]
+    fn near_full_threshold() {
+        assert_eq!(disk(5, 100).used_percent(), 95);
+        assert!(disk(5, 100).is_near_full());
+        assert!(disk(10, 100).is_near_full());
+        assert!(!disk(11, 100).is_near_full());
+        // nothing sensible to say about a zero-sized filesystem
+        assert!(!disk(0, 0).is_near_full());
+    }
+
+    #[test]
+    fn usage_reported_for_existing_roots_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots = vec![dir.path().to_path_buf(), dir.path().join("missing")];
+        let (usage, diagnostics) = usage_for_roots(&roots);
+
+        assert_eq!(usage.len(), 1);
+        assert!(usage[0].disk_total_bytes > 0);
+        assert!(usage[0].disk_free_bytes <= usage[0].disk_total_bytes);
+        #[cfg(unix)]
+        assert!(usage[0].device_id.is_some());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].contains("could not read free space"));
+    }
+}
@@ -6,13 +6,17 @@
 //! - Volumes (total and reclaimable)
 //! - Build cache (total and reclaimable)
 //!
+//! With --build-cache-ages, build cache is instead broken down by last use
+//! via `docker buildx du --verbose`, falling back to the single aggregate
+//! when buildx isn't available.
+//!
 //! Also detects Docker Desktop VM disk images on macOS and Windows:
 //! - macOS: ~/Library/Containers/com.docker.docker/Data/vms/0/data/Docker.raw
 //! - Windows: %USERPROFILE%\AppData\Local\Docker\wsl\data\ext4.vhdx
 //!
 //! Handles gracefully:
 //! - Docker not installed
-//! - Docker daemon not running
+//! - Docker daemon not running or still starting (progress notes, Ctrl-C aborts)
 //! - Permission denied
 //!
 //! Does not walk Docker's internal storage directories directly.
@@ -23,10 +27,13 @@ use std::io::Read;
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
-use super::detector::{BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
 use crate::config::Config;
 use crate::platform;
 
+/// How often a slow docker query says it's still waiting (progressive mode).
+const WAIT_NOTICE_INTERVAL: Duration = Duration::from_secs(3);
+
 pub struct DockerDetector;
 
 #[derive(Debug, Deserialize)]
@@ -52,9 +59,39 @@ impl Detector for DockerDetector {
         let mut diagnostics = Vec::new();
 
         // get docker API resources (images, containers, volumes, build cache)
-        match run_docker_system_df(config) {
+        let context = config.docker_context.as_deref();
+        let timeout = config.timeout_for("docker");
+        // a query that used up its whole timeout was killed, not refused
+        let mut errored = false;
+        if config.verbose() {
+            diagnostics.push(format!("docker: querying {}", describe_target(context)));
+        }
+        let started = Instant::now();
+        match run_docker_system_df(timeout, config.verbose(), context, config.progressive) {
             Ok(mut entries) => all_entries.append(&mut entries),
-            Err(e) => diagnostics.push(e),
+            Err(e) => {
+                errored |= started.elapsed() >= timeout;
+                diagnostics.push(e);
+            }
+        }
+
+        let has_build_cache = all_entries
+            .iter()
+            .any(|e| matches!(e.location, Location::Aggregate(ref n) if n == "Build Cache"));
+        if config.build_cache_ages && has_build_cache {
+            let started = Instant::now();
+            match run_buildx_du(timeout, context, config.progressive) {
+                Ok(buckets) => {
+                    all_entries.retain(
+                        |e| !matches!(e.location, Location::Aggregate(ref n) if n == "Build Cache"),
+                    );
+                    all_entries.extend(buckets);
+                }
+                Err(e) => {
+                    errored |= started.elapsed() >= timeout;
+                    diagnostics.push(format!("{e} (build cache shown as one total)"));
+                }
+            }
         }
 
         // detect Docker Desktop VM disk images (macOS/Windows only)
@@ -65,16 +102,117 @@ impl Detector for DockerDetector {
         DetectorResult {
             entries: all_entries,
             diagnostics,
+            errored,
+        }
+    }
+}
+
+/// Run `docker system df` and turn each non-empty type into an aggregate entry.
+/// With `strict`, unparseable output lines are an error instead of being skipped.
+/// With `progress`, a slow daemon gets a "waiting" line on stderr every few seconds.
+pub fn run_docker_system_df(
+    timeout: Duration,
+    strict: bool,
+    context: Option<&str>,
+    progress: bool,
+) -> Result<Vec<BloatEntry>, String> {
+    let stdout = run_docker(
+        &["system", "df", "--format", "json"],
+        timeout,
+        context,
+        progress,
+    )?;
+    let mut entries = Vec::new();
+
+    // docker system df outputs JSONL (one JSON object per line)
+    for line in stdout.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let df_entry: DockerDfEntry = match serde_json::from_str(line) {
+            Ok(e) => e,
+            Err(e) => {
+                if strict {
+                    return Err(format!("docker: failed to parse output: {e}"));
+                }
+                continue;
+            }
+        };
+
+        let size_bytes = parse_docker_size(&df_entry.size)?;
+        let reclaimable_bytes = parse_docker_size(&df_entry.reclaimable)?;
+
+        // only create entries for types that have actual data
+        if size_bytes == 0 {
+            continue;
         }
+
+        let name = match df_entry.type_.as_str() {
+            "Images" => "docker images",
+            "Containers" => "docker containers",
+            "Local Volumes" => "docker volumes",
+            "Build Cache" => "docker build cache",
+            other => other,
+        };
+
+        entries.push(BloatEntry {
+            category: BloatCategory::ContainerData,
+            name: name.to_string(),
+            location: Location::Aggregate(df_entry.type_.clone()),
+            size_bytes,
+            reclaimable_bytes,
+            last_modified: None,
+            cleanup_hint: Some(get_cleanup_hint(&df_entry.type_)),
+            manual: false,
+            tags: docker_tags(&df_entry.type_),
+            detector: None,
+            file_count: None,
+        });
     }
+
+    Ok(entries)
 }
 
-fn run_docker_system_df(config: &Config) -> Result<Vec<BloatEntry>, String> {
-    let mut child = Command::new("docker")
-        .arg("system")
-        .arg("df")
-        .arg("--format")
-        .arg("json")
+/// A `docker` command aimed at `context` (`detectors.docker_context`), or
+/// otherwise wherever the docker CLI itself points: $DOCKER_HOST, then
+/// $DOCKER_CONTEXT, then the current context. Shared with clean so both
+/// talk to the same daemon.
+pub fn docker_command(context: Option<&str>) -> Command {
+    let mut cmd = Command::new("docker");
+    if let Some(context) = context {
+        cmd.args(["--context", context]);
+    }
+    cmd
+}
+
+/// Which daemon `docker_command(context)` talks to, for diagnostics.
+pub fn describe_target(context: Option<&str>) -> String {
+    if let Some(context) = context {
+        return format!("context '{context}' (from config)");
+    }
+    match std::env::var("DOCKER_HOST") {
+        Ok(host) if !host.is_empty() => format!("DOCKER_HOST={host}"),
+        _ => match std::env::var("DOCKER_CONTEXT") {
+            Ok(context) if !context.is_empty() => {
+                format!("context '{context}' (from DOCKER_CONTEXT)")
+            }
+            _ => "the current docker context".to_string(),
+        },
+    }
+}
+
+/// Run `docker <args>` with a timeout and return its stdout. Errors are
+/// phrased for diagnostics, with the common daemon/permission cases spelled out.
+fn run_docker(
+    args: &[&str],
+    timeout: Duration,
+    context: Option<&str>,
+    progress: bool,
+) -> Result<String, String> {
+    super::debug(|| format!("running docker {}", args.join(" ")));
+    let mut child = docker_command(context)
+        .args(args)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
@@ -86,18 +224,37 @@ fn run_docker_system_df(config: &Config) -> Result<Vec<BloatEntry>, String> {
             }
         })?;
 
+    // drain stdout while waiting; `buildx du --verbose` can print more than
+    // the pipe buffer holds and would block until the timeout otherwise
+    let stdout_reader = child.stdout.take().map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut out = String::new();
+            pipe.read_to_string(&mut out).map(|_| out)
+        })
+    });
+
     let start = Instant::now();
+    let mut next_notice = WAIT_NOTICE_INTERVAL;
 
     let status = loop {
         match child.try_wait() {
             Ok(Some(status)) => break status,
             Ok(None) => {
-                if start.elapsed() > config.timeout {
+                if crate::interrupt::requested() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err("docker: query cancelled by Ctrl-C".to_string());
+                }
+                if progress && start.elapsed() >= next_notice {
+                    eprintln!("waiting for docker daemon... ({}s)", next_notice.as_secs());
+                    next_notice += WAIT_NOTICE_INTERVAL;
+                }
+                if start.elapsed() > timeout {
                     let _ = child.kill();
                     let _ = child.wait();
                     return Err(format!(
                         "docker: timed out after {} seconds (is Docker Desktop starting?)",
-                        config.timeout.as_secs()
+                        timeout.as_secs()
                     ));
                 }
                 std::thread::sleep(Duration::from_millis(100));
@@ -128,58 +285,175 @@ fn run_docker_system_df(config: &Config) -> Result<Vec<BloatEntry>, String> {
         return Err(format!("docker: command failed: {}", stderr.trim()));
     }
 
-    let mut raw_stdout = String::new();
-    if let Some(mut pipe) = child.stdout.take() {
-        pipe.read_to_string(&mut raw_stdout)
-            .map_err(|e| format!("docker: failed to read output: {e}"))?;
+    match stdout_reader.map(|reader| reader.join()) {
+        Some(Ok(Ok(stdout))) => Ok(stdout),
+        Some(Ok(Err(e))) => Err(format!("docker: failed to read output: {e}")),
+        Some(Err(_)) => Err("docker: failed to read output".to_string()),
+        None => Ok(String::new()),
     }
-    let stdout = raw_stdout;
-    let mut entries = Vec::new();
+}
 
-    // docker system df outputs JSONL (one JSON object per line)
-    for line in stdout.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
+/// Build cache age buckets: aggregate name, entry name, minimum days since
+/// last use, and the prune filter that removes that bucket and anything older.
+/// Records used more recently than the last bucket are reported as in use.
+const BUILD_CACHE_BUCKETS: &[(&str, &str, u64, &str)] = &[
+    (
+        "Build Cache (unused 30d+)",
+        "docker build cache, unused 30d+",
+        30,
+        "docker buildx prune --filter until=720h",
+    ),
+    (
+        "Build Cache (unused 7-30d)",
+        "docker build cache, unused 7-30d",
+        7,
+        "docker buildx prune --filter until=168h",
+    ),
+];
+
+/// Minimum days since last use for a build cache age bucket, by aggregate
+/// name. None for every other aggregate, including the in-use bucket.
+pub fn build_cache_bucket_days(aggregate: &str) -> Option<u64> {
+    BUILD_CACHE_BUCKETS
+        .iter()
+        .find(|(name, _, _, _)| *name == aggregate)
+        .map(|(_, _, min_days, _)| *min_days)
+}
 
-        let df_entry: DockerDfEntry = match serde_json::from_str(line) {
-            Ok(e) => e,
-            Err(e) => {
-                if config.verbose {
-                    return Err(format!("docker: failed to parse output: {e}"));
-                }
-                continue;
-            }
-        };
+/// One record from `docker buildx du --verbose`.
+#[derive(Debug, PartialEq)]
+struct BuildCacheRecord {
+    size_bytes: u64,
+    reclaimable: bool,
+    /// Seconds since last use; None when docker didn't say
+    idle_secs: Option<u64>,
+}
 
-        let size_bytes = parse_docker_size(&df_entry.size)?;
-        let reclaimable_bytes = parse_docker_size(&df_entry.reclaimable)?;
+/// Query buildx for per-record build cache usage and group it by last use.
+fn run_buildx_du(
+    timeout: Duration,
+    context: Option<&str>,
+    progress: bool,
+) -> Result<Vec<BloatEntry>, String> {
+    let stdout = run_docker(&["buildx", "du", "--verbose"], timeout, context, progress)?;
+    Ok(bucket_build_cache(&parse_buildx_du(&stdout)?))
+}
 
-        // only create entries for types that have actual data
-        if size_bytes == 0 {
+/// Parse the `key: value` blocks printed by `docker buildx du --verbose`.
+/// The trailing totals block has no ID and is ignored.
+fn parse_buildx_du(output: &str) -> Result<Vec<BuildCacheRecord>, String> {
+    let mut records = Vec::new();
+    for block in output.split("\n\n") {
+        let field = |key: &str| {
+            block.lines().find_map(|line| {
+                let (k, v) = line.split_once(':')?;
+                (k.trim() == key).then(|| v.trim())
+            })
+        };
+        if field("ID").is_none() {
             continue;
         }
-
-        let name = match df_entry.type_.as_str() {
-            "Images" => "docker images",
-            "Containers" => "docker containers",
-            "Local Volumes" => "docker volumes",
-            "Build Cache" => "docker build cache",
-            other => other,
+        let size_bytes = match field("Size") {
+            Some(size) => parse_docker_size(size)?,
+            None => continue,
         };
+        records.push(BuildCacheRecord {
+            size_bytes,
+            reclaimable: field("Reclaimable") == Some("true"),
+            idle_secs: field("Last used").and_then(parse_docker_ago),
+        });
+    }
+    Ok(records)
+}
 
+/// Parse docker's human "last used" durations ("3 weeks ago", "About an hour ago").
+fn parse_docker_ago(text: &str) -> Option<u64> {
+    let text = text.trim().trim_end_matches(" ago").to_lowercase();
+    match text.as_str() {
+        "less than a second" => return Some(0),
+        "about a minute" => return Some(60),
+        "about an hour" => return Some(3600),
+        _ => {}
+    }
+    let (num, unit) = text.split_once(' ')?;
+    let num: u64 = num.parse().ok()?;
+    let unit_secs = match unit.trim_end_matches('s') {
+        "second" => 1,
+        "minute" => 60,
+        "hour" => 3600,
+        "day" => 86_400,
+        "week" => 7 * 86_400,
+        "month" => 30 * 86_400,
+        "year" => 365 * 86_400,
+        _ => return None,
+    };
+    Some(num.saturating_mul(unit_secs))
+}
+
+/// Sum records into age buckets. Records with an unknown last use count as
+/// in use, so nothing is ever suggested for pruning on a guess.
+fn bucket_build_cache(records: &[BuildCacheRecord]) -> Vec<BloatEntry> {
+    let mut bucket_sizes = vec![(0u64, 0u64); BUILD_CACHE_BUCKETS.len()];
+    let mut in_use = 0u64;
+
+    for record in records {
+        let days = record.idle_secs.map(|s| s / 86_400);
+        let bucket = days.and_then(|days| {
+            BUILD_CACHE_BUCKETS
+                .iter()
+                .position(|(_, _, min_days, _)| days >= *min_days)
+        });
+        match bucket {
+            Some(i) => {
+                bucket_sizes[i].0 += record.size_bytes;
+                if record.reclaimable {
+                    bucket_sizes[i].1 += record.size_bytes;
+                }
+            }
+            None => in_use += record.size_bytes,
+        }
+    }
+
+    let mut entries: Vec<BloatEntry> = BUILD_CACHE_BUCKETS
+        .iter()
+        .zip(bucket_sizes)
+        .filter(|(_, (size, _))| *size > 0)
+        .map(
+            |((aggregate, name, _, hint), (size, reclaimable))| BloatEntry {
+                category: BloatCategory::ContainerData,
+                name: name.to_string(),
+                location: Location::Aggregate(aggregate.to_string()),
+                size_bytes: size,
+                reclaimable_bytes: reclaimable,
+                last_modified: None,
+                cleanup_hint: Some(hint.to_string()),
+                manual: false,
+                tags: tags(&["docker", "build-cache"]),
+                detector: None,
+                file_count: None,
+            },
+        )
+        .collect();
+
+    if in_use > 0 {
         entries.push(BloatEntry {
             category: BloatCategory::ContainerData,
-            name: name.to_string(),
-            location: Location::Aggregate(df_entry.type_.clone()),
-            size_bytes,
-            reclaimable_bytes,
+            name: "docker build cache, used in last 7d".to_string(),
+            location: Location::Aggregate("Build Cache (used in last 7d)".to_string()),
+            size_bytes: in_use,
+            reclaimable_bytes: 0,
             last_modified: None,
-            cleanup_hint: Some(get_cleanup_hint(&df_entry.type_)),
+            cleanup_hint: Some(
+                "in active use; `docker buildx prune --filter until=168h` keeps it".to_string(),
+            ),
+            manual: false,
+            tags: tags(&["docker", "build-cache"]),
+            detector: None,
+            file_count: None,
         });
     }
 
-    Ok(entries)
+    entries
 }
 
 fn parse_docker_size(size_str: &str) -> Result<u64, String> {
@@ -228,6 +502,13 @@ fn parse_docker_size(size_str: &str) -> Result<u64, String> {
     Ok((num * multiplier as f64) as u64)
 }
 
+fn docker_tags(type_: &str) -> Vec<String> {
+    match type_ {
+        "Build Cache" => tags(&["docker", "build-cache"]),
+        _ => tags(&["docker"]),
+    }
+}
+
 fn get_cleanup_hint(type_: &str) -> String {
     match type_ {
         "Images" => "docker image prune -a".to_string(),
@@ -271,7 +552,7 @@ fn detect_docker_desktop_vm(config: &Config) -> Option<BloatEntry> {
 
     // check if the VM disk file exists
     if !vm_path.exists() {
-        if config.verbose {
+        if config.verbose() {
             eprintln!("docker: VM disk not found at {}", vm_path.display());
         }
         return None;
@@ -281,19 +562,25 @@ fn detect_docker_desktop_vm(config: &Config) -> Option<BloatEntry> {
     let metadata = match fs::metadata(&vm_path) {
         Ok(m) => m,
         Err(e) => {
-            if config.verbose {
+            if config.verbose() {
                 eprintln!("docker: failed to get VM disk metadata: {e}");
             }
             return None;
         }
     };
 
-    let size_bytes = metadata.len();
+    // Docker.raw and ext4.vhdx are sparse: len() is the configured disk
+    // size, not what the image takes on the host
+    let (size_bytes, logical) = super::sparse_size(&metadata);
 
     // only report if there's actual data
     if size_bytes == 0 {
         return None;
     }
+    let cleanup_hint = match super::sparse_note(size_bytes, logical) {
+        Some(note) => format!("{cleanup_hint} ({note})"),
+        None => cleanup_hint,
+    };
 
     Some(BloatEntry {
         category: BloatCategory::ContainerData,
@@ -303,6 +590,10 @@ fn detect_docker_desktop_vm(config: &Config) -> Option<BloatEntry> {
         reclaimable_bytes: 0, // we can't determine reclaimable size without analyzing the VM
         last_modified: None,  // timestamp not needed for VM disk
         cleanup_hint: Some(cleanup_hint),
+        manual: true,
+        tags: tags(&["docker", "vm-disk"]),
+        detector: None,
+        file_count: None,
     })
 }
 
@@ -310,6 +601,18 @@ fn detect_docker_desktop_vm(config: &Config) -> Option<BloatEntry> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn docker_command_passes_configured_context() {
+        let cmd = docker_command(Some("colima"));
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args, ["--context", "colima"]);
+        assert_eq!(docker_command(None).get_args().count(), 0);
+        assert_eq!(
+            describe_target(Some("colima")),
+            "context 'colima' (from config)"
+        );
+    }
+
     #[test]
     fn test_parse_docker_size() {
         assert_eq!(parse_docker_size("0B").unwrap(), 0);
@@ -325,4 +628,80 @@ mod tests {
         assert_eq!(parse_docker_size("27.57MB").unwrap(), 27_570_000);
         assert_eq!(parse_docker_size("578.6kB (2%)").unwrap(), 578_600);
     }
+
+    #[test]
+    fn parses_docker_ago_durations() {
+        assert_eq!(parse_docker_ago("Less than a second ago"), Some(0));
+        assert_eq!(parse_docker_ago("About an hour ago"), Some(3600));
+        assert_eq!(parse_docker_ago("1 day ago"), Some(86_400));
+        assert_eq!(parse_docker_ago("3 weeks ago"), Some(21 * 86_400));
+        assert_eq!(parse_docker_ago("2 months ago"), Some(60 * 86_400));
+        assert_eq!(parse_docker_ago("<nil>"), None);
+    }
+
+    const BUILDX_DU: &str = "ID:\t\tabc\n\
+Created at:\t2024-01-01 10:00:00 +0000 UTC\n\
+Mutable:\tfalse\n\
+Reclaimable:\ttrue\n\
+Size:\t\t1.5GB\n\
+Last used:\t2 months ago\n\
+\n\
+ID:\t\tdef\n\
+Reclaimable:\ttrue\n\
+Size:\t\t200MB\n\
+Last used:\t10 days ago\n\
+\n\
+ID:\t\tghi\n\
+Reclaimable:\tfalse\n\
+Size:\t\t50MB\n\
+Last used:\t45 days ago\n\
+\n\
+ID:\t\tjkl\n\
+Reclaimable:\ttrue\n\
+Size:\t\t300MB\n\
+Last used:\tAbout an hour ago\n\
+\n\
+Shared:\t\t0B\n\
+Private:\t2.05GB\n\
+Reclaimable:\t2GB\n\
+Total:\t\t2.05GB\n";
+
+    #[test]
+    fn buildx_du_records_parsed_and_totals_skipped() {
+        let records = parse_buildx_du(BUILDX_DU).unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(
+            records[0],
+            BuildCacheRecord {
+                size_bytes: 1_500_000_000,
+                reclaimable: true,
+                idle_secs: Some(60 * 86_400),
+            }
+        );
+        assert!(!records[2].reclaimable);
+    }
+
+    #[test]
+    fn build_cache_grouped_by_last_use() {
+        let entries = bucket_build_cache(&parse_buildx_du(BUILDX_DU).unwrap());
+        let by_name = |name: &str| entries.iter().find(|e| e.name == name).unwrap();
+
+        let old = by_name("docker build cache, unused 30d+");
+        assert_eq!(old.size_bytes, 1_550_000_000);
+        // the 50MB record is in use by a build step and not reclaimable
+        assert_eq!(old.reclaimable_bytes, 1_500_000_000);
+        assert_eq!(
+            old.cleanup_hint.as_deref(),
+            Some("docker buildx prune --filter until=720h")
+        );
+
+        assert_eq!(
+            by_name("docker build cache, unused 7-30d").size_bytes,
+            200_000_000
+        );
+
+        let recent = by_name("docker build cache, used in last 7d");
+        assert_eq!(recent.size_bytes, 300_000_000);
+        assert_eq!(recent.reclaimable_bytes, 0);
+    }
 }
@@ -0,0 +1,172 @@
+//! ~/Downloads detector (opt-in: --detect-downloads).
+//!
+//! Installers, disk images and one-off files pile up in Downloads and are
+//! almost always safe to remove, but they're the user's own files. So the
+//! detector only runs when asked, only reports top-level items untouched for
+//! `downloads_min_age_days`, and clean only deletes them when `--category
+//! other` is given explicitly.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use walkdir::WalkDir;
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform;
+
+pub struct DownloadsDetector;
+
+impl Detector for DownloadsDetector {
+    fn name(&self) -> &'static str {
+        "downloads"
+    }
+
+    fn available(&self, _config: &Config) -> bool {
+        true
+    }
+
+    fn scan(&self, config: &Config) -> DetectorResult {
+        let Some(dir) = downloads_dir() else {
+            return DetectorResult::with_diagnostic(
+                "downloads: could not determine home directory".into(),
+            );
+        };
+        scan_downloads(
+            &dir,
+            config.downloads_min_age_days,
+            chrono::Utc::now().timestamp(),
+        )
+    }
+}
+
+fn downloads_dir() -> Option<PathBuf> {
+    platform::home_dir().map(|home| home.join("Downloads"))
+}
+
+/// Whether `entry` is an item straight under ~/Downloads. Clean leaves
+/// these alone unless the Other category was asked for by name.
+pub fn is_download(entry: &BloatEntry) -> bool {
+    match (&entry.location, downloads_dir()) {
+        (Location::FilesystemPath(path), Some(dir)) => path.parent() == Some(dir.as_path()),
+        _ => false,
+    }
+}
+
+/// One entry per top-level item in `dir` last modified at least
+/// `min_age_days` before `now`. A folder's age is that of its newest file.
+fn scan_downloads(dir: &Path, min_age_days: u64, now: i64) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+    let Ok(children) = std::fs::read_dir(dir) else {
+        return result;
+    };
+    let cutoff = now.saturating_sub((min_age_days as i64).saturating_mul(86_400));
+
+    let mut items: Vec<PathBuf> = children
+        .flatten()
+        .filter(|e| {
+            // .DS_Store, .localized and partial downloads' hidden state
+            !e.file_name().to_string_lossy().starts_with('.')
+                && e.file_type().is_ok_and(|t| !t.is_symlink())
+        })
+        .map(|e| e.path())
+        .collect();
+    items.sort();
+
+    for item in items {
+        let (size, newest) = measure(&item);
+        let Some(modified) = newest else {
+            continue;
+        };
+        if size == 0 || modified > cutoff {
+            continue;
+        }
+        let days = (now - modified) / 86_400;
+        let name = item
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        result.entries.push(BloatEntry {
+            category: BloatCategory::Other,
+            name: format!("download ({name})"),
+            location: Location::FilesystemPath(item),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified: Some(modified),
+            cleanup_hint: Some(format!(
+                "untouched for {days} days. delete if you no longer need it (clean needs --category other)"
+            )),
+            manual: false,
+            tags: tags(&["downloads"]),
+            detector: None,
+            file_count: None,
+        });
+    }
+
+    result
+}
+
+/// Total bytes and newest mtime of a file, or of the files under a folder.
+fn measure(path: &Path) -> (u64, Option<i64>) {
+    let mut size = 0u64;
+    let mut newest: Option<i64> = None;
+    for entry in WalkDir::new(path).follow_links(false).into_iter().flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        size = size.saturating_add(meta.len());
+        if let Some(secs) = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        {
+            let secs = secs.as_secs() as i64;
+            newest = Some(newest.map_or(secs, |n| n.max(secs)));
+        }
+    }
+    (size, newest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_old_top_level_items_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("installer.dmg"), [0u8; 300]).unwrap();
+        std::fs::create_dir(dir.path().join("photos")).unwrap();
+        std::fs::write(dir.path().join("photos/a.jpg"), [0u8; 200]).unwrap();
+        std::fs::write(dir.path().join(".DS_Store"), [0u8; 10]).unwrap();
+
+        // everything was just written, so 30 days from now it's all 30 days old
+        let now = chrono::Utc::now().timestamp();
+        let later = now + 30 * 86_400 + 60;
+
+        assert!(scan_downloads(dir.path(), 30, now).entries.is_empty());
+
+        let result = scan_downloads(dir.path(), 30, later);
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["download (installer.dmg)", "download (photos)"]);
+        let photos = &result.entries[1];
+        assert_eq!(photos.category, BloatCategory::Other);
+        assert_eq!(photos.size_bytes, 200);
+        assert_eq!(photos.reclaimable_bytes, 200);
+        assert!(photos
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .starts_with("untouched for 30 days"));
+    }
+
+    #[test]
+    fn missing_downloads_folder_is_quiet() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = scan_downloads(&dir.path().join("Downloads"), 30, 0);
+        assert!(result.entries.is_empty());
+        assert!(result.diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+//! Include/exclude glob filtering for the project walk.
+//!
+//! Patterns containing a `/` are matched against the full path; patterns
+//! without one are matched against the directory name alone, so `target`
+//! and `**/target` behave the same. `*` matches any run of characters,
+//! including `/`, and `?` matches one character.
+//!
+//! - `--exclude`: matching directories are pruned, nothing under them is scanned.
+//! - `--include`: when any are given, only matching directories (and anything
+//!   inside them) are checked for artifacts. Other directories are still
+//!   descended into if they could lead to a match.
+//! - Both: exclude wins. An excluded directory is pruned even if it, or
+//!   something under it, matches an include pattern.
+
+use std::path::Path;
+
+use crate::util::glob_match;
+
+#[derive(Debug, Default, Clone)]
+pub struct PathFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl PathFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        PathFilter { include, exclude }
+    }
+
+    /// True when the walker should not enter `path` at all.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude.iter().any(|p| pattern_matches(p, path))
+    }
+
+    /// True when `path` may be checked for artifacts: no include patterns,
+    /// or it or one of its ancestors matches one.
+    pub fn is_included(&self, path: &Path) -> bool {
+        self.include.is_empty()
+            || path
+                .ancestors()
+                .any(|a| self.include.iter().any(|p| pattern_matches(p, a)))
+    }
+
+    /// True when descending into `path` could still reach an included directory.
+    pub fn may_contain_included(&self, path: &Path) -> bool {
+        if self.include.is_empty() || self.is_included(path) {
+            return true;
+        }
+
+        let path_str = path.to_string_lossy();
+        self.include.iter().any(|pattern| {
+            // name-only patterns can match at any depth
+            if !pattern.contains('/') {
+                return true;
+            }
+            // compare against the literal part before the first wildcard:
+            // /code/**/dist can only match under /code, and /code/web is on the way there
+            let literal = pattern
+                .find(['*', '?'])
+                .map_or(pattern.as_str(), |i| &pattern[..i]);
+            path_str.starts_with(literal) || literal.starts_with(path_str.as_ref())
+        })
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &Path) -> bool {
+    if pattern.contains('/') {
+        glob_match(pattern, &path.to_string_lossy())
+    } else {
+        path.file_name()
+            .is_some_and(|name| glob_match(pattern, &name.to_string_lossy()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+        PathFilter::new(
+            include.iter().map(|s| s.to_string()).collect(),
+            exclude.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn empty_filter_allows_everything() {
+        let f = filter(&[], &[]);
+        let p = Path::new("/home/me/code/app/node_modules");
+        assert!(!f.is_excluded(p));
+        assert!(f.is_included(p));
+        assert!(f.may_contain_included(p));
+    }
+
+    #[test]
+    fn exclude_by_name_and_by_path() {
+        let f = filter(&[], &["vendor", "/home/me/archive/*"]);
+        assert!(f.is_excluded(Path::new("/home/me/code/app/vendor")));
+        assert!(f.is_excluded(Path::new("/home/me/archive/old")));
+        assert!(!f.is_excluded(Path::new("/home/me/code/app")));
+    }
+
+    #[test]
+    fn include_covers_matches_and_their_contents() {
+        let f = filter(&["**/node_modules"], &[]);
+        assert!(f.is_included(Path::new("/code/app/node_modules")));
+        assert!(f.is_included(Path::new("/code/app/node_modules/pkg")));
+        assert!(!f.is_included(Path::new("/code/app/target")));
+        // anything could still contain a node_modules
+        assert!(f.may_contain_included(Path::new("/code/app")));
+    }
+
+    #[test]
+    fn include_prunes_paths_off_the_literal_prefix() {
+        let f = filter(&["/code/web/*"], &[]);
+        assert!(f.may_contain_included(Path::new("/code")));
+        assert!(f.may_contain_included(Path::new("/code/web")));
+        assert!(f.may_contain_included(Path::new("/code/web/app")));
+        assert!(!f.may_contain_included(Path::new("/code/api")));
+    }
+
+    #[test]
+    fn exclude_wins_over_include() {
+        let f = filter(&["node_modules"], &["legacy"]);
+        let inside = Path::new("/code/legacy");
+        assert!(f.is_excluded(inside));
+        // the walker prunes excluded dirs before include is consulted
+        assert!(f.may_contain_included(inside));
+    }
+}
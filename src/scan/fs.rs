@@ -0,0 +1,207 @@
+//! Filesystem access for detectors that can also run against a remote host.
+//!
+//! Artifact recognition and cache discovery only need to list directories,
+//! stat paths and read small manifests. Going through `FileSystem` lets the
+//! same heuristics run locally (`LocalFs`) and over SFTP (`--remote`).
+
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    Other,
+}
+
+/// One directory entry. `len` is the file size for regular files and is not
+/// meaningful for anything else.
+#[derive(Debug, Clone)]
+pub struct FsEntry {
+    pub path: PathBuf,
+    pub kind: FileKind,
+    pub len: u64,
+}
+
+impl FsEntry {
+    pub fn file_name(&self) -> Option<&str> {
+        self.path.file_name().and_then(|n| n.to_str())
+    }
+}
+
+pub trait FileSystem {
+    /// List `path`'s entries without following symlinks.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>>;
+
+    /// Whether anything exists at `path` (a dangling symlink counts).
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Read a file if it is at most `limit` bytes. Used for project manifests.
+    fn read_small_file(&self, path: &Path, limit: u64) -> Option<String>;
+
+    /// Like `read_dir` for walks that only look at kinds: `len` may be 0.
+    fn read_dir_kinds(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+        self.read_dir(path)
+    }
+
+    /// Size and file count of everything under `path`.
+    fn measure(&self, path: &Path) -> io::Result<super::DirSize>;
+
+    /// The device `path` lives on, to keep walks on one filesystem. `None`
+    /// where that can't be told, and then the walk never stops at a mount.
+    fn device_id(&self, _path: &Path) -> Option<u64> {
+        None
+    }
+}
+
+/// The machine heft runs on.
+pub struct LocalFs;
+
+impl LocalFs {
+    /// The entries of `path`, stat'ing files for their length only when `sized`.
+    fn list(path: &Path, sized: bool) -> io::Result<Vec<FsEntry>> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let kind = if file_type.is_symlink() {
+                FileKind::Symlink
+            } else if file_type.is_dir() {
+                FileKind::Dir
+            } else if file_type.is_file() {
+                FileKind::File
+            } else {
+                FileKind::Other
+            };
+            let len = match kind {
+                FileKind::File if sized => entry.metadata().map(|m| m.len()).unwrap_or(0),
+                _ => 0,
+            };
+            entries.push(FsEntry {
+                path: entry.path(),
+                kind,
+                len,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+impl FileSystem for LocalFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+        Self::list(path, true)
+    }
+
+    // the directory entry has the kind; the length would cost a stat per file
+    fn read_dir_kinds(&self, path: &Path) -> io::Result<Vec<FsEntry>> {
+        Self::list(path, false)
+    }
+
+    fn measure(&self, path: &Path) -> io::Result<super::DirSize> {
+        super::measure_dir(path)
+    }
+
+    fn device_id(&self, path: &Path) -> Option<u64> {
+        super::device_id(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.symlink_metadata().is_ok()
+    }
+
+    fn read_small_file(&self, path: &Path, limit: u64) -> Option<String> {
+        if std::fs::metadata(path).ok()?.len() > limit {
+            return None;
+        }
+        let mut content = String::new();
+        std::fs::File::open(path)
+            .ok()?
+            .take(limit)
+            .read_to_string(&mut content)
+            .ok()?;
+        Some(content)
+    }
+}
+
+/// Total size of the regular files under `path`, never following symlinks.
+/// Unreadable directories are skipped with a warning, like calculate_dir_size.
+pub fn dir_size(fs: &dyn FileSystem, path: &Path) -> io::Result<super::DirSize> {
+    let mut total = 0u64;
+    let mut files = 0u64;
+    let mut warnings = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    let mut first = true;
+
+    while let Some(dir) = pending.pop() {
+        let entries = match fs.read_dir(&dir) {
+            Ok(entries) => entries,
+            // the root itself failing is an error for the caller, not a warning
+            Err(e) if first => return Err(e),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                warnings.push(format!("permission denied: {}", dir.display()));
+                continue;
+            }
+            Err(e) => {
+                warnings.push(format!("failed to traverse {}: {e}", dir.display()));
+                continue;
+            }
+        };
+        first = false;
+        super::count_visited(entries.len() as u64);
+
+        for entry in entries {
+            match entry.kind {
+                FileKind::File => {
+                    total = total.saturating_add(entry.len);
+                    files += 1;
+                }
+                FileKind::Dir => pending.push(entry.path),
+                FileKind::Symlink | FileKind::Other => {}
+            }
+        }
+    }
+
+    Ok(super::DirSize {
+        bytes: total,
+        files,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_dir_size_counts_nested_files_and_skips_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        std::fs::write(dir.path().join("a/one"), [0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("a/b/two"), [0u8; 50]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("a"), dir.path().join("loop")).unwrap();
+
+        let size = dir_size(&LocalFs, dir.path()).unwrap();
+        assert_eq!(size.bytes, 150);
+        assert_eq!(size.files, 2);
+        assert!(size.warnings.is_empty());
+    }
+
+    #[test]
+    fn missing_root_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(dir_size(&LocalFs, &dir.path().join("nope")).is_err());
+    }
+
+    #[test]
+    fn read_small_file_respects_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.json");
+        std::fs::write(&path, r#"{"name": "web"}"#).unwrap();
+        assert!(LocalFs.read_small_file(&path, 1024).is_some());
+        assert!(LocalFs.read_small_file(&path, 4).is_none());
+        assert!(LocalFs.exists(&path));
+        assert!(!LocalFs.exists(&dir.path().join("missing")));
+    }
+}
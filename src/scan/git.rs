@@ -0,0 +1,236 @@
+//! Git repository bloat: large .git directories and the git-lfs cache.
+//!
+//! Committed binaries and long histories can make .git outgrow the working
+//! tree many times over. Every .git under the scan roots of at least
+//! `MIN_GIT_BYTES` is reported under its repository's name with `git gc` as
+//! the hint, and LFS objects inside it are called out separately. The
+//! history is the user's own, so these entries have zero reclaimable bytes
+//! and clean never touches them.
+//!
+//! The global LFS cache (~/.cache/git-lfs) only holds downloads that can be
+//! fetched again, so it is reported as reclaimable.
+
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform;
+
+/// .git directories smaller than this are not worth a line.
+const MIN_GIT_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Directories never searched for repositories: dependencies and build
+/// output don't hold the user's own.
+const GIT_SEARCH_SKIP: &[&str] = &["node_modules", "target", "vendor", "site-packages"];
+
+pub struct GitDetector;
+
+impl Detector for GitDetector {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn available(&self, _config: &Config) -> bool {
+        true
+    }
+
+    fn scan(&self, config: &Config) -> DetectorResult {
+        let mut result = DetectorResult::empty();
+        for root in &config.roots {
+            let repos = scan_repos(root, config, MIN_GIT_BYTES);
+            result.entries.extend(repos.entries);
+            result.diagnostics.extend(repos.diagnostics);
+        }
+
+        let Some(home) = platform::home_dir() else {
+            result
+                .diagnostics
+                .push("git: could not determine home directory".into());
+            return result;
+        };
+        let lfs = scan_lfs_cache(&home.join(".cache/git-lfs"), config.min_entry_bytes);
+        result.entries.extend(lfs.entries);
+        result.diagnostics.extend(lfs.diagnostics);
+        result
+    }
+}
+
+/// Every .git directory under `root` of at least `min_bytes`. Nothing
+/// inside a .git is searched, but the working tree around it is, so nested
+/// repositories are found too.
+fn scan_repos(root: &Path, config: &Config, min_bytes: u64) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+    let filter = &config.path_filter;
+
+    let mut walker = WalkDir::new(root)
+        .follow_links(false)
+        .same_file_system(!super::cross_filesystems())
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            if e.depth() == 0 {
+                return true;
+            }
+            if !e.file_type().is_dir() || filter.is_excluded(e.path()) {
+                return false;
+            }
+            let name = e.file_name().to_string_lossy();
+            name == ".git" || (!name.starts_with('.') && !GIT_SEARCH_SKIP.contains(&name.as_ref()))
+        });
+
+    let mut git_dirs: Vec<PathBuf> = Vec::new();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if entry.file_name() == ".git" {
+            walker.skip_current_dir();
+            if filter.is_included(entry.path()) {
+                git_dirs.push(entry.into_path());
+            }
+        }
+    }
+
+    for git_dir in git_dirs {
+        let size = match super::calculate_dir_size(&git_dir) {
+            Ok((size, warnings)) => {
+                result.diagnostics.extend(
+                    warnings
+                        .into_iter()
+                        .map(|w| format!("{w} (size may be underestimated)")),
+                );
+                size
+            }
+            Err(e) => {
+                result
+                    .diagnostics
+                    .push(format!("git: failed to size {}: {e}", git_dir.display()));
+                continue;
+            }
+        };
+        if size < min_bytes && !super::sizes_skipped() {
+            continue;
+        }
+
+        let repo = git_dir
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "unknown".to_string());
+        let lfs = super::calculate_dir_size(&git_dir.join("lfs")).map_or(0, |(size, _)| size);
+        let mut hint = "history and objects. git gc --aggressive --prune=now repacks them; never delete .git itself".to_string();
+        let mut entry_tags = tags(&["git"]);
+        if lfs > 0 {
+            hint.push_str(&format!(
+                ". {} of this is LFS objects: git lfs prune drops the ones no recent commit needs",
+                crate::util::format_bytes(lfs)
+            ));
+            entry_tags.push("lfs".to_string());
+        }
+        let last_modified = std::fs::metadata(&git_dir)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+
+        result.entries.push(BloatEntry {
+            category: BloatCategory::ProjectArtifacts,
+            name: format!(".git ({repo})"),
+            location: Location::FilesystemPath(git_dir),
+            size_bytes: size,
+            reclaimable_bytes: 0,
+            last_modified,
+            cleanup_hint: Some(hint),
+            manual: false,
+            tags: entry_tags,
+            detector: None,
+            file_count: None,
+        });
+    }
+
+    result
+}
+
+/// The shared git-lfs download cache, if there is one.
+fn scan_lfs_cache(dir: &Path, min_bytes: u64) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+    let Ok((size, warnings)) = super::calculate_dir_size(dir) else {
+        return result;
+    };
+    if !super::big_enough(size, min_bytes) {
+        return result;
+    }
+    result.diagnostics.extend(
+        warnings
+            .into_iter()
+            .map(|w| format!("{w} (size may be underestimated)")),
+    );
+    result.entries.push(BloatEntry {
+        category: BloatCategory::PackageCache,
+        name: "git-lfs cache".to_string(),
+        location: Location::FilesystemPath(dir.to_path_buf()),
+        size_bytes: size,
+        reclaimable_bytes: size,
+        last_modified: None,
+        cleanup_hint: Some("safe to delete, LFS objects are downloaded again on checkout".into()),
+        manual: false,
+        tags: tags(&["git", "lfs"]),
+        detector: None,
+        file_count: None,
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(dir: &Path, bytes: usize) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("data"), vec![0u8; bytes]).unwrap();
+    }
+
+    #[test]
+    fn large_git_dirs_reported_with_lfs_share() {
+        let root = tempfile::tempdir().unwrap();
+        fill(&root.path().join("game/.git/objects/pack"), 300);
+        fill(&root.path().join("game/.git/lfs/objects"), 200);
+        fill(&root.path().join("game/libs/engine/.git/objects"), 150);
+        fill(&root.path().join("small/.git/objects"), 10);
+        // dependencies and hidden dirs carry their own clones
+        fill(&root.path().join("web/node_modules/dep/.git/objects"), 900);
+        fill(&root.path().join(".cache/repo/.git/objects"), 900);
+
+        let result = scan_repos(root.path(), &Config::default(), 100);
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, [".git (game)", ".git (engine)"]);
+        let game = &result.entries[0];
+        assert_eq!(game.size_bytes, 500);
+        assert_eq!(game.reclaimable_bytes, 0);
+        assert_eq!(game.category, BloatCategory::ProjectArtifacts);
+        assert_eq!(game.tags, ["git", "lfs"]);
+        let hint = game.cleanup_hint.as_deref().unwrap();
+        assert!(hint.contains("git gc --aggressive --prune=now"));
+        assert!(hint.contains("git lfs prune"));
+        assert!(!result.entries[1]
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .contains("LFS"));
+    }
+
+    #[test]
+    fn lfs_cache_is_reclaimable() {
+        let home = tempfile::tempdir().unwrap();
+        let cache = home.path().join(".cache/git-lfs");
+        assert!(scan_lfs_cache(&cache, 0).entries.is_empty());
+
+        fill(&cache.join("objects"), 50);
+        let result = scan_lfs_cache(&cache, 0);
+        assert_eq!(result.entries[0].reclaimable_bytes, 50);
+        assert_eq!(result.entries[0].category, BloatCategory::PackageCache);
+    }
+}
@@ -0,0 +1,296 @@
+//! Log file accumulation.
+//!
+//! Logs pile up under ~/Library/Logs (macOS), /var/log, and app state dirs
+//! like ~/.local/state, and a single app looping on an error can write
+//! gigabytes. Any log file of at least `BIG_LOG_BYTES` gets its own entry so
+//! the runaway stands out; the rest are totalled per app folder, and only
+//! folders of at least `MIN_LOG_DIR_BYTES` are reported, so thousands of
+//! tiny logs don't flood the output.
+//!
+//! Logs under home are the user's and safe to delete. System logs are
+//! rotated by the OS and need root, so those entries are informational.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use walkdir::WalkDir;
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform::{self, Platform};
+
+/// A single log file this big is reported on its own.
+const BIG_LOG_BYTES: u64 = 100 * 1024 * 1024;
+
+/// App log folders smaller than this are not worth a line.
+const MIN_LOG_DIR_BYTES: u64 = 100 * 1024 * 1024;
+
+const USER_LOG_HINT: &str = "safe to delete or truncate, apps start new logs as needed";
+const SYSTEM_LOG_HINT: &str = "system logs, rotated by the OS. trim as root: delete old rotated files (*.gz, *.1) or sudo journalctl --vacuum-size=500M";
+const RUNAWAY_NOTE: &str =
+    "a log this size usually means something is failing over and over; look at the end of it first";
+
+pub struct LogsDetector;
+
+impl Detector for LogsDetector {
+    fn name(&self) -> &'static str {
+        "logs"
+    }
+
+    fn available(&self, _config: &Config) -> bool {
+        true
+    }
+
+    fn scan(&self, config: &Config) -> DetectorResult {
+        let Some(home) = platform::home_dir() else {
+            return DetectorResult::with_diagnostic(
+                "logs: could not determine home directory".into(),
+            );
+        };
+        let mut result = DetectorResult::empty();
+        for root in log_roots(&home, config.platform) {
+            let found = scan_log_root(&root, BIG_LOG_BYTES, MIN_LOG_DIR_BYTES);
+            result.entries.extend(found.entries);
+            result.diagnostics.extend(found.diagnostics);
+        }
+        result
+    }
+}
+
+/// A directory logs collect in.
+struct LogRoot {
+    dir: PathBuf,
+    /// Under home, so heft may delete what it finds
+    cleanable: bool,
+    /// Holds other state too: only files named like logs count, and only
+    /// individually, never whole folders
+    logs_only: bool,
+}
+
+fn log_roots(home: &Path, platform: Platform) -> Vec<LogRoot> {
+    let root = |dir: PathBuf, cleanable, logs_only| LogRoot {
+        dir,
+        cleanable,
+        logs_only,
+    };
+    match platform {
+        Platform::MacOS => vec![
+            root(home.join("Library/Logs"), true, false),
+            root(PathBuf::from("/Library/Logs"), false, false),
+            root(PathBuf::from("/var/log"), false, false),
+        ],
+        Platform::Linux => vec![
+            root(home.join(".local/state"), true, true),
+            root(PathBuf::from("/var/log"), false, false),
+        ],
+        Platform::Windows | Platform::Unknown => Vec::new(),
+    }
+}
+
+/// app.log, app.log.1, app.log.gz
+fn is_log_name(name: &str) -> bool {
+    name.ends_with(".log") || name.contains(".log.")
+}
+
+/// Per app folder below a root: total of the files not reported on their
+/// own, and the newest mtime among them.
+#[derive(Default)]
+struct Folder {
+    bytes: u64,
+    newest: Option<i64>,
+}
+
+fn scan_log_root(root: &LogRoot, big_bytes: u64, min_dir_bytes: u64) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+    if !root.dir.is_dir() {
+        return result;
+    }
+
+    let mut folders: Vec<(PathBuf, Folder)> = Vec::new();
+    let mut unreadable = 0u64;
+    for entry in WalkDir::new(&root.dir)
+        .follow_links(false)
+        .same_file_system(!super::cross_filesystems())
+        .sort_by_file_name()
+    {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => {
+                // /var/log is mostly root-only; one line says it better than hundreds
+                unreadable += 1;
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        if root.logs_only && !is_log_name(&name) {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else {
+            unreadable += 1;
+            continue;
+        };
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+
+        if meta.len() >= big_bytes {
+            result.entries.push(log_entry(
+                root,
+                format!("log file ({name})"),
+                entry.path().to_path_buf(),
+                meta.len(),
+                modified,
+                true,
+            ));
+            continue;
+        }
+        // loose files at the top and logs among other state stay unreported
+        if root.logs_only || entry.depth() < 2 {
+            continue;
+        }
+        let Some(app) = entry
+            .path()
+            .strip_prefix(&root.dir)
+            .ok()
+            .and_then(|rest| rest.components().next())
+            .map(|first| root.dir.join(first))
+        else {
+            continue;
+        };
+        let folder = match folders.iter_mut().find(|(path, _)| *path == app) {
+            Some((_, folder)) => folder,
+            None => {
+                folders.push((app, Folder::default()));
+                &mut folders.last_mut().unwrap().1
+            }
+        };
+        folder.bytes = folder.bytes.saturating_add(meta.len());
+        folder.newest = folder.newest.max(modified);
+    }
+
+    for (path, folder) in folders {
+        if folder.bytes < min_dir_bytes {
+            continue;
+        }
+        let app = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        result.entries.push(log_entry(
+            root,
+            format!("logs ({app})"),
+            path,
+            folder.bytes,
+            folder.newest,
+            false,
+        ));
+    }
+
+    if unreadable > 0 {
+        result.diagnostics.push(format!(
+            "logs: {unreadable} unreadable entries under {} (size may be underestimated)",
+            root.dir.display()
+        ));
+    }
+    result
+}
+
+fn log_entry(
+    root: &LogRoot,
+    name: String,
+    path: PathBuf,
+    size: u64,
+    last_modified: Option<i64>,
+    runaway: bool,
+) -> BloatEntry {
+    let hint = if root.cleanable {
+        USER_LOG_HINT
+    } else {
+        SYSTEM_LOG_HINT
+    };
+    let hint = if runaway {
+        format!("{hint}. {RUNAWAY_NOTE}")
+    } else {
+        hint.to_string()
+    };
+    BloatEntry {
+        category: BloatCategory::SystemCache,
+        name,
+        location: Location::FilesystemPath(path),
+        size_bytes: size,
+        reclaimable_bytes: if root.cleanable { size } else { 0 },
+        last_modified,
+        cleanup_hint: Some(hint),
+        manual: false,
+        tags: tags(&["logs"]),
+        detector: None,
+        file_count: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, file: &str, bytes: usize) {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![b'x'; bytes]).unwrap();
+    }
+
+    #[test]
+    fn big_files_stand_out_and_small_ones_total_per_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Chatty/run.log", 500);
+        write(dir.path(), "Chatty/old/run.log.1", 60);
+        write(dir.path(), "Chatty/old/run.log.2", 60);
+        write(dir.path(), "Quiet/app.log", 10);
+        write(dir.path(), "loose.log", 90);
+        let root = LogRoot {
+            dir: dir.path().to_path_buf(),
+            cleanable: true,
+            logs_only: false,
+        };
+
+        let result = scan_log_root(&root, 200, 100);
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["log file (run.log)", "logs (Chatty)"]);
+        // the big file isn't counted twice
+        assert_eq!(result.entries[1].size_bytes, 120);
+        assert!(result.entries.iter().all(
+            |e| e.category == BloatCategory::SystemCache && e.reclaimable_bytes == e.size_bytes
+        ));
+        assert!(result.entries[0]
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .contains("failing over and over"));
+    }
+
+    #[test]
+    fn state_dirs_only_report_big_log_files_and_system_logs_are_informational() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "app/history", 900);
+        write(dir.path(), "app/app.log", 300);
+        write(dir.path(), "app/debug.log", 150);
+        let root = LogRoot {
+            dir: dir.path().to_path_buf(),
+            cleanable: false,
+            logs_only: true,
+        };
+
+        let result = scan_log_root(&root, 200, 100);
+
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].name, "log file (app.log)");
+        assert_eq!(result.entries[0].reclaimable_bytes, 0);
+        assert!(is_log_name("syslog.log.2.gz"));
+        assert!(!is_log_name("catalog"));
+    }
+}
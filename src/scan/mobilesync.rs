@@ -0,0 +1,202 @@
+//! iOS device backup detector (macOS and Windows).
+//!
+//! Finder and iTunes back iPhones and iPads up into MobileSync/Backup, one
+//! folder per device, and never drop the backups of devices long since
+//! replaced; 50-150 GB is common. Each backup is reported on its own, named
+//! by the device when its Info.plist says so.
+//!
+//! Backups are the user's data, not a cache: entries are reclaimable, but
+//! clean only takes them when asked by `--tag ios-backup` or `--path`, and
+//! the hint says to check the backup isn't the only copy first.
+
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform::{self, Platform};
+
+/// Tag on every backup entry; clean skips them unless it is asked for.
+pub const BACKUP_TAG: &str = "ios-backup";
+
+const BACKUP_HINT: &str = "a full device backup, your data rather than a cache. manage in Finder/iTunes → Manage Backups, or delete the backup folder; first make sure the device is gone or backed up elsewhere (iCloud, a newer backup)";
+
+pub struct MobileSyncDetector;
+
+impl Detector for MobileSyncDetector {
+    fn name(&self) -> &'static str {
+        "mobilesync"
+    }
+
+    fn available(&self, config: &Config) -> bool {
+        matches!(config.platform, Platform::MacOS | Platform::Windows)
+    }
+
+    fn scan(&self, config: &Config) -> DetectorResult {
+        let Some(home) = platform::home_dir() else {
+            return DetectorResult::with_diagnostic(
+                "mobilesync: could not determine home directory".into(),
+            );
+        };
+        let mut result = DetectorResult::empty();
+        for dir in backup_dirs(&home, config.platform) {
+            let found = scan_backups(&dir);
+            result.entries.extend(found.entries);
+            result.diagnostics.extend(found.diagnostics);
+        }
+        result
+    }
+}
+
+/// Whether `entry` is a device backup, which clean leaves alone unless
+/// selected by tag or path.
+pub fn is_backup(entry: &BloatEntry) -> bool {
+    entry.tags.iter().any(|t| t == BACKUP_TAG)
+}
+
+/// Where backups live: Finder's folder on macOS; on Windows the classic
+/// iTunes one under %APPDATA% and the Microsoft Store iTunes / Apple
+/// Devices one under the profile.
+fn backup_dirs(home: &Path, platform: Platform) -> Vec<PathBuf> {
+    match platform {
+        Platform::MacOS => vec![home.join("Library/Application Support/MobileSync/Backup")],
+        Platform::Windows => {
+            let appdata = std::env::var_os("APPDATA")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join("AppData").join("Roaming"));
+            vec![
+                appdata
+                    .join("Apple Computer")
+                    .join("MobileSync")
+                    .join("Backup"),
+                home.join("Apple").join("MobileSync").join("Backup"),
+            ]
+        }
+        Platform::Linux | Platform::Unknown => Vec::new(),
+    }
+}
+
+/// One entry per backup folder in `dir`.
+fn scan_backups(dir: &Path) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+    let Ok(children) = std::fs::read_dir(dir) else {
+        return result;
+    };
+    let mut backups: Vec<PathBuf> = children
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    backups.sort();
+
+    for backup in backups {
+        let size = match super::calculate_dir_size(&backup) {
+            Ok((size, warnings)) => {
+                result.diagnostics.extend(
+                    warnings
+                        .into_iter()
+                        .map(|w| format!("{w} (size may be underestimated)")),
+                );
+                size
+            }
+            Err(e) => {
+                result.diagnostics.push(format!(
+                    "mobilesync: failed to size {}: {e}",
+                    backup.display()
+                ));
+                continue;
+            }
+        };
+        if size == 0 && !super::sizes_skipped() {
+            continue;
+        }
+
+        // Info.plist is rewritten at the end of every backup
+        let info = backup.join("Info.plist");
+        let device = std::fs::read(&info)
+            .ok()
+            .and_then(|plist| device_name(&plist))
+            .unwrap_or_else(|| {
+                backup
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+        let last_modified = std::fs::metadata(&info)
+            .or_else(|_| std::fs::metadata(&backup))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64);
+
+        result.entries.push(BloatEntry {
+            category: BloatCategory::SystemCache,
+            name: format!("iOS backup ({device})"),
+            location: Location::FilesystemPath(backup),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified,
+            cleanup_hint: Some(BACKUP_HINT.to_string()),
+            manual: false,
+            tags: tags(&[BACKUP_TAG]),
+            detector: None,
+            file_count: None,
+        });
+    }
+
+    result
+}
+
+/// The "Device Name" from a backup's Info.plist. Only the XML form is read;
+/// binary plists fall back to the folder name.
+fn device_name(plist: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(plist).ok()?;
+    let after_key = &text[text.find("<key>Device Name</key>")?..];
+    let start = after_key.find("<string>")? + "<string>".len();
+    let end = after_key[start..].find("</string>")? + start;
+    let name = after_key[start..end]
+        .replace("&apos;", "'")
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_backup_reported_by_device_name() {
+        let dir = tempfile::tempdir().unwrap();
+        for (backup, bytes) in [("00008101-ABC", 300), ("00008030-DEF", 200)] {
+            let path = dir.path().join(backup);
+            std::fs::create_dir_all(path.join("3f")).unwrap();
+            std::fs::write(path.join("3f/3f2a"), vec![0u8; bytes]).unwrap();
+        }
+        std::fs::write(
+            dir.path().join("00008101-ABC/Info.plist"),
+            "<plist><dict><key>Build Version</key><string>21E236</string>\
+             <key>Device Name</key><string>Sam&apos;s iPhone</string></dict></plist>",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("empty")).unwrap();
+
+        let result = scan_backups(dir.path());
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["iOS backup (00008030-DEF)", "iOS backup (Sam's iPhone)"]
+        );
+        let backup = &result.entries[1];
+        assert!(backup.size_bytes >= 300);
+        assert_eq!(backup.reclaimable_bytes, backup.size_bytes);
+        assert_eq!(backup.category, BloatCategory::SystemCache);
+        assert!(is_backup(backup));
+        assert!(backup.last_modified.is_some());
+        assert_eq!(device_name(b"bplist00\x01\x02"), None);
+    }
+}
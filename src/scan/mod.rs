@@ -1,31 +1,91 @@
+pub mod appsupport;
 pub mod caches;
+pub mod database;
 pub mod detector;
+pub mod disk;
 pub mod docker;
+pub mod downloads;
+pub mod filter;
+pub mod fs;
+pub mod git;
+pub mod logs;
+pub mod mobilesync;
 pub mod projects;
+pub mod score;
+pub mod system;
+pub mod toolchains;
+pub mod windows;
 pub mod xcode;
 
-use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use serde::Serialize;
 use walkdir::WalkDir;
 
 use crate::config::Config;
+use crate::report::OutputFormat;
 use crate::spinner::Spinner;
-use crate::util::format_bytes;
-use detector::{BloatEntry, Detector, DetectorResult};
+use crate::util::{self, format_bytes};
+use detector::{BloatCategory, BloatEntry, Detector, DetectorResult};
 
 #[derive(Serialize)]
 pub struct ScanResult {
     pub entries: Vec<BloatEntry>,
     pub diagnostics: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub duration_ms: Option<u128>,
+    pub duration_ms: Option<u64>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub detector_timings: Vec<(String, u128)>,
+    pub detector_timings: Vec<(String, u64)>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub peak_memory_bytes: Option<usize>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub detector_memory: Vec<(String, usize)>,
+    /// Files and directories visited while walking, across all detectors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_scanned: Option<u64>,
+    /// Free and total space of the filesystem under each scan root
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disks: Vec<disk::DiskUsage>,
+    /// What each detector did, in run order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub coverage: Vec<DetectorCoverage>,
+}
+
+/// How a detector fared in a scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CoverageStatus {
+    Ran,
+    /// Ran, but panicked or timed out, so its results are incomplete
+    Errored,
+    Disabled,
+    /// Needs a flag to run (downloads)
+    OptIn,
+    Unavailable,
+}
+
+impl CoverageStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CoverageStatus::Ran => "ran",
+            CoverageStatus::Errored => "errored",
+            CoverageStatus::Disabled => "disabled",
+            CoverageStatus::OptIn => "opt-in",
+            CoverageStatus::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// One detector's status and what it contributed.
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectorCoverage {
+    pub name: String,
+    pub status: CoverageStatus,
+    pub entries: usize,
+    pub size_bytes: u64,
+    pub reclaimable_bytes: u64,
 }
 
 impl ScanResult {
@@ -37,6 +97,9 @@ impl ScanResult {
             detector_timings: Vec::new(),
             peak_memory_bytes: None,
             detector_memory: Vec::new(),
+            files_scanned: None,
+            disks: Vec::new(),
+            coverage: Vec::new(),
         }
     }
 
@@ -46,16 +109,138 @@ impl ScanResult {
     }
 }
 
-pub fn run(config: &Config) -> ScanResult {
-    let start = std::time::Instant::now();
-    let mut scan_result = ScanResult::empty();
+/// Running count of filesystem entries visited by the size walks. Detectors
+/// add to it as they go; `run` reports the difference across a scan.
+static ENTRIES_VISITED: AtomicU64 = AtomicU64::new(0);
+
+/// `config.cross_filesystems` for the current scan, so the size walks can
+/// honour it without every detector passing the config down.
+static CROSS_FILESYSTEMS: AtomicBool = AtomicBool::new(false);
 
-    let detectors: Vec<Box<dyn Detector>> = vec![
+pub(crate) fn cross_filesystems() -> bool {
+    CROSS_FILESYSTEMS.load(Ordering::Relaxed)
+}
+
+/// `config.skip_sizes` for the current scan (--no-sizes): the size walks
+/// return 0 at once, and detectors keep entries they'd drop as empty.
+static SKIP_SIZES: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn sizes_skipped() -> bool {
+    SKIP_SIZES.load(Ordering::Relaxed)
+}
+
+/// Whether a non-empty entry of `size` clears `min_bytes`
+/// (`config.min_entry_bytes`). Always true with --no-sizes, where every
+/// size reads 0.
+pub(crate) fn big_enough(size: u64, min_bytes: u64) -> bool {
+    sizes_skipped() || (size > 0 && size >= min_bytes)
+}
+
+/// -vvv for the current scan: log the directories walked, which cache
+/// locations exist, size walks, detector results and external commands to
+/// stderr as they happen.
+static DEBUG: AtomicBool = AtomicBool::new(false);
+
+/// Print `message()` as a debug line when -vvv is on. Built lazily so the
+/// formatting costs nothing otherwise.
+pub(crate) fn debug(message: impl FnOnce() -> String) {
+    if DEBUG.load(Ordering::Relaxed) {
+        eprintln!("debug: {}", message());
+    }
+}
+
+/// Filesystem device of `path` (not following symlinks), to spot mount
+/// points. None where the platform doesn't expose one.
+pub(crate) fn device_id(path: &Path) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        path.symlink_metadata().ok().map(|m| m.dev())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+pub(crate) fn count_visited(entries: u64) {
+    ENTRIES_VISITED.fetch_add(entries, Ordering::Relaxed);
+}
+
+pub(crate) fn entries_visited() -> u64 {
+    ENTRIES_VISITED.load(Ordering::Relaxed)
+}
+
+/// Every detector, in run order. Names match `config::DETECTOR_NAMES`.
+pub fn registry() -> Vec<Box<dyn Detector>> {
+    vec![
         Box::new(projects::ProjectDetector),
+        Box::new(git::GitDetector),
         Box::new(caches::CacheDetector),
+        Box::new(toolchains::ToolchainDetector),
         Box::new(docker::DockerDetector),
         Box::new(xcode::XcodeDetector),
-    ];
+        Box::new(appsupport::AppSupportDetector),
+        Box::new(mobilesync::MobileSyncDetector),
+        Box::new(database::DatabaseDetector),
+        Box::new(system::SystemDetector),
+        Box::new(logs::LogsDetector),
+        Box::new(windows::WindowsDetector),
+        Box::new(downloads::DownloadsDetector),
+    ]
+}
+
+/// Why `detector` won't run, if it won't. Config (--disable, --only,
+/// `[detectors]`) is checked here for every detector, so `available` only
+/// has to answer for the platform.
+fn skip_status(detector: &dyn Detector, config: &Config) -> Option<CoverageStatus> {
+    let name = detector.name();
+    if !config.is_detector_enabled(name) {
+        Some(CoverageStatus::Disabled)
+    } else if name == "downloads" && !config.detect_downloads {
+        // the user's own files: never scanned unless asked for
+        Some(CoverageStatus::OptIn)
+    } else if !detector.available(config) {
+        Some(CoverageStatus::Unavailable)
+    } else {
+        None
+    }
+}
+
+/// The diagnostic for a detector skipped with `status`.
+fn skip_message(name: &str, status: CoverageStatus) -> String {
+    match status {
+        CoverageStatus::Disabled => format!("{name}: skipped (disabled by config)"),
+        CoverageStatus::OptIn => format!("{name}: skipped (opt-in, use --detect-downloads)"),
+        _ => format!("{name}: skipped (not available on this platform)"),
+    }
+}
+
+/// Ran and errored detectors' coverage: counts and sizes of what they found.
+fn detector_coverage(name: &str, result: &DetectorResult) -> DetectorCoverage {
+    DetectorCoverage {
+        name: name.to_string(),
+        status: if result.errored {
+            CoverageStatus::Errored
+        } else {
+            CoverageStatus::Ran
+        },
+        entries: result.entries.len(),
+        size_bytes: result.entries.iter().map(|e| e.size_bytes).sum(),
+        reclaimable_bytes: result.entries.iter().map(|e| e.reclaimable_bytes).sum(),
+    }
+}
+
+pub fn run(config: &Config) -> ScanResult {
+    let start = std::time::Instant::now();
+    let visited_before = entries_visited();
+    let mut scan_result = ScanResult::empty();
+    CROSS_FILESYSTEMS.store(config.cross_filesystems, Ordering::Relaxed);
+    SKIP_SIZES.store(config.skip_sizes, Ordering::Relaxed);
+    DEBUG.store(config.verbose_level >= 3, Ordering::Relaxed);
+
+    let detectors = registry();
 
     // Reserve space for per-detector metrics
     scan_result.detector_timings.reserve(detectors.len());
@@ -70,8 +255,14 @@ pub fn run(config: &Config) -> ScanResult {
         peak_memory = Some(usage.physical_mem);
     }
 
-    // Show a spinner when running interactively without progressive output
-    let use_spinner = !config.progressive && !config.json_output;
+    // Show a spinner when running interactively without progressive output;
+    // machine-readable formats stay quiet, and -vvv lines would tear it
+    let use_spinner = !config.progressive
+        && config.verbose_level < 3
+        && matches!(
+            config.output_format,
+            OutputFormat::Table | OutputFormat::TableWide | OutputFormat::Compact
+        );
     let spinner = if use_spinner {
         Spinner::start("Scanning...")
     } else {
@@ -81,21 +272,19 @@ pub fn run(config: &Config) -> ScanResult {
     for detector in detectors {
         let detector_name = detector.name();
 
-        // Skip disabled or unavailable detectors
-        if !config.is_detector_enabled(detector_name) {
-            let msg = format!("{detector_name}: skipped (disabled by config)");
-            if config.progressive {
-                eprintln!("{msg}");
-            }
-            scan_result.diagnostics.push(msg);
-            continue;
-        }
-        if !detector.available(config) {
-            let msg = format!("{detector_name}: skipped (not available on this platform)");
+        if let Some(status) = skip_status(detector.as_ref(), config) {
+            let msg = skip_message(detector_name, status);
             if config.progressive {
                 eprintln!("{msg}");
             }
             scan_result.diagnostics.push(msg);
+            scan_result.coverage.push(DetectorCoverage {
+                name: detector_name.to_string(),
+                status,
+                entries: 0,
+                size_bytes: 0,
+                reclaimable_bytes: 0,
+            });
             continue;
         }
 
@@ -120,13 +309,20 @@ pub fn run(config: &Config) -> ScanResult {
 
         // Run detector and measure timing
         let detector_start = std::time::Instant::now();
-        let result = detector.scan(config);
+        let mut result = scan_guarded(detector.as_ref(), config);
         let detector_duration = detector_start.elapsed();
 
+        apply_category_overrides(&mut result.entries, &config.category_overrides);
+        for entry in &mut result.entries {
+            entry
+                .detector
+                .get_or_insert_with(|| detector_name.to_string());
+        }
+
         // Store timing (always available)
         scan_result
             .detector_timings
-            .push((detector_name.to_string(), detector_duration.as_millis()));
+            .push((detector_name.to_string(), millis(detector_duration)));
 
         // Sample memory AFTER detector completes (if tracking enabled)
         if peak_memory.is_some() {
@@ -162,6 +358,18 @@ pub fn run(config: &Config) -> ScanResult {
             );
         }
 
+        debug(|| {
+            format!(
+                "{detector_name}: {} entries, {} diagnostics in {}ms",
+                result.entries.len(),
+                result.diagnostics.len(),
+                millis(detector_duration)
+            )
+        });
+
+        scan_result
+            .coverage
+            .push(detector_coverage(detector_name, &result));
         scan_result.merge(result);
     }
 
@@ -170,7 +378,12 @@ pub fn run(config: &Config) -> ScanResult {
         sp.stop();
     }
 
-    scan_result.duration_ms = Some(start.elapsed().as_millis());
+    scan_result.duration_ms = Some(millis(start.elapsed()));
+    scan_result.files_scanned = Some(entries_visited().saturating_sub(visited_before));
+
+    let (disks, disk_diagnostics) = disk::usage_for_roots(&config.roots);
+    scan_result.disks = disks;
+    scan_result.diagnostics.extend(disk_diagnostics);
 
     // Store peak memory if sampling was available
     scan_result.peak_memory_bytes = peak_memory;
@@ -178,15 +391,88 @@ pub fn run(config: &Config) -> ScanResult {
     scan_result
 }
 
+/// Run one detector, turning a panic into a diagnostic so a bug in one
+/// detector doesn't throw away everything the others found.
+fn scan_guarded(detector: &dyn Detector, config: &Config) -> DetectorResult {
+    // detectors only read the config; nothing shared is left half-updated
+    match panic::catch_unwind(AssertUnwindSafe(|| detector.scan(config))) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            DetectorResult::errored(format!("detector {} panicked: {message}", detector.name()))
+        }
+    }
+}
+
+/// Whole milliseconds in `duration`, the unit every stored and reported
+/// duration uses. Saturates instead of truncating the u128 `as_millis`.
+pub(crate) fn millis(duration: std::time::Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Remap entry categories per the config's `[category_overrides]`.
+/// An exact name match wins over a glob; otherwise the first matching glob applies.
+pub(crate) fn apply_category_overrides(
+    entries: &mut [BloatEntry],
+    overrides: &[(String, BloatCategory)],
+) {
+    if overrides.is_empty() {
+        return;
+    }
+    for entry in entries {
+        let matched = overrides
+            .iter()
+            .find(|(pattern, _)| *pattern == entry.name)
+            .or_else(|| {
+                overrides
+                    .iter()
+                    .find(|(pattern, _)| util::glob_match(pattern, &entry.name))
+            });
+        if let Some((_, category)) = matched {
+            entry.category = *category;
+        }
+    }
+}
+
+/// What sizing a directory found.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DirSize {
+    pub bytes: u64,
+    /// Regular files counted, for `clean --verify` to compare
+    pub files: u64,
+    /// Parts that couldn't be read, so `bytes` may be low
+    pub warnings: Vec<String>,
+}
+
 pub(crate) fn calculate_dir_size(path: &Path) -> Result<(u64, Vec<String>), std::io::Error> {
+    measure_dir(path).map(|size| (size.bytes, size.warnings))
+}
+
+/// Like `calculate_dir_size`, also counting the files.
+pub(crate) fn measure_dir(path: &Path) -> Result<DirSize, std::io::Error> {
+    if sizes_skipped() {
+        return Ok(DirSize::default());
+    }
     let mut total = 0u64;
+    let mut files = 0u64;
     let mut warnings = Vec::new();
     let mut overflowed = false;
+    let mut visited = 0u64;
 
-    for entry in WalkDir::new(path).follow_links(false).into_iter() {
+    for entry in WalkDir::new(path)
+        .follow_links(false)
+        .same_file_system(!cross_filesystems())
+        .into_iter()
+    {
         match entry {
             Ok(entry) => {
+                visited += 1;
                 if entry.file_type().is_file() {
+                    files += 1;
                     match entry.metadata() {
                         Ok(metadata) => {
                             let file_size = metadata.len();
@@ -231,5 +517,352 @@ pub(crate) fn calculate_dir_size(path: &Path) -> Result<(u64, Vec<String>), std:
         }
     }
 
-    Ok((total, warnings))
+    count_visited(visited);
+    debug(|| {
+        format!(
+            "sized {}: {} over {visited} entries",
+            path.display(),
+            format_bytes(total)
+        )
+    });
+    Ok(DirSize {
+        bytes: total,
+        files,
+        warnings,
+    })
+}
+
+/// Allocated and logical size of a file that may be sparse, like VM and
+/// container disk images, where `len()` is the size the image may grow to.
+/// Unix counts allocated 512-byte blocks. Windows has no std API for it, so
+/// both are the logical length there.
+pub(crate) fn sparse_size(metadata: &std::fs::Metadata) -> (u64, u64) {
+    let logical = metadata.len();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        // block rounding makes small dense files look a little bigger
+        (metadata.blocks().saturating_mul(512).min(logical), logical)
+    }
+    #[cfg(not(unix))]
+    {
+        (logical, logical)
+    }
+}
+
+/// Cleanup-hint suffix for a sparse file: its logical size when less is
+/// allocated, or a warning where the allocation can't be measured.
+pub(crate) fn sparse_note(allocated: u64, logical: u64) -> Option<String> {
+    if cfg!(not(unix)) {
+        Some("size shown is the logical size, allocated space can't be measured here and may be lower".to_string())
+    } else if allocated < logical {
+        Some(format!(
+            "sparse: {} logical, only the {} shown is allocated",
+            format_bytes(logical),
+            format_bytes(allocated)
+        ))
+    } else {
+        None
+    }
+}
+
+/// One immediate child of an inspected directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildSize {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size_bytes: u64,
+}
+
+/// Size of each immediate child of `path`, largest first (`heft inspect`).
+/// Directories are sized like any entry; symlinks count as nothing. A
+/// child that can't be read is left out with a warning rather than failing
+/// the whole listing.
+pub fn child_sizes(path: &Path) -> Result<(Vec<ChildSize>, Vec<String>), std::io::Error> {
+    let mut children = Vec::new();
+    let mut warnings = Vec::new();
+
+    for child in std::fs::read_dir(path)? {
+        let child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                warnings.push(format!(
+                    "failed to read an entry of {}: {e}",
+                    path.display()
+                ));
+                continue;
+            }
+        };
+        let file_type = match child.file_type() {
+            Ok(file_type) => file_type,
+            Err(e) => {
+                warnings.push(format!("failed to stat {}: {e}", child.path().display()));
+                continue;
+            }
+        };
+        let (is_dir, size_bytes) = if file_type.is_dir() {
+            match calculate_dir_size(&child.path()) {
+                Ok((size, child_warnings)) => {
+                    warnings.extend(child_warnings);
+                    (true, size)
+                }
+                Err(e) => {
+                    warnings.push(format!("failed to size {}: {e}", child.path().display()));
+                    (true, 0)
+                }
+            }
+        } else if file_type.is_file() {
+            match child.metadata() {
+                Ok(metadata) => (false, metadata.len()),
+                Err(e) => {
+                    warnings.push(format!("failed to stat {}: {e}", child.path().display()));
+                    (false, 0)
+                }
+            }
+        } else {
+            (false, 0)
+        };
+        children.push(ChildSize {
+            path: child.path(),
+            is_dir,
+            size_bytes,
+        });
+    }
+
+    children.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.path.cmp(&b.path)));
+    Ok((children, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use detector::Location;
+
+    fn entry(name: &str, category: BloatCategory) -> BloatEntry {
+        BloatEntry {
+            category,
+            name: name.to_string(),
+            location: Location::Aggregate(name.to_string()),
+            size_bytes: 1,
+            reclaimable_bytes: 1,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    struct PanickingDetector;
+
+    impl Detector for PanickingDetector {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        fn available(&self, _config: &Config) -> bool {
+            true
+        }
+
+        fn scan(&self, _config: &Config) -> DetectorResult {
+            panic!("weird filename {}", "\u{fffd}");
+        }
+    }
+
+    #[test]
+    fn child_sizes_sorted_largest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("huge-dep/dist")).unwrap();
+        std::fs::write(dir.path().join("huge-dep/dist/bundle.js"), [0u8; 500]).unwrap();
+        std::fs::write(dir.path().join("huge-dep/index.js"), [0u8; 100]).unwrap();
+        std::fs::create_dir(dir.path().join("small-dep")).unwrap();
+        std::fs::write(dir.path().join("small-dep/index.js"), [0u8; 50]).unwrap();
+        std::fs::write(dir.path().join(".package-lock.json"), [0u8; 200]).unwrap();
+
+        let (children, warnings) = child_sizes(dir.path()).unwrap();
+        assert!(warnings.is_empty());
+        let summary: Vec<_> = children
+            .iter()
+            .map(|c| {
+                (
+                    c.path.file_name().unwrap().to_str().unwrap(),
+                    c.is_dir,
+                    c.size_bytes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("huge-dep", true, 600),
+                (".package-lock.json", false, 200),
+                ("small-dep", true, 50)
+            ]
+        );
+
+        assert!(child_sizes(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn device_id_tells_mount_points_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        assert_eq!(device_id(dir.path()), device_id(&dir.path().join("sub")));
+        // procfs is always its own mount
+        assert_ne!(device_id(Path::new("/")), device_id(Path::new("/proc")));
+        assert_eq!(device_id(&dir.path().join("missing")), None);
+    }
+
+    #[test]
+    fn panicking_detector_becomes_diagnostic() {
+        let result = scan_guarded(&PanickingDetector, &Config::default());
+        assert!(result.entries.is_empty());
+        assert_eq!(
+            result.diagnostics,
+            vec!["detector broken panicked: weird filename \u{fffd}".to_string()]
+        );
+    }
+
+    #[test]
+    fn coverage_counts_entries_and_flags_errors() {
+        let mut result = DetectorResult::empty();
+        result
+            .entries
+            .push(entry("npm cache", BloatCategory::PackageCache));
+        let coverage = detector_coverage("caches", &result);
+        assert_eq!(coverage.status, CoverageStatus::Ran);
+        assert_eq!(coverage.entries, 1);
+
+        // only the flag counts, not what a diagnostic happens to say
+        let result = DetectorResult::with_diagnostic("brew --cache timed out".to_string());
+        assert_eq!(
+            detector_coverage("caches", &result).status,
+            CoverageStatus::Ran
+        );
+
+        let result = scan_guarded(&PanickingDetector, &Config::default());
+        assert_eq!(
+            detector_coverage("broken", &result).status,
+            CoverageStatus::Errored
+        );
+    }
+
+    #[test]
+    fn skipped_detectors_recorded_in_coverage() {
+        // nothing actually runs: everything else is disabled
+        let config = Config {
+            roots: Vec::new(),
+            platform: crate::platform::Platform::Linux,
+            output_format: OutputFormat::Json,
+            disabled_detectors: crate::config::DETECTOR_NAMES
+                .iter()
+                .filter(|name| !["xcode", "downloads"].contains(name))
+                .map(|name| name.to_string())
+                .collect(),
+            ..Config::default()
+        };
+        let result = run(&config);
+
+        let status = |name: &str| {
+            result
+                .coverage
+                .iter()
+                .find(|c| c.name == name)
+                .map(|c| c.status)
+        };
+        assert_eq!(result.coverage.len(), crate::config::DETECTOR_NAMES.len());
+        assert_eq!(status("docker"), Some(CoverageStatus::Disabled));
+        assert_eq!(status("xcode"), Some(CoverageStatus::Unavailable));
+        assert_eq!(status("downloads"), Some(CoverageStatus::OptIn));
+    }
+
+    #[test]
+    fn registry_matches_detector_names() {
+        let names: Vec<_> = registry().iter().map(|d| d.name()).collect();
+        assert_eq!(names, crate::config::DETECTOR_NAMES);
+    }
+
+    #[test]
+    fn every_detector_can_be_disabled() {
+        for detector in registry() {
+            let name = detector.name();
+            let config = Config {
+                disabled_detectors: [name.to_string()].into(),
+                ..Config::default()
+            };
+            assert_eq!(
+                skip_status(detector.as_ref(), &config),
+                Some(CoverageStatus::Disabled),
+                "{name} ran while disabled"
+            );
+            // disabling one detector leaves the others alone
+            for other in registry().iter().filter(|d| d.name() != name) {
+                assert_ne!(
+                    skip_status(other.as_ref(), &config),
+                    Some(CoverageStatus::Disabled),
+                    "{} disabled along with {name}",
+                    other.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn enabled_detector_skipped_only_when_unavailable() {
+        let config = Config {
+            platform: crate::platform::Platform::Linux,
+            ..Config::default()
+        };
+        assert_eq!(
+            skip_status(&xcode::XcodeDetector, &config),
+            Some(CoverageStatus::Unavailable)
+        );
+        assert_eq!(skip_status(&caches::CacheDetector, &config), None);
+        assert_eq!(skip_status(&docker::DockerDetector, &config), None);
+    }
+
+    #[test]
+    fn category_overrides_prefer_exact_name_over_glob() {
+        let mut entries = vec![
+            entry("xcode DerivedData", BloatCategory::IdeData),
+            entry("xcode archives", BloatCategory::IdeData),
+            entry("npm cache", BloatCategory::PackageCache),
+        ];
+        let overrides = vec![
+            ("xcode*".to_string(), BloatCategory::Other),
+            (
+                "xcode DerivedData".to_string(),
+                BloatCategory::ProjectArtifacts,
+            ),
+        ];
+        apply_category_overrides(&mut entries, &overrides);
+
+        assert_eq!(entries[0].category, BloatCategory::ProjectArtifacts);
+        assert_eq!(entries[1].category, BloatCategory::Other);
+        // unmatched names keep their detector category
+        assert_eq!(entries[2].category, BloatCategory::PackageCache);
+    }
+    #[cfg(unix)]
+    #[test]
+    fn sparse_files_report_allocated_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("disk.raw");
+        let file = std::fs::File::create(&image).unwrap();
+        file.set_len(64 * 1024 * 1024).unwrap();
+
+        let (allocated, logical) = sparse_size(&std::fs::metadata(&image).unwrap());
+        assert_eq!(logical, 64 * 1024 * 1024);
+        assert!(allocated < logical);
+        assert!(sparse_note(allocated, logical)
+            .unwrap()
+            .contains("64.0 MB logical"));
+
+        std::fs::write(dir.path().join("dense"), [1u8; 10]).unwrap();
+        let (allocated, logical) =
+            sparse_size(&std::fs::metadata(dir.path().join("dense")).unwrap());
+        assert_eq!((allocated, logical), (10, 10));
+        assert_eq!(sparse_note(allocated, logical), None);
+    }
 }
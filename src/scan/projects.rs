@@ -1,14 +1,17 @@
 //! Detects build artifacts in project directories.
 
 use std::collections::HashSet;
-use std::fs;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::time::SystemTime;
 
 use walkdir::WalkDir;
 
 use super::detector::{BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use super::fs::{FileSystem, LocalFs};
 use crate::config::Config;
+use crate::util;
 
 pub struct ProjectDetector;
 
@@ -22,213 +25,629 @@ impl Detector for ProjectDetector {
     }
 
     fn scan(&self, config: &Config) -> DetectorResult {
-        let mut entries = Vec::new();
         let mut diagnostics = Vec::new();
-        let mut seen_projects: HashSet<PathBuf> = HashSet::new();
-
-        for root in &config.roots {
-            if !root.exists() {
-                diagnostics.push(format!(
-                    "skipping {}: directory does not exist",
-                    root.display()
-                ));
-                continue;
-            }
+        let roots: Vec<&PathBuf> = config
+            .roots
+            .iter()
+            .filter(|root| {
+                let exists = root.exists();
+                if !exists {
+                    diagnostics.push(format!(
+                        "skipping {}: directory does not exist",
+                        root.display()
+                    ));
+                }
+                exists
+            })
+            .collect();
+
+        // roots on separate disks walk in parallel; each walk dedupes its own
+        // projects and merge_roots dedupes across them
+        let per_root: Vec<DetectorResult> = if roots.len() > 1 {
+            std::thread::scope(|s| {
+                let handles: Vec<_> = roots
+                    .iter()
+                    .map(|root| s.spawn(move || scan_root(root, config)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| {
+                        h.join().unwrap_or_else(|_| {
+                            DetectorResult::errored("projects: a root walk panicked".into())
+                        })
+                    })
+                    .collect()
+            })
+        } else {
+            roots.iter().map(|root| scan_root(root, config)).collect()
+        };
 
-            scan_directory(root, &mut entries, &mut seen_projects, &mut diagnostics);
-        }
+        let mut result = merge_roots(per_root, config.explain);
+        diagnostics.append(&mut result.diagnostics);
+        result.diagnostics = diagnostics;
+        result
+    }
+}
 
-        DetectorResult {
-            entries,
-            diagnostics,
+fn scan_root(root: &Path, config: &Config) -> DetectorResult {
+    let mut result = scan_tree(&LocalFs, root, config);
+    // source timestamps are only collected locally
+    for entry in &mut result.entries {
+        if let Location::FilesystemPath(path) = &entry.location {
+            entry.last_modified = get_source_last_modified(path.parent().unwrap_or(path));
         }
     }
+    result
 }
 
-fn scan_directory(
-    root: &Path,
-    entries: &mut Vec<BloatEntry>,
-    seen_projects: &mut HashSet<PathBuf>,
-    diagnostics: &mut Vec<String>,
-) {
-    // once we find an artifact like node_modules, we dont want to look inside it
-    // for more artifacts. this set tracks what weve already claimed.
-    let mut seen_artifacts: HashSet<PathBuf> = HashSet::new();
-
-    let walker = WalkDir::new(root)
-        .follow_links(false)
-        .sort_by_file_name()
-        .into_iter()
-        .filter_entry(|e| !is_hidden(e.file_name()));
-
-    for entry in walker.filter_map(|e| e.ok()) {
-        if !entry.file_type().is_dir() {
-            continue;
+/// Combine per-root results in root order. An entry whose project sits
+/// inside a project already reported from an earlier root is dropped, the
+/// same outcome as walking the roots one after another.
+fn merge_roots(per_root: Vec<DetectorResult>, explain: bool) -> DetectorResult {
+    let mut merged = DetectorResult::empty();
+    let mut seen_projects: HashSet<PathBuf> = HashSet::new();
+
+    for result in per_root {
+        for entry in result.entries {
+            let project_root = match &entry.location {
+                Location::FilesystemPath(path) => path.parent().unwrap_or(path).to_path_buf(),
+                _ => {
+                    merged.entries.push(entry);
+                    continue;
+                }
+            };
+            if project_root.ancestors().any(|a| seen_projects.contains(a)) {
+                if explain {
+                    if let Location::FilesystemPath(path) = &entry.location {
+                        merged.diagnostics.push(format!(
+                            "explain: {}: skipped, an enclosing project was already reported",
+                            path.display()
+                        ));
+                    }
+                }
+                continue;
+            }
+            seen_projects.insert(project_root);
+            merged.entries.push(entry);
         }
+        merged.diagnostics.extend(result.diagnostics);
+        merged.errored |= result.errored;
+    }
 
-        let path = entry.path();
+    merged
+}
 
-        // already inside something we detected, skip
-        // walk ancestors instead of iterating all seen — O(depth) not O(n)
-        if path.ancestors().any(|a| seen_artifacts.contains(a)) {
-            continue;
+/// The project artifact walk, over the local disk or a remote host. Once
+/// an artifact like node_modules is found it is sized with a separate walk
+/// and never descended into, so nothing needs an "is this inside something
+/// claimed" lookup. Other filesystems mounted below the root (NFS/SMB
+/// shares, bind mounts) are left out unless --cross-filesystems, where
+/// `fs` can tell devices apart. Entries have no last_modified.
+pub(crate) fn scan_tree(fs: &dyn FileSystem, root: &Path, config: &Config) -> DetectorResult {
+    let explain = config.explain;
+    let filter = &config.path_filter;
+    let mut entries = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut seen_projects: HashSet<PathBuf> = HashSet::new();
+
+    let root_device = if config.cross_filesystems {
+        None
+    } else {
+        fs.device_id(root)
+    };
+    let mut mounts = Vec::new();
+    // hidden, excluded and mounted directories are pruned, the root included
+    let mut walked = |path: &Path| {
+        if is_hidden(path.file_name().unwrap_or_default(), &config.artifact_rules)
+            || filter.is_excluded(path)
+            || !filter.may_contain_included(path)
+        {
+            return false;
+        }
+        if let Some(root_device) = root_device {
+            if fs.device_id(path).is_some_and(|d| d != root_device) {
+                mounts.push(path.to_path_buf());
+                return false;
+            }
         }
+        true
+    };
 
-        let dir_name = match path.file_name().and_then(|n| n.to_str()) {
-            Some(name) => name,
-            None => continue,
+    // pre-order, children in name order
+    let mut pending = Vec::new();
+    if walked(root) {
+        pending.push(root.to_path_buf());
+    }
+    let mut visited = pending.len() as u64;
+    while let Some(path) = pending.pop() {
+        let path = path.as_path();
+        super::debug(|| format!("visiting {}", path.display()));
+
+        // outside every --include pattern, only walked through to reach one
+        let artifact = match path.file_name().and_then(|n| n.to_str()) {
+            Some(dir_name) if filter.is_included(path) => {
+                match detect_artifact(fs, path, dir_name, &config.artifact_rules) {
+                    Verdict::NotCandidate => None,
+                    Verdict::Rejected(reason) => {
+                        if explain {
+                            diagnostics
+                                .push(format!("explain: {}: rejected, {reason}", path.display()));
+                        }
+                        None
+                    }
+                    Verdict::Accepted(artifact, reason) => {
+                        if explain {
+                            diagnostics
+                                .push(format!("explain: {}: accepted, {reason}", path.display()));
+                        }
+                        Some(artifact)
+                    }
+                }
+            }
+            _ => None,
         };
 
-        if let Some(artifact) = detect_artifact(path, dir_name) {
+        if let Some(artifact) = artifact {
             let project_root = path.parent().unwrap_or(path);
 
             // monorepos have node_modules at root and also in each package.
             // if weve seen the root already, skip the nested ones.
             // walk ancestors instead of iterating all seen — O(depth) not O(n)
             if project_root.ancestors().any(|a| seen_projects.contains(a)) {
-                seen_artifacts.insert(path.to_path_buf());
+                if explain {
+                    diagnostics.push(format!(
+                        "explain: {}: skipped, an enclosing project was already reported",
+                        path.display()
+                    ));
+                }
                 continue;
             }
 
-            match super::calculate_dir_size(path) {
-                Ok((size, warnings)) => {
-                    let project_name = determine_project_name(project_root, &artifact);
-                    let last_modified = get_source_last_modified(project_root);
-
-                    entries.push(BloatEntry {
-                        category: BloatCategory::ProjectArtifacts,
-                        name: project_name,
-                        location: Location::FilesystemPath(path.to_path_buf()),
-                        size_bytes: size,
-                        reclaimable_bytes: size,
-                        last_modified,
-                        cleanup_hint: Some(artifact.cleanup_hint.to_string()),
-                    });
-
+            match fs.measure(path) {
+                Ok(size) => {
+                    // too small to list, but still the project's artifact: its
+                    // nested copies stay unreported
+                    if super::big_enough(size.bytes, config.min_entry_bytes) {
+                        entries.push(BloatEntry {
+                            category: artifact.category,
+                            name: determine_project_name(fs, project_root, &artifact),
+                            location: Location::FilesystemPath(path.to_path_buf()),
+                            size_bytes: size.bytes,
+                            reclaimable_bytes: size.bytes,
+                            last_modified: None,
+                            cleanup_hint: Some(artifact.cleanup_hint.clone()),
+                            manual: false,
+                            tags: artifact.tags.clone(),
+                            detector: None,
+                            file_count: Some(size.files),
+                        });
+                    }
                     seen_projects.insert(project_root.to_path_buf());
-                    seen_artifacts.insert(path.to_path_buf());
-
-                    for warning in warnings {
+                    for warning in size.warnings {
                         diagnostics.push(format!("{warning} (size may be underestimated)"));
                     }
+                    continue;
                 }
-                Err(e) => {
-                    diagnostics.push(format!(
-                        "failed to calculate size of {}: {}",
-                        path.display(),
-                        e
-                    ));
-                }
+                // not sized, so it's walked like any other directory
+                Err(e) => diagnostics.push(format!(
+                    "failed to calculate size of {}: {e}",
+                    path.display()
+                )),
+            }
+        }
+
+        let mut children = match fs.read_dir_kinds(path) {
+            Ok(children) => children,
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                super::debug(|| format!("permission denied: {}", path.display()));
+                continue;
+            }
+            Err(e) => {
+                diagnostics.push(format!("failed to traverse {}: {e}", path.display()));
+                continue;
             }
+        };
+        visited += children.len() as u64;
+        children.retain(|c| c.kind == super::fs::FileKind::Dir && walked(&c.path));
+        // pushed in reverse name order, so they pop in name order
+        children.sort_by(|a, b| b.path.cmp(&a.path));
+        pending.extend(children.into_iter().map(|c| c.path));
+    }
+
+    for mount in mounts {
+        diagnostics.push(format!(
+            "skipping {}: a different filesystem (use --cross-filesystems to include it)",
+            mount.display()
+        ));
+    }
+    super::count_visited(visited);
+    DetectorResult {
+        entries,
+        diagnostics,
+        errored: false,
+    }
+}
+
+/// What a shallow pass over a root found (`heft estimate`).
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Estimate {
+    /// Directories the full scan would size
+    pub candidates: usize,
+    /// Files and directories seen, inside candidates too
+    pub entries: u64,
+    /// The walk stopped at max_depth with directories left unexplored, so
+    /// both counts are lower bounds
+    pub depth_limited: bool,
+}
+
+impl Estimate {
+    pub fn add(&mut self, other: Estimate) {
+        self.candidates += other.candidates;
+        self.entries += other.entries;
+        self.depth_limited |= other.depth_limited;
+    }
+}
+
+/// Walk `root` no deeper than `max_depth`, recognizing artifact directories
+/// the same way the scan does but sizing nothing.
+pub fn estimate(root: &Path, config: &Config, max_depth: usize) -> Estimate {
+    let filter = &config.path_filter;
+    let walker = WalkDir::new(root)
+        .follow_links(false)
+        .same_file_system(!config.cross_filesystems)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            !is_hidden(e.file_name(), &config.artifact_rules) && !filter.is_excluded(e.path())
+        });
+
+    let mut estimate = Estimate::default();
+    // the walk is depth-first, so a candidate's contents follow it directly
+    let mut candidate: Option<PathBuf> = None;
+    for entry in walker.flatten() {
+        estimate.entries += 1;
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if entry.depth() == max_depth {
+            estimate.depth_limited = true;
+        }
+        let path = entry.path();
+        if candidate.as_ref().is_some_and(|c| path.starts_with(c)) {
+            continue;
+        }
+        candidate = None;
+        if !filter.is_included(path) {
+            continue;
+        }
+        let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if let Verdict::Accepted(..) =
+            detect_artifact(&LocalFs, path, dir_name, &config.artifact_rules)
+        {
+            estimate.candidates += 1;
+            candidate = Some(path.to_path_buf());
         }
     }
+    estimate
 }
 
 struct ArtifactType {
+    cleanup_hint: String,
+    manifest_file: Option<&'static str>,
+    category: BloatCategory,
+    /// The project it belonged to is gone (node_modules without a package.json)
+    orphaned: bool,
+    /// Ecosystem tags for built-in artifacts; config rules carry none
+    tags: Vec<String>,
+}
+
+/// A user-defined artifact from `[[project_artifact]]` in config.toml,
+/// consulted when no built-in heuristic accepts a directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactRule {
+    pub dir_name: String,
+    /// File that must sit next to the directory; may be a glob like `*.tf`
+    pub requires_sibling: Option<String>,
+    pub category: BloatCategory,
+    pub cleanup_hint: String,
+}
+
+/// Outcome of checking a directory against the artifact heuristics.
+/// The reason strings feed `--explain`.
+enum Verdict {
+    /// name isn't one we ever treat as an artifact
+    NotCandidate,
+    Rejected(String),
+    Accepted(ArtifactType, String),
+}
+
+fn accept(
     cleanup_hint: &'static str,
     manifest_file: Option<&'static str>,
+    reason: &str,
+) -> Verdict {
+    Verdict::Accepted(
+        ArtifactType {
+            cleanup_hint: cleanup_hint.to_string(),
+            manifest_file,
+            category: BloatCategory::ProjectArtifacts,
+            orphaned: false,
+            tags: Vec::new(),
+        },
+        reason.to_string(),
+    )
+}
+
+fn reject(reason: &str) -> Verdict {
+    Verdict::Rejected(reason.to_string())
 }
 
 // checks if a directory is a known build artifact. returns info about how to
-// clean it up and where to find the project name.
-fn detect_artifact(path: &Path, dir_name: &str) -> Option<ArtifactType> {
-    let parent = path.parent()?;
+// clean it up and where to find the project name, or why it was turned down.
+// built-in heuristics win; config rules only get a say when they decline.
+fn detect_artifact(
+    fs: &dyn FileSystem,
+    path: &Path,
+    dir_name: &str,
+    rules: &[ArtifactRule],
+) -> Verdict {
+    let builtin = match builtin_artifact(fs, path, dir_name) {
+        Verdict::Accepted(mut artifact, reason) => {
+            artifact.tags = builtin_tags(dir_name, &artifact);
+            return Verdict::Accepted(artifact, reason);
+        }
+        other => other,
+    };
+    let Some(parent) = path.parent() else {
+        return builtin;
+    };
+
+    let mut rule_rejection = None;
+    for rule in rules.iter().filter(|r| r.dir_name == dir_name) {
+        match &rule.requires_sibling {
+            Some(sibling) if !has_sibling(fs, parent, sibling) => {
+                rule_rejection = Some(format!("no {sibling} in parent (config rule)"));
+            }
+            Some(sibling) => {
+                return config_accept(rule, format!("found {sibling} in parent (config rule)"))
+            }
+            None => return config_accept(rule, "matched config rule".to_string()),
+        }
+    }
+
+    match (builtin, rule_rejection) {
+        (Verdict::NotCandidate, Some(reason)) => Verdict::Rejected(reason),
+        (builtin, _) => builtin,
+    }
+}
+
+/// The ecosystem a built-in artifact belongs to, plus "orphaned" for
+/// node_modules whose project is gone.
+fn builtin_tags(dir_name: &str, artifact: &ArtifactType) -> Vec<String> {
+    let ecosystem = match (dir_name, artifact.manifest_file) {
+        ("vendor", Some("composer.json")) => "php",
+        ("vendor", _) => "go",
+        ("node_modules", _) => "nodejs",
+        ("target", _) => "rust",
+        (".gradle" | "build", _) => "java",
+        ("DerivedData", _) => "xcode",
+        ("bin" | "obj", _) => "dotnet",
+        _ => "python",
+    };
+    let mut tags = vec![ecosystem.to_string()];
+    if artifact.orphaned {
+        tags.push("orphaned".to_string());
+    }
+    tags
+}
+
+fn config_accept(rule: &ArtifactRule, reason: String) -> Verdict {
+    Verdict::Accepted(
+        ArtifactType {
+            cleanup_hint: rule.cleanup_hint.clone(),
+            manifest_file: None,
+            category: rule.category,
+            orphaned: false,
+            tags: Vec::new(),
+        },
+        reason,
+    )
+}
+
+/// Whether `dir` holds a file named `pattern`, which may be a glob.
+fn has_sibling(fs: &dyn FileSystem, dir: &Path, pattern: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return fs.exists(&dir.join(pattern));
+    }
+    fs.read_dir(dir)
+        .map(|entries| {
+            entries
+                .iter()
+                .any(|e| util::glob_match(pattern, e.file_name().unwrap_or("")))
+        })
+        .unwrap_or(false)
+}
+
+fn builtin_artifact(fs: &dyn FileSystem, path: &Path, dir_name: &str) -> Verdict {
+    let Some(parent) = path.parent() else {
+        return Verdict::NotCandidate;
+    };
 
     match dir_name {
-        "node_modules" => Some(ArtifactType {
-            cleanup_hint: "safe to delete, reinstall with npm install",
-            manifest_file: Some("package.json"),
-        }),
+        // globally installed CLIs, not a project's dependencies
+        "node_modules" if is_npm_global(fs, path) => {
+            reject("npm global packages (lib/node_modules or under the npm prefix)")
+        }
+        // with no package.json next to it the project was deleted and only
+        // its dependencies are left
+        "node_modules" if !fs.exists(&parent.join("package.json")) => Verdict::Accepted(
+            ArtifactType {
+                cleanup_hint: "orphaned: no package.json next to it, nothing can use these dependencies. safe to delete".to_string(),
+                manifest_file: None,
+                category: BloatCategory::ProjectArtifacts,
+                orphaned: true,
+                tags: Vec::new(),
+            },
+            "node_modules without package.json in parent (orphaned)".to_string(),
+        ),
+        "node_modules" => accept(
+            "safe to delete, reinstall with npm install",
+            Some("package.json"),
+            "node_modules is always an install artifact",
+        ),
 
         // lots of projects have a target dir, only match if theres a Cargo.toml
-        "target" if parent.join("Cargo.toml").exists() => Some(ArtifactType {
-            cleanup_hint: "safe to delete, rebuild with cargo build",
-            manifest_file: Some("Cargo.toml"),
-        }),
+        "target" => {
+            if fs.exists(&parent.join("Cargo.toml")) {
+                accept(
+                    "safe to delete, rebuild with cargo build",
+                    Some("Cargo.toml"),
+                    "found Cargo.toml in parent",
+                )
+            } else {
+                reject("no Cargo.toml in parent")
+            }
+        }
 
         // python caches show up everywhere including inside installed packages.
         // only count ones that are in actual projects, not in site-packages.
-        "__pycache__" | ".pytest_cache" | ".mypy_cache" | ".tox"
-            if !is_inside_installed_packages(path) =>
-        {
-            Some(ArtifactType {
-                cleanup_hint: "safe to delete, regenerated automatically",
-                manifest_file: None,
-            })
+        "__pycache__" | ".pytest_cache" | ".mypy_cache" | ".tox" => {
+            if is_inside_installed_packages(path) {
+                reject("inside installed packages (site-packages, node_modules or a venv)")
+            } else {
+                accept(
+                    "safe to delete, regenerated automatically",
+                    None,
+                    "python cache outside installed packages",
+                )
+            }
         }
 
-        ".venv" | "venv" if has_python_project(parent) => Some(ArtifactType {
-            cleanup_hint: "virtual environment, recreate with python -m venv",
-            manifest_file: None,
-        }),
+        // PEP 582 local packages (pdm and friends)
+        "__pypackages__" => {
+            if fs.exists(&parent.join("pyproject.toml")) {
+                accept(
+                    "safe to delete, reinstall with pdm install",
+                    None,
+                    "found pyproject.toml in parent",
+                )
+            } else {
+                reject("no pyproject.toml in parent")
+            }
+        }
 
-        "vendor" if parent.join("go.mod").exists() => Some(ArtifactType {
-            cleanup_hint: "safe to delete, restore with go mod vendor",
-            manifest_file: Some("go.mod"),
-        }),
+        ".venv" | "venv" => {
+            if has_python_project(fs, parent) {
+                accept(
+                    "virtual environment, recreate with python -m venv",
+                    None,
+                    "found python project file in parent",
+                )
+            } else {
+                reject("no requirements.txt, setup.py, pyproject.toml or setup.cfg in parent")
+            }
+        }
 
-        "vendor" if parent.join("composer.json").exists() => Some(ArtifactType {
-            cleanup_hint: "safe to delete, restore with composer install",
-            manifest_file: Some("composer.json"),
-        }),
+        "vendor" => {
+            if fs.exists(&parent.join("go.mod")) {
+                accept(
+                    "safe to delete, restore with go mod vendor",
+                    Some("go.mod"),
+                    "found go.mod in parent",
+                )
+            } else if fs.exists(&parent.join("composer.json")) {
+                accept(
+                    "safe to delete, restore with composer install",
+                    Some("composer.json"),
+                    "found composer.json in parent",
+                )
+            } else {
+                reject("no go.mod or composer.json in parent")
+            }
+        }
 
-        ".gradle"
-            if parent.join("build.gradle").exists() || parent.join("build.gradle.kts").exists() =>
-        {
-            Some(ArtifactType {
-                cleanup_hint: "safe to delete, rebuild with gradle build",
-                manifest_file: None,
-            })
+        ".gradle" => {
+            if has_gradle_build_file(fs, parent) {
+                accept(
+                    "safe to delete, rebuild with gradle build",
+                    None,
+                    "found build.gradle in parent",
+                )
+            } else {
+                reject("no build.gradle or build.gradle.kts in parent")
+            }
         }
 
         // only flag "build" dirs as gradle if they actually contain gradle artifacts
         // prevents false positives on legitimate build folders used for other purposes
-        "build"
-            if (parent.join("build.gradle").exists()
-                || parent.join("build.gradle.kts").exists())
-                && is_gradle_build_dir(path) =>
-        {
-            Some(ArtifactType {
-                cleanup_hint: "safe to delete, rebuild with gradle build",
-                manifest_file: None,
-            })
+        "build" => {
+            if !has_gradle_build_file(fs, parent) {
+                reject("no build.gradle or build.gradle.kts in parent")
+            } else if let Some(marker) = gradle_build_marker(fs, path) {
+                accept(
+                    "safe to delete, rebuild with gradle build",
+                    None,
+                    &format!("found {marker}/"),
+                )
+            } else {
+                reject("gradle project but no classes/, libs/, tmp/, generated/ or intermediates/ inside")
+            }
         }
 
         // only flag DerivedData if it's actually from xcode
         // check for xcode markers or being in the xcode cache location
-        "DerivedData" if is_xcode_derived_data(path, parent) => Some(ArtifactType {
-            cleanup_hint: "xcode build artifacts, safe to delete",
-            manifest_file: None,
-        }),
+        "DerivedData" => {
+            if is_xcode_derived_data(fs, path, parent) {
+                accept(
+                    "xcode build artifacts, safe to delete",
+                    None,
+                    "xcode project, cache location or build markers found",
+                )
+            } else {
+                reject("no xcode project, cache location or build markers")
+            }
+        }
 
         // .NET build output — only match if a project file is present
-        "bin" | "obj" if has_dotnet_project(parent) => Some(ArtifactType {
-            cleanup_hint: "safe to delete, rebuild with dotnet build",
-            manifest_file: None,
-        }),
+        "bin" | "obj" => {
+            if has_dotnet_project(fs, parent) {
+                accept(
+                    "safe to delete, rebuild with dotnet build",
+                    None,
+                    "found .NET project file in parent",
+                )
+            } else {
+                reject("no .csproj/.fsproj/.vbproj/.sln or NuGet marker in parent")
+            }
+        }
 
-        _ => None,
+        _ => Verdict::NotCandidate,
     }
 }
 
-fn has_dotnet_project(dir: &Path) -> bool {
+fn has_gradle_build_file(fs: &dyn FileSystem, dir: &Path) -> bool {
+    fs.exists(&dir.join("build.gradle")) || fs.exists(&dir.join("build.gradle.kts"))
+}
+
+fn has_dotnet_project(fs: &dyn FileSystem, dir: &Path) -> bool {
     // fast exists() checks — each is a single stat() call, no directory listing
     // global.json is intentionally excluded: it's also used by Volta, npm workspaces,
     // and other JS tooling, so it's not a reliable .NET-only marker.
-    if dir.join("Directory.Build.props").exists()
-        || dir.join("packages.config").exists()
-        || dir.join("NuGet.Config").exists()
+    if fs.exists(&dir.join("Directory.Build.props"))
+        || fs.exists(&dir.join("packages.config"))
+        || fs.exists(&dir.join("NuGet.Config"))
     {
         return true;
     }
 
     // fall back to read_dir to find variable-named project files (*.csproj etc.)
-    // use file_name() directly to avoid allocating a full PathBuf per entry
-    std::fs::read_dir(dir)
+    fs.read_dir(dir)
         .map(|entries| {
-            entries.flatten().any(|e| {
-                let name = e.file_name();
-                let s = name.to_str().unwrap_or("");
+            entries.iter().any(|e| {
+                let s = e.file_name().unwrap_or("");
                 s.ends_with(".csproj")
                     || s.ends_with(".fsproj")
                     || s.ends_with(".vbproj")
@@ -238,11 +657,73 @@ fn has_dotnet_project(dir: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn has_python_project(dir: &Path) -> bool {
-    dir.join("requirements.txt").exists()
-        || dir.join("setup.py").exists()
-        || dir.join("pyproject.toml").exists()
-        || dir.join("setup.cfg").exists()
+fn has_python_project(fs: &dyn FileSystem, dir: &Path) -> bool {
+    [
+        "requirements.txt",
+        "setup.py",
+        "pyproject.toml",
+        "setup.cfg",
+    ]
+    .iter()
+    .any(|file| fs.exists(&dir.join(file)))
+}
+
+/// Whether `path` is npm's global package directory or inside it: the
+/// global node_modules under a configured prefix, or any `lib/node_modules`
+/// without a package.json in `lib`, which is how node's own prefix (and
+/// every nvm version) lays it out.
+fn is_npm_global(fs: &dyn FileSystem, path: &Path) -> bool {
+    if npm_global_modules().is_some_and(|global| path.starts_with(global)) {
+        return true;
+    }
+    path.ancestors().any(|dir| {
+        dir.file_name() == Some(OsStr::new("node_modules"))
+            && dir.parent().is_some_and(|lib| {
+                lib.file_name() == Some(OsStr::new("lib")) && !fs.exists(&lib.join("package.json"))
+            })
+    })
+}
+
+/// The global node_modules of a prefix set with $npm_config_prefix or
+/// `prefix=` in ~/.npmrc (what `npm prefix -g` reports), or the Windows
+/// default under %APPDATA%. Looked up once per process.
+fn npm_global_modules() -> Option<&'static Path> {
+    static GLOBAL: OnceLock<Option<PathBuf>> = OnceLock::new();
+    GLOBAL
+        .get_or_init(|| {
+            let home = crate::platform::home_dir();
+            let configured = std::env::var_os("npm_config_prefix")
+                .or_else(|| std::env::var_os("NPM_CONFIG_PREFIX"))
+                .map(PathBuf::from)
+                .or_else(|| {
+                    let home = home.as_deref()?;
+                    let npmrc = std::fs::read_to_string(home.join(".npmrc")).ok()?;
+                    npmrc_prefix(&npmrc, home)
+                });
+            if cfg!(windows) {
+                configured
+                    .or_else(|| std::env::var_os("APPDATA").map(|d| PathBuf::from(d).join("npm")))
+                    .map(|prefix| prefix.join("node_modules"))
+            } else {
+                configured.map(|prefix| prefix.join("lib").join("node_modules"))
+            }
+        })
+        .as_deref()
+}
+
+/// The `prefix` setting of an .npmrc, with `~` and `${HOME}` expanded.
+fn npmrc_prefix(npmrc: &str, home: &Path) -> Option<PathBuf> {
+    let value = npmrc.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "prefix").then(|| value.trim())
+    })?;
+    let value = value.trim_matches('"');
+    let home_str = home.to_string_lossy();
+    let expanded = match value.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(value.replace("${HOME}", &home_str)),
+    };
+    (!expanded.as_os_str().is_empty()).then_some(expanded)
 }
 
 fn is_inside_installed_packages(path: &Path) -> bool {
@@ -261,18 +742,16 @@ fn is_inside_installed_packages(path: &Path) -> bool {
 }
 
 // verify a "build" directory actually contains gradle artifacts, not just any folder named build.
-// checks for typical gradle output directories to avoid false positives.
-fn is_gradle_build_dir(path: &Path) -> bool {
-    path.join("classes").exists()
-        || path.join("libs").exists()
-        || path.join("tmp").exists()
-        || path.join("generated").exists()
-        || path.join("intermediates").exists()
+// returns the first typical gradle output directory found, to avoid false positives.
+fn gradle_build_marker(fs: &dyn FileSystem, path: &Path) -> Option<&'static str> {
+    ["classes", "libs", "tmp", "generated", "intermediates"]
+        .into_iter()
+        .find(|marker| fs.exists(&path.join(marker)))
 }
 
 // verify a "DerivedData" directory is actually from xcode, not just any folder with that name.
 // checks for xcode-specific markers or being in the standard xcode cache location.
-fn is_xcode_derived_data(path: &Path, parent: &Path) -> bool {
+fn is_xcode_derived_data(fs: &dyn FileSystem, path: &Path, parent: &Path) -> bool {
     // check if in standard xcode cache location (~/Library/Developer/Xcode/DerivedData)
     // fixed: properly check if ancestor named "Xcode" has parent named "Developer"
     let in_xcode_cache = path.ancestors().any(|ancestor| {
@@ -297,9 +776,9 @@ fn is_xcode_derived_data(path: &Path, parent: &Path) -> bool {
         .take_while(|ancestor| home.as_deref().map(|h| *ancestor != h).unwrap_or(true))
         .take(10)
         .any(|ancestor| {
-            if let Ok(entries) = std::fs::read_dir(ancestor) {
-                entries.flatten().any(|e| {
-                    e.path()
+            if let Ok(entries) = fs.read_dir(ancestor) {
+                entries.iter().any(|e| {
+                    e.path
                         .extension()
                         .and_then(|ext| ext.to_str())
                         .map(|s| s == "xcodeproj" || s == "xcworkspace")
@@ -311,17 +790,16 @@ fn is_xcode_derived_data(path: &Path, parent: &Path) -> bool {
         });
 
     // check for xcode-specific subdirectories in DerivedData
-    let has_xcode_markers = path.join("Build").exists()
-        || path.join("Logs").exists()
-        || path.join("ModuleCache").exists()
-        || path.join("info.plist").exists();
+    let has_xcode_markers = ["Build", "Logs", "ModuleCache", "info.plist"]
+        .iter()
+        .any(|marker| fs.exists(&path.join(marker)));
 
     in_xcode_cache || has_xcode_project || has_xcode_markers
 }
 
 // we skip hidden directories during traversal, but some artifacts we care about
 // start with a dot. this returns false for those so we still find them.
-fn is_hidden(name: &std::ffi::OsStr) -> bool {
+fn is_hidden(name: &std::ffi::OsStr, rules: &[ArtifactRule]) -> bool {
     name.to_str()
         .map(|s| {
             if !s.starts_with('.') {
@@ -330,15 +808,19 @@ fn is_hidden(name: &std::ffi::OsStr) -> bool {
             !matches!(
                 s,
                 ".venv" | ".pytest_cache" | ".mypy_cache" | ".tox" | ".gradle"
-            )
+            ) && !rules.iter().any(|r| r.dir_name == s)
         })
         .unwrap_or(false)
 }
 
-fn determine_project_name(project_root: &Path, artifact: &ArtifactType) -> String {
+fn determine_project_name(
+    fs: &dyn FileSystem,
+    project_root: &Path,
+    artifact: &ArtifactType,
+) -> String {
     if let Some(manifest) = artifact.manifest_file {
         let manifest_path = project_root.join(manifest);
-        if let Some(name) = read_project_name_from_manifest(&manifest_path) {
+        if let Some(name) = read_project_name_from_manifest(fs, &manifest_path) {
             return name;
         }
     }
@@ -350,15 +832,10 @@ fn determine_project_name(project_root: &Path, artifact: &ArtifactType) -> Strin
         .to_string()
 }
 
-fn read_project_name_from_manifest(path: &Path) -> Option<String> {
-    // check file size before reading to prevent OOM on maliciously large files
+fn read_project_name_from_manifest(fs: &dyn FileSystem, path: &Path) -> Option<String> {
+    // size-capped read to prevent OOM on maliciously large files
     const MAX_MANIFEST_SIZE: u64 = 1024 * 1024; // 1MB
-    let metadata = fs::metadata(path).ok()?;
-    if metadata.len() > MAX_MANIFEST_SIZE {
-        return None;
-    }
-
-    let content = fs::read_to_string(path).ok()?;
+    let content = fs.read_small_file(path, MAX_MANIFEST_SIZE)?;
     let filename = path.file_name()?.to_str()?;
 
     match filename {
@@ -438,6 +915,7 @@ fn get_source_last_modified(project_root: &Path) -> Option<i64> {
                             | "target"
                             | ".venv"
                             | "venv"
+                            | "__pypackages__"
                             | "vendor"
                             | "__pycache__"
                             | "build"
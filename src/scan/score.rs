@@ -0,0 +1,80 @@
+//! Reclaim score: one number ranking entries for automated cleanup.
+//!
+//! Big and old beats big and fresh beats small. Size counts on a log scale,
+//! so a 10 GB cache doesn't drown out everything else, and age multiplies it
+//! by up to 4x at 90 days untouched. Entries with no modification time
+//! (caches, docker) are regenerable by nature and get the neutral 1x.
+//! Nothing reclaimable scores 0.
+
+use super::detector::BloatEntry;
+
+const MIB: f64 = 1024.0 * 1024.0;
+const SECS_PER_DAY: f64 = 86_400.0;
+/// Days untouched after which the age factor stops growing
+const FULL_AGE_DAYS: f64 = 90.0;
+const MAX_AGE_FACTOR: f64 = 4.0;
+
+/// Score for `entry` as of the unix timestamp `now`, rounded to two decimals.
+pub fn reclaim_score(entry: &BloatEntry, now: i64) -> f64 {
+    if entry.reclaimable_bytes == 0 {
+        return 0.0;
+    }
+    let size = (1.0 + entry.reclaimable_bytes as f64 / MIB).log2();
+    let age = match entry.last_modified {
+        Some(modified) => {
+            let days = (now.saturating_sub(modified).max(0)) as f64 / SECS_PER_DAY;
+            1.0 + (MAX_AGE_FACTOR - 1.0) * (days / FULL_AGE_DAYS).min(1.0)
+        }
+        None => 1.0,
+    };
+    (size * age * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, Location};
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn entry(reclaimable: u64, age_days: Option<i64>) -> BloatEntry {
+        BloatEntry {
+            category: BloatCategory::ProjectArtifacts,
+            name: "app".to_string(),
+            location: Location::Aggregate("app".to_string()),
+            size_bytes: reclaimable,
+            reclaimable_bytes: reclaimable,
+            last_modified: age_days.map(|d| NOW - d * 86_400),
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    #[test]
+    fn bigger_and_older_score_higher() {
+        let gib = 1024 * 1024 * 1024;
+        let fresh = reclaim_score(&entry(gib, Some(0)), NOW);
+        let old = reclaim_score(&entry(gib, Some(45)), NOW);
+        let ancient = reclaim_score(&entry(gib, Some(365)), NOW);
+        assert!(fresh < old && old < ancient);
+        // age stops counting at 90 days: 4x the fresh score
+        assert_eq!(ancient, reclaim_score(&entry(gib, Some(90)), NOW));
+        assert!((ancient - 4.0 * fresh).abs() < 0.05);
+
+        assert!(
+            reclaim_score(&entry(gib, None), NOW) > reclaim_score(&entry(gib / 1024, None), NOW)
+        );
+    }
+
+    #[test]
+    fn nothing_reclaimable_scores_zero() {
+        let mut manual = entry(5 * 1024 * 1024, Some(365));
+        manual.reclaimable_bytes = 0;
+        assert_eq!(reclaim_score(&manual, NOW), 0.0);
+        // undated entries are neutral: log2(1 + 1 MiB / 1 MiB) = 1
+        assert_eq!(reclaim_score(&entry(1024 * 1024, None), NOW), 1.0);
+    }
+}
@@ -0,0 +1,348 @@
+//! Swap, hibernation and virtual machine detector.
+//!
+//! Reports the large system files people forget about: the hibernation
+//! image (sleepimage, hiberfil.sys), swap files, and virtual machines under
+//! the hypervisors' default folders. None of these are safe to just delete —
+//! hibernation and swap files are recreated by the OS and VM folders hold
+//! real data — so every entry is informational with zero reclaimable bytes
+//! and a hint on how to shrink it properly. clean never selects them.
+
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform::{self, Platform};
+
+/// Extensions of suspended VM memory images (VMware, VirtualBox saved state, Parallels).
+const MEMORY_IMAGE_EXTENSIONS: &[&str] = &["vmem", "vmss", "sav", "mem"];
+
+pub struct SystemDetector;
+
+impl Detector for SystemDetector {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    fn available(&self, _config: &Config) -> bool {
+        true
+    }
+
+    fn scan(&self, config: &Config) -> DetectorResult {
+        let mut result = DetectorResult::empty();
+
+        for (path, name, hint) in system_files(config.platform) {
+            // pagefile.sys and friends are locked but still stat-able
+            if let Ok(meta) = std::fs::metadata(&path) {
+                if meta.is_file() && meta.len() > 0 {
+                    let kind = if name.starts_with("hibernation") {
+                        "hibernation"
+                    } else {
+                        "swap"
+                    };
+                    result.entries.push(BloatEntry {
+                        category: BloatCategory::SystemCache,
+                        name: name.to_string(),
+                        location: Location::FilesystemPath(path),
+                        size_bytes: meta.len(),
+                        reclaimable_bytes: 0,
+                        last_modified: None,
+                        cleanup_hint: Some(hint.to_string()),
+                        // hibernation can be switched off; swap and page files are needed
+                        manual: kind == "hibernation",
+                        tags: tags(&[kind]),
+                        detector: None,
+                        file_count: None,
+                    });
+                }
+            }
+        }
+
+        let Some(home) = platform::home_dir() else {
+            result
+                .diagnostics
+                .push("system: could not determine home directory".into());
+            return result;
+        };
+        let vms = scan_vm_dirs(&home, config.platform);
+        result.entries.extend(vms.entries);
+        result.diagnostics.extend(vms.diagnostics);
+        result
+    }
+}
+
+/// Hibernation and swap files for a platform: (path, name, hint).
+fn system_files(platform: Platform) -> Vec<(PathBuf, &'static str, &'static str)> {
+    match platform {
+        Platform::MacOS => {
+            let mut files = vec![(
+                PathBuf::from("/private/var/vm/sleepimage"),
+                "hibernation image",
+                "recreated by macOS. to stop using it: sudo pmset -a hibernatemode 0, then sudo rm /private/var/vm/sleepimage",
+            )];
+            // swapfile0, swapfile1, ... come and go with memory pressure
+            if let Ok(dir) = std::fs::read_dir("/private/var/vm") {
+                let mut swaps: Vec<PathBuf> = dir
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| {
+                        p.file_name()
+                            .and_then(|n| n.to_str())
+                            .is_some_and(|n| n.starts_with("swapfile"))
+                    })
+                    .collect();
+                swaps.sort();
+                files.extend(swaps.into_iter().map(|p| {
+                    (
+                        p,
+                        "swap file",
+                        "managed by macOS, released on reboot. do not delete",
+                    )
+                }));
+            }
+            files
+        }
+        Platform::Windows => {
+            let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+            let root = PathBuf::from(format!("{drive}\\"));
+            vec![
+                (
+                    root.join("hiberfil.sys"),
+                    "hibernation file",
+                    "recreated by Windows. to remove it: powercfg /hibernate off (admin)",
+                ),
+                (
+                    root.join("pagefile.sys"),
+                    "page file",
+                    "managed by Windows. resize under System → Advanced system settings → Performance → Virtual memory",
+                ),
+                (
+                    root.join("swapfile.sys"),
+                    "swap file",
+                    "managed by Windows for store apps. do not delete",
+                ),
+            ]
+        }
+        Platform::Linux => ["/swapfile", "/swap.img"]
+            .into_iter()
+            .map(|p| {
+                (
+                    PathBuf::from(p),
+                    "swap file",
+                    "in use while listed in swapon --show. to shrink: sudo swapoff, recreate smaller with fallocate + mkswap, swapon",
+                )
+            })
+            .collect(),
+        Platform::Unknown => Vec::new(),
+    }
+}
+
+/// Default VM folders under `home` for a platform: (dir, hypervisor label, hint).
+fn vm_dirs(home: &Path, platform: Platform) -> Vec<(PathBuf, &'static str, &'static str)> {
+    let virtualbox = (
+        home.join("VirtualBox VMs"),
+        "VirtualBox",
+        "holds VM data. remove unused VMs in VirtualBox → right-click → Remove → Delete all files",
+    );
+    match platform {
+        Platform::MacOS => vec![
+            virtualbox,
+            (
+                home.join("Parallels"),
+                "Parallels",
+                "holds VM data. remove unused VMs in Control Center → right-click → Remove → Move to Trash, or reclaim space via Configure → General → Reclaim",
+            ),
+            (
+                home.join("Virtual Machines.localized"),
+                "VMware",
+                "holds VM data. remove unused VMs in VMware Fusion → Delete, or shut down suspended VMs to drop their memory images",
+            ),
+            (
+                home.join("Library/Containers/com.utmapp.UTM/Data/Documents"),
+                "UTM",
+                "holds VM data. remove unused VMs in UTM → right-click → Delete",
+            ),
+        ],
+        Platform::Windows => vec![
+            virtualbox,
+            (
+                home.join("Documents\\Virtual Machines"),
+                "VMware",
+                "holds VM data. remove unused VMs in VMware → Manage → Delete from Disk, or shut down suspended VMs to drop their memory images",
+            ),
+        ],
+        Platform::Linux => vec![
+            virtualbox,
+            (
+                home.join("vmware"),
+                "VMware",
+                "holds VM data. remove unused VMs in VMware → Manage → Delete from Disk, or shut down suspended VMs to drop their memory images",
+            ),
+        ],
+        Platform::Unknown => Vec::new(),
+    }
+}
+
+/// One entry per VM folder, sized as a whole. Suspended memory images are
+/// called out in the hint since a clean shutdown drops them.
+fn scan_vm_dirs(home: &Path, platform: Platform) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+
+    for (dir, label, hint) in vm_dirs(home, platform) {
+        let Ok(children) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut vms: Vec<PathBuf> = children
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .collect();
+        vms.sort();
+
+        for vm in vms {
+            let (size, logical, memory, newest) = measure_vm(&vm, &mut result.diagnostics);
+            if size == 0 {
+                continue;
+            }
+            let vm_name = vm.file_stem().and_then(|n| n.to_str()).unwrap_or("unknown");
+            let mut hint = if memory > 0 {
+                format!(
+                    "{hint}. {} of this is a suspended memory image",
+                    crate::util::format_bytes(memory)
+                )
+            } else {
+                hint.to_string()
+            };
+            if let Some(note) = super::sparse_note(size, logical) {
+                hint.push_str(&format!(" ({note})"));
+            }
+            result.entries.push(BloatEntry {
+                category: BloatCategory::ContainerData,
+                name: format!("{label} VM ({vm_name})"),
+                location: Location::FilesystemPath(vm),
+                size_bytes: size,
+                reclaimable_bytes: 0,
+                last_modified: newest,
+                cleanup_hint: Some(hint),
+                manual: false,
+                tags: tags(&["vm", &label.to_ascii_lowercase()]),
+                detector: None,
+                file_count: None,
+            });
+        }
+    }
+
+    result
+}
+
+/// Allocated bytes, logical bytes, bytes in memory images, and newest file
+/// mtime under a VM folder. Disk images (.vdi, .qcow2, .hdd) are usually
+/// sparse, so allocated can be far below logical.
+fn measure_vm(vm: &Path, diagnostics: &mut Vec<String>) -> (u64, u64, u64, Option<i64>) {
+    let mut total = 0u64;
+    let mut logical = 0u64;
+    let mut memory = 0u64;
+    let mut newest: Option<i64> = None;
+
+    for entry in WalkDir::new(vm).follow_links(false) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                diagnostics.push(format!("system: {e} (size may be underestimated)"));
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        let (allocated, len) = super::sparse_size(&meta);
+        total = total.saturating_add(allocated);
+        logical = logical.saturating_add(len);
+        let is_memory = entry
+            .path()
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| MEMORY_IMAGE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+        if is_memory {
+            memory = memory.saturating_add(allocated);
+        }
+        if let Some(secs) = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        {
+            let secs = secs.as_secs() as i64;
+            newest = Some(newest.map_or(secs, |n| n.max(secs)));
+        }
+    }
+
+    (total, logical, memory, newest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vm_folders_reported_with_memory_images_in_hint() {
+        let home = tempfile::tempdir().unwrap();
+        let vm = home.path().join("VirtualBox VMs/ubuntu");
+        std::fs::create_dir_all(vm.join("Snapshots")).unwrap();
+        std::fs::write(vm.join("ubuntu.vdi"), [0u8; 300]).unwrap();
+        std::fs::write(vm.join("Snapshots/state.sav"), [0u8; 100]).unwrap();
+        // empty VM folders are noise
+        std::fs::create_dir_all(home.path().join("VirtualBox VMs/empty")).unwrap();
+
+        let result = scan_vm_dirs(home.path(), Platform::Linux);
+
+        assert_eq!(result.entries.len(), 1);
+        let entry = &result.entries[0];
+        assert_eq!(entry.name, "VirtualBox VM (ubuntu)");
+        assert_eq!(entry.category, BloatCategory::ContainerData);
+        assert_eq!(entry.size_bytes, 400);
+        assert_eq!(entry.reclaimable_bytes, 0);
+        assert!(entry.last_modified.is_some());
+        assert!(entry
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .contains("suspended memory image"));
+    }
+
+    #[test]
+    fn vm_bundle_names_drop_extension() {
+        let home = tempfile::tempdir().unwrap();
+        let vm = home.path().join("Parallels/win11.pvm");
+        std::fs::create_dir_all(&vm).unwrap();
+        std::fs::write(vm.join("disk.hdd"), [0u8; 10]).unwrap();
+
+        let result = scan_vm_dirs(home.path(), Platform::MacOS);
+
+        assert_eq!(result.entries[0].name, "Parallels VM (win11)");
+        assert!(!result.entries[0]
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .contains("memory image"));
+    }
+
+    #[test]
+    fn system_files_listed_per_platform() {
+        let names = |p| {
+            system_files(p)
+                .into_iter()
+                .map(|(path, _, _)| path)
+                .collect::<Vec<_>>()
+        };
+        assert!(names(Platform::MacOS).contains(&PathBuf::from("/private/var/vm/sleepimage")));
+        assert!(names(Platform::Linux).contains(&PathBuf::from("/swapfile")));
+        assert!(names(Platform::Windows)
+            .iter()
+            .any(|p| p.to_string_lossy().ends_with("hiberfil.sys")));
+        assert!(names(Platform::Unknown).is_empty());
+    }
+}
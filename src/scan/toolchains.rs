@@ -0,0 +1,366 @@
+//! Version manager leftovers: installed runtimes nothing uses anymore.
+//!
+//! asdf, nvm and pyenv keep every version ever installed. A version counts as
+//! in use when a pin file under the scan roots (`.tool-versions`, `.nvmrc`,
+//! `.node-version`, `.python-version`) or the manager's global default asks
+//! for it, either exactly or as a prefix ("18" pins v18.17.0). The newest
+//! installed version of each tool is always kept, since aliases like
+//! `lts/*` or `system` can't be resolved here. Everything else is reported
+//! as a likely orphan with the manager's uninstall command.
+//!
+//! Pins outside the scan roots (a shell's `nvm use`, a virtualenv built on a
+//! pyenv version) can't be seen, so clean only deletes these when asked by
+//! `--tag toolchain` or `--path`; the uninstall command is the better route.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform;
+
+/// How deep below each root to look for pin files.
+const PIN_SEARCH_DEPTH: usize = 8;
+
+/// Directories never searched for pin files: dependencies and build output
+/// carry their own, which say nothing about what the user runs.
+const PIN_SEARCH_SKIP: &[&str] = &["node_modules", "target", "vendor", "site-packages"];
+
+/// Tag on every runtime entry; clean skips them unless it is asked for.
+pub const TOOLCHAIN_TAG: &str = "toolchain";
+
+pub struct ToolchainDetector;
+
+impl Detector for ToolchainDetector {
+    fn name(&self) -> &'static str {
+        "toolchains"
+    }
+
+    fn available(&self, _config: &Config) -> bool {
+        true
+    }
+
+    fn scan(&self, config: &Config) -> DetectorResult {
+        let Some(home) = platform::home_dir() else {
+            return DetectorResult::with_diagnostic(
+                "toolchains: could not determine home directory".into(),
+            );
+        };
+        let installed = installed_versions(&home);
+        if installed.is_empty() {
+            return DetectorResult::empty();
+        }
+        let pins = find_pins(&home, &config.roots);
+        scan_orphans(installed, &pins, config.min_entry_bytes)
+    }
+}
+
+/// Whether `entry` is an installed runtime, which clean leaves alone unless
+/// selected by tag or path.
+pub fn is_toolchain(entry: &BloatEntry) -> bool {
+    entry.tags.iter().any(|t| t == TOOLCHAIN_TAG)
+}
+
+/// One installed version of a tool.
+#[derive(Debug)]
+struct Installed {
+    manager: &'static str,
+    tool: String,
+    version: String,
+    path: PathBuf,
+}
+
+/// Requested versions, keyed by (manager, tool).
+type Pins = HashMap<(&'static str, String), Vec<String>>;
+
+/// Every version installed by asdf, nvm and pyenv under `home`.
+fn installed_versions(home: &Path) -> Vec<Installed> {
+    let mut installed = Vec::new();
+
+    for tool_dir in subdirs(&home.join(".asdf/installs")) {
+        let tool = file_name(&tool_dir);
+        for version in subdirs(&tool_dir) {
+            installed.push(Installed {
+                manager: "asdf",
+                tool: tool.clone(),
+                version: file_name(&version),
+                path: version,
+            });
+        }
+    }
+    for version in subdirs(&home.join(".nvm/versions/node")) {
+        installed.push(Installed {
+            manager: "nvm",
+            tool: "node".to_string(),
+            version: file_name(&version),
+            path: version,
+        });
+    }
+    // pyenv-virtualenv keeps named envs next to the versions; only real
+    // interpreter versions start with a digit
+    for version in subdirs(&home.join(".pyenv/versions")) {
+        let name = file_name(&version);
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            installed.push(Installed {
+                manager: "pyenv",
+                tool: "python".to_string(),
+                version: name,
+                path: version,
+            });
+        }
+    }
+
+    installed
+}
+
+/// Real directories (not symlinks) directly under `dir`, sorted.
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(children) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = children
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Global defaults under `home` plus every pin file under `roots`.
+fn find_pins(home: &Path, roots: &[PathBuf]) -> Pins {
+    let mut pins = Pins::new();
+    add_pin_file(&mut pins, &home.join(".tool-versions"), ".tool-versions");
+    add_pin_file(&mut pins, &home.join(".nvm/alias/default"), ".nvmrc");
+    add_pin_file(&mut pins, &home.join(".pyenv/version"), ".python-version");
+
+    for root in roots {
+        let walker = WalkDir::new(root)
+            .max_depth(PIN_SEARCH_DEPTH)
+            .follow_links(false)
+            .same_file_system(!super::cross_filesystems())
+            .into_iter()
+            .filter_entry(|e| {
+                if e.depth() == 0 || !e.file_type().is_dir() {
+                    return true;
+                }
+                let name = e.file_name().to_string_lossy();
+                !name.starts_with('.') && !PIN_SEARCH_SKIP.contains(&name.as_ref())
+            });
+        for entry in walker.flatten() {
+            if entry.file_type().is_file() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                add_pin_file(&mut pins, entry.path(), &name);
+            }
+        }
+    }
+
+    pins
+}
+
+/// Record the versions requested by a pin file, read as the file `kind`.
+fn add_pin_file(pins: &mut Pins, path: &Path, kind: &str) {
+    let key = match kind {
+        ".tool-versions" => None,
+        ".nvmrc" | ".node-version" => Some(("nvm", "node")),
+        ".python-version" => Some(("pyenv", "python")),
+        _ => return,
+    };
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        match key {
+            // "nodejs 18.17.0 16.20.0"
+            None => {
+                if let Some(tool) = words.next() {
+                    pins.entry(("asdf", tool.to_string()))
+                        .or_default()
+                        .extend(words.map(str::to_string));
+                }
+            }
+            Some((manager, tool)) => {
+                pins.entry((manager, tool.to_string()))
+                    .or_default()
+                    .extend(words.map(str::to_string));
+            }
+        }
+    }
+}
+
+/// Whether `pin` asks for `version`: equal, or a prefix ending on a
+/// component boundary. A leading "v" is ignored on both.
+fn pin_matches(pin: &str, version: &str) -> bool {
+    let pin = pin.strip_prefix('v').unwrap_or(pin);
+    let version = version.strip_prefix('v').unwrap_or(version);
+    version == pin
+        || version
+            .strip_prefix(pin)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Numeric components for ordering versions: "v18.9.1" -> [18, 9, 1].
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split(['.', '-'])
+        .map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().unwrap_or(0)
+        })
+        .collect()
+}
+
+/// An entry for every installed version that isn't pinned and isn't the
+/// newest of its tool.
+fn scan_orphans(installed: Vec<Installed>, pins: &Pins, min_bytes: u64) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+
+    let mut newest: HashMap<(&'static str, &str), &str> = HashMap::new();
+    for item in &installed {
+        let slot = newest
+            .entry((item.manager, item.tool.as_str()))
+            .or_insert(item.version.as_str());
+        if version_key(&item.version) > version_key(slot) {
+            *slot = item.version.as_str();
+        }
+    }
+
+    let no_pins = Vec::new();
+    for item in &installed {
+        if newest.get(&(item.manager, item.tool.as_str())) == Some(&item.version.as_str()) {
+            continue;
+        }
+        let requested = pins
+            .get(&(item.manager, item.tool.clone()))
+            .unwrap_or(&no_pins);
+        if requested.iter().any(|pin| pin_matches(pin, &item.version)) {
+            continue;
+        }
+
+        let size = match super::calculate_dir_size(&item.path) {
+            Ok((size, warnings)) => {
+                result.diagnostics.extend(
+                    warnings
+                        .into_iter()
+                        .map(|w| format!("{w} (size may be underestimated)")),
+                );
+                size
+            }
+            Err(e) => {
+                result.diagnostics.push(format!(
+                    "toolchains: failed to size {}: {e}",
+                    item.path.display()
+                ));
+                continue;
+            }
+        };
+        if !super::big_enough(size, min_bytes) {
+            continue;
+        }
+
+        let (version, tool) = (&item.version, &item.tool);
+        let hint = match item.manager {
+            "nvm" => format!(
+                "not pinned by any .nvmrc under the scan roots or the nvm default. nvm uninstall {version}"
+            ),
+            "pyenv" => format!(
+                "not pinned by any .python-version under the scan roots or pyenv global. pyenv uninstall {version} (virtualenvs made from it stop working)"
+            ),
+            _ => format!(
+                "not pinned by any .tool-versions under the scan roots or in ~/.tool-versions. asdf uninstall {tool} {version}"
+            ),
+        };
+        result.entries.push(BloatEntry {
+            category: BloatCategory::PackageCache,
+            name: format!("{tool} {version} ({})", item.manager),
+            location: Location::FilesystemPath(item.path.clone()),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified: None,
+            cleanup_hint: Some(hint),
+            manual: false,
+            tags: tags(&[tool.as_str(), item.manager, TOOLCHAIN_TAG]),
+            detector: None,
+            file_count: None,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install(home: &Path, dir: &str) {
+        let path = home.join(dir);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("bin"), [0u8; 10]).unwrap();
+    }
+
+    #[test]
+    fn pins_match_exactly_or_by_prefix() {
+        assert!(pin_matches("18", "v18.17.0"));
+        assert!(pin_matches("v18.17.0", "v18.17.0"));
+        assert!(pin_matches("3.11", "3.11.4"));
+        assert!(!pin_matches("3.1", "3.11.4"));
+        assert!(!pin_matches("lts/*", "v20.1.0"));
+        assert!(version_key("v18.9.1") < version_key("v18.10.0"));
+    }
+
+    #[test]
+    fn unpinned_versions_reported_but_newest_and_pinned_kept() {
+        let home = tempfile::tempdir().unwrap();
+        let code = home.path().join("code");
+        for dir in [
+            ".nvm/versions/node/v16.20.0",
+            ".nvm/versions/node/v18.17.0",
+            ".nvm/versions/node/v20.5.0",
+            ".pyenv/versions/3.8.10",
+            ".pyenv/versions/3.11.4",
+            ".pyenv/versions/my-env",
+            ".asdf/installs/ruby/3.1.0",
+            ".asdf/installs/ruby/3.2.2",
+            "code/app/node_modules/dep",
+        ] {
+            install(home.path(), dir);
+        }
+        std::fs::write(code.join("app/.nvmrc"), "18\n").unwrap();
+        // a dependency's pin says nothing about what the user runs
+        std::fs::write(code.join("app/node_modules/dep/.nvmrc"), "16").unwrap();
+        std::fs::create_dir_all(home.path().join(".pyenv")).unwrap();
+        std::fs::write(home.path().join(".pyenv/version"), "3.8.10\n").unwrap();
+        std::fs::write(
+            home.path().join(".tool-versions"),
+            "# global\nnodejs 20.5.0\n",
+        )
+        .unwrap();
+
+        let pins = find_pins(home.path(), &[code]);
+        let result = scan_orphans(installed_versions(home.path()), &pins, 0);
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["ruby 3.1.0 (asdf)", "node v16.20.0 (nvm)"]);
+        assert!(result.entries[1]
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .ends_with("nvm uninstall v16.20.0"));
+        assert!(result
+            .entries
+            .iter()
+            .all(|e| e.category == BloatCategory::PackageCache && e.reclaimable_bytes == 10));
+    }
+}
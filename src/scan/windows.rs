@@ -0,0 +1,179 @@
+//! Windows Update and temp folder detector (Windows only).
+//!
+//! Windows keeps downloaded updates in SoftwareDistribution\Download, lets
+//! C:\Windows\Temp and the per-user %TEMP% grow without bound, and holds
+//! deleted files in the Recycle Bin until someone empties it. Each location
+//! that exists is reported as one entry with the built-in way to clear it.
+//!
+//! Nothing here is deleted by heft: the system folders need admin rights
+//! and Disk Cleanup knows which updates are still pending, and removing
+//! %TEMP% itself (rather than its contents) breaks programs that expect it
+//! to exist. Entries are manual, so `--include-manual` totals them.
+
+use std::path::{Path, PathBuf};
+
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use crate::config::Config;
+use crate::platform::Platform;
+
+const UPDATE_HINT: &str = "downloaded Windows updates. run Disk Cleanup (cleanmgr) → Clean up system files → Windows Update Cleanup";
+const SYSTEM_TEMP_HINT: &str = "system temp files. run Disk Cleanup (cleanmgr) → Clean up system files → Temporary files (admin)";
+const USER_TEMP_HINT: &str = "your temp files. Settings → System → Storage → Temporary files, or delete the contents of %TEMP% (files in use are skipped)";
+const RECYCLE_BIN_HINT: &str =
+    "deleted files kept for restore. empty the Recycle Bin, or Clear-RecycleBin in PowerShell";
+
+pub struct WindowsDetector;
+
+impl Detector for WindowsDetector {
+    fn name(&self) -> &'static str {
+        "windows"
+    }
+
+    fn available(&self, config: &Config) -> bool {
+        config.platform == Platform::Windows
+    }
+
+    fn scan(&self, _config: &Config) -> DetectorResult {
+        let var = |name| std::env::var_os(name).map(PathBuf::from);
+        let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        let drive = PathBuf::from(format!("{drive}\\"));
+        let system_root = var("SystemRoot").unwrap_or_else(|| drive.join("Windows"));
+        let temps = [
+            var("TEMP").or_else(|| var("TMP")),
+            var("LOCALAPPDATA").map(|dir| dir.join("Temp")),
+        ];
+        scan_locations(locations(&system_root, &drive, &temps))
+    }
+}
+
+/// A folder worth reporting: (path, name, hint, tag).
+type Target = (PathBuf, &'static str, &'static str, &'static str);
+
+/// Every location to check, given the Windows directory, the system drive
+/// root and candidate user temp folders. %TEMP% is normally
+/// %LOCALAPPDATA%\Temp, so user temp folders are listed once per path.
+fn locations(system_root: &Path, drive: &Path, temps: &[Option<PathBuf>]) -> Vec<Target> {
+    let mut targets = vec![
+        (
+            system_root.join("SoftwareDistribution").join("Download"),
+            "Windows Update cache",
+            UPDATE_HINT,
+            "windows-update",
+        ),
+        (
+            system_root.join("Temp"),
+            "Windows temp",
+            SYSTEM_TEMP_HINT,
+            "temp",
+        ),
+    ];
+    let mut seen: Vec<String> = Vec::new();
+    for temp in temps.iter().flatten() {
+        // paths are case-insensitive on Windows and %TEMP% may carry a trailing slash
+        let key = temp
+            .to_string_lossy()
+            .trim_end_matches(['\\', '/'])
+            .to_lowercase();
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+        targets.push((temp.clone(), "user temp", USER_TEMP_HINT, "temp"));
+    }
+    targets.push((
+        drive.join("$Recycle.Bin"),
+        "Recycle Bin",
+        RECYCLE_BIN_HINT,
+        "recycle-bin",
+    ));
+    targets
+}
+
+/// One entry per target that exists and holds something.
+fn scan_locations(targets: Vec<Target>) -> DetectorResult {
+    let mut result = DetectorResult::empty();
+
+    for (path, name, hint, tag) in targets {
+        if !path.is_dir() {
+            continue;
+        }
+        let size = match super::calculate_dir_size(&path) {
+            Ok((size, warnings)) => {
+                // locked files and other users' Recycle Bin folders
+                result.diagnostics.extend(
+                    warnings
+                        .into_iter()
+                        .map(|w| format!("{w} (size may be underestimated)")),
+                );
+                size
+            }
+            Err(e) => {
+                result
+                    .diagnostics
+                    .push(format!("windows: failed to size {}: {e}", path.display()));
+                continue;
+            }
+        };
+        if size == 0 && !super::sizes_skipped() {
+            continue;
+        }
+        result.entries.push(BloatEntry {
+            category: BloatCategory::SystemCache,
+            name: name.to_string(),
+            location: Location::FilesystemPath(path),
+            size_bytes: size,
+            reclaimable_bytes: 0,
+            last_modified: None,
+            cleanup_hint: Some(hint.to_string()),
+            manual: true,
+            tags: tags(&["windows", tag]),
+            detector: None,
+            file_count: None,
+        });
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn existing_locations_reported_once() {
+        let root = tempfile::tempdir().unwrap();
+        let windows = root.path().join("Windows");
+        let temp = root.path().join("Users/me/AppData/Local/Temp");
+        for dir in [
+            windows.join("SoftwareDistribution/Download/update"),
+            temp.clone(),
+            root.path().join("$Recycle.Bin/S-1-5-21"),
+        ] {
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("data"), [0u8; 100]).unwrap();
+        }
+        // empty folders are noise
+        std::fs::create_dir_all(windows.join("Temp")).unwrap();
+
+        let temps = [
+            Some(temp.clone()),
+            Some(PathBuf::from(format!("{}/", temp.display()))),
+        ];
+        let result = scan_locations(locations(&windows, root.path(), &temps));
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Windows Update cache", "user temp", "Recycle Bin"]);
+        assert!(result.entries.iter().all(|e| {
+            e.category == BloatCategory::SystemCache
+                && e.size_bytes == 100
+                && e.reclaimable_bytes == 0
+                && e.manual
+        }));
+        assert_eq!(result.entries[0].tags, ["windows", "windows-update"]);
+        assert!(result.entries[0]
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .contains("cleanmgr"));
+    }
+}
@@ -1,16 +1,36 @@
 //! Xcode build artifact detector (macOS only).
 //!
-//! Detects ~/Library/Developer/Xcode/DerivedData, the central location
-//! where Xcode stores all compiled build products, indexes, and logs.
-//! Can grow to 10-30 GB on active iOS/macOS projects and is fully safe
-//! to delete — Xcode rebuilds it on next build.
+//! Detects what Xcode accumulates under ~/Library/Developer:
+//! - Xcode/DerivedData: compiled build products, indexes and logs. Can grow
+//!   to 10-30 GB on active projects and is fully safe to delete — Xcode
+//!   rebuilds it on next build.
+//! - Xcode/iOS DeviceSupport (and watchOS): symbols copied from every OS
+//!   version a device has been plugged in with, 5-10 GB. Re-copied on demand.
+//! - CoreSimulator/Caches: dyld and runtime caches, rebuilt on demand.
+//! - CoreSimulator/Devices: simulator data, often past 30 GB. Holds app data,
+//!   so it's reported for `xcrun simctl` rather than deleted.
+//! - Xcode/Archives: builds submitted to the App Store. Their dSYMs are
+//!   needed to symbolicate crash reports, so also report-only.
+//!
+//! Each location is reported only if it exists and is non-empty.
+
+use std::path::{Path, PathBuf};
 
-use super::detector::{BloatCategory, BloatEntry, Detector, DetectorResult, Location};
+use super::detector::{tags, BloatCategory, BloatEntry, Detector, DetectorResult, Location};
 use crate::config::Config;
 use crate::platform::{self, Platform};
 
 pub struct XcodeDetector;
 
+/// One Xcode data directory. `manual` ones are reported with zero
+/// reclaimable bytes; clean never deletes them.
+struct XcodeLocation {
+    name: &'static str,
+    path: PathBuf,
+    cleanup_hint: &'static str,
+    manual: bool,
+}
+
 impl Detector for XcodeDetector {
     fn name(&self) -> &'static str {
         "xcode"
@@ -30,42 +50,147 @@ impl Detector for XcodeDetector {
             }
         };
 
-        let derived_data = home.join("Library/Developer/Xcode/DerivedData");
-
-        if !derived_data.exists() {
-            return DetectorResult::empty();
-        }
+        scan_locations(
+            xcode_locations(&home),
+            config.verbose(),
+            config.min_entry_bytes,
+        )
+    }
+}
 
-        match super::calculate_dir_size(&derived_data) {
-            Ok((size, warnings)) if size > 0 => {
-                let mut diagnostics: Vec<String> = warnings
-                    .into_iter()
-                    .map(|w| format!("{w} (size may be underestimated)"))
-                    .collect();
+fn xcode_locations(home: &Path) -> Vec<XcodeLocation> {
+    let developer = home.join("Library/Developer");
+    vec![
+        XcodeLocation {
+            name: "Xcode DerivedData",
+            path: developer.join("Xcode/DerivedData"),
+            cleanup_hint: "safe to delete, Xcode rebuilds on next build. or: Xcode → Settings → Locations → Derived Data → arrow button",
+            manual: false,
+        },
+        XcodeLocation {
+            name: "Xcode iOS DeviceSupport",
+            path: developer.join("Xcode/iOS DeviceSupport"),
+            cleanup_hint: "safe to delete, especially folders for iOS versions you no longer test on. re-copied when a device connects",
+            manual: false,
+        },
+        XcodeLocation {
+            name: "Xcode watchOS DeviceSupport",
+            path: developer.join("Xcode/watchOS DeviceSupport"),
+            cleanup_hint: "safe to delete, especially folders for watchOS versions you no longer test on. re-copied when a watch connects",
+            manual: false,
+        },
+        XcodeLocation {
+            name: "simulator caches",
+            path: developer.join("CoreSimulator/Caches"),
+            cleanup_hint: "safe to delete while the simulator is closed, rebuilt on next launch",
+            manual: false,
+        },
+        XcodeLocation {
+            name: "simulator devices",
+            path: developer.join("CoreSimulator/Devices"),
+            cleanup_hint: "xcrun simctl delete unavailable, then delete unused simulators in Xcode → Window → Devices and Simulators",
+            manual: true,
+        },
+        XcodeLocation {
+            name: "Xcode archives",
+            path: developer.join("Xcode/Archives"),
+            cleanup_hint: "delete old archives in Xcode → Window → Organizer; keep the ones whose dSYMs you need for crash reports",
+            manual: true,
+        },
+    ]
+}
 
-                if config.verbose {
-                    diagnostics.push(format!("xcode: DerivedData at {}", derived_data.display()));
-                }
+fn scan_locations(locations: Vec<XcodeLocation>, verbose: bool, min_bytes: u64) -> DetectorResult {
+    let mut result = DetectorResult::empty();
 
-                DetectorResult {
-                    entries: vec![BloatEntry {
-                        category: BloatCategory::IdeData,
-                        name: "Xcode DerivedData".to_string(),
-                        location: Location::FilesystemPath(derived_data),
-                        size_bytes: size,
-                        reclaimable_bytes: size,
-                        last_modified: None,
-                        cleanup_hint: Some(
-                            "safe to delete, Xcode rebuilds on next build. or: Xcode → Settings → Locations → Derived Data → arrow button".to_string()
-                        ),
-                    }],
-                    diagnostics,
+    for location in locations {
+        if !location.path.exists() {
+            continue;
+        }
+        match super::calculate_dir_size(&location.path) {
+            Ok((size, warnings)) if super::big_enough(size, min_bytes) => {
+                result.diagnostics.extend(
+                    warnings
+                        .into_iter()
+                        .map(|w| format!("{w} (size may be underestimated)")),
+                );
+                if verbose {
+                    result.diagnostics.push(format!(
+                        "xcode: {} at {}",
+                        location.name,
+                        location.path.display()
+                    ));
                 }
+                result.entries.push(BloatEntry {
+                    category: BloatCategory::IdeData,
+                    name: location.name.to_string(),
+                    location: Location::FilesystemPath(location.path),
+                    size_bytes: size,
+                    reclaimable_bytes: if location.manual { 0 } else { size },
+                    last_modified: None,
+                    cleanup_hint: Some(location.cleanup_hint.to_string()),
+                    manual: location.manual,
+                    tags: tags(&["xcode"]),
+                    detector: None,
+                    file_count: None,
+                });
             }
-            Ok(_) => DetectorResult::empty(),
-            Err(e) => DetectorResult::with_diagnostic(format!(
-                "xcode: failed to calculate DerivedData size: {e}"
+            Ok(_) => {}
+            Err(e) => result.diagnostics.push(format!(
+                "xcode: failed to calculate {} size: {e}",
+                location.name
             )),
         }
     }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_existing_nonempty_location_reported() {
+        let home = tempfile::tempdir().unwrap();
+        let developer = home.path().join("Library/Developer");
+        for (dir, bytes) in [
+            ("Xcode/DerivedData/App-abc", 100),
+            ("Xcode/iOS DeviceSupport/17.4 (21E219)", 300),
+            ("CoreSimulator/Devices/1234-ABCD", 500),
+        ] {
+            std::fs::create_dir_all(developer.join(dir)).unwrap();
+            std::fs::write(developer.join(dir).join("data"), vec![0u8; bytes]).unwrap();
+        }
+        // exists but empty: not worth a line
+        std::fs::create_dir_all(developer.join("Xcode/Archives")).unwrap();
+
+        let result = scan_locations(xcode_locations(home.path()), false, 0);
+
+        let names: Vec<_> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Xcode DerivedData",
+                "Xcode iOS DeviceSupport",
+                "simulator devices"
+            ]
+        );
+        assert!(result
+            .entries
+            .iter()
+            .all(|e| e.category == BloatCategory::IdeData));
+        assert_eq!(result.entries[1].reclaimable_bytes, 300);
+
+        // simulator data holds app state, so it's pointed at simctl instead
+        let devices = &result.entries[2];
+        assert_eq!(devices.size_bytes, 500);
+        assert_eq!(devices.reclaimable_bytes, 0);
+        assert!(devices.manual);
+        assert!(devices
+            .cleanup_hint
+            .as_deref()
+            .unwrap()
+            .starts_with("xcrun simctl delete unavailable"));
+    }
 }
@@ -5,6 +5,8 @@ use std::thread;
 use std::time::Duration;
 
 const FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// Frames in ASCII mode (--ascii)
+const ASCII_FRAMES: &[&str] = &["|", "/", "-", "\\"];
 const INTERVAL: Duration = Duration::from_millis(80);
 
 pub struct Spinner {
@@ -14,9 +16,10 @@ pub struct Spinner {
 }
 
 impl Spinner {
-    /// Start a spinner on stderr. Returns None if stderr is not a terminal.
+    /// Start a spinner on stderr. Returns None if stderr is not a terminal,
+    /// or in CI where a terminal's redraws end up as log noise.
     pub fn start(msg: &str) -> Option<Self> {
-        if !atty_stderr() {
+        if !atty_stderr() || crate::platform::is_ci() {
             return None;
         }
 
@@ -26,14 +29,19 @@ impl Spinner {
         let r = Arc::clone(&running);
         let m = Arc::clone(&message);
 
+        let frames = if crate::util::ascii() {
+            ASCII_FRAMES
+        } else {
+            FRAMES
+        };
         let handle = thread::spawn(move || {
             let mut frame = 0;
             let mut stderr = io::stderr();
             while r.load(Ordering::Relaxed) {
                 let msg = m.lock().unwrap().clone();
-                let _ = write!(stderr, "\r\x1b[2K{} {}", FRAMES[frame], msg);
+                let _ = write!(stderr, "\r\x1b[2K{} {}", frames[frame], msg);
                 let _ = stderr.flush();
-                frame = (frame + 1) % FRAMES.len();
+                frame = (frame + 1) % frames.len();
                 thread::sleep(INTERVAL);
             }
             // Clear the spinner line
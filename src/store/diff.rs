@@ -4,9 +4,11 @@
 //! - Matches entries by category and project name (not exact path)
 //! - Shows per-category deltas: grew, shrank, new, gone
 //! - Net change summary
+//! - Optionally ignores case and surrounding whitespace in names (`heft diff --ignore-case`)
+//! - Optionally pairs renamed entries (`heft diff --fuzzy`, see `pair_renamed`)
 
-use crate::scan::detector::{BloatCategory, BloatEntry};
-use std::collections::HashMap;
+use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DiffType {
@@ -24,6 +26,18 @@ pub struct DiffEntry {
     pub new_size: u64,
     pub delta: i64,
     pub diff_type: DiffType,
+    /// Name in the older snapshot, when `pair_renamed` matched it to this one
+    pub renamed_from: Option<String>,
+}
+
+impl DiffEntry {
+    /// The name, with the old one appended for renamed entries.
+    pub fn label(&self) -> String {
+        match &self.renamed_from {
+            Some(old) => format!("{} (was {old})", self.name),
+            None => self.name.clone(),
+        }
+    }
 }
 
 pub struct DiffResult {
@@ -36,9 +50,42 @@ pub struct DiffResult {
 }
 
 /// Create a unique key for matching entries across snapshots.
-/// Uses category + name since paths can change.
-fn make_key(entry: &BloatEntry) -> String {
-    format!("{}:{}", entry.category.as_str(), entry.name)
+/// Uses category + name since paths can change. With `normalize_names`,
+/// names of filesystem entries (taken from manifests and directory names)
+/// are trimmed and lowercased so "MyApp" and "myapp" match; docker and
+/// other aggregate names stay exact.
+fn make_key(entry: &BloatEntry, normalize_names: bool) -> String {
+    match entry.location {
+        Location::FilesystemPath(_) if normalize_names => format!(
+            "{}:{}",
+            entry.category.as_str(),
+            entry.name.trim().to_lowercase()
+        ),
+        _ => format!("{}:{}", entry.category.as_str(), entry.name),
+    }
+}
+
+/// Normalized keys that more than one differently-named entry of the same
+/// snapshot maps to. Those entries keep their exact names as keys, so
+/// "MyApp" and "myapp" side by side don't overwrite each other.
+fn colliding_keys(snapshots: [&[BloatEntry]; 2]) -> HashSet<String> {
+    let mut colliding = HashSet::new();
+    for entries in snapshots {
+        let mut names: HashMap<String, &str> = HashMap::new();
+        for entry in entries {
+            let key = make_key(entry, true);
+            match names.get(&key) {
+                Some(name) if *name != entry.name => {
+                    colliding.insert(key);
+                }
+                Some(_) => {}
+                None => {
+                    names.insert(key, &entry.name);
+                }
+            }
+        }
+    }
+    colliding
 }
 
 /// Compare two sets of entries and produce diff entries
@@ -49,16 +96,31 @@ pub fn compare_entries(
     to_id: i64,
     from_timestamp: i64,
     to_timestamp: i64,
+    normalize_names: bool,
 ) -> DiffResult {
+    let colliding = if normalize_names {
+        colliding_keys([from_entries, to_entries])
+    } else {
+        HashSet::new()
+    };
+    let key = |entry: &BloatEntry| {
+        let key = make_key(entry, normalize_names);
+        if colliding.contains(&key) {
+            make_key(entry, false)
+        } else {
+            key
+        }
+    };
+
     // build lookup maps using category + name as key
     let mut from_map: HashMap<String, &BloatEntry> = HashMap::new();
     for entry in from_entries {
-        from_map.insert(make_key(entry), entry);
+        from_map.insert(key(entry), entry);
     }
 
     let mut to_map: HashMap<String, &BloatEntry> = HashMap::new();
     for entry in to_entries {
-        to_map.insert(make_key(entry), entry);
+        to_map.insert(key(entry), entry);
     }
 
     let mut diff_entries = Vec::new();
@@ -86,6 +148,8 @@ pub fn compare_entries(
                     new_size: to_entry.size_bytes,
                     delta,
                     diff_type,
+                    renamed_from: (from_entry.name != to_entry.name)
+                        .then(|| from_entry.name.clone()),
                 });
 
                 net_change = net_change.saturating_add(delta);
@@ -101,6 +165,7 @@ pub fn compare_entries(
                 new_size: to_entry.size_bytes,
                 delta,
                 diff_type: DiffType::New,
+                renamed_from: None,
             });
 
             net_change = net_change.saturating_add(delta);
@@ -119,6 +184,7 @@ pub fn compare_entries(
                 new_size: 0,
                 delta,
                 diff_type: DiffType::Gone,
+                renamed_from: None,
             });
 
             net_change = net_change.saturating_add(delta);
@@ -135,6 +201,117 @@ pub fn compare_entries(
     }
 }
 
+/// Pair Gone entries with New ones in the same category that are probably
+/// the same thing renamed (a moved project, a changed package.json name),
+/// turning each pair into one Grew/Shrank entry. A pair matches when the
+/// location is identical, or failing that when the names are within a
+/// third of the longer name's length in edit distance; the closest name
+/// wins. Renamed entries whose size didn't change drop out like any other
+/// unchanged entry. The net change is unaffected.
+pub fn pair_renamed(
+    result: &mut DiffResult,
+    from_entries: &[BloatEntry],
+    to_entries: &[BloatEntry],
+) {
+    let location_of = |entries: &[BloatEntry], diff: &DiffEntry| {
+        entries
+            .iter()
+            .find(|e| e.category == diff.category && e.name == diff.name)
+            .map(|e| e.location.clone())
+    };
+
+    let mut gone: Vec<usize> = Vec::new();
+    let mut new: Vec<usize> = Vec::new();
+    for (i, entry) in result.entries.iter().enumerate() {
+        match entry.diff_type {
+            DiffType::Gone => gone.push(i),
+            DiffType::New => new.push(i),
+            _ => {}
+        }
+    }
+    // deterministic pairing regardless of HashMap order
+    gone.sort_by(|&a, &b| result.entries[a].name.cmp(&result.entries[b].name));
+    new.sort_by(|&a, &b| result.entries[a].name.cmp(&result.entries[b].name));
+
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    for &g in &gone {
+        let old = &result.entries[g];
+        let old_location = location_of(from_entries, old);
+        let candidates = new.iter().copied().filter(|&n| {
+            result.entries[n].category == old.category && !pairs.iter().any(|&(_, p)| p == n)
+        });
+
+        let mut best: Option<(usize, usize)> = None;
+        for n in candidates {
+            let renamed = &result.entries[n];
+            if old_location.is_some() && old_location == location_of(to_entries, renamed) {
+                best = Some((n, 0));
+                break;
+            }
+            let distance = levenshtein(&old.name, &renamed.name);
+            let longest = old.name.chars().count().max(renamed.name.chars().count());
+            if distance * 3 <= longest && best.map_or(true, |(_, d)| distance < d) {
+                best = Some((n, distance));
+            }
+        }
+        if let Some((n, _)) = best {
+            pairs.push((g, n));
+        }
+    }
+
+    let mut remove = Vec::new();
+    for (g, n) in pairs {
+        let old_name = result.entries[g].name.clone();
+        let old_size = result.entries[g].old_size;
+        let entry = &mut result.entries[n];
+        let new_size = i64::try_from(entry.new_size).unwrap_or(i64::MAX);
+        entry.delta = new_size.saturating_sub(i64::try_from(old_size).unwrap_or(i64::MAX));
+        entry.old_size = old_size;
+        entry.renamed_from = Some(old_name);
+        entry.diff_type = if entry.delta >= 0 {
+            DiffType::Grew
+        } else {
+            DiffType::Shrank
+        };
+        remove.push(g);
+        if entry.delta == 0 {
+            remove.push(n);
+        }
+    }
+    remove.sort_unstable();
+    for i in remove.into_iter().rev() {
+        result.entries.remove(i);
+    }
+}
+
+/// Entries that grew or appeared by more than `tolerance` bytes, biggest
+/// growth first.
+pub fn growth_beyond(result: &DiffResult, tolerance: u64) -> Vec<&DiffEntry> {
+    let mut grown: Vec<&DiffEntry> = result
+        .entries
+        .iter()
+        .filter(|e| matches!(e.diff_type, DiffType::Grew | DiffType::New))
+        .filter(|e| e.delta.unsigned_abs() > tolerance)
+        .collect();
+    grown.sort_by_key(|e| std::cmp::Reverse(e.delta));
+    grown
+}
+
+/// Edit distance between two strings, by character.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,11 +327,15 @@ mod tests {
             reclaimable_bytes: size,
             last_modified: None,
             cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
         }
     }
 
     fn diff(from: &[BloatEntry], to: &[BloatEntry]) -> DiffResult {
-        compare_entries(from, to, 1, 2, 0, 100)
+        compare_entries(from, to, 1, 2, 0, 100, false)
     }
 
     #[test]
@@ -234,10 +415,165 @@ mod tests {
 
     #[test]
     fn snapshot_ids_preserved() {
-        let result = compare_entries(&[], &[], 7, 13, 1000, 2000);
+        let result = compare_entries(&[], &[], 7, 13, 1000, 2000, false);
         assert_eq!(result.from_id, 7);
         assert_eq!(result.to_id, 13);
         assert_eq!(result.from_timestamp, 1000);
         assert_eq!(result.to_timestamp, 2000);
     }
+
+    // ── normalized names ─────────────────────────────────────────────────────
+
+    #[test]
+    fn case_only_rename_reported_as_grew_when_normalized() {
+        let from = [project("MyApp", "/code/myapp/node_modules", 100)];
+        let to = [project(" myapp ", "/code/myapp/node_modules", 150)];
+
+        let result = compare_entries(&from, &to, 1, 2, 0, 100, true);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].diff_type, DiffType::Grew);
+        assert_eq!(result.entries[0].delta, 50);
+        assert_eq!(result.entries[0].renamed_from.as_deref(), Some("MyApp"));
+        assert_eq!(result.net_change, 50);
+
+        // without normalization it's gone + new
+        let result = diff(&from, &to);
+        assert_eq!(result.entries.len(), 2);
+    }
+
+    #[test]
+    fn case_only_rename_with_same_size_not_reported() {
+        let from = [project("MyApp", "/code/a", 100)];
+        let to = [project("myapp", "/code/a", 100)];
+        let result = compare_entries(&from, &to, 1, 2, 0, 100, true);
+        assert!(result.entries.is_empty());
+    }
+
+    #[test]
+    fn names_that_normalize_alike_in_one_snapshot_stay_apart() {
+        let from = [
+            project("MyApp", "/code/a", 100),
+            project("myapp", "/code/b", 300),
+        ];
+        let to = [
+            project("MyApp", "/code/a", 150),
+            project("myapp", "/code/b", 300),
+        ];
+        let result = compare_entries(&from, &to, 1, 2, 0, 100, true);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].name, "MyApp");
+        assert_eq!(result.entries[0].delta, 50);
+        assert_eq!(result.net_change, 50);
+    }
+
+    #[test]
+    fn aggregate_names_stay_case_sensitive() {
+        let aggregate = |name: &str, size| BloatEntry {
+            location: Location::Aggregate(name.to_string()),
+            ..entry(name, size)
+        };
+        let from = [aggregate("Docker images", 100)];
+        let to = [aggregate("docker images", 200)];
+        let result = compare_entries(&from, &to, 1, 2, 0, 100, true);
+        assert_eq!(result.entries.len(), 2);
+    }
+
+    // ── fuzzy matching ───────────────────────────────────────────────────────
+
+    fn project(name: &str, path: &str, size: u64) -> BloatEntry {
+        BloatEntry {
+            category: BloatCategory::ProjectArtifacts,
+            location: Location::FilesystemPath(PathBuf::from(path)),
+            ..entry(name, size)
+        }
+    }
+
+    fn fuzzy(from: &[BloatEntry], to: &[BloatEntry]) -> DiffResult {
+        let mut result = diff(from, to);
+        pair_renamed(&mut result, from, to);
+        result
+    }
+
+    #[test]
+    fn renamed_project_paired_by_similar_name() {
+        let from = [project(
+            "checkout-api",
+            "/code/checkout-api/node_modules",
+            100,
+        )];
+        let to = [project(
+            "checkout-api-v2",
+            "/code/checkout-api-v2/node_modules",
+            150,
+        )];
+
+        let result = fuzzy(&from, &to);
+        assert_eq!(result.entries.len(), 1);
+        let entry = &result.entries[0];
+        assert_eq!(entry.diff_type, DiffType::Grew);
+        assert_eq!(
+            (entry.old_size, entry.new_size, entry.delta),
+            (100, 150, 50)
+        );
+        assert_eq!(entry.label(), "checkout-api-v2 (was checkout-api)");
+        assert_eq!(result.net_change, 50);
+    }
+
+    #[test]
+    fn renamed_project_paired_by_identical_path() {
+        // package.json name changed, directory didn't
+        let from = [project("web", "/code/site/node_modules", 300)];
+        let to = [project("marketing-site", "/code/site/node_modules", 200)];
+
+        let result = fuzzy(&from, &to);
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.entries[0].diff_type, DiffType::Shrank);
+        assert_eq!(result.entries[0].renamed_from.as_deref(), Some("web"));
+    }
+
+    #[test]
+    fn genuinely_new_project_not_paired() {
+        let from = [project("billing", "/code/billing/target", 100)];
+        let to = [project("dashboard", "/code/dashboard/node_modules", 100)];
+
+        let result = fuzzy(&from, &to);
+        let mut types: Vec<_> = result.entries.iter().map(|e| e.diff_type.clone()).collect();
+        types.sort_by_key(|t| format!("{t:?}"));
+        assert_eq!(types, [DiffType::Gone, DiffType::New]);
+        assert!(result.entries.iter().all(|e| e.renamed_from.is_none()));
+        // and without --fuzzy, renames stay as gone + new
+        let plain = diff(
+            &[project("checkout-api", "/a", 1)],
+            &[project("checkout-api-v2", "/b", 2)],
+        );
+        assert_eq!(plain.entries.len(), 2);
+    }
+
+    #[test]
+    fn growth_beyond_tolerance() {
+        let result = diff(
+            &[entry("npm cache", 1000), entry("pip cache", 1000)],
+            &[
+                entry("npm cache", 5000),
+                entry("pip cache", 1100),
+                entry("go modules", 300),
+            ],
+        );
+        let names = |t| {
+            growth_beyond(&result, t)
+                .iter()
+                .map(|e| e.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(0), ["npm cache", "go modules", "pip cache"]);
+        assert_eq!(names(200), ["npm cache", "go modules"]);
+        assert!(names(10_000).is_empty());
+    }
+
+    #[test]
+    fn levenshtein_distances() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
 }
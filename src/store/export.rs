@@ -0,0 +1,449 @@
+//! Snapshot export and import.
+//!
+//! Serializes a snapshot's metadata and entries to a versioned JSON document,
+//! optionally compressed. The compression is picked from the file extension:
+//! - `.gz` → gzip
+//! - `.zst` / `.zstd` → zstd
+//! - anything else → plain JSON
+//!
+//! Import validates the format version and inserts the snapshot under a new id.
+//!
+//! Also provides the whole-database NDJSON dump used by `heft db export`.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::snapshot::{Snapshot, Store};
+use crate::scan::detector::BloatEntry;
+
+/// Bumped whenever the export layout changes. Version 2 added each
+/// entry's detector; version 1 files still import, without detectors.
+pub const EXPORT_FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+pub struct SnapshotExport {
+    pub format_version: u32,
+    pub snapshot: Snapshot,
+    pub entries: Vec<ExportEntry>,
+}
+
+impl SnapshotExport {
+    /// The entries as the store keeps them, detectors included.
+    pub fn bloat_entries(&self) -> Vec<BloatEntry> {
+        self.entries
+            .iter()
+            .map(|e| BloatEntry {
+                detector: e.detector.clone(),
+                ..e.entry.clone()
+            })
+            .collect()
+    }
+}
+
+/// An entry as exported: the scan JSON fields plus the detector that found
+/// it, which the scan output leaves out but snapshots keep.
+#[derive(Serialize, Deserialize)]
+pub struct ExportEntry {
+    #[serde(flatten)]
+    pub entry: BloatEntry,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detector: Option<String>,
+}
+
+impl From<BloatEntry> for ExportEntry {
+    fn from(entry: BloatEntry) -> Self {
+        ExportEntry {
+            detector: entry.detector.clone(),
+            entry,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Load a snapshot and its entries from the store into an export document.
+pub fn build_export(store: &Store, id: i64) -> Result<SnapshotExport, Box<dyn std::error::Error>> {
+    let snapshot = store
+        .get_snapshot(id)?
+        .ok_or_else(|| format!("snapshot {id} not found"))?;
+    let entries = store.load_snapshot_entries(id)?;
+
+    Ok(SnapshotExport {
+        format_version: EXPORT_FORMAT_VERSION,
+        snapshot,
+        entries: entries.into_iter().map(ExportEntry::from).collect(),
+    })
+}
+
+/// Serialize an export document to `writer`, compressing as requested.
+pub fn write_export<W: Write>(
+    export: &SnapshotExport,
+    writer: W,
+    compression: Compression,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match compression {
+        Compression::None => {
+            let mut writer = writer;
+            serde_json::to_writer_pretty(&mut writer, export)?;
+            writer.flush()?;
+        }
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+            serde_json::to_writer(&mut encoder, export)?;
+            encoder.finish()?.flush()?;
+        }
+        Compression::Zstd => {
+            let mut encoder = zstd::Encoder::new(writer, 0)?;
+            serde_json::to_writer(&mut encoder, export)?;
+            encoder.finish()?.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse an export document from `reader`, decompressing as requested.
+/// Rejects documents written with an unknown format version.
+pub fn read_export<R: Read>(
+    reader: R,
+    compression: Compression,
+) -> Result<SnapshotExport, Box<dyn std::error::Error>> {
+    export_from_value(read_value(reader, compression)?)
+}
+
+fn read_value<R: Read>(
+    reader: R,
+    compression: Compression,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    Ok(match compression {
+        Compression::None => serde_json::from_reader(reader)?,
+        Compression::Gzip => serde_json::from_reader(flate2::read::GzDecoder::new(reader))?,
+        Compression::Zstd => serde_json::from_reader(zstd::Decoder::new(reader)?)?,
+    })
+}
+
+fn export_from_value(
+    value: serde_json::Value,
+) -> Result<SnapshotExport, Box<dyn std::error::Error>> {
+    // check the version before the full parse so an incompatible file gets
+    // a clear message instead of a serde field error
+    let version = value
+        .get("format_version")
+        .and_then(|v| v.as_u64())
+        .ok_or("not a heft snapshot export (missing format_version)")?;
+    if version == 0 || version > u64::from(EXPORT_FORMAT_VERSION) {
+        return Err(format!(
+            "unsupported snapshot export version {version} (expected 1 to {EXPORT_FORMAT_VERSION})"
+        )
+        .into());
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Entries to compare a scan against (`scan --baseline`), and when they
+/// were recorded.
+pub struct Baseline {
+    pub entries: Vec<BloatEntry>,
+    pub timestamp: i64,
+}
+
+/// Parse a baseline: either a snapshot export (any compression) or the
+/// output of `heft scan --format json`. Scan output carries no timestamp,
+/// so the file's modification time stands in.
+pub fn read_baseline(path: &Path) -> Result<Baseline, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+    let modified = file
+        .metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64);
+    let value = read_value(BufReader::new(file), Compression::from_path(path))
+        .map_err(|e| format!("{} is not valid JSON: {e}", path.display()))?;
+    baseline_from_value(value, modified)
+}
+
+fn baseline_from_value(
+    value: serde_json::Value,
+    modified: i64,
+) -> Result<Baseline, Box<dyn std::error::Error>> {
+    if value.get("format_version").is_some() {
+        let export = export_from_value(value)?;
+        return Ok(Baseline {
+            entries: export.bloat_entries(),
+            timestamp: export.snapshot.timestamp,
+        });
+    }
+
+    // scan output: {"status": ..., "entries": [...], ...}
+    match (value.get("status"), value.get("entries")) {
+        (Some(_), Some(entries)) => {
+            let entries = Vec::<BloatEntry>::deserialize(entries)
+                .map_err(|e| format!("malformed baseline entries: {e}"))?;
+            Ok(Baseline {
+                entries,
+                timestamp: modified,
+            })
+        }
+        _ => Err(
+            "not a heft baseline (expected `heft scan --format json` output or a snapshot export)"
+                .into(),
+        ),
+    }
+}
+
+/// Export snapshot `id` to `path`, or to stdout as plain JSON when `path` is None.
+pub fn export_to(
+    store: &Store,
+    id: i64,
+    path: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let export = build_export(store, id)?;
+    match path {
+        Some(path) => {
+            let file = File::create(path)
+                .map_err(|e| format!("failed to create {}: {e}", path.display()))?;
+            write_export(&export, BufWriter::new(file), Compression::from_path(path))
+        }
+        None => write_export(&export, io::stdout().lock(), Compression::None),
+    }
+}
+
+/// Import the snapshot stored at `path`. Returns the newly assigned id.
+pub fn import_from(store: &mut Store, path: &Path) -> Result<i64, Box<dyn std::error::Error>> {
+    let file = File::open(path).map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+    let export = read_export(BufReader::new(file), Compression::from_path(path))?;
+    store.insert_snapshot(&export.snapshot, &export.bloat_entries())
+}
+
+/// Write every stored entry across all snapshots as newline-delimited JSON,
+/// one object per line. Rows are streamed, never collected. Returns the row count.
+pub fn write_entries_ndjson<W: Write>(
+    store: &Store,
+    writer: W,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut writer = writer;
+    let count = store.for_each_entry_row(|row| {
+        serde_json::to_writer(&mut writer, &row)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    })?;
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::detector::{BloatCategory, Location};
+    use crate::scan::ScanResult;
+    use std::path::PathBuf;
+
+    fn populated_store() -> (Store, i64) {
+        let mut store = Store::open_in_memory().unwrap();
+        let result = ScanResult {
+            entries: vec![BloatEntry {
+                category: BloatCategory::PackageCache,
+                name: "npm cache".to_string(),
+                location: Location::FilesystemPath(PathBuf::from("/tmp/npm")),
+                size_bytes: 1_000_000,
+                reclaimable_bytes: 1_000_000,
+                last_modified: None,
+                cleanup_hint: Some("npm cache clean --force".to_string()),
+                manual: false,
+                tags: Vec::new(),
+                detector: Some("caches".to_string()),
+                file_count: None,
+            }],
+            diagnostics: vec![],
+            duration_ms: Some(250),
+            detector_timings: vec![],
+            peak_memory_bytes: Some(4096),
+            detector_memory: vec![],
+            files_scanned: None,
+            disks: vec![],
+            coverage: vec![],
+        };
+        let id = store.save_snapshot(&result).unwrap();
+        (store, id)
+    }
+
+    fn roundtrip(compression: Compression) -> SnapshotExport {
+        let (store, id) = populated_store();
+        let export = build_export(&store, id).unwrap();
+        let mut buf = Vec::new();
+        write_export(&export, &mut buf, compression).unwrap();
+        read_export(buf.as_slice(), compression).unwrap()
+    }
+
+    #[test]
+    fn compression_from_extension() {
+        assert_eq!(
+            Compression::from_path(Path::new("snap.json.gz")),
+            Compression::Gzip
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("snap.json.zst")),
+            Compression::Zstd
+        );
+        assert_eq!(
+            Compression::from_path(Path::new("snap.json")),
+            Compression::None
+        );
+    }
+
+    #[test]
+    fn roundtrip_all_compressions() {
+        for compression in [Compression::None, Compression::Gzip, Compression::Zstd] {
+            let export = roundtrip(compression);
+            assert_eq!(export.entries.len(), 1, "{compression:?}");
+            assert_eq!(export.entries[0].entry.name, "npm cache");
+            assert_eq!(export.entries[0].detector.as_deref(), Some("caches"));
+            assert_eq!(export.snapshot.scan_duration_ms, 250);
+            assert_eq!(export.snapshot.peak_memory_bytes, Some(4096));
+        }
+    }
+
+    #[test]
+    fn import_assigns_new_id_and_keeps_timestamp() {
+        let (mut store, id) = populated_store();
+        let export = build_export(&store, id).unwrap();
+
+        let new_id = store
+            .insert_snapshot(&export.snapshot, &export.bloat_entries())
+            .unwrap();
+        assert_ne!(new_id, id);
+
+        let imported = store.get_snapshot(new_id).unwrap().unwrap();
+        assert_eq!(imported.timestamp, export.snapshot.timestamp);
+        assert_eq!(imported.total_bytes, 1_000_000);
+        assert_eq!(store.load_snapshot_entries(new_id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn detectors_survive_export_and_import() {
+        let (store, id) = populated_store();
+        let mut buf = Vec::new();
+        write_export(
+            &build_export(&store, id).unwrap(),
+            &mut buf,
+            Compression::None,
+        )
+        .unwrap();
+
+        let path = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        std::fs::write(path.path(), &buf).unwrap();
+        let mut other = Store::open_in_memory().unwrap();
+        let new_id = import_from(&mut other, path.path()).unwrap();
+
+        let entries = other.load_snapshot_entries(new_id).unwrap();
+        assert_eq!(entries[0].detector.as_deref(), Some("caches"));
+    }
+
+    #[test]
+    fn version_1_exports_import_without_detectors() {
+        let (store, id) = populated_store();
+        let mut export = serde_json::to_value(build_export(&store, id).unwrap()).unwrap();
+        export["format_version"] = serde_json::json!(1);
+        export["entries"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("detector");
+
+        let export = export_from_value(export).unwrap();
+        assert_eq!(export.bloat_entries()[0].name, "npm cache");
+        assert_eq!(export.bloat_entries()[0].detector, None);
+    }
+
+    #[test]
+    fn baseline_from_scan_json_or_export() {
+        let (store, id) = populated_store();
+        let result = ScanResult {
+            entries: store.load_snapshot_entries(id).unwrap(),
+            ..ScanResult::empty()
+        };
+        let scan_json: serde_json::Value =
+            serde_json::from_str(&crate::report::json::render_scored(&result, 0)).unwrap();
+        let baseline = baseline_from_value(scan_json, 42).unwrap();
+        assert_eq!(baseline.entries[0].name, "npm cache");
+        assert_eq!(baseline.timestamp, 42);
+
+        let export = serde_json::to_value(build_export(&store, id).unwrap()).unwrap();
+        let baseline = baseline_from_value(export.clone(), 42).unwrap();
+        assert_eq!(baseline.entries.len(), 1);
+        assert_ne!(baseline.timestamp, 42);
+
+        let mut future = export;
+        future["format_version"] = serde_json::json!(99);
+        let err = baseline_from_value(future, 0).err().unwrap().to_string();
+        assert!(err.contains("unsupported snapshot export version 99"));
+        let err = baseline_from_value(
+            serde_json::json!({"status": "found", "entries": [{"name": 1}]}),
+            0,
+        )
+        .err()
+        .unwrap()
+        .to_string();
+        assert!(err.starts_with("malformed baseline entries"));
+        assert!(baseline_from_value(serde_json::json!([1, 2]), 0).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let doc = r#"{"format_version": 99, "snapshot": {}, "entries": []}"#;
+        let err = read_export(doc.as_bytes(), Compression::None)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("unsupported snapshot export version 99"));
+    }
+
+    #[test]
+    fn rejects_non_export_json() {
+        let err = read_export(r#"{"entries": []}"#.as_bytes(), Compression::None)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("missing format_version"));
+    }
+
+    #[test]
+    fn ndjson_writes_one_object_per_entry() {
+        let (store, id) = populated_store();
+        let mut buf = Vec::new();
+        let count = write_entries_ndjson(&store, &mut buf).unwrap();
+        assert_eq!(count, 1);
+
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let row: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(row["snapshot_id"], id);
+        assert_eq!(row["name"], "npm cache");
+        assert_eq!(row["size_bytes"], 1_000_000);
+    }
+
+    #[test]
+    fn export_missing_snapshot_errors() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(build_export(&store, 42).is_err());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod diff;
+pub mod export;
 pub mod snapshot;
+pub mod stats;
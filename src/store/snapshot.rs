@@ -1,10 +1,13 @@
 use crate::scan::detector::{BloatCategory, BloatEntry, Location};
 use crate::scan::ScanResult;
-use rusqlite::{params, Connection};
-use std::path::PathBuf;
+use crate::util;
+use rusqlite::{params, Connection, ErrorCode, OpenFlags, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Snapshot metadata stored in database
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Snapshot {
     pub id: i64,
     pub timestamp: i64,
@@ -12,17 +15,71 @@ pub struct Snapshot {
     pub reclaimable_bytes: u64,
     pub scan_duration_ms: u64,
     pub peak_memory_bytes: Option<usize>,
+    /// Free-form annotation (`heft snapshot annotate`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Database location (~/.local/share/heft/heft.db or platform equivalent).
+/// Does not create anything; the file may not exist yet.
+pub fn db_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "heft").map(|dirs| dirs.data_dir().join("heft.db"))
 }
 
-/// Get the database path (~/.local/share/heft/heft.db or platform equivalent)
+/// Get the database path, creating the data directory if needed
+/// (unless in read-only mode)
 fn get_db_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let data_dir = directories::ProjectDirs::from("", "", "heft")
-        .ok_or("Could not determine data directory")?
-        .data_dir()
-        .to_path_buf();
+    let path = db_path().ok_or("Could not determine data directory")?;
+    if let Some(data_dir) = path.parent() {
+        if !util::read_only() {
+            std::fs::create_dir_all(data_dir)?;
+        }
+    }
+    Ok(path)
+}
+
+/// Error for writes attempted in read-only mode.
+fn ensure_writable() -> Result<(), Box<dyn std::error::Error>> {
+    if util::read_only() {
+        return Err("read-only mode: not writing to the snapshot database".into());
+    }
+    Ok(())
+}
 
-    std::fs::create_dir_all(&data_dir)?;
-    Ok(data_dir.join("heft.db"))
+/// The database's `-wal` and `-shm` sidecar files, which WAL mode keeps
+/// next to it.
+fn sidecars(path: &Path) -> [PathBuf; 2] {
+    ["-wal", "-shm"].map(|suffix| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    })
+}
+
+/// Bytes the database takes on disk: the file plus its sidecars. None when
+/// there is no database file.
+pub fn database_size(path: &Path) -> Option<u64> {
+    let main = std::fs::metadata(path).ok()?.len();
+    let sidecars: u64 = sidecars(path)
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum();
+    Some(main + sidecars)
+}
+
+/// Bytes a vacuum would give back: free pages sitting inside the file,
+/// plus the write-ahead log it checkpoints away. Opens the database
+/// read-only so probing never creates or modifies it.
+pub fn vacuum_reclaimable_bytes(path: &Path) -> rusqlite::Result<u64> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let free_pages: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    let [wal, _] = sidecars(path);
+    let wal_bytes = std::fs::metadata(wal).map_or(0, |m| m.len());
+    Ok((free_pages.max(0) as u64)
+        .saturating_mul(page_size.max(0) as u64)
+        .saturating_add(wal_bytes))
 }
 
 fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
@@ -33,7 +90,9 @@ fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
             total_bytes INTEGER NOT NULL,
             reclaimable_bytes INTEGER NOT NULL,
             scan_duration_ms INTEGER NOT NULL,
-            peak_memory_bytes INTEGER
+            peak_memory_bytes INTEGER,
+            note TEXT,
+            scan_config TEXT
         )",
         [],
     )?;
@@ -49,31 +108,162 @@ fn init_schema(conn: &Connection) -> rusqlite::Result<()> {
             reclaimable_bytes INTEGER NOT NULL,
             last_modified INTEGER,
             cleanup_hint TEXT,
+            tags TEXT,
+            manual INTEGER NOT NULL DEFAULT 0,
+            detector TEXT,
             FOREIGN KEY(snapshot_id) REFERENCES snapshots(id) ON DELETE CASCADE
         )",
         [],
     )?;
 
+    // pending cleanups from `clean --dry-run --save-plan`. Entries are copied
+    // rather than pointing into a snapshot, so pruning history never changes
+    // what a reviewer approved
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS plans (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at INTEGER NOT NULL,
+            executed_at INTEGER
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS plan_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            plan_id INTEGER NOT NULL,
+            category TEXT NOT NULL,
+            name TEXT NOT NULL,
+            location TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            reclaimable_bytes INTEGER NOT NULL,
+            last_modified INTEGER,
+            cleanup_hint TEXT,
+            tags TEXT,
+            manual INTEGER NOT NULL DEFAULT 0,
+            detector TEXT,
+            FOREIGN KEY(plan_id) REFERENCES plans(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // databases from before entry tags, snapshot notes, scan settings, the
+    // manual flag and entry detectors
+    if !has_column(conn, "entries", "tags") {
+        conn.execute("ALTER TABLE entries ADD COLUMN tags TEXT", [])?;
+    }
+    if !has_column(conn, "snapshots", "note") {
+        conn.execute("ALTER TABLE snapshots ADD COLUMN note TEXT", [])?;
+    }
+    if !has_column(conn, "snapshots", "scan_config") {
+        conn.execute("ALTER TABLE snapshots ADD COLUMN scan_config TEXT", [])?;
+    }
+    for table in ["entries", "plan_entries"] {
+        if !has_column(conn, table, "manual") {
+            conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN manual INTEGER NOT NULL DEFAULT 0"),
+                [],
+            )?;
+        }
+        if !has_column(conn, table, "detector") {
+            conn.execute(&format!("ALTER TABLE {table} ADD COLUMN detector TEXT"), [])?;
+        }
+    }
+
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_entries_snapshot_id ON entries(snapshot_id)",
         [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_plan_entries_plan_id ON plan_entries(plan_id)",
+        [],
+    )?;
 
     Ok(())
 }
 
+fn has_column(conn: &Connection, table: &str, column: &str) -> bool {
+    conn.prepare(&format!("SELECT {column} FROM {table} LIMIT 0"))
+        .is_ok()
+}
+
+/// Tags are stored comma-joined, NULL when there are none.
+fn join_tags(tags: &[String]) -> Option<String> {
+    (!tags.is_empty()).then(|| tags.join(","))
+}
+
+fn split_tags(stored: Option<String>) -> Vec<String> {
+    stored
+        .map(|s| s.split(',').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
 /// Database handle. Open once per command, reuse across all operations.
 pub struct Store {
     conn: Connection,
+    /// False for a pre-tags database opened read-only, which can't be migrated
+    tags_column: bool,
+    /// Likewise for a database from before snapshot notes
+    note_column: bool,
+    /// Likewise for a database from before the entries' manual flag
+    manual_column: bool,
+    /// Likewise for a database from before entries recorded their detector
+    detector_column: bool,
+}
+
+/// How long a connection waits on another process's lock before giving up.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Attempts at a snapshot write when the database stays locked past the busy timeout.
+const WRITE_ATTEMPTS: u32 = 3;
+
+fn is_busy(err: &rusqlite::Error) -> bool {
+    matches!(
+        err.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
 }
 
 impl Store {
     pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
-        let db_path = get_db_path()?;
-        let conn = Connection::open(db_path)?;
+        Self::open_at(&get_db_path()?)
+    }
+
+    /// Open (or create) the database at `path`. WAL mode lets a reader and a
+    /// writer overlap, and the busy timeout makes a second writer wait instead
+    /// of failing with "database is locked" (e.g. cron scan + manual scan).
+    ///
+    /// In read-only mode an existing database is opened read-only as is, and
+    /// a missing one is an error rather than created.
+    pub fn open_at(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if util::read_only() {
+            let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+            conn.busy_timeout(BUSY_TIMEOUT)?;
+            let tags_column = has_column(&conn, "entries", "tags");
+            let note_column = has_column(&conn, "snapshots", "note");
+            let manual_column = has_column(&conn, "entries", "manual");
+            let detector_column = has_column(&conn, "entries", "detector");
+            return Ok(Store {
+                conn,
+                tags_column,
+                note_column,
+                manual_column,
+                detector_column,
+            });
+        }
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        // journal_mode returns the resulting mode as a row
+        let _mode: String = conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
         init_schema(&conn)?;
-        Ok(Store { conn })
+        Ok(Store {
+            conn,
+            tags_column: true,
+            note_column: true,
+            manual_column: true,
+            detector_column: true,
+        })
     }
 
     #[cfg(test)]
@@ -81,7 +271,25 @@ impl Store {
         let conn = Connection::open_in_memory()?;
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
         init_schema(&conn)?;
-        Ok(Store { conn })
+        Ok(Store {
+            conn,
+            tags_column: true,
+            note_column: true,
+            manual_column: true,
+            detector_column: true,
+        })
+    }
+
+    /// Rebuild the database file, returning the free pages to the filesystem,
+    /// and empty the write-ahead log the rebuild went through
+    pub fn vacuum(&self) -> Result<(), Box<dyn std::error::Error>> {
+        ensure_writable()?;
+        self.conn.execute_batch("VACUUM;")?;
+        // the checkpoint reports (busy, log pages, checkpointed pages) as a row
+        let _busy: i64 = self
+            .conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))?;
+        Ok(())
     }
 
     /// Save a scan result as a snapshot
@@ -104,47 +312,66 @@ impl Store {
             .duration_since(std::time::UNIX_EPOCH)?
             .as_secs() as i64;
 
-        let tx = self.conn.transaction()?;
+        let snapshot = Snapshot {
+            id: 0,
+            timestamp,
+            total_bytes,
+            reclaimable_bytes,
+            scan_duration_ms: result.duration_ms.unwrap_or(0),
+            peak_memory_bytes: result.peak_memory_bytes,
+            note: None,
+        };
+
+        self.insert_snapshot(&snapshot, &result.entries)
+    }
+
+    /// Insert snapshot metadata and its entries, assigning a new id.
+    /// The id on `snapshot` is ignored; everything else is stored as given.
+    ///
+    /// Retried a few times if another process holds the write lock longer
+    /// than the busy timeout.
+    pub fn insert_snapshot(
+        &mut self,
+        snapshot: &Snapshot,
+        entries: &[BloatEntry],
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        ensure_writable()?;
+        let mut attempt = 1;
+        loop {
+            match self.try_insert_snapshot(snapshot, entries) {
+                Err(e) if is_busy(&e) && attempt < WRITE_ATTEMPTS => attempt += 1,
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    fn try_insert_snapshot(
+        &mut self,
+        snapshot: &Snapshot,
+        entries: &[BloatEntry],
+    ) -> rusqlite::Result<i64> {
+        // take the write lock up front so the busy handler can wait for it;
+        // a deferred transaction upgrading mid-way fails immediately instead
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
 
         tx.execute(
-            "INSERT INTO snapshots (timestamp, total_bytes, reclaimable_bytes, scan_duration_ms, peak_memory_bytes)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO snapshots (timestamp, total_bytes, reclaimable_bytes, scan_duration_ms, peak_memory_bytes, note)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
-                timestamp,
-                i64::try_from(total_bytes).unwrap_or(i64::MAX),
-                i64::try_from(reclaimable_bytes).unwrap_or(i64::MAX),
-                i64::try_from(result.duration_ms.unwrap_or(0)).unwrap_or(i64::MAX),
-                result.peak_memory_bytes.map(|m| i64::try_from(m).unwrap_or(i64::MAX))
+                snapshot.timestamp,
+                i64::try_from(snapshot.total_bytes).unwrap_or(i64::MAX),
+                i64::try_from(snapshot.reclaimable_bytes).unwrap_or(i64::MAX),
+                i64::try_from(snapshot.scan_duration_ms).unwrap_or(i64::MAX),
+                snapshot.peak_memory_bytes.map(|m| i64::try_from(m).unwrap_or(i64::MAX)),
+                snapshot.note
             ],
         )?;
 
         let snapshot_id = tx.last_insert_rowid();
 
-        let mut stmt = tx.prepare_cached(
-            "INSERT INTO entries (snapshot_id, category, name, location, size_bytes, reclaimable_bytes, last_modified, cleanup_hint)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
-        )?;
-
-        for entry in &result.entries {
-            let location_str = match &entry.location {
-                Location::FilesystemPath(p) => p.to_string_lossy().to_string(),
-                Location::DockerObject(name) => format!("docker:{name}"),
-                Location::Aggregate(name) => format!("aggregate:{name}"),
-            };
-
-            stmt.execute(params![
-                snapshot_id,
-                entry.category.as_str(),
-                entry.name,
-                location_str,
-                i64::try_from(entry.size_bytes).unwrap_or(i64::MAX),
-                i64::try_from(entry.reclaimable_bytes).unwrap_or(i64::MAX),
-                entry.last_modified,
-                entry.cleanup_hint.as_deref()
-            ])?;
-        }
-
-        drop(stmt);
+        insert_entries(&tx, "entries", "snapshot_id", snapshot_id, entries)?;
         tx.commit()?;
 
         Ok(snapshot_id)
@@ -152,14 +379,31 @@ impl Store {
 
     /// List all snapshots
     pub fn list_snapshots(&self) -> Result<Vec<Snapshot>, Box<dyn std::error::Error>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, timestamp, total_bytes, reclaimable_bytes, scan_duration_ms, peak_memory_bytes
+        self.list_snapshots_filtered(&SnapshotFilter::default())
+    }
+
+    /// List snapshots newest first, restricted to `filter`'s time window and limit.
+    pub fn list_snapshots_filtered(
+        &self,
+        filter: &SnapshotFilter,
+    ) -> Result<Vec<Snapshot>, Box<dyn std::error::Error>> {
+        // NULL bounds disable the comparison; a negative LIMIT means no limit in sqlite
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
              FROM snapshots
-             ORDER BY timestamp DESC, id DESC"
-        )?;
+             WHERE (?1 IS NULL OR timestamp >= ?1)
+               AND (?2 IS NULL OR timestamp < ?2)
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?3",
+            self.snapshot_columns()
+        ))?;
 
+        let limit = filter.limit.map_or(-1, |n| n.min(i64::MAX as usize) as i64);
         let snapshots = stmt
-            .query_map([], snapshot_from_row)?
+            .query_map(
+                params![filter.since, filter.until, limit],
+                snapshot_from_row,
+            )?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(snapshots)
@@ -167,11 +411,12 @@ impl Store {
 
     /// Get a specific snapshot by ID
     pub fn get_snapshot(&self, id: i64) -> Result<Option<Snapshot>, Box<dyn std::error::Error>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, timestamp, total_bytes, reclaimable_bytes, scan_duration_ms, peak_memory_bytes
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
              FROM snapshots
-             WHERE id = ?1"
-        )?;
+             WHERE id = ?1",
+            self.snapshot_columns()
+        ))?;
 
         let mut rows = stmt.query(params![id])?;
 
@@ -184,12 +429,13 @@ impl Store {
 
     /// Get the most recent snapshot
     pub fn get_latest_snapshot(&self) -> Result<Option<Snapshot>, Box<dyn std::error::Error>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, timestamp, total_bytes, reclaimable_bytes, scan_duration_ms, peak_memory_bytes
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}
              FROM snapshots
              ORDER BY timestamp DESC, id DESC
-             LIMIT 1"
-        )?;
+             LIMIT 1",
+            self.snapshot_columns()
+        ))?;
 
         let mut rows = stmt.query([])?;
 
@@ -200,55 +446,381 @@ impl Store {
         }
     }
 
+    /// Number of entries stored for a snapshot
+    pub fn entry_count(&self, snapshot_id: i64) -> Result<u64, Box<dyn std::error::Error>> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM entries WHERE snapshot_id = ?1",
+            params![snapshot_id],
+            |row| row.get(0),
+        )?;
+        Ok(count.max(0) as u64)
+    }
+
+    /// Delete every snapshot taken before `timestamp`, with their entries
+    /// (the foreign key cascades). Returns how many snapshots went.
+    pub fn delete_snapshots_before(
+        &self,
+        timestamp: i64,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        ensure_writable()?;
+        let deleted = self.conn.execute(
+            "DELETE FROM snapshots WHERE timestamp < ?1",
+            params![timestamp],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Stream every entry of every snapshot, oldest snapshot first, without
+    /// collecting them. Stops at the first error returned by `f`.
+    pub fn for_each_entry_row<F>(&self, mut f: F) -> Result<u64, Box<dyn std::error::Error>>
+    where
+        F: FnMut(EntryRow) -> Result<(), Box<dyn std::error::Error>>,
+    {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT s.id, s.timestamp, e.category, e.name, e.location, e.size_bytes,
+                    e.reclaimable_bytes, e.last_modified, e.cleanup_hint, {}
+             FROM entries e
+             JOIN snapshots s ON s.id = e.snapshot_id
+             ORDER BY s.timestamp ASC, s.id ASC, e.id ASC",
+            self.tags_select("e.tags")
+        ))?;
+
+        let mut rows = stmt.query([])?;
+        let mut count = 0u64;
+        while let Some(row) = rows.next()? {
+            f(EntryRow {
+                snapshot_id: row.get(0)?,
+                timestamp: row.get(1)?,
+                category: row.get(2)?,
+                name: row.get(3)?,
+                location: row.get(4)?,
+                size_bytes: row.get::<_, i64>(5)?.max(0) as u64,
+                reclaimable_bytes: row.get::<_, i64>(6)?.max(0) as u64,
+                last_modified: row.get(7)?,
+                cleanup_hint: row.get(8)?,
+                tags: split_tags(row.get(9)?),
+            })?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Save the entries a dry run selected as a pending cleanup plan,
+    /// returning its id. A plan with nothing in it is refused.
+    pub fn save_plan(&mut self, entries: &[BloatEntry]) -> Result<i64, Box<dyn std::error::Error>> {
+        ensure_writable()?;
+        if entries.is_empty() {
+            return Err("nothing selected, so there is no plan to save".into());
+        }
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() as i64;
+
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
+        tx.execute(
+            "INSERT INTO plans (created_at) VALUES (?1)",
+            params![created_at],
+        )?;
+        let plan_id = tx.last_insert_rowid();
+        insert_entries(&tx, "plan_entries", "plan_id", plan_id, entries)?;
+        tx.commit()?;
+
+        Ok(plan_id)
+    }
+
+    /// All saved plans, newest first
+    pub fn list_plans(&self) -> Result<Vec<Plan>, Box<dyn std::error::Error>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{PLAN_SELECT}
+             GROUP BY p.id
+             ORDER BY p.created_at DESC, p.id DESC"
+        ))?;
+
+        let plans = stmt
+            .query_map([], plan_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(plans)
+    }
+
+    /// Get a plan by ID
+    pub fn get_plan(&self, plan_id: i64) -> Result<Option<Plan>, Box<dyn std::error::Error>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!(
+                    "{PLAN_SELECT}
+                     WHERE p.id = ?1
+                     GROUP BY p.id"
+                ),
+                params![plan_id],
+                plan_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Load the entries of a saved plan, in the order they were saved
+    pub fn load_plan_entries(
+        &self,
+        plan_id: i64,
+    ) -> Result<Vec<BloatEntry>, Box<dyn std::error::Error>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT category, name, location, size_bytes, reclaimable_bytes, last_modified, cleanup_hint, tags, {}, {}
+             FROM plan_entries
+             WHERE plan_id = ?1
+             ORDER BY id ASC",
+            self.manual_select(),
+            self.detector_select()
+        ))?;
+        let entries = stmt
+            .query_map(params![plan_id], entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Record that a plan has been run, so it isn't approved twice
+    pub fn mark_plan_executed(&self, plan_id: i64) -> Result<(), Box<dyn std::error::Error>> {
+        ensure_writable()?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() as i64;
+        self.conn.execute(
+            "UPDATE plans SET executed_at = ?1 WHERE id = ?2",
+            params![now, plan_id],
+        )?;
+        Ok(())
+    }
+
+    /// Record the settings the scan behind snapshot `id` ran with
+    /// (`Config::scan_key`), so `clean --use-recent` can tell whether the
+    /// snapshot matches the scan it would run.
+    pub fn set_scan_config(&self, id: i64, key: &str) -> Result<(), Box<dyn std::error::Error>> {
+        ensure_writable()?;
+        self.conn.execute(
+            "UPDATE snapshots SET scan_config = ?1 WHERE id = ?2",
+            params![key, id],
+        )?;
+        Ok(())
+    }
+
+    /// The settings recorded by `set_scan_config`; None for snapshots saved
+    /// without them (imports, older versions).
+    pub fn scan_config(&self, id: i64) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let key = self
+            .conn
+            .query_row(
+                "SELECT scan_config FROM snapshots WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(key.flatten())
+    }
+
+    /// Set or replace the note on snapshot `id`; an empty note removes it.
+    /// False when there is no such snapshot.
+    pub fn set_note(&self, id: i64, note: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        ensure_writable()?;
+        let note = note.trim();
+        let changed = self.conn.execute(
+            "UPDATE snapshots SET note = ?1 WHERE id = ?2",
+            params![(!note.is_empty()).then_some(note), id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Columns `snapshot_from_row` reads, with NULL for the note on a
+    /// pre-notes database
+    fn snapshot_columns(&self) -> String {
+        format!(
+            "id, timestamp, total_bytes, reclaimable_bytes, scan_duration_ms, peak_memory_bytes, {}",
+            if self.note_column { "note" } else { "NULL" }
+        )
+    }
+
+    /// The tags column for a SELECT, or NULL on a pre-tags database
+    fn tags_select(&self, column: &'static str) -> &'static str {
+        if self.tags_column {
+            column
+        } else {
+            "NULL"
+        }
+    }
+
+    /// The manual column for a SELECT, or 0 on a pre-manual database
+    fn manual_select(&self) -> &'static str {
+        if self.manual_column {
+            "manual"
+        } else {
+            "0"
+        }
+    }
+
+    /// The detector column for a SELECT, or NULL on a pre-detector database
+    fn detector_select(&self) -> &'static str {
+        if self.detector_column {
+            "detector"
+        } else {
+            "NULL"
+        }
+    }
+
     /// Load entries for a specific snapshot
     pub fn load_snapshot_entries(
         &self,
         snapshot_id: i64,
     ) -> Result<Vec<BloatEntry>, Box<dyn std::error::Error>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT category, name, location, size_bytes, reclaimable_bytes, last_modified, cleanup_hint
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT category, name, location, size_bytes, reclaimable_bytes, last_modified, cleanup_hint, {}, {}, {}
              FROM entries
-             WHERE snapshot_id = ?1"
-        )?;
+             WHERE snapshot_id = ?1",
+            self.tags_select("tags"),
+            self.manual_select(),
+            self.detector_select()
+        ))?;
 
         let entries = stmt
-            .query_map(params![snapshot_id], |row| {
-                let category_str: String = row.get(0)?;
-                let location_str: String = row.get(2)?;
-
-                let location = if let Some(docker_name) = location_str.strip_prefix("docker:") {
-                    Location::DockerObject(docker_name.to_string())
-                } else if let Some(agg_name) = location_str.strip_prefix("aggregate:") {
-                    Location::Aggregate(agg_name.to_string())
-                } else {
-                    Location::FilesystemPath(PathBuf::from(location_str))
-                };
-
-                let category = match category_str.as_str() {
-                    "ProjectArtifacts" => BloatCategory::ProjectArtifacts,
-                    "ContainerData" => BloatCategory::ContainerData,
-                    "PackageCache" => BloatCategory::PackageCache,
-                    "IdeData" => BloatCategory::IdeData,
-                    "SystemCache" => BloatCategory::SystemCache,
-                    _ => BloatCategory::Other,
-                };
-
-                Ok(BloatEntry {
-                    category,
-                    name: row.get(1)?,
-                    location,
-                    size_bytes: row.get::<_, i64>(3)?.max(0) as u64,
-                    reclaimable_bytes: row.get::<_, i64>(4)?.max(0) as u64,
-                    last_modified: row.get(5)?,
-                    cleanup_hint: row.get(6)?,
-                })
-            })?
+            .query_map(params![snapshot_id], entry_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(entries)
     }
 }
 
+/// A saved cleanup plan awaiting review, as listed by `clean --list-plans`.
+#[derive(Debug)]
+pub struct Plan {
+    pub id: i64,
+    pub created_at: i64,
+    /// When `clean --execute-plan` ran it; None while pending
+    pub executed_at: Option<i64>,
+    pub entry_count: u64,
+    pub reclaimable_bytes: u64,
+}
+
+/// Time window and count limit for listing snapshots. Bounds are unix
+/// timestamps; `since` is inclusive, `until` exclusive.
+#[derive(Debug, Default, Clone)]
+pub struct SnapshotFilter {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// One entry joined with its snapshot, as stored in the database.
+/// Location keeps its stored text form (`/path`, `docker:id`, `aggregate:name`).
+#[derive(Debug, Serialize)]
+pub struct EntryRow {
+    pub snapshot_id: i64,
+    pub timestamp: i64,
+    pub category: String,
+    pub name: String,
+    pub location: String,
+    pub size_bytes: u64,
+    pub reclaimable_bytes: u64,
+    pub last_modified: Option<i64>,
+    pub cleanup_hint: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Write `entries` into `table` (entries or plan_entries), each pointing at
+/// `owner_id` through `owner_column`.
+fn insert_entries(
+    tx: &rusqlite::Transaction,
+    table: &str,
+    owner_column: &str,
+    owner_id: i64,
+    entries: &[BloatEntry],
+) -> rusqlite::Result<()> {
+    let mut stmt = tx.prepare_cached(&format!(
+        "INSERT INTO {table} ({owner_column}, category, name, location, size_bytes, reclaimable_bytes, last_modified, cleanup_hint, tags, manual, detector)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
+    ))?;
+
+    for entry in entries {
+        let location_str = match &entry.location {
+            Location::FilesystemPath(p) => p.to_string_lossy().to_string(),
+            Location::DockerObject(name) => format!("docker:{name}"),
+            Location::Aggregate(name) => format!("aggregate:{name}"),
+        };
+
+        stmt.execute(params![
+            owner_id,
+            entry.category.as_str(),
+            entry.name,
+            location_str,
+            i64::try_from(entry.size_bytes).unwrap_or(i64::MAX),
+            i64::try_from(entry.reclaimable_bytes).unwrap_or(i64::MAX),
+            entry.last_modified,
+            entry.cleanup_hint.as_deref(),
+            join_tags(&entry.tags),
+            entry.manual,
+            entry.detector.as_deref()
+        ])?;
+    }
+
+    Ok(())
+}
+
+/// Plans with their entry count and reclaimable total, read by `plan_from_row`.
+const PLAN_SELECT: &str =
+    "SELECT p.id, p.created_at, p.executed_at, COUNT(e.id), COALESCE(SUM(e.reclaimable_bytes), 0)
+     FROM plans p
+     LEFT JOIN plan_entries e ON e.plan_id = p.id";
+
+fn plan_from_row(row: &rusqlite::Row) -> rusqlite::Result<Plan> {
+    Ok(Plan {
+        id: row.get(0)?,
+        created_at: row.get(1)?,
+        executed_at: row.get(2)?,
+        entry_count: row.get::<_, i64>(3)?.max(0) as u64,
+        reclaimable_bytes: row.get::<_, i64>(4)?.max(0) as u64,
+    })
+}
+
+/// An entry from `category, name, location, size_bytes, reclaimable_bytes,
+/// last_modified, cleanup_hint, tags, manual, detector`, in that order.
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<BloatEntry> {
+    let category_str: String = row.get(0)?;
+    let location_str: String = row.get(2)?;
+
+    let location = if let Some(docker_name) = location_str.strip_prefix("docker:") {
+        Location::DockerObject(docker_name.to_string())
+    } else if let Some(agg_name) = location_str.strip_prefix("aggregate:") {
+        Location::Aggregate(agg_name.to_string())
+    } else {
+        Location::FilesystemPath(PathBuf::from(location_str))
+    };
+
+    let category = match category_str.as_str() {
+        "ProjectArtifacts" => BloatCategory::ProjectArtifacts,
+        "ContainerData" => BloatCategory::ContainerData,
+        "PackageCache" => BloatCategory::PackageCache,
+        "IdeData" => BloatCategory::IdeData,
+        "SystemCache" => BloatCategory::SystemCache,
+        _ => BloatCategory::Other,
+    };
+
+    Ok(BloatEntry {
+        category,
+        name: row.get(1)?,
+        location,
+        size_bytes: row.get::<_, i64>(3)?.max(0) as u64,
+        reclaimable_bytes: row.get::<_, i64>(4)?.max(0) as u64,
+        last_modified: row.get(5)?,
+        cleanup_hint: row.get(6)?,
+        manual: row.get(8)?,
+        tags: split_tags(row.get(7)?),
+        detector: row.get(9)?,
+        file_count: None,
+    })
+}
+
 fn snapshot_from_row(row: &rusqlite::Row) -> rusqlite::Result<Snapshot> {
     Ok(Snapshot {
         id: row.get(0)?,
@@ -257,6 +829,7 @@ fn snapshot_from_row(row: &rusqlite::Row) -> rusqlite::Result<Snapshot> {
         reclaimable_bytes: row.get::<_, i64>(3)?.max(0) as u64,
         scan_duration_ms: row.get::<_, i64>(4)?.max(0) as u64,
         peak_memory_bytes: row.get::<_, Option<i64>>(5)?.map(|m| m.max(0) as usize),
+        note: row.get(6)?,
     })
 }
 
@@ -274,6 +847,10 @@ mod tests {
             reclaimable_bytes: size,
             last_modified: None,
             cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
         }
     }
 
@@ -285,6 +862,9 @@ mod tests {
             peak_memory_bytes: None,
             detector_timings: vec![],
             detector_memory: vec![],
+            files_scanned: None,
+            disks: vec![],
+            coverage: vec![],
         }
     }
 
@@ -318,6 +898,84 @@ mod tests {
         assert_eq!(snapshots[0].id, id);
     }
 
+    #[test]
+    fn list_filters_by_time_window_and_limit() {
+        let mut store = Store::open_in_memory().unwrap();
+        for timestamp in [100, 200, 300, 400] {
+            let snapshot = Snapshot {
+                id: 0,
+                timestamp,
+                total_bytes: 0,
+                reclaimable_bytes: 0,
+                scan_duration_ms: 0,
+                peak_memory_bytes: None,
+                note: None,
+            };
+            store.insert_snapshot(&snapshot, &[]).unwrap();
+        }
+
+        let timestamps = |filter: SnapshotFilter| -> Vec<i64> {
+            store
+                .list_snapshots_filtered(&filter)
+                .unwrap()
+                .iter()
+                .map(|s| s.timestamp)
+                .collect()
+        };
+
+        assert_eq!(
+            timestamps(SnapshotFilter {
+                since: Some(200),
+                until: Some(400),
+                limit: None,
+            }),
+            vec![300, 200]
+        );
+        assert_eq!(
+            timestamps(SnapshotFilter {
+                limit: Some(1),
+                ..Default::default()
+            }),
+            vec![400]
+        );
+        assert_eq!(timestamps(SnapshotFilter::default()).len(), 4);
+    }
+
+    #[test]
+    fn delete_before_removes_older_snapshots_and_their_entries() {
+        let mut store = Store::open_in_memory().unwrap();
+        let mut ids = Vec::new();
+        for timestamp in [100, 200, 300] {
+            let snapshot = Snapshot {
+                id: 0,
+                timestamp,
+                total_bytes: 0,
+                reclaimable_bytes: 0,
+                scan_duration_ms: 0,
+                peak_memory_bytes: None,
+                note: None,
+            };
+            ids.push(
+                store
+                    .insert_snapshot(&snapshot, &[make_entry("npm cache", 10)])
+                    .unwrap(),
+            );
+        }
+        assert_eq!(store.entry_count(ids[0]).unwrap(), 1);
+
+        assert_eq!(store.delete_snapshots_before(300).unwrap(), 2);
+
+        let left: Vec<i64> = store
+            .list_snapshots()
+            .unwrap()
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(left, vec![ids[2]]);
+        assert_eq!(store.entry_count(ids[0]).unwrap(), 0);
+        assert_eq!(store.entry_count(ids[2]).unwrap(), 1);
+    }
+
     #[test]
     fn snapshot_totals_computed_correctly() {
         let mut store = Store::open_in_memory().unwrap();
@@ -345,6 +1003,142 @@ mod tests {
         assert!(names.contains(&"cargo"));
     }
 
+    #[test]
+    fn tags_manual_flag_and_detector_roundtrip_and_old_databases_migrate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heft.db");
+        // an entries table from before tags existed
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE entries (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    snapshot_id INTEGER NOT NULL,
+                    category TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    location TEXT NOT NULL,
+                    size_bytes INTEGER NOT NULL,
+                    reclaimable_bytes INTEGER NOT NULL,
+                    last_modified INTEGER,
+                    cleanup_hint TEXT
+                );",
+            )
+            .unwrap();
+
+        let mut store = Store::open_at(&path).unwrap();
+        let mut tagged = make_entry("cargo registry", 100);
+        tagged.tags = vec!["rust".to_string(), "package-manager".to_string()];
+        tagged.detector = Some("caches".to_string());
+        let mut manual = make_entry("docker desktop VM disk", 50);
+        manual.manual = true;
+        let id = store
+            .save_snapshot(&make_result(vec![tagged, manual.clone()]))
+            .unwrap();
+
+        let loaded = store.load_snapshot_entries(id).unwrap();
+        assert_eq!(loaded[0].tags, ["rust", "package-manager"]);
+        assert!(loaded[1].tags.is_empty());
+        assert!(!loaded[0].manual);
+        assert!(loaded[1].manual);
+        assert_eq!(loaded[0].detector.as_deref(), Some("caches"));
+        assert_eq!(loaded[1].detector, None);
+
+        let plan = store.save_plan(&[manual, loaded[0].clone()]).unwrap();
+        let planned = store.load_plan_entries(plan).unwrap();
+        assert!(planned[0].manual);
+        assert_eq!(planned[1].detector.as_deref(), Some("caches"));
+    }
+
+    #[test]
+    fn scan_config_recorded_per_snapshot() {
+        let mut store = Store::open_in_memory().unwrap();
+        let first = store
+            .save_snapshot(&make_result(vec![make_entry("npm cache", 10)]))
+            .unwrap();
+        let second = store
+            .save_snapshot(&make_result(vec![make_entry("npm cache", 10)]))
+            .unwrap();
+        store.set_scan_config(second, "roots=[/srv/code]").unwrap();
+
+        assert_eq!(store.scan_config(first).unwrap(), None);
+        assert_eq!(
+            store.scan_config(second).unwrap().as_deref(),
+            Some("roots=[/srv/code]")
+        );
+        assert_eq!(store.scan_config(second + 1).unwrap(), None);
+    }
+
+    #[test]
+    fn notes_set_replaced_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heft.db");
+        // a snapshots table from before notes existed
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE snapshots (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    timestamp INTEGER NOT NULL,
+                    total_bytes INTEGER NOT NULL,
+                    reclaimable_bytes INTEGER NOT NULL,
+                    scan_duration_ms INTEGER NOT NULL,
+                    peak_memory_bytes INTEGER
+                );",
+            )
+            .unwrap();
+
+        let mut store = Store::open_at(&path).unwrap();
+        let id = store
+            .save_snapshot(&make_result(vec![make_entry("npm cache", 10)]))
+            .unwrap();
+        assert_eq!(store.get_snapshot(id).unwrap().unwrap().note, None);
+
+        assert!(store.set_note(id, "before the big cleanup").unwrap());
+        assert!(store.set_note(id, " after all ").unwrap());
+        assert_eq!(
+            store.get_snapshot(id).unwrap().unwrap().note.as_deref(),
+            Some("after all")
+        );
+        assert_eq!(
+            store.list_snapshots().unwrap()[0].note.as_deref(),
+            Some("after all")
+        );
+
+        assert!(store.set_note(id, "").unwrap());
+        assert_eq!(store.get_latest_snapshot().unwrap().unwrap().note, None);
+        assert!(!store.set_note(id + 1, "nothing here").unwrap());
+    }
+
+    #[test]
+    fn plans_saved_listed_and_marked_executed() {
+        let mut store = Store::open_in_memory().unwrap();
+        let mut registry = make_entry("cargo registry", 300);
+        registry.tags = vec!["rust".to_string()];
+        let id = store
+            .save_plan(&[registry, make_entry("npm cache", 200)])
+            .unwrap();
+
+        let plans = store.list_plans().unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].entry_count, 2);
+        assert_eq!(plans[0].reclaimable_bytes, 500);
+        assert_eq!(plans[0].executed_at, None);
+
+        let entries = store.load_plan_entries(id).unwrap();
+        let names: Vec<_> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["cargo registry", "npm cache"]);
+        assert_eq!(entries[0].tags, ["rust"]);
+
+        store.mark_plan_executed(id).unwrap();
+        let plan = store.get_plan(id).unwrap().unwrap();
+        assert!(plan.executed_at.is_some());
+        assert_eq!(plan.entry_count, 2);
+        assert!(store.get_plan(id + 1).unwrap().is_none());
+
+        assert!(store.save_plan(&[]).is_err());
+        assert_eq!(store.list_plans().unwrap().len(), 1);
+    }
+
     #[test]
     fn load_entries_sizes_preserved() {
         let mut store = Store::open_in_memory().unwrap();
@@ -371,6 +1165,137 @@ mod tests {
         assert_eq!(latest.id, latest_id);
     }
 
+    #[test]
+    fn entry_rows_span_all_snapshots() {
+        let mut store = Store::open_in_memory().unwrap();
+        let id1 = store
+            .save_snapshot(&make_result(vec![make_entry("a", 100)]))
+            .unwrap();
+        let id2 = store
+            .save_snapshot(&make_result(vec![
+                make_entry("b", 200),
+                make_entry("c", 300),
+            ]))
+            .unwrap();
+
+        let mut rows = Vec::new();
+        let count = store
+            .for_each_entry_row(|row| {
+                rows.push(row);
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(rows[0].snapshot_id, id1);
+        assert_eq!(rows[0].name, "a");
+        assert_eq!(rows[0].category, "PackageCache");
+        assert_eq!(rows[0].location, "/tmp/test");
+        assert!(rows[1..].iter().all(|r| r.snapshot_id == id2));
+    }
+
+    #[test]
+    fn concurrent_writers_both_succeed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heft.db");
+        // create the schema once so the writers only race on inserts
+        Store::open_at(&path).unwrap();
+
+        let writers: Vec<_> = (0..2)
+            .map(|w| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let mut store = Store::open_at(&path).unwrap();
+                    for i in 0..20 {
+                        let entries = (0..50)
+                            .map(|e| make_entry(&format!("w{w}-{i}-{e}"), e))
+                            .collect();
+                        store.save_snapshot(&make_result(entries)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let store = Store::open_at(&path).unwrap();
+        let snapshots = store.list_snapshots().unwrap();
+        assert_eq!(snapshots.len(), 40);
+        for snapshot in snapshots {
+            assert_eq!(store.load_snapshot_entries(snapshot.id).unwrap().len(), 50);
+        }
+    }
+
+    #[test]
+    fn open_at_enables_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(&dir.path().join("heft.db")).unwrap();
+        let mode: String = store
+            .conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+    }
+
+    #[test]
+    fn vacuum_reclaimable_counts_free_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heft.db");
+        let conn = Connection::open(&path).unwrap();
+        init_schema(&conn).unwrap();
+        let mut store = Store {
+            conn,
+            tags_column: true,
+            note_column: true,
+            manual_column: true,
+            detector_column: true,
+        };
+
+        let big: Vec<BloatEntry> = (0..2_000)
+            .map(|i| make_entry(&format!("entry-{i}"), i))
+            .collect();
+        let id = store.save_snapshot(&make_result(big)).unwrap();
+        assert_eq!(vacuum_reclaimable_bytes(&path).unwrap(), 0);
+
+        store
+            .conn
+            .execute("DELETE FROM entries WHERE snapshot_id = ?1", params![id])
+            .unwrap();
+        assert!(vacuum_reclaimable_bytes(&path).unwrap() > 0);
+
+        store.vacuum().unwrap();
+        assert_eq!(vacuum_reclaimable_bytes(&path).unwrap(), 0);
+    }
+
+    #[test]
+    fn vacuum_shrinks_the_database_and_its_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("heft.db");
+        let mut store = Store::open_at(&path).unwrap();
+        let big: Vec<BloatEntry> = (0..2_000)
+            .map(|i| make_entry(&format!("entry-{i}"), i))
+            .collect();
+        let id = store.save_snapshot(&make_result(big)).unwrap();
+        store
+            .conn
+            .execute("DELETE FROM entries WHERE snapshot_id = ?1", params![id])
+            .unwrap();
+
+        // WAL mode: recent writes sit in the -wal file, counted with the rest
+        let [wal, _] = sidecars(&path);
+        assert!(std::fs::metadata(&wal).unwrap().len() > 0);
+        let before = database_size(&path).unwrap();
+        assert!(before > std::fs::metadata(&path).unwrap().len());
+        assert!(vacuum_reclaimable_bytes(&path).unwrap() > 0);
+
+        store.vacuum().unwrap();
+        let after = database_size(&path).unwrap();
+        assert!(after < before, "{after} >= {before}");
+        assert_eq!(vacuum_reclaimable_bytes(&path).unwrap(), 0);
+        assert_eq!(database_size(&dir.path().join("missing.db")), None);
+    }
+
     #[test]
     fn multiple_snapshots_listed_desc() {
         let mut store = Store::open_in_memory().unwrap();
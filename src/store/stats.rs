@@ -0,0 +1,141 @@
+//! Trends across saved snapshots (`heft stats`).
+//!
+//! Where diff compares two points, stats aggregates every snapshot in a time
+//! window:
+//! - Average and peak reclaimable space
+//! - Space freed: the sum of every drop in reclaimable between one snapshot
+//!   and the next, so a cleanup counts even if caches grew back afterwards
+//! - The category that was largest in the most snapshots, i.e. the one that
+//!   keeps coming back
+
+use serde::Serialize;
+
+use super::snapshot::Snapshot;
+use crate::clean::category_sort_order;
+use crate::scan::detector::{BloatCategory, BloatEntry};
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub snapshots: usize,
+    pub from_timestamp: i64,
+    pub to_timestamp: i64,
+    pub average_reclaimable: u64,
+    pub peak_reclaimable: u64,
+    pub peak_snapshot_id: i64,
+    pub peak_timestamp: i64,
+    pub freed_bytes: u64,
+    /// Largest category by reclaimable in the most snapshots; None when no
+    /// snapshot had entries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_category: Option<BloatCategory>,
+    /// How many snapshots `top_category` was the largest in
+    pub top_category_count: usize,
+}
+
+/// The category holding the most reclaimable space among `entries`. Ties go
+/// to the category clean lists first.
+pub fn largest_category(entries: &[BloatEntry]) -> Option<BloatCategory> {
+    let mut totals: Vec<(BloatCategory, u64)> = Vec::new();
+    for entry in entries.iter().filter(|e| e.reclaimable_bytes > 0) {
+        match totals.iter_mut().find(|(c, _)| *c == entry.category) {
+            Some((_, total)) => *total += entry.reclaimable_bytes,
+            None => totals.push((entry.category, entry.reclaimable_bytes)),
+        }
+    }
+    totals.sort_by_key(|(category, _)| category_sort_order(category));
+    totals
+        .iter()
+        .rev()
+        .max_by_key(|(_, total)| *total)
+        .map(|(category, _)| *category)
+}
+
+/// Aggregate `snapshots`, oldest first, each paired with its largest
+/// category. None when there are no snapshots.
+pub fn summarize(snapshots: &[(Snapshot, Option<BloatCategory>)]) -> Option<Stats> {
+    let (first, _) = snapshots.first()?;
+    let (last, _) = snapshots.last()?;
+
+    let total: u128 = snapshots
+        .iter()
+        .map(|(s, _)| u128::from(s.reclaimable_bytes))
+        .sum();
+    // the first of equal peaks
+    let (peak, _) = snapshots
+        .iter()
+        .rev()
+        .max_by_key(|(s, _)| s.reclaimable_bytes)?;
+    let freed_bytes = snapshots
+        .windows(2)
+        .map(|pair| {
+            pair[0]
+                .0
+                .reclaimable_bytes
+                .saturating_sub(pair[1].0.reclaimable_bytes)
+        })
+        .sum();
+
+    let mut counts: Vec<(BloatCategory, usize)> = Vec::new();
+    for category in snapshots.iter().filter_map(|(_, c)| *c) {
+        match counts.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((category, 1)),
+        }
+    }
+    counts.sort_by_key(|(category, _)| category_sort_order(category));
+    let top = counts.iter().rev().max_by_key(|(_, count)| *count);
+
+    Some(Stats {
+        snapshots: snapshots.len(),
+        from_timestamp: first.timestamp,
+        to_timestamp: last.timestamp,
+        average_reclaimable: (total / snapshots.len() as u128) as u64,
+        peak_reclaimable: peak.reclaimable_bytes,
+        peak_snapshot_id: peak.id,
+        peak_timestamp: peak.timestamp,
+        freed_bytes,
+        top_category: top.map(|(category, _)| *category),
+        top_category_count: top.map_or(0, |(_, count)| *count),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(id: i64, reclaimable: u64) -> Snapshot {
+        Snapshot {
+            id,
+            timestamp: 1_700_000_000 + id * 86_400,
+            total_bytes: reclaimable * 2,
+            reclaimable_bytes: reclaimable,
+            scan_duration_ms: 0,
+            peak_memory_bytes: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn averages_peaks_and_drops_across_the_window() {
+        use BloatCategory::{PackageCache, ProjectArtifacts};
+        let series = [
+            (snapshot(1, 100), Some(PackageCache)),
+            (snapshot(2, 400), Some(ProjectArtifacts)),
+            (snapshot(3, 150), Some(PackageCache)),
+            (snapshot(4, 400), Some(PackageCache)),
+            (snapshot(5, 50), None),
+        ];
+
+        let stats = summarize(&series).unwrap();
+
+        assert_eq!(stats.snapshots, 5);
+        assert_eq!(stats.average_reclaimable, 220);
+        assert_eq!(stats.peak_reclaimable, 400);
+        assert_eq!(stats.peak_snapshot_id, 2);
+        // 400 -> 150 and 400 -> 50; the regrowth in between doesn't offset it
+        assert_eq!(stats.freed_bytes, 600);
+        assert_eq!(stats.top_category, Some(PackageCache));
+        assert_eq!(stats.top_category_count, 3);
+        assert!(summarize(&[]).is_none());
+    }
+}
@@ -0,0 +1,511 @@
+//! Full-screen browser for scan results (`heft scan --tui`).
+//!
+//! Categories are listed as collapsible groups with their entries underneath.
+//! Entries can be marked and deleted in place; deletion goes through
+//! clean::run one entry at a time, so the usual path validation applies and
+//! a failure on one entry doesn't hide what happened to the rest.
+//!
+//! Keys:
+//! - up/down (k/j): move
+//! - right/enter (l): expand category, left (h): collapse
+//! - space: mark entry, or every entry of a category
+//! - d: delete marked entries (asks first)
+//! - q/esc: quit
+
+use std::collections::BTreeSet;
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::clean::{self, CleanMode, CleanOptions};
+use crate::scan::detector::{BloatCategory, BloatEntry, Location};
+use crate::scan::ScanResult;
+use crate::util::format_bytes;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Row {
+    Category(BloatCategory),
+    Entry(usize),
+}
+
+struct Group {
+    category: BloatCategory,
+    /// indices into App::entries, largest first
+    entries: Vec<usize>,
+    expanded: bool,
+}
+
+struct App {
+    entries: Vec<BloatEntry>,
+    /// entries deleted during this session, hidden from the list
+    removed: BTreeSet<usize>,
+    groups: Vec<Group>,
+    marked: BTreeSet<usize>,
+    cursor: usize,
+    confirming: bool,
+    status: String,
+    /// `clean.never_delete`, honoured by every delete
+    never_delete: Vec<String>,
+    /// `detectors.docker_context`, so docker deletes reach the daemon the
+    /// scan looked at
+    docker_context: Option<String>,
+}
+
+impl App {
+    fn new(result: &ScanResult, never_delete: &[String], docker_context: Option<&str>) -> Self {
+        let entries = result.entries.clone();
+
+        let mut groups: Vec<Group> = Vec::new();
+        for (i, entry) in entries.iter().enumerate() {
+            match groups.iter_mut().find(|g| g.category == entry.category) {
+                Some(group) => group.entries.push(i),
+                None => groups.push(Group {
+                    category: entry.category,
+                    entries: vec![i],
+                    expanded: false,
+                }),
+            }
+        }
+        groups.sort_by_key(|g| clean::category_sort_order(&g.category));
+        for group in &mut groups {
+            group
+                .entries
+                .sort_by_key(|&i| std::cmp::Reverse(entries[i].size_bytes));
+        }
+
+        App {
+            entries,
+            removed: BTreeSet::new(),
+            groups,
+            marked: BTreeSet::new(),
+            cursor: 0,
+            confirming: false,
+            status: "space: mark  d: delete marked  enter: expand  q: quit".to_string(),
+            never_delete: never_delete.to_vec(),
+            docker_context: docker_context.map(str::to_string),
+        }
+    }
+
+    fn live_entries<'a>(&'a self, group: &'a Group) -> impl Iterator<Item = usize> + 'a {
+        group
+            .entries
+            .iter()
+            .copied()
+            .filter(|i| !self.removed.contains(i))
+    }
+
+    /// Visible rows: each category, followed by its entries when expanded.
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for group in &self.groups {
+            if self.live_entries(group).next().is_none() {
+                continue;
+            }
+            rows.push(Row::Category(group.category));
+            if group.expanded {
+                rows.extend(self.live_entries(group).map(Row::Entry));
+            }
+        }
+        rows
+    }
+
+    fn selected(&self) -> Option<Row> {
+        self.rows().get(self.cursor).copied()
+    }
+
+    fn group_mut(&mut self, category: BloatCategory) -> Option<&mut Group> {
+        self.groups.iter_mut().find(|g| g.category == category)
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let len = self.rows().len();
+        if len == 0 {
+            self.cursor = 0;
+            return;
+        }
+        self.cursor = self.cursor.saturating_add_signed(delta).min(len - 1);
+    }
+
+    fn set_expanded(&mut self, expanded: bool) {
+        let category = match self.selected() {
+            Some(Row::Category(c)) => c,
+            Some(Row::Entry(i)) => self.entries[i].category,
+            None => return,
+        };
+        if let Some(group) = self.group_mut(category) {
+            group.expanded = expanded;
+        }
+        // collapsing from an entry row moves the cursor back to its category
+        if !expanded {
+            if let Some(pos) = self
+                .rows()
+                .iter()
+                .position(|r| *r == Row::Category(category))
+            {
+                self.cursor = pos;
+            }
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        match self.selected() {
+            Some(Row::Entry(i)) => {
+                if self.marked.contains(&i) {
+                    self.marked.remove(&i);
+                } else {
+                    self.marked.insert(i);
+                }
+            }
+            Some(Row::Category(category)) => {
+                let Some(group) = self.groups.iter().find(|g| g.category == category) else {
+                    return;
+                };
+                let members: Vec<usize> = self.live_entries(group).collect();
+                if members.iter().all(|i| self.marked.contains(i)) {
+                    for i in members {
+                        self.marked.remove(&i);
+                    }
+                } else {
+                    self.marked.extend(members);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn marked_bytes(&self) -> u64 {
+        self.marked
+            .iter()
+            .map(|&i| self.entries[i].reclaimable_bytes)
+            .sum()
+    }
+
+    /// Delete every marked entry through clean::run, one at a time. Entries
+    /// a plain `heft clean` leaves alone (device backups, unpinned runtimes,
+    /// downloads, reported-only entries) are reported as skipped.
+    fn delete_marked(&mut self) {
+        let mut freed = 0u64;
+        let mut deleted = 0usize;
+        let mut skipped = Vec::new();
+        let mut errors = Vec::new();
+
+        for i in std::mem::take(&mut self.marked) {
+            let single = ScanResult {
+                entries: vec![self.entries[i].clone()],
+                ..ScanResult::empty()
+            };
+            let options = CleanOptions {
+                never_delete: self.never_delete.clone(),
+                docker_context: self.docker_context.clone(),
+                ..Default::default()
+            };
+            let result = clean::run(&single, CleanMode::Execute, &options);
+            if let Some(reason) = result.refused {
+                errors.push(reason);
+                continue;
+            }
+            if result.actions.is_empty() {
+                skipped.push(self.entries[i].name.clone());
+                continue;
+            }
+            let failures = result.errors();
+            if failures.is_empty() {
+                freed += result.bytes_freed;
+                deleted += 1;
+                self.removed.insert(i);
+            } else {
+                errors.extend(failures.into_iter().map(str::to_string));
+            }
+        }
+
+        self.status = format!("deleted {deleted} entries, freed {}", format_bytes(freed));
+        if let Some(first) = skipped.first() {
+            self.status.push_str(&format!(
+                ", skipped {} that clean won't delete unasked ({first})",
+                skipped.len()
+            ));
+        }
+        if let Some(first) = errors.first() {
+            self.status
+                .push_str(&format!(" — {} failed: {first}", errors.len()));
+        }
+        self.move_cursor(0);
+    }
+
+    /// Returns false once the user quits.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if self.confirming {
+            self.confirming = false;
+            if matches!(code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.delete_marked();
+            } else {
+                self.status = "deletion cancelled".to_string();
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::Right | KeyCode::Enter | KeyCode::Char('l') => self.set_expanded(true),
+            KeyCode::Left | KeyCode::Char('h') => self.set_expanded(false),
+            KeyCode::Char(' ') => self.toggle_mark(),
+            KeyCode::Char('d') => {
+                if self.marked.is_empty() {
+                    self.status = "nothing marked (space marks an entry)".to_string();
+                } else {
+                    self.confirming = true;
+                    self.status = format!(
+                        "delete {} marked entries ({})? [y/n]",
+                        self.marked.len(),
+                        format_bytes(self.marked_bytes())
+                    );
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn row_line(&self, row: Row) -> String {
+        match row {
+            Row::Category(category) => {
+                let group = self.groups.iter().find(|g| g.category == category);
+                let (count, size, expanded) = group
+                    .map(|g| {
+                        let live: Vec<usize> = self.live_entries(g).collect();
+                        let size: u64 = live.iter().map(|&i| self.entries[i].size_bytes).sum();
+                        (live.len(), size, g.expanded)
+                    })
+                    .unwrap_or((0, 0, false));
+                format!(
+                    "{} {:<20} {:>10}  ({count} items)",
+                    if expanded { "▾" } else { "▸" },
+                    category.label(),
+                    format_bytes(size)
+                )
+            }
+            Row::Entry(i) => {
+                let entry = &self.entries[i];
+                format!(
+                    "    [{}] {:<40} {:>10}",
+                    if self.marked.contains(&i) { "x" } else { " " },
+                    entry.name,
+                    format_bytes(entry.size_bytes)
+                )
+            }
+        }
+    }
+
+    fn details(&self) -> Vec<Line<'static>> {
+        match self.selected() {
+            Some(Row::Entry(i)) => {
+                let entry = &self.entries[i];
+                let location = match &entry.location {
+                    Location::FilesystemPath(p) => p.display().to_string(),
+                    Location::DockerObject(id) => format!("docker:{id}"),
+                    Location::Aggregate(name) => name.clone(),
+                };
+                vec![
+                    Line::from(format!("location:    {location}")),
+                    Line::from(format!(
+                        "size:        {}  (reclaimable {})",
+                        format_bytes(entry.size_bytes),
+                        format_bytes(entry.reclaimable_bytes)
+                    )),
+                    Line::from(format!(
+                        "cleanup:     {}",
+                        entry.cleanup_hint.as_deref().unwrap_or("-")
+                    )),
+                ]
+            }
+            Some(Row::Category(_)) => vec![Line::from(
+                "enter expands, space marks every entry in the category",
+            )],
+            None => vec![Line::from("nothing left to show")],
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [list_area, detail_area, status_area] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(5),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let rows = self.rows();
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|&row| ListItem::new(self.row_line(row)))
+            .collect();
+        let title = format!(
+            " heft — {} marked ({}) ",
+            self.marked.len(),
+            format_bytes(self.marked_bytes())
+        );
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.cursor));
+        frame.render_stateful_widget(list, list_area, &mut state);
+
+        let details = Paragraph::new(self.details())
+            .block(Block::default().borders(Borders::ALL).title(" details "))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(details, detail_area);
+
+        frame.render_widget(Paragraph::new(self.status.as_str()), status_area);
+    }
+}
+
+/// Take over the terminal and browse `result` until the user quits.
+/// Paths matching `never_delete` are refused like in clean, and docker
+/// objects are deleted through `docker_context` like in clean.
+pub fn run(
+    result: &ScanResult,
+    never_delete: &[String],
+    docker_context: Option<&str>,
+) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let outcome = event_loop(
+        &mut terminal,
+        App::new(result, never_delete, docker_context),
+    );
+    ratatui::restore();
+    outcome
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            // windows reports both press and release
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn entry(name: &str, category: BloatCategory, size: u64) -> BloatEntry {
+        BloatEntry {
+            category,
+            name: name.to_string(),
+            location: Location::FilesystemPath(PathBuf::from(format!("/tmp/heft-tui/{name}"))),
+            size_bytes: size,
+            reclaimable_bytes: size,
+            last_modified: None,
+            cleanup_hint: None,
+            manual: false,
+            tags: Vec::new(),
+            detector: None,
+            file_count: None,
+        }
+    }
+
+    fn app() -> App {
+        App::new(
+            &ScanResult {
+                entries: vec![
+                    entry("npm cache", BloatCategory::PackageCache, 100),
+                    entry("web", BloatCategory::ProjectArtifacts, 50),
+                    entry("api", BloatCategory::ProjectArtifacts, 500),
+                ],
+                ..ScanResult::empty()
+            },
+            &[],
+            None,
+        )
+    }
+
+    #[test]
+    fn categories_start_collapsed_in_clean_order() {
+        let app = app();
+        assert_eq!(
+            app.rows(),
+            vec![
+                Row::Category(BloatCategory::ProjectArtifacts),
+                Row::Category(BloatCategory::PackageCache),
+            ]
+        );
+    }
+
+    #[test]
+    fn expand_lists_entries_largest_first() {
+        let mut app = app();
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(
+            app.rows()[..3],
+            [
+                Row::Category(BloatCategory::ProjectArtifacts),
+                Row::Entry(2),
+                Row::Entry(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn space_on_category_marks_all_its_entries() {
+        let mut app = app();
+        app.handle_key(KeyCode::Char(' '));
+        assert_eq!(app.marked, BTreeSet::from([1, 2]));
+        assert_eq!(app.marked_bytes(), 550);
+
+        app.handle_key(KeyCode::Char(' '));
+        assert!(app.marked.is_empty());
+    }
+
+    #[test]
+    fn delete_asks_for_confirmation() {
+        let mut app = app();
+        app.handle_key(KeyCode::Char(' '));
+        app.handle_key(KeyCode::Char('d'));
+        assert!(app.confirming);
+
+        app.handle_key(KeyCode::Char('n'));
+        assert!(!app.confirming);
+        assert_eq!(app.marked.len(), 2);
+        assert!(app.removed.is_empty());
+    }
+
+    #[test]
+    fn entries_clean_leaves_alone_are_reported_skipped() {
+        let mut backup = entry("iPhone backup", BloatCategory::Other, 800);
+        backup.tags = vec![crate::scan::mobilesync::BACKUP_TAG.to_string()];
+        let mut app = App::new(
+            &ScanResult {
+                entries: vec![backup],
+                ..ScanResult::empty()
+            },
+            &[],
+            None,
+        );
+        app.marked.insert(0);
+        app.delete_marked();
+
+        assert!(app.removed.is_empty());
+        assert_eq!(
+            app.status,
+            "deleted 0 entries, freed 0 B, skipped 1 that clean won't delete unasked (iPhone backup)"
+        );
+    }
+
+    #[test]
+    fn quit_keys_stop_the_loop() {
+        let mut app = app();
+        assert!(!app.handle_key(KeyCode::Char('q')));
+        assert!(!app.handle_key(KeyCode::Esc));
+    }
+}
@@ -1,18 +1,400 @@
 //! Shared utility functions
 
-/// Format bytes into human-readable sizes (B, KB, MB, GB)
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// How format_bytes renders sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// 1024-based, one decimal (the default)
+    #[default]
+    Binary,
+    /// 1000-based, one decimal, as macOS Finder shows
+    Si,
+    /// Exact byte counts
+    Bytes,
+}
+
+/// Process-wide unit preference, set once at startup from --units / config.
+static UNITS: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_units(units: Units) {
+    UNITS.store(units as u8, Ordering::Relaxed);
+}
+
+fn units() -> Units {
+    match UNITS.load(Ordering::Relaxed) {
+        1 => Units::Si,
+        2 => Units::Bytes,
+        _ => Units::Binary,
+    }
+}
+
+/// Process-wide read-only mode (--read-only / HEFT_READ_ONLY): no snapshot
+/// database writes or creation, no deletions.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+}
+
+pub fn read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Process-wide ASCII mode (--ascii, `display.ascii`, NO_COLOR, or stdout
+/// not being a terminal): heft's own symbols are printed as plain ASCII.
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+pub fn set_ascii(ascii: bool) {
+    ASCII.store(ascii, Ordering::Relaxed);
+}
+
+pub fn ascii() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// `text` for human-readable output: in ASCII mode the dashes, arrows and
+/// quotes heft uses in messages and hints become ASCII. Other characters,
+/// such as those in file names, pass through.
+pub fn plain(text: &str) -> std::borrow::Cow<'_, str> {
+    if !ascii() || text.is_ascii() {
+        std::borrow::Cow::Borrowed(text)
+    } else {
+        std::borrow::Cow::Owned(ascii_symbols(text))
+    }
+}
+
+fn ascii_symbols(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '—' | '–' | '─' => out.push('-'),
+            '│' => out.push('|'),
+            '→' => out.push_str("->"),
+            '←' => out.push_str("<-"),
+            '…' => out.push_str("..."),
+            '×' => out.push('x'),
+            '‘' | '’' => out.push('\''),
+            '“' | '”' => out.push('"'),
+            '✓' | '✔' | '✅' => out.push_str("[ok]"),
+            '✗' | '❌' => out.push_str("[x]"),
+            '📈' => out.push_str("[+]"),
+            '📉' => out.push_str("[-]"),
+            '🆕' => out.push_str("[new]"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Format bytes into human-readable sizes (B, KB, MB, GB) in the
+/// process-wide units.
 pub fn format_bytes(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = 1024 * KB;
-    const GB: u64 = 1024 * MB;
-
-    if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
+    format_bytes_in(bytes, units())
+}
+
+/// Format bytes in the given units.
+pub fn format_bytes_in(bytes: u64, units: Units) -> String {
+    let base: u64 = match units {
+        Units::Binary => 1024,
+        Units::Si => 1000,
+        Units::Bytes => return format!("{bytes} B"),
+    };
+    let kb = base;
+    let mb = base * kb;
+    let gb = base * mb;
+
+    if bytes >= gb {
+        format!("{:.1} GB", bytes as f64 / gb as f64)
+    } else if bytes >= mb {
+        format!("{:.1} MB", bytes as f64 / mb as f64)
+    } else if bytes >= kb {
+        format!("{:.1} KB", bytes as f64 / kb as f64)
     } else {
         format!("{bytes} B")
     }
 }
+
+/// Share of `goal` that `bytes` covers, in whole percent (can pass 100).
+pub fn goal_percent(bytes: u64, goal: u64) -> u64 {
+    if goal == 0 {
+        return 100;
+    }
+    u64::try_from(u128::from(bytes) * 100 / u128::from(goal)).unwrap_or(u64::MAX)
+}
+
+/// "12.0 GB of 20.0 GB goal (60%)", for --goal.
+pub fn goal_progress(bytes: u64, goal: u64) -> String {
+    format!(
+        "{} of {} goal ({}%)",
+        format_bytes(bytes),
+        format_bytes(goal),
+        goal_percent(bytes, goal)
+    )
+}
+
+/// `goal_progress` behind a 20-cell bar, for running totals during clean.
+pub fn goal_bar(bytes: u64, goal: u64) -> String {
+    format!(
+        "{} {}",
+        progress_bar(goal_percent(bytes, goal), ascii()),
+        goal_progress(bytes, goal)
+    )
+}
+
+fn progress_bar(percent: u64, ascii: bool) -> String {
+    const WIDTH: u64 = 20;
+    let filled = percent.min(100) * WIDTH / 100;
+    let (full, empty) = if ascii { ('#', '-') } else { ('█', '░') };
+    let mut bar = String::from("[");
+    bar.extend((0..WIDTH).map(|i| if i < filled { full } else { empty }));
+    bar.push(']');
+    bar
+}
+
+/// Parse a human size like "20GB", "1.5 G", "500mb" or "4096" into bytes.
+/// Units are binary (1024-based) to match format_bytes.
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let s = input.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num_str, unit) = s.split_at(split);
+
+    let num: f64 = num_str
+        .parse()
+        .map_err(|_| format!("invalid size '{input}' (expected e.g. 500MB, 20GB)"))?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        "t" | "tb" | "tib" => 1024 * 1024 * 1024 * 1024,
+        other => return Err(format!("unknown size unit '{other}' in '{input}'")),
+    };
+
+    Ok((num * multiplier as f64) as u64)
+}
+
+/// Parse a --verify tolerance factor; must be a finite number of at least 1.
+pub fn parse_tolerance(input: &str) -> Result<f64, String> {
+    let factor: f64 = input
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid tolerance '{input}' (expected a factor like 2 or 1.5)"))?;
+    if !factor.is_finite() || factor < 1.0 {
+        return Err(format!("tolerance must be at least 1, got {input}"));
+    }
+    Ok(factor)
+}
+
+/// Parse a detector name for --only and --disable; unknown names are an error
+/// rather than silently matching nothing.
+pub fn parse_detector(input: &str) -> Result<String, String> {
+    let name = input.trim();
+    if crate::config::DETECTOR_NAMES.contains(&name) {
+        Ok(name.to_string())
+    } else {
+        Err(format!(
+            "unknown detector '{name}' (expected one of: {})",
+            crate::config::DETECTOR_NAMES.join(", ")
+        ))
+    }
+}
+
+/// Parse a point in time for snapshot filtering into a unix timestamp (UTC).
+///
+/// Accepts an absolute `YYYY-MM-DD` date or a relative age like `12h`, `7d`
+/// or `2w` counted back from `now`. A date means the start of that day,
+/// or the start of the next day when `end_of_day` is set so that an
+/// exclusive upper bound still covers the whole day.
+pub fn parse_time_bound(input: &str, now: i64, end_of_day: bool) -> Result<i64, String> {
+    let input = input.trim();
+
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let date = if end_of_day {
+            date.succ_opt()
+                .ok_or_else(|| format!("date out of range: {input}"))?
+        } else {
+            date
+        };
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp());
+    }
+
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (num_str, unit) = input.split_at(split);
+    let seconds_per_unit: i64 = match unit.to_lowercase().as_str() {
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => {
+            return Err(format!(
+                "invalid time '{input}' (expected YYYY-MM-DD or an age like 12h, 7d, 2w)"
+            ))
+        }
+    };
+    let num: i64 = num_str
+        .parse()
+        .map_err(|_| format!("invalid number in '{input}'"))?;
+
+    num.checked_mul(seconds_per_unit)
+        .and_then(|secs| now.checked_sub(secs))
+        .ok_or_else(|| format!("time out of range: {input}"))
+}
+
+/// Clap parser for `--since`: inclusive lower bound.
+pub fn parse_since(input: &str) -> Result<i64, String> {
+    parse_time_bound(input, chrono::Utc::now().timestamp(), false)
+}
+
+/// Clap parser for `--until`: exclusive upper bound, covering a whole named day.
+pub fn parse_until(input: &str) -> Result<i64, String> {
+    parse_time_bound(input, chrono::Utc::now().timestamp(), true)
+}
+
+/// Match `text` against a shell-style glob where `*` matches any run of
+/// characters (including none) and `?` matches exactly one. Case-sensitive.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // position of the last `*` seen and the text index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // let the last `*` swallow one more character and retry
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_symbols_replace_heft_punctuation_only() {
+        assert_eq!(
+            ascii_symbols("1 diagnostic — run with -v. Xcode → Settings"),
+            "1 diagnostic - run with -v. Xcode -> Settings"
+        );
+        // names from the filesystem keep their characters
+        assert_eq!(ascii_symbols("café (naïve)"), "café (naïve)");
+    }
+
+    #[test]
+    fn goal_percent_and_bar() {
+        assert_eq!(goal_percent(12, 20), 60);
+        assert_eq!(goal_percent(30, 20), 150);
+        assert_eq!(goal_percent(5, 0), 100);
+        assert_eq!(progress_bar(60, true), "[############--------]");
+        // past the goal the bar stays full
+        assert_eq!(progress_bar(150, true), format!("[{}]", "#".repeat(20)));
+    }
+
+    #[test]
+    fn format_bytes_in_each_unit() {
+        assert_eq!(format_bytes_in(1_500_000, Units::Binary), "1.4 MB");
+        assert_eq!(format_bytes_in(1_500_000, Units::Si), "1.5 MB");
+        assert_eq!(format_bytes_in(1_500_000, Units::Bytes), "1500000 B");
+        assert_eq!(format_bytes_in(999, Units::Si), "999 B");
+        assert_eq!(
+            format_bytes_in(2 * 1024 * 1024 * 1024, Units::Binary),
+            "2.0 GB"
+        );
+    }
+
+    #[test]
+    fn parse_tolerance_rejects_below_one() {
+        assert_eq!(parse_tolerance("1.5").unwrap(), 1.5);
+        assert!(parse_tolerance("0.5").is_err());
+        assert!(parse_tolerance("abc").is_err());
+        assert!(parse_tolerance("inf").is_err());
+    }
+
+    #[test]
+    fn parse_time_bound_dates_and_ages() {
+        let now = 1_700_000_000;
+        // 2024-01-15 00:00:00 UTC
+        assert_eq!(
+            parse_time_bound("2024-01-15", now, false).unwrap(),
+            1_705_276_800
+        );
+        assert_eq!(
+            parse_time_bound("2024-01-15", now, true).unwrap(),
+            1_705_276_800 + 86_400
+        );
+        assert_eq!(
+            parse_time_bound("7d", now, false).unwrap(),
+            now - 7 * 86_400
+        );
+        assert_eq!(
+            parse_time_bound("2w", now, true).unwrap(),
+            now - 14 * 86_400
+        );
+        assert_eq!(
+            parse_time_bound("12H", now, false).unwrap(),
+            now - 12 * 3600
+        );
+        assert!(parse_time_bound("7", now, false).is_err());
+        assert!(parse_time_bound("d", now, false).is_err());
+        assert!(parse_time_bound("2024-13-01", now, false).is_err());
+        assert!(parse_time_bound("yesterday", now, false).is_err());
+    }
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("docker*", "docker images"));
+        assert!(glob_match("*cache", "npm cache"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("vscode ?ogs", "vscode logs"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(glob_match("npm cache", "npm cache"));
+        assert!(!glob_match("docker*", "my docker"));
+        assert!(!glob_match("a*b", "acbd"));
+        assert!(!glob_match("?", ""));
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        assert_eq!(parse_size("10KB").unwrap(), 10 * 1024);
+        assert_eq!(parse_size("500mb").unwrap(), 500 * 1024 * 1024);
+        assert_eq!(parse_size("20 GB").unwrap(), 20 * 1024 * 1024 * 1024);
+        assert_eq!(parse_size("1.5G").unwrap(), 1024 * 1024 * 1024 * 3 / 2);
+        assert_eq!(parse_size("2TiB").unwrap(), 2 * 1024 * 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn parse_size_rejects_garbage() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("GB").is_err());
+        assert!(parse_size("20 parsecs").is_err());
+    }
+
+    #[test]
+    fn detector_names_checked() {
+        assert_eq!(parse_detector("docker").unwrap(), "docker");
+        assert!(parse_detector("dockr")
+            .unwrap_err()
+            .starts_with("unknown detector 'dockr' (expected one of: projects, git,"));
+    }
+}
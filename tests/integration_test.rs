@@ -1,11 +1,15 @@
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::time::Duration;
 
 use heft::config::Config;
 use heft::platform::Platform;
+use heft::report::OutputFormat;
 use heft::scan;
 use heft::scan::detector::BloatCategory;
+use heft::scan::filter::PathFilter;
 
 // helper to filter results by category
 fn project_entries(result: &scan::ScanResult) -> Vec<&scan::detector::BloatEntry> {
@@ -31,10 +35,13 @@ fn test_config(root: PathBuf) -> Config {
         roots: vec![root],
         timeout: Duration::from_secs(30),
         disabled_detectors: std::collections::HashSet::from(["docker".to_string()]),
-        json_output: false,
-        verbose: false,
+        output_format: OutputFormat::Table,
+        verbose_level: 0,
         progressive: false,
+        // fixtures are a few bytes each
+        min_entry_bytes: 0,
         platform: Platform::Linux,
+        ..Config::default()
     }
 }
 
@@ -70,6 +77,91 @@ fn detects_node_modules_in_project() {
     assert!(projects[0].size_bytes > 0);
 }
 
+#[test]
+fn node_modules_without_package_json_flagged_orphaned() {
+    let temp = tmpdir();
+    let active = temp.path().join("active");
+    let leftover = temp.path().join("deleted-app");
+    for project in [&active, &leftover] {
+        fs::create_dir_all(project.join("node_modules/dep")).unwrap();
+        fs::write(project.join("node_modules/dep/index.js"), "x").unwrap();
+    }
+    fs::write(active.join("package.json"), r#"{"name": "active"}"#).unwrap();
+    // npm's global prefix has no package.json by design and is not orphaned
+    let global = temp
+        .path()
+        .join("nvm/versions/node/v20.11.0/lib/node_modules");
+    fs::create_dir_all(global.join("typescript/node_modules/dep")).unwrap();
+    fs::write(global.join("typescript/package.json"), "{}").unwrap();
+    fs::write(global.join("typescript/node_modules/dep/index.js"), "x").unwrap();
+
+    let result = scan::run(&test_config(temp.path().to_path_buf()));
+    let mut projects = project_entries(&result);
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].name, "active");
+    assert!(!projects[0].tags.iter().any(|t| t == "orphaned"));
+    // the name stays what it was so diffs keep matching it
+    assert_eq!(projects[1].name, "deleted-app");
+    assert!(projects[1].tags.iter().any(|t| t == "orphaned"));
+    assert!(projects[1]
+        .cleanup_hint
+        .as_deref()
+        .unwrap()
+        .starts_with("orphaned"));
+}
+
+#[test]
+fn multiple_roots_scanned_and_overlap_reported_once() {
+    let work = tmpdir();
+    let personal = tmpdir();
+    for (root, name) in [(&work, "api"), (&personal, "blog")] {
+        let project = root.path().join(name);
+        fs::create_dir_all(project.join("node_modules/dep")).unwrap();
+        fs::write(
+            project.join("package.json"),
+            format!(r#"{{"name": "{name}"}}"#),
+        )
+        .unwrap();
+        fs::write(project.join("node_modules/dep/index.js"), "x").unwrap();
+    }
+
+    // the second root lies inside the first; api must not show up twice
+    let mut config = test_config(work.path().to_path_buf());
+    config.roots.push(personal.path().to_path_buf());
+    config.roots.push(work.path().join("api"));
+    let result = scan::run(&config);
+
+    let mut names: Vec<_> = project_entries(&result)
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["api", "blog"]);
+}
+
+#[test]
+fn estimate_counts_candidates_without_descending_into_depth_limit() {
+    let temp = tmpdir();
+    let project = temp.path().join("web");
+    fs::create_dir_all(project.join("node_modules/a/b/c/d")).unwrap();
+    fs::write(project.join("package.json"), r#"{"name": "web"}"#).unwrap();
+    fs::write(project.join("node_modules/a/b/c/d/index.js"), "x").unwrap();
+
+    let config = test_config(temp.path().to_path_buf());
+    let full = scan::projects::estimate(temp.path(), &config, 10);
+    assert_eq!(full.candidates, 1);
+    assert!(!full.depth_limited);
+    // web, package.json, node_modules, a, b, c, d, index.js plus the root
+    assert_eq!(full.entries, 9);
+
+    let shallow = scan::projects::estimate(temp.path(), &config, 3);
+    assert_eq!(shallow.candidates, 1);
+    assert!(shallow.depth_limited);
+    assert!(shallow.entries < full.entries);
+}
+
 #[test]
 fn detects_cargo_target_in_rust_project() {
     let temp = tmpdir();
@@ -137,6 +229,34 @@ fn detects_python_venv() {
     assert!(projects[0].cleanup_hint.as_ref().unwrap().contains("venv"));
 }
 
+#[test]
+fn detects_pypackages_with_pyproject() {
+    let temp = tmpdir();
+    let project = temp.path().join("pep582-project");
+    let lib = project.join("__pypackages__").join("3.12").join("lib");
+
+    fs::create_dir_all(&lib).unwrap();
+    fs::write(project.join("pyproject.toml"), "[project]\nname = \"x\"").unwrap();
+    fs::write(lib.join("requests.py"), "# fake").unwrap();
+
+    let result = scan::run(&test_config(temp.path().to_path_buf()));
+    let projects = project_entries(&result);
+
+    assert_eq!(projects.len(), 1);
+    assert!(projects[0].cleanup_hint.as_ref().unwrap().contains("pdm"));
+}
+
+#[test]
+fn ignores_pypackages_without_pyproject() {
+    let temp = tmpdir();
+    let dir = temp.path().join("loose").join("__pypackages__");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("x.py"), "# fake").unwrap();
+
+    let result = scan::run(&test_config(temp.path().to_path_buf()));
+    assert!(project_entries(&result).is_empty());
+}
+
 #[test]
 fn detects_pytest_cache() {
     let temp = tmpdir();
@@ -186,10 +306,239 @@ fn does_not_detect_target_without_cargo_toml() {
     assert!(project_entries(&result).is_empty());
 }
 
+#[test]
+fn explain_records_rejected_and_accepted_candidates() {
+    let temp = tmpdir();
+    let not_rust = temp.path().join("not-rust").join("target");
+    let rust = temp.path().join("my-crate");
+
+    fs::create_dir_all(&not_rust).unwrap();
+    fs::create_dir_all(rust.join("target")).unwrap();
+    fs::write(rust.join("Cargo.toml"), "[package]\nname = \"my-crate\"").unwrap();
+    fs::write(rust.join("target").join("out"), "x").unwrap();
+
+    let config = Config {
+        explain: true,
+        ..test_config(temp.path().to_path_buf())
+    };
+    let result = scan::run(&config);
+
+    assert!(result
+        .diagnostics
+        .iter()
+        .any(|d| d.contains("not-rust") && d.contains("rejected, no Cargo.toml in parent")));
+    assert!(result
+        .diagnostics
+        .iter()
+        .any(|d| d.contains("my-crate") && d.contains("accepted, found Cargo.toml")));
+}
+
+#[test]
+fn explain_off_adds_no_diagnostics() {
+    let temp = tmpdir();
+    fs::create_dir_all(temp.path().join("not-rust").join("target")).unwrap();
+
+    let result = scan::run(&test_config(temp.path().to_path_buf()));
+    assert!(!result.diagnostics.iter().any(|d| d.starts_with("explain:")));
+}
+
+// ============================================================================
+// --roots-from tests
+// ============================================================================
+
+/// Run `heft scan --roots-from <source> --format json` for projects only,
+/// feeding `stdin` to it, and return the stdout.
+fn scan_roots_from(home: &Path, source: &str, stdin: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_heft"))
+        .args(["--read-only", "scan", "--roots-from", source])
+        .args(["--only", "projects", "--min-size", "0", "--format", "json"])
+        .env("HOME", home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn roots_read_from_a_file_and_from_stdin() {
+    let temp = tmpdir();
+    for name in ["listed", "unlisted"] {
+        let project = temp.path().join(name);
+        fs::create_dir_all(project.join("node_modules/dep")).unwrap();
+        fs::write(project.join("package.json"), "{}").unwrap();
+        fs::write(project.join("node_modules/dep/index.js"), "x").unwrap();
+    }
+    let listed = temp.path().join("listed");
+    let list = format!(
+        "# curated
+{}
+
+",
+        listed.display()
+    );
+    fs::write(temp.path().join("roots.txt"), &list).unwrap();
+
+    let from_file = scan_roots_from(
+        temp.path(),
+        temp.path().join("roots.txt").to_str().unwrap(),
+        "",
+    );
+    let from_stdin = scan_roots_from(temp.path(), "-", &list);
+    for output in [from_file, from_stdin] {
+        assert!(output.contains("/listed/node_modules"), "{output}");
+        assert!(!output.contains("unlisted"), "{output}");
+    }
+}
+
+#[test]
+fn clean_refuses_to_run_when_the_config_file_is_broken() {
+    let temp = tmpdir();
+    let project = temp.path().join("code/app");
+    fs::create_dir_all(project.join("node_modules/dep")).unwrap();
+    fs::write(project.join("package.json"), "{}").unwrap();
+    fs::write(project.join("node_modules/dep/index.js"), "x").unwrap();
+    let config_dir = temp.path().join(".config/heft");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        format!(
+            "[clean]\nnever_delete = [\"{}\"]\ntypo = true\n",
+            project.display()
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_heft"))
+        .args(["clean", "--yes", "--roots"])
+        .arg(temp.path().join("code"))
+        .env("HOME", temp.path())
+        .env("XDG_CONFIG_HOME", temp.path().join(".config"))
+        .env("XDG_DATA_HOME", temp.path().join(".local/share"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("failed to parse config file"), "{stderr}");
+    assert!(stderr.contains("never_delete"), "{stderr}");
+    assert!(project.join("node_modules").exists());
+}
+
+// ============================================================================
+// --include / --exclude tests
+// ============================================================================
+
+// one node project and one rust project side by side
+fn node_and_rust_projects(root: &std::path::Path) {
+    let web = root.join("web");
+    fs::create_dir_all(web.join("node_modules")).unwrap();
+    fs::write(web.join("package.json"), r#"{"name": "web"}"#).unwrap();
+    fs::write(web.join("node_modules").join("dep.js"), "x").unwrap();
+
+    let api = root.join("api");
+    fs::create_dir_all(api.join("target")).unwrap();
+    fs::write(api.join("Cargo.toml"), "[package]\nname = \"api\"").unwrap();
+    fs::write(api.join("target").join("bin"), "x").unwrap();
+}
+
+fn filtered_names(root: PathBuf, include: &[&str], exclude: &[&str]) -> Vec<String> {
+    let config = Config {
+        path_filter: PathFilter::new(
+            include.iter().map(|s| s.to_string()).collect(),
+            exclude.iter().map(|s| s.to_string()).collect(),
+        ),
+        ..test_config(root)
+    };
+    let result = scan::run(&config);
+    let mut names: Vec<String> = project_entries(&result)
+        .iter()
+        .map(|e| e.name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn include_only_scans_matching_directories() {
+    let temp = tmpdir();
+    node_and_rust_projects(temp.path());
+    let names = filtered_names(temp.path().to_path_buf(), &["**/node_modules"], &[]);
+    assert_eq!(names, vec!["web"]);
+}
+
+#[test]
+fn exclude_prunes_matching_directories() {
+    let temp = tmpdir();
+    node_and_rust_projects(temp.path());
+    let names = filtered_names(temp.path().to_path_buf(), &[], &["web"]);
+    assert_eq!(names, vec!["api"]);
+}
+
+#[test]
+fn exclude_wins_when_include_also_matches() {
+    let temp = tmpdir();
+    node_and_rust_projects(temp.path());
+    let names = filtered_names(
+        temp.path().to_path_buf(),
+        &["node_modules", "target"],
+        &["web"],
+    );
+    assert_eq!(names, vec!["api"]);
+}
+
+#[test]
+fn include_path_prefix_limits_walk() {
+    let temp = tmpdir();
+    node_and_rust_projects(temp.path());
+    let pattern = format!("{}/api/*", temp.path().display());
+    let names = filtered_names(temp.path().to_path_buf(), &[&pattern], &[]);
+    assert_eq!(names, vec!["api"]);
+}
+
 // ============================================================================
 // .NET bin/obj detection tests
 // ============================================================================
 
+#[test]
+fn config_artifact_rule_requires_sibling_glob() {
+    use heft::scan::projects::ArtifactRule;
+
+    let temp = tmpdir();
+    let infra = temp.path().join("infra");
+    let stray = temp.path().join("stray");
+    for dir in [&infra, &stray] {
+        fs::create_dir_all(dir.join(".terraform/providers")).unwrap();
+        fs::write(dir.join(".terraform/providers/aws"), "binary").unwrap();
+    }
+    fs::write(infra.join("main.tf"), "").unwrap();
+
+    let config = Config {
+        artifact_rules: vec![ArtifactRule {
+            dir_name: ".terraform".to_string(),
+            requires_sibling: Some("*.tf".to_string()),
+            category: BloatCategory::ProjectArtifacts,
+            cleanup_hint: "terraform init".to_string(),
+        }],
+        ..test_config(temp.path().to_path_buf())
+    };
+    let result = scan::run(&config);
+    let projects = project_entries(&result);
+
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].name, "infra");
+    assert_eq!(projects[0].cleanup_hint.as_deref(), Some("terraform init"));
+}
+
 #[test]
 fn detects_dotnet_bin_obj_with_csproj() {
     let temp = tmpdir();
@@ -263,16 +612,33 @@ fn scan_runs_without_panic() {
         roots: vec![PathBuf::from("/tmp")],
         timeout: Duration::from_secs(30),
         disabled_detectors: std::collections::HashSet::from(["docker".to_string()]),
-        json_output: false,
-        verbose: false,
+        output_format: OutputFormat::Table,
+        verbose_level: 0,
         progressive: false,
         platform: Platform::Linux,
+        ..Config::default()
     };
 
     // should not panic, may or may not find caches
     let _result = scan::run(&config);
 }
 
+#[test]
+fn files_scanned_counts_walked_entries() {
+    let temp = tmpdir();
+    let package = temp.path().join("app/node_modules/pkg");
+    fs::create_dir_all(&package).unwrap();
+    fs::write(temp.path().join("app/package.json"), "{}").unwrap();
+    for i in 0..20 {
+        fs::write(package.join(format!("f{i}.js")), "x").unwrap();
+    }
+
+    let result = scan::run(&test_config(temp.path().to_path_buf()));
+
+    // other tests share the counter, so only a lower bound is stable
+    assert!(result.files_scanned.unwrap() >= 20);
+}
+
 #[test]
 fn detects_cache_directory() {
     let temp = tmpdir();
@@ -292,11 +658,16 @@ fn cache_entries_have_correct_category() {
     let config = Config {
         roots: vec![PathBuf::from("/nonexistent")],
         timeout: Duration::from_secs(30),
-        disabled_detectors: std::collections::HashSet::from(["docker".to_string()]),
-        json_output: false,
-        verbose: false,
+        // the database detector reports heft.db as Other on machines with history
+        disabled_detectors: std::collections::HashSet::from([
+            "docker".to_string(),
+            "database".to_string(),
+        ]),
+        output_format: OutputFormat::Table,
+        verbose_level: 0,
         progressive: false,
         platform: Platform::Linux,
+        ..Config::default()
     };
 
     let result = scan::run(&config);